@@ -0,0 +1,70 @@
+//! Storage benchmarks for large task directories
+//!
+//! Run with `cargo bench`. Each benchmark is repeated for the flat and
+//! sharded layouts over 10,000 generated tasks.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use gittask::models::serialize_task;
+use gittask::storage::layout;
+use gittask::storage::{FileStore, TaskFilter, TaskLayout, TaskLocation};
+use gittask::{Task, TaskKind};
+use std::path::Path;
+use tempfile::TempDir;
+
+const TASK_COUNT: u64 = 10_000;
+
+/// Write `TASK_COUNT` task files directly, bypassing ID allocation
+fn populate(layout: TaskLayout) -> (TempDir, TaskLocation) {
+    let temp = TempDir::new().unwrap();
+    std::fs::create_dir(temp.path().join(".git")).unwrap();
+    let location = TaskLocation::find_project_from(temp.path()).unwrap();
+    location.ensure_exists().unwrap();
+
+    for id in 1..=TASK_COUNT {
+        let mut task = Task::new(id, TaskKind::Task, format!("Generated task {}", id));
+        task.description = "Some description text for the task.".to_string();
+        let dir = layout.dir_for(&location.tasks_dir, id);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(task.filename()), serialize_task(&task).unwrap()).unwrap();
+    }
+    layout::migrate(&location.tasks_dir, layout).unwrap();
+
+    (temp, location)
+}
+
+fn bench_layouts(c: &mut Criterion) {
+    let mut group = c.benchmark_group("store");
+    group.sample_size(10);
+
+    for layout in [TaskLayout::Flat, TaskLayout::Sharded] {
+        let (_temp, location) = populate(layout);
+        let tasks_dir: &Path = &location.tasks_dir;
+
+        group.bench_with_input(BenchmarkId::new("scan", layout), &layout, |b, _| {
+            b.iter(|| layout::task_files(tasks_dir).unwrap().len())
+        });
+
+        group.bench_with_input(BenchmarkId::new("list", layout), &layout, |b, _| {
+            let store = FileStore::new(location.clone());
+            b.iter(|| store.list(&TaskFilter::default()).unwrap().len())
+        });
+
+        group.bench_with_input(BenchmarkId::new("read_cold", layout), &layout, |b, _| {
+            b.iter(|| {
+                FileStore::new(location.clone())
+                    .read(TASK_COUNT / 2)
+                    .unwrap()
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("read_warm", layout), &layout, |b, _| {
+            let store = FileStore::new(location.clone());
+            b.iter(|| store.read(TASK_COUNT / 2).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_layouts);
+criterion_main!(benches);
@@ -10,11 +10,24 @@ struct Args {
     /// Use global tasks directory (~/.tasks) instead of project-local
     #[arg(short, long)]
     global: bool,
+
+    /// Only offer query tools (list/get/stats); reject calls that modify tasks
+    #[arg(long)]
+    read_only: bool,
+
+    /// Serve only this project root (repeatable); every tool call then needs a `project` argument
+    #[arg(long, value_name = "PATH", conflicts_with = "global")]
+    allow: Vec<std::path::PathBuf>,
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
 
-    gittask::mcp::run_mcp_server(args.global).await
+    gittask::mcp::run_mcp_server(gittask::mcp::ServerOptions {
+        global: args.global,
+        read_only: args.read_only,
+        allow: args.allow,
+    })
+    .await
 }
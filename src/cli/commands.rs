@@ -1,26 +1,74 @@
 //! CLI command definitions using clap
 
-use crate::models::{Priority, TaskKind, TaskStatus};
-use chrono::NaiveDate;
+use crate::cli::output_template::OutputTemplate;
+use crate::models::{Due, Priority, ReminderOffset, Resolution, TaskKind};
+use crate::storage::matrix::DEFAULT_URGENT_DAYS;
+use crate::storage::review::DEFAULT_STALE_DAYS;
+use crate::storage::{ImportFormat, ProjectColor, TaskLayout, TimeField};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use clap::{Parser, Subcommand};
 
 /// Git-versioned task management using Markdown files
 #[derive(Parser, Debug)]
 #[command(name = "gittask")]
 #[command(author, version, about, long_about = None)]
+#[command(disable_help_subcommand = true)]
 pub struct Cli {
     /// Use global tasks directory (~/.tasks) instead of project-local
     #[arg(short, long, global = true)]
     pub global: bool,
 
+    /// Operate on a registered project by name
+    #[arg(long, global = true, conflicts_with_all = ["global", "dir"])]
+    pub project: Option<String>,
+
+    /// Run as if gittask was started in this directory
+    #[arg(
+        short = 'C',
+        global = true,
+        value_name = "PATH",
+        conflicts_with = "global"
+    )]
+    pub dir: Option<std::path::PathBuf>,
+
+    /// Refuse every change to tasks (also `read_only` under `[storage]` in config.toml)
+    #[arg(long, global = true)]
+    pub read_only: bool,
+
+    /// Fail on task files that can't be parsed instead of skipping them
+    /// (also `strict` under `[storage]` in config.toml)
+    #[arg(long, global = true)]
+    pub strict: bool,
+
+    /// Change nothing; list the files that would be created, modified,
+    /// moved or deleted instead
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
     #[command(subcommand)]
     pub command: Commands,
 }
 
 #[derive(Subcommand, Debug)]
 pub enum Commands {
-    /// Initialize the .tasks directory
-    Init,
+    /// Initialize the .tasks directory with a README, config and git attributes
+    Init {
+        /// Create a nested task scope in the current directory instead of the repo root
+        #[arg(long)]
+        here: bool,
+
+        /// Also add a templates/ directory with an example template
+        #[arg(long)]
+        templates: bool,
+
+        /// Commit the scaffold without asking
+        #[arg(long)]
+        commit: bool,
+
+        /// With --global, link every repository under DIR that has a .tasks directory
+        #[arg(long, value_name = "DIR", conflicts_with_all = ["here", "commit"])]
+        scan: Option<std::path::PathBuf>,
+    },
 
     /// Add a new task
     Add {
@@ -35,17 +83,52 @@ pub enum Commands {
         #[arg(short, long)]
         description: Option<String>,
 
-        /// Priority (low, medium, high, critical)
+        /// Priority (low, medium, high, critical, or p3 to p0)
         #[arg(short, long, value_parser = parse_priority)]
         priority: Option<Priority>,
 
-        /// Due date (YYYY-MM-DD)
-        #[arg(long, value_parser = parse_date)]
-        due: Option<NaiveDate>,
+        /// Due date (YYYY-MM-DD, today, tomorrow, a weekday or an offset like +3d,
+        /// 2w or 1m, where m means months), optionally with a time
+        /// (2026-03-04T17:00, "fri 9:30")
+        #[arg(long, value_parser = parse_due)]
+        due: Option<Due>,
+
+        /// Date work can start (as for --due, without a time); lists hide the
+        /// task until then
+        #[arg(long, value_name = "DATE", value_parser = parse_until)]
+        scheduled: Option<NaiveDate>,
+
+        /// Reminders relative to the due date (comma-separated, e.g. -1d,-2h)
+        #[arg(long, value_delimiter = ',', allow_hyphen_values = true)]
+        remind: Vec<ReminderOffset>,
+
+        /// Estimated effort in hours (e.g. 4, 1.5h or 90m)
+        #[arg(long, value_parser = parse_estimate)]
+        estimate: Option<f64>,
 
         /// Tags (comma-separated)
         #[arg(short, long, value_delimiter = ',')]
         tags: Vec<String>,
+
+        /// External tracker ID (e.g. GH#123, PROJ-42)
+        #[arg(long)]
+        external_id: Option<String>,
+
+        /// Who is responsible for the task (e.g. an email address)
+        #[arg(long)]
+        assignee: Option<String>,
+
+        /// GTD context (e.g. @computer, @errands)
+        #[arg(short, long)]
+        context: Option<String>,
+
+        /// Start from a template in .tasks/templates/ (explicit options win)
+        #[arg(short = 'T', long)]
+        template: Option<String>,
+
+        /// Make it a subtask of this task
+        #[arg(long, value_name = "ID")]
+        parent: Option<u64>,
     },
 
     /// List tasks
@@ -55,20 +138,198 @@ pub enum Commands {
         kind: Option<TaskKind>,
 
         /// Filter by status
-        #[arg(short, long, value_parser = parse_status)]
-        status: Option<TaskStatus>,
+        #[arg(short, long)]
+        status: Option<String>,
 
         /// Filter by priority
         #[arg(short, long, value_parser = parse_priority)]
         priority: Option<Priority>,
 
-        /// Filter by tags (comma-separated)
+        /// Filter by tags (comma-separated; `area/*` matches every tag under area/)
         #[arg(short, long, value_delimiter = ',')]
         tags: Vec<String>,
 
+        /// Filter by GTD context
+        #[arg(short, long)]
+        context: Option<String>,
+
+        /// Group by the tags under a namespace, e.g. `area` for area/auth, area/billing
+        #[arg(long, value_name = "NAMESPACE", conflicts_with_all = ["all_scopes", "by_urgency", "watch"])]
+        group_by: Option<String>,
+
         /// Include archived tasks
         #[arg(short = 'a', long)]
         include_archived: bool,
+
+        /// Only tasks completed on or after this date (YYYY-MM-DD)
+        #[arg(long, value_parser = parse_date)]
+        completed_since: Option<NaiveDate>,
+
+        /// Filter by external tracker ID
+        #[arg(long)]
+        external_id: Option<String>,
+
+        /// Only tasks assigned to this person (e.g. their git user.email)
+        #[arg(long)]
+        assignee: Option<String>,
+
+        /// Only tasks that aren't waiting on an open task
+        #[arg(long)]
+        unblocked: bool,
+
+        /// Only tasks scheduled to start on or before this date
+        #[arg(long, value_name = "DATE", value_parser = parse_until)]
+        scheduled_before: Option<NaiveDate>,
+
+        /// Only tasks scheduled to start on or after this date
+        #[arg(long, value_name = "DATE", value_parser = parse_until)]
+        scheduled_after: Option<NaiveDate>,
+
+        /// Also list tasks scheduled to start after today (hidden by default)
+        #[arg(long)]
+        include_scheduled: bool,
+
+        /// Only open tasks due within `[display] due_soon_days` (default 3) and not yet overdue
+        #[arg(long)]
+        due_soon: bool,
+
+        /// Aggregate every task scope inside the repository
+        #[arg(long)]
+        all_scopes: bool,
+
+        /// Only tasks you created, claimed or are assigned (matched against `git config user.email`)
+        #[arg(long)]
+        mine: bool,
+
+        /// Sort by urgency score (weights from `[urgency]` in config.toml)
+        #[arg(short = 'u', long, conflicts_with_all = ["all_scopes", "watch"])]
+        by_urgency: bool,
+
+        /// Group by status, each in the order set with move-up/move-down
+        #[arg(long, conflicts_with_all = ["by_urgency", "group_by"])]
+        ordered: bool,
+
+        /// Show subtasks indented under their parents
+        #[arg(long, conflicts_with_all = ["all_scopes", "by_urgency", "group_by"])]
+        tree: bool,
+
+        /// Keep running and redraw the list whenever a task file changes
+        #[arg(short, long, conflicts_with = "all_scopes")]
+        watch: bool,
+
+        /// Output format: table, or template (one line per task, see --template)
+        #[arg(long, default_value = "table", value_parser = parse_list_format,
+              conflicts_with_all = ["group_by", "tree", "watch"])]
+        format: ListFormat,
+
+        /// Line printed per task with --format template, e.g. "{{id}}\t{{title}} ({{status}})"
+        #[arg(long, requires = "format", value_parser = parse_output_template)]
+        template: Option<OutputTemplate>,
+
+        /// Exit with status 3 if any task is listed (for CI checks)
+        #[arg(long, conflicts_with = "watch")]
+        fail_if_any: bool,
+    },
+
+    /// Print the number of tasks matching the filters
+    Count {
+        /// Filter by kind
+        #[arg(short, long, value_parser = parse_kind)]
+        kind: Option<TaskKind>,
+
+        /// Filter by status
+        #[arg(short, long)]
+        status: Option<String>,
+
+        /// Filter by priority
+        #[arg(short, long, value_parser = parse_priority)]
+        priority: Option<Priority>,
+
+        /// Filter by tags (comma-separated; `area/*` matches every tag under area/)
+        #[arg(short, long, value_delimiter = ',')]
+        tags: Vec<String>,
+
+        /// Filter by GTD context
+        #[arg(short, long)]
+        context: Option<String>,
+
+        /// Include archived tasks
+        #[arg(short = 'a', long)]
+        include_archived: bool,
+
+        /// Only tasks assigned to this person (e.g. their git user.email)
+        #[arg(long)]
+        assignee: Option<String>,
+
+        /// Only tasks that aren't waiting on an open task
+        #[arg(long)]
+        unblocked: bool,
+
+        /// Also count tasks scheduled to start after today
+        #[arg(long)]
+        include_scheduled: bool,
+
+        /// Exit with status 3 if the count is not zero (for CI checks)
+        #[arg(long)]
+        fail_if_any: bool,
+    },
+
+    /// Show the most urgent open tasks that aren't blocked
+    Next {
+        /// How many tasks to show
+        #[arg(short = 'n', long, default_value_t = 1)]
+        count: usize,
+
+        /// Filter by kind
+        #[arg(short, long, value_parser = parse_kind)]
+        kind: Option<TaskKind>,
+
+        /// Filter by tags (comma-separated)
+        #[arg(short, long, value_delimiter = ',')]
+        tags: Vec<String>,
+
+        /// Filter by GTD context
+        #[arg(short, long)]
+        context: Option<String>,
+    },
+
+    /// Show a random open task
+    Random {
+        /// Filter by kind
+        #[arg(short, long, value_parser = parse_kind)]
+        kind: Option<TaskKind>,
+
+        /// Filter by priority
+        #[arg(short, long, value_parser = parse_priority)]
+        priority: Option<Priority>,
+
+        /// Filter by tags (comma-separated)
+        #[arg(short, long, value_delimiter = ',')]
+        tags: Vec<String>,
+
+        /// Filter by GTD context
+        #[arg(short, long)]
+        context: Option<String>,
+    },
+
+    /// Search task titles and descriptions with a regex (with -g, across
+    /// every registered project)
+    #[command(alias = "search")]
+    Grep {
+        /// Regular expression to search for
+        pattern: String,
+
+        /// Case-insensitive matching
+        #[arg(short, long)]
+        ignore_case: bool,
+
+        /// Only search tasks with this tag (repeatable)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+
+        /// Only search tasks with this status
+        #[arg(short, long)]
+        status: Option<String>,
     },
 
     /// Show task details
@@ -77,20 +338,148 @@ pub enum Commands {
         id: String,
     },
 
+    /// List the tasks whose descriptions refer to a task (#N or project:N)
+    Backlinks {
+        /// Task ID (or project:id for qualified ID)
+        id: String,
+    },
+
+    /// Print the path of a task file, or open it
+    Open {
+        /// Task ID (or project:id for qualified ID)
+        id: String,
+
+        /// Open the file in $VISUAL/$EDITOR
+        #[arg(short, long, conflicts_with = "reveal")]
+        editor: bool,
+
+        /// Reveal the file in the system file manager
+        #[arg(short, long)]
+        reveal: bool,
+    },
+
     /// Mark task(s) as completed
     Complete {
         /// Task ID(s) (or project:id for qualified IDs)
         ids: Vec<String>,
     },
 
+    /// Close tasks with a resolution, recording the current commit
+    Close {
+        /// Task ID(s) (or project:id for qualified IDs)
+        #[arg(required = true)]
+        ids: Vec<String>,
+
+        /// Resolution (done, wontfix, duplicate, invalid)
+        #[arg(short, long, default_value = "done", value_parser = parse_resolution)]
+        reason: Resolution,
+
+        /// Commit the closed tasks' files afterwards, one commit per project
+        #[arg(long)]
+        commit: bool,
+    },
+
+    /// Reopen a completed or archived task, keeping how it was closed in its history
+    Reopen {
+        /// Task ID (or project:id for qualified ID)
+        id: String,
+
+        /// Reopen as in-progress instead of pending
+        #[arg(long)]
+        in_progress: bool,
+    },
+
+    /// Pin a task so it stays at the top of task lists
+    Pin {
+        /// Task ID (or project:id for qualified ID)
+        id: String,
+    },
+
+    /// Unpin a task
+    Unpin {
+        /// Task ID (or project:id for qualified ID)
+        id: String,
+    },
+
+    /// Move a task up in the hand-curated order of its status
+    MoveUp {
+        /// Task ID (or project:id for qualified ID)
+        id: String,
+    },
+
+    /// Move a task down in the hand-curated order of its status
+    MoveDown {
+        /// Task ID (or project:id for qualified ID)
+        id: String,
+    },
+
     /// Change task status
     Status {
         /// Task ID (or project:id for qualified ID)
         id: String,
 
-        /// New status (pending, in-progress, completed, archived)
-        #[arg(value_parser = parse_status)]
-        status: TaskStatus,
+        /// New status (pending, in-progress, completed, archived, or one from `[workflow]`)
+        status: String,
+
+        /// Refuse instead of warning when a WIP limit would be exceeded
+        #[arg(long)]
+        strict: bool,
+    },
+
+    /// Start working on a task: mark it in progress and start the clock
+    Start {
+        /// Task ID (or project:id for qualified ID)
+        id: String,
+
+        /// Also create and check out a `task/<id>-<slug>` branch
+        #[arg(short, long)]
+        branch: bool,
+
+        /// Refuse instead of warning when a WIP limit would be exceeded
+        #[arg(long)]
+        strict: bool,
+    },
+
+    /// Claim a task so others know you are working on it
+    Claim {
+        /// Task ID (or project:id for qualified ID)
+        id: String,
+
+        /// Release the claim instead
+        #[arg(short, long)]
+        release: bool,
+
+        /// Take over or release a claim held by someone else
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Manage the code locations a task refers to
+    Ref {
+        #[command(subcommand)]
+        action: RefAction,
+    },
+
+    /// Hand a task over to another registered project as a linked copy
+    Delegate {
+        /// Task ID (or project:id for qualified ID)
+        id: String,
+
+        /// Project to create the copy in (name or alias)
+        #[arg(value_name = "PROJECT")]
+        target: String,
+    },
+
+    /// Manage and export dependencies between tasks
+    Deps {
+        #[command(subcommand)]
+        action: DepsAction,
+    },
+
+    /// Stop the running work session on a task
+    Stop {
+        /// Task ID (or project:id for qualified ID)
+        id: String,
     },
 
     /// Update task properties
@@ -102,21 +491,95 @@ pub enum Commands {
         #[arg(long)]
         title: Option<String>,
 
-        /// New description
+        /// New description (replaces existing)
         #[arg(short, long)]
         description: Option<String>,
 
+        /// Append text to the existing description
+        #[arg(long, conflicts_with = "description")]
+        append_description: Option<String>,
+
+        /// Prepend text to the existing description
+        #[arg(long, conflicts_with = "description")]
+        prepend_description: Option<String>,
+
         /// New priority
         #[arg(short, long, value_parser = parse_priority)]
         priority: Option<Priority>,
 
-        /// New due date (YYYY-MM-DD)
-        #[arg(long, value_parser = parse_date)]
-        due: Option<NaiveDate>,
+        /// New due date (as for add, or "none" to clear)
+        #[arg(long, value_parser = parse_clearable_due)]
+        due: Option<Clearable<Due>>,
+
+        /// New start date (as for add, or "none" to clear)
+        #[arg(long, value_name = "DATE", value_parser = parse_clearable_scheduled)]
+        scheduled: Option<Clearable<NaiveDate>>,
+
+        /// New reminders (comma-separated, replaces existing)
+        #[arg(long, value_delimiter = ',', allow_hyphen_values = true)]
+        remind: Option<Vec<ReminderOffset>>,
+
+        /// Remove all reminders
+        #[arg(long, conflicts_with = "remind")]
+        clear_reminders: bool,
+
+        /// New estimate in hours (as for add, or "none" to clear)
+        #[arg(long, value_parser = parse_clearable_estimate)]
+        estimate: Option<Clearable<f64>>,
 
         /// New tags (comma-separated, replaces existing)
         #[arg(short, long, value_delimiter = ',')]
         tags: Option<Vec<String>>,
+
+        /// Remove all tags
+        #[arg(long, conflicts_with = "tags")]
+        clear_tags: bool,
+
+        /// Add a tag (repeatable)
+        #[arg(long = "add-tag")]
+        add_tags: Vec<String>,
+
+        /// Remove a tag (repeatable)
+        #[arg(long = "remove-tag")]
+        remove_tags: Vec<String>,
+
+        /// New external tracker ID (or "none" to clear)
+        #[arg(long, value_parser = parse_clearable_string)]
+        external_id: Option<Clearable<String>>,
+
+        /// New assignee (or "none" to clear)
+        #[arg(long, value_parser = parse_clearable_string)]
+        assignee: Option<Clearable<String>>,
+
+        /// New GTD context (or "none" to clear)
+        #[arg(short, long, value_parser = parse_clearable_string)]
+        context: Option<Clearable<String>>,
+
+        /// Make it a subtask of this task (or "none" to make it top-level)
+        #[arg(long, value_name = "ID", value_parser = parse_clearable_id)]
+        parent: Option<Clearable<u64>>,
+    },
+
+    /// Add a comment to a task's discussion
+    Comment {
+        /// Task ID (or project:id for qualified ID)
+        id: String,
+
+        /// Comment text (Markdown)
+        text: String,
+
+        /// Who wrote it (default: git user.email or user.name)
+        #[arg(long)]
+        author: Option<String>,
+    },
+
+    /// Check or uncheck an item of a task's `- [ ]` checklist
+    Check {
+        /// Task ID (or project:id for qualified ID)
+        id: String,
+
+        /// Item number (from 1) or a unique piece of its text
+        item: String,
     },
 
     /// Delete a task
@@ -129,13 +592,323 @@ pub enum Commands {
         force: bool,
     },
 
+    /// Show recently updated tasks and what changed
+    Log {
+        /// Time window: relative (e.g. 30m, 12h, 7d, 2w; m means minutes) or a
+        /// date (YYYY-MM-DD)
+        #[arg(long, default_value = "7d", value_parser = parse_since)]
+        since: DateTime<Utc>,
+    },
+
+    /// Show the commits that changed a task, following title renames
+    History {
+        /// Task ID (or project:id for qualified ID)
+        id: String,
+    },
+
+    /// Commit changes under .tasks/ on their own, with a generated message
+    Commit {
+        /// Show the message and files without committing (like --dry-run)
+        #[arg(short = 'n')]
+        preview: bool,
+    },
+
+    /// Three-way merge a task file (run by git as the `gittask` merge driver)
+    ///
+    /// The result is written to OURS; exits with an error if the description
+    /// still has conflict markers.
+    MergeFile {
+        /// Common ancestor version (%O)
+        base: std::path::PathBuf,
+
+        /// Current branch's version, overwritten with the result (%A)
+        ours: std::path::PathBuf,
+
+        /// Other branch's version (%B)
+        theirs: std::path::PathBuf,
+    },
+
+    /// Manage the git hooks that complete tasks when their branch is merged
+    Hooks {
+        #[command(subcommand)]
+        action: HooksAction,
+    },
+
+    /// Print markdown release notes for tasks closed between two git refs
+    ReleaseNotes {
+        /// Previous release ref (tag, branch or commit); omit to start from the beginning
+        from: Option<String>,
+
+        /// Ref the release is cut from
+        #[arg(long, default_value = "HEAD")]
+        to: String,
+
+        /// Heading for the notes (defaults to the `to` ref)
+        #[arg(long)]
+        title: Option<String>,
+    },
+
+    /// Export tasks as JSON
+    Export {
+        /// Write to a file instead of stdout, with a checksum manifest next to it
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+
+        /// Filter by kind
+        #[arg(short, long, value_parser = parse_kind)]
+        kind: Option<TaskKind>,
+
+        /// Filter by status
+        #[arg(short, long)]
+        status: Option<String>,
+
+        /// Filter by tags (comma-separated)
+        #[arg(short, long, value_delimiter = ',')]
+        tags: Vec<String>,
+
+        /// Include archived tasks
+        #[arg(short = 'a', long)]
+        include_archived: bool,
+
+        /// Start of the time window: relative (e.g. 30m, 12h, 14d, 2w; m means
+        /// minutes) or a date (YYYY-MM-DD)
+        #[arg(long, value_parser = parse_since)]
+        since: Option<DateTime<Utc>>,
+
+        /// End of the time window (exclusive): relative or a date
+        #[arg(long, value_parser = parse_since)]
+        until: Option<DateTime<Utc>>,
+
+        /// Timestamp the window applies to (created, updated, completed, any)
+        #[arg(long, default_value = "any", value_parser = parse_time_field)]
+        by: TimeField,
+    },
+
+    /// Check an export file against its checksum manifest
+    VerifyExport {
+        /// Export file written with `export --output`
+        file: std::path::PathBuf,
+
+        /// Manifest to check against (default: <file>.manifest.json)
+        #[arg(long)]
+        manifest: Option<std::path::PathBuf>,
+    },
+
+    /// Import tasks from a file or stdin
+    Import {
+        /// Input file (gzip-compressed if it ends in .gz), or "-" for stdin
+        #[arg(default_value = "-")]
+        input: String,
+
+        /// Input format
+        #[arg(short, long, default_value = "json", value_parser = parse_import_format)]
+        format: ImportFormat,
+
+        /// Validate and report without creating tasks (like --dry-run)
+        #[arg(short = 'n')]
+        preview: bool,
+
+        /// Update tasks whose external ID (or fingerprint) matches a record
+        /// instead of skipping the record
+        #[arg(short, long)]
+        update: bool,
+    },
+
+    /// Create and update tasks from the project's GitHub issues (needs `gh`)
+    Sync {
+        /// Repository as owner/name (defaults to the one `gh` picks from the remote)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Keep syncing at this interval, e.g. 15m or 1h
+        #[arg(long, value_parser = parse_interval)]
+        interval: Option<Duration>,
+
+        /// Run the interval loop in the background, logging to .tasks/.sync.log
+        #[arg(long, requires = "interval")]
+        daemon: bool,
+
+        /// Stop the running sync loop instead
+        #[arg(long, conflicts_with_all = ["repo", "interval", "daemon"])]
+        stop: bool,
+    },
+
+    /// Create todos from TODO/FIXME/XXX/HACK comments in source files
+    Scan {
+        /// File or directory to scan
+        #[arg(default_value = ".")]
+        path: std::path::PathBuf,
+
+        /// Report what would be created without creating tasks (like --dry-run)
+        #[arg(short = 'n')]
+        preview: bool,
+    },
+
+    /// Build or refresh the on-disk task index
+    Index {
+        /// Remove the index instead
+        #[arg(long)]
+        remove: bool,
+    },
+
+    /// Check that every task file parses, IDs are unique, file names match
+    /// and dependencies exist, reporting file:line for each problem
+    /// (run by the pre-push hook)
+    Validate,
+
+    /// Check the tasks directory for duplicate IDs and unreadable files
+    Doctor {
+        /// Renumber tasks that share an ID (the oldest keeps it)
+        #[arg(long)]
+        fix: bool,
+
+        /// Move task files that can't be parsed into .tasks/.broken/
+        #[arg(long)]
+        quarantine: bool,
+    },
+
+    /// Normalize task files: frontmatter key order, date formats, tag case
+    /// and trailing whitespace (rules in `[fmt]` of config.toml)
+    Fmt {
+        /// Only list the files that aren't formatted, failing if there are any
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// Show or change how task files are arranged on disk
+    Layout {
+        /// Layout to migrate to (flat, sharded)
+        #[arg(value_parser = parse_layout)]
+        layout: Option<TaskLayout>,
+    },
+
     /// Show task statistics
-    Stats,
+    Stats {
+        /// Output format: table, or json with per-week and per-priority metrics
+        #[arg(long, default_value = "table", value_parser = parse_output_format)]
+        format: OutputFormat,
+    },
+
+    /// Archive old completed tasks and move old archived ones into a snapshot
+    Prune {
+        /// Archive tasks completed more than this many days ago (overrides `[retention]`)
+        #[arg(long, value_name = "DAYS")]
+        archive_after: Option<u32>,
+
+        /// Snapshot and remove archived tasks older than this many days (overrides `[retention]`)
+        #[arg(long, value_name = "DAYS")]
+        purge_after: Option<u32>,
+
+        /// Show what would change without changing anything (like --dry-run)
+        #[arg(short = 'n')]
+        preview: bool,
+    },
+
+    /// Step through overdue and stale tasks, deciding what to do with each
+    ///
+    /// Each task asks for a key followed by Enter: k(eep), s(nooze),
+    /// a(rchive), b(ump) or q(uit); Enter alone skips the task.
+    Review {
+        /// Days without changes or review before a task counts as stale
+        #[arg(long, default_value_t = DEFAULT_STALE_DAYS)]
+        stale_days: i64,
+    },
+
+    /// List reminders firing soon (across registered projects with --global)
+    Reminders {
+        /// How many days ahead to look
+        #[arg(long, default_value_t = 7)]
+        days: i64,
+    },
+
+    /// Compare estimated work due by a date with the `[capacity]` weekly hours
+    Plan {
+        /// End of the period: YYYY-MM-DD, a weekday, or e.g. +2w
+        #[arg(long, value_parser = parse_until)]
+        until: NaiveDate,
+
+        /// Break the totals down per assignee
+        #[arg(long)]
+        by_assignee: bool,
+    },
+
+    /// Sort open tasks into urgent/important quadrants (Eisenhower matrix)
+    Matrix {
+        /// Due within this many days counts as urgent
+        #[arg(long, default_value_t = DEFAULT_URGENT_DAYS)]
+        within: i64,
+    },
+
+    /// Show tasks in kanban columns, ordered by `[workflow] columns`
+    Board,
+
+    /// Render matching tasks as a checklist in a Markdown file (e.g. a README)
+    ///
+    /// The checklist goes between markers under the section heading and is
+    /// replaced on every run. Without a file, refreshes every `[[embed]]`
+    /// entry in config.toml.
+    Embed {
+        /// Markdown file to update
+        file: Option<std::path::PathBuf>,
+
+        /// Heading to put the checklist under
+        #[arg(long, default_value = "## Tasks")]
+        section: String,
+
+        /// Which tasks to list, e.g. "status:pending kind:task tag:backend"
+        #[arg(long, default_value = "")]
+        filter: String,
+    },
+
+    /// Serve a read-only JSON API (and optionally a dashboard) on localhost
+    Serve {
+        /// Port to listen on
+        #[arg(short, long, default_value_t = 7878)]
+        port: u16,
+
+        /// Also serve the web dashboard at /
+        #[arg(long)]
+        ui: bool,
+    },
+
+    /// Summarize open tasks by GTD context
+    Contexts,
+
+    /// Count open tasks per tag
+    Tags {
+        #[command(subcommand)]
+        action: Option<TagsAction>,
+    },
+
+    /// List task templates
+    Templates,
+
+    /// Show help with examples for a command, or a topic (ids, filters, query)
+    Help {
+        /// Command or topic name
+        topic: Option<String>,
+    },
+
+    /// Print the gittask(1) man page, or write one page per command to a directory
+    Man {
+        /// Write gittask.1 and gittask-<command>.1 files here instead
+        #[arg(long, value_name = "DIR")]
+        out_dir: Option<std::path::PathBuf>,
+    },
 
     /// Register a project for global aggregation
     Link {
         /// Project path (defaults to current directory)
         path: Option<std::path::PathBuf>,
+
+        /// Also refer to the project by this name (e.g. in qualified IDs)
+        #[arg(long)]
+        alias: Option<String>,
+
+        /// Color of the project in aggregated tables: red, green, yellow,
+        /// blue, magenta, cyan or gray (or "none" to clear)
+        #[arg(long, value_parser = parse_clearable_color)]
+        color: Option<Clearable<ProjectColor>>,
     },
 
     /// Unregister a project from global aggregation
@@ -145,14 +918,213 @@ pub enum Commands {
     },
 
     /// List registered projects
-    Projects,
+    Projects {
+        /// Print only the names and aliases projects can be referred to by,
+        /// one per line (for shell completion)
+        #[arg(long)]
+        names: bool,
+    },
+}
+
+/// How a command prints its results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "table" => Ok(OutputFormat::Table),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!("Unknown format: {} (expected table or json)", s)),
+        }
+    }
+}
+
+/// How `list` prints tasks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ListFormat {
+    #[default]
+    Table,
+    /// One line per task from `--template`
+    Template,
+}
+
+impl std::str::FromStr for ListFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "table" => Ok(ListFormat::Table),
+            "template" => Ok(ListFormat::Template),
+            _ => Err(format!(
+                "Unknown format: {} (expected table or template)",
+                s
+            )),
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum TagsAction {
+    /// Show tags as a tree of their `/`-separated namespaces
+    Tree,
+}
+
+/// How `deps graph` prints the dependency graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GraphFormat {
+    /// One `blocker -> task` line per dependency
+    #[default]
+    Text,
+    /// Graphviz DOT
+    Dot,
+}
+
+impl std::str::FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(GraphFormat::Text),
+            "dot" => Ok(GraphFormat::Dot),
+            _ => Err(format!("Unknown format: {} (expected text or dot)", s)),
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DepsAction {
+    /// Make a task wait on another; rejected if it would create a cycle
+    Add {
+        /// Task ID (or project:id for qualified ID)
+        id: String,
+
+        /// ID of the task it waits on, in the same project
+        blocker: u64,
+    },
+
+    /// Stop a task waiting on another
+    Remove {
+        /// Task ID (or project:id for qualified ID)
+        id: String,
+
+        /// ID of the task it waits on
+        blocker: u64,
+    },
+
+    /// Print the dependency graph
+    Graph {
+        /// Output format: text or dot (for Graphviz)
+        #[arg(long, default_value = "text", value_parser = parse_graph_format)]
+        format: GraphFormat,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum RefAction {
+    /// Point a task at a file or line, e.g. src/auth/token.rs:88
+    Add {
+        /// Task ID (or project:id for qualified ID)
+        id: String,
+
+        /// `path` or `path:line`, relative to the project root
+        reference: String,
+    },
+
+    /// Remove a reference from a task
+    Remove {
+        /// Task ID (or project:id for qualified ID)
+        id: String,
+
+        /// Reference exactly as listed on the task
+        reference: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum HooksAction {
+    /// Install the post-merge, post-rewrite and pre-push hooks
+    Install {
+        /// Replace existing hooks not installed by gittask
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Remove the hooks installed by gittask
+    Uninstall,
+
+    /// Run a hook (called by the installed hook scripts)
+    #[command(hide = true)]
+    Run {
+        /// Hook name
+        hook: String,
+
+        /// Arguments git passed to the hook
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+}
+
+/// An update value that is either set or explicitly cleared
+#[derive(Debug, Clone, PartialEq)]
+pub enum Clearable<T> {
+    Set(T),
+    Clear,
+}
+
+impl<T> Clearable<T> {
+    /// Convert into the optional value to store
+    pub fn into_option(self) -> Option<T> {
+        match self {
+            Clearable::Set(v) => Some(v),
+            Clearable::Clear => None,
+        }
+    }
 }
 
 fn parse_kind(s: &str) -> Result<TaskKind, String> {
     s.parse()
 }
 
-fn parse_status(s: &str) -> Result<TaskStatus, String> {
+fn parse_resolution(s: &str) -> Result<Resolution, String> {
+    s.parse()
+}
+
+fn parse_import_format(s: &str) -> Result<ImportFormat, String> {
+    s.parse()
+}
+
+fn parse_output_format(s: &str) -> Result<OutputFormat, String> {
+    s.parse()
+}
+
+fn parse_interval(s: &str) -> Result<Duration, String> {
+    crate::storage::parse_interval(s)
+}
+
+fn parse_list_format(s: &str) -> Result<ListFormat, String> {
+    s.parse()
+}
+
+fn parse_output_template(s: &str) -> Result<OutputTemplate, String> {
+    s.parse()
+}
+
+fn parse_graph_format(s: &str) -> Result<GraphFormat, String> {
+    s.parse()
+}
+
+fn parse_layout(s: &str) -> Result<TaskLayout, String> {
+    s.parse()
+}
+
+fn parse_time_field(s: &str) -> Result<TimeField, String> {
     s.parse()
 }
 
@@ -163,3 +1135,105 @@ fn parse_priority(s: &str) -> Result<Priority, String> {
 fn parse_date(s: &str) -> Result<NaiveDate, String> {
     NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|e| format!("Invalid date: {}", e))
 }
+
+fn parse_until(s: &str) -> Result<NaiveDate, String> {
+    crate::models::parse_due_date(s, crate::models::today())
+}
+
+fn parse_due(s: &str) -> Result<Due, String> {
+    crate::models::parse_due_now(s)
+}
+
+/// Parse a point in time as a relative offset into the past (`30m`, `12h`, `7d`, `2w`) or a date
+///
+/// Unlike due dates, where `m` is calendar months, `m` here is minutes.
+fn parse_since(s: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc());
+    }
+
+    let split = s.char_indices().last().map(|(i, _)| i).unwrap_or(0);
+    let (amount, unit) = s.split_at(split);
+    let amount: i64 = amount
+        .parse()
+        .map_err(|_| format!("Invalid time: {} (expected e.g. 7d or YYYY-MM-DD)", s))?;
+
+    let offset = match unit {
+        "m" => Duration::minutes(amount),
+        "h" => Duration::hours(amount),
+        "d" => Duration::days(amount),
+        "w" => Duration::weeks(amount),
+        _ => return Err(format!("Invalid time unit in {} (use m, h, d or w)", s)),
+    };
+
+    Ok(Utc::now() - offset)
+}
+
+fn parse_clearable_string(s: &str) -> Result<Clearable<String>, String> {
+    if s.eq_ignore_ascii_case("none") {
+        Ok(Clearable::Clear)
+    } else {
+        Ok(Clearable::Set(s.to_string()))
+    }
+}
+
+fn parse_clearable_id(s: &str) -> Result<Clearable<u64>, String> {
+    if s.eq_ignore_ascii_case("none") {
+        Ok(Clearable::Clear)
+    } else {
+        s.trim_start_matches('#')
+            .parse()
+            .map(Clearable::Set)
+            .map_err(|_| format!("Invalid task ID: {} (expected a number or none)", s))
+    }
+}
+
+fn parse_clearable_color(s: &str) -> Result<Clearable<ProjectColor>, String> {
+    if s.eq_ignore_ascii_case("none") {
+        Ok(Clearable::Clear)
+    } else {
+        s.parse().map(Clearable::Set)
+    }
+}
+
+/// Parse an effort estimate in hours: `4`, `1.5h` or `90m`
+fn parse_estimate(s: &str) -> Result<f64, String> {
+    let s = s.trim();
+    let hours = match s.strip_suffix('m') {
+        Some(minutes) => minutes.parse::<f64>().map(|m| m / 60.0),
+        None => s.strip_suffix('h').unwrap_or(s).parse::<f64>(),
+    };
+    hours
+        .ok()
+        .filter(|h| h.is_finite() && *h >= 0.0)
+        .ok_or_else(|| {
+            format!(
+                "Invalid estimate: {} (expected hours, e.g. 4, 1.5h or 90m)",
+                s
+            )
+        })
+}
+
+fn parse_clearable_estimate(s: &str) -> Result<Clearable<f64>, String> {
+    if s.eq_ignore_ascii_case("none") {
+        Ok(Clearable::Clear)
+    } else {
+        parse_estimate(s).map(Clearable::Set)
+    }
+}
+
+fn parse_clearable_scheduled(s: &str) -> Result<Clearable<NaiveDate>, String> {
+    if s.eq_ignore_ascii_case("none") {
+        Ok(Clearable::Clear)
+    } else {
+        parse_until(s).map(Clearable::Set)
+    }
+}
+
+fn parse_clearable_due(s: &str) -> Result<Clearable<Due>, String> {
+    if s.eq_ignore_ascii_case("none") {
+        Ok(Clearable::Clear)
+    } else {
+        parse_due(s).map(Clearable::Set)
+    }
+}
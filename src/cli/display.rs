@@ -1,12 +1,38 @@
 //! Display formatting for CLI output
 
-use crate::models::Task;
-use crate::storage::{AggregatedTask, ProjectStatus, TaskStats};
+use crate::cli::output_template::OutputTemplate;
+use crate::models::{Resolution, Task, TaskStatus};
+use crate::storage::config::DisplayConfig;
+use crate::storage::{
+    AGE_BUCKETS, ActivityEntry, AggregatedMatch, AggregatedTask, Backlink, CapacityPlan,
+    EisenhowerMatrix, GrepMatch, HistoryEntry, PlanLine, PlannedChange, ProjectColor,
+    ProjectStatus, Quadrant, ResolvedLink, TagNode, TaskStats, TaskTemplate, UpcomingReminder,
+    ValidationError,
+};
+use std::io::IsTerminal;
+use std::sync::OnceLock;
 use tabled::{
     Table, Tabled,
-    settings::{Alignment, Modify, Style, object::Columns},
+    settings::{
+        Alignment, Color, Modify, Style,
+        object::{Cell, Columns},
+    },
 };
 
+/// Date and time formatting for everything printed, set once at startup
+static DATES: OnceLock<DisplayConfig> = OnceLock::new();
+
+/// Use the project's `[display]` settings for dates and times
+///
+/// Only the first call has an effect; without one, defaults apply.
+pub fn set_date_display(config: DisplayConfig) {
+    let _ = DATES.set(config);
+}
+
+fn dates() -> &'static DisplayConfig {
+    DATES.get_or_init(DisplayConfig::default)
+}
+
 /// Task row for table display
 #[derive(Tabled)]
 struct TaskRow {
@@ -29,10 +55,10 @@ impl From<&Task> for TaskRow {
         TaskRow {
             id: format!("{}", task.id),
             kind: task.kind.to_string(),
-            title: truncate(&task.title, 40),
-            status: task.status.to_string(),
+            title: pin_marker(task) + &truncate(&task.title, 40) + &checklist_marker(task),
+            status: status_label(task),
             priority: task.priority.to_string(),
-            due: task.due.map(|d| d.to_string()).unwrap_or_default(),
+            due: due_label(task),
         }
     }
 }
@@ -53,6 +79,85 @@ pub fn display_task_list(tasks: &[Task]) {
     println!("{}", table);
 }
 
+/// Print one line per task from an output template, with each task's project name
+pub fn display_templated<'a>(
+    template: &OutputTemplate,
+    tasks: impl IntoIterator<Item = (&'a str, &'a Task)>,
+) {
+    for (project, task) in tasks {
+        println!("{}", template.render(task, Some(project)));
+    }
+}
+
+/// Display tasks as a table with subtasks indented under their parents
+pub fn display_task_tree(tree: &[(usize, Task)]) {
+    if tree.is_empty() {
+        log::info!("No tasks found.");
+        return;
+    }
+
+    let rows: Vec<TaskRow> = tree
+        .iter()
+        .map(|(depth, task)| {
+            let mut row = TaskRow::from(task);
+            if *depth > 0 {
+                row.title = format!("{}└ {}", "  ".repeat(depth - 1), row.title);
+            }
+            row
+        })
+        .collect();
+    let table = Table::new(rows)
+        .with(Style::rounded())
+        .with(Modify::new(Columns::single(0)).with(Alignment::right()))
+        .to_string();
+
+    println!("{}", table);
+}
+
+/// Ranked task row for table display (includes urgency column)
+#[derive(Tabled)]
+struct RankedTaskRow {
+    #[tabled(rename = "ID")]
+    id: String,
+    #[tabled(rename = "Title")]
+    title: String,
+    #[tabled(rename = "Status")]
+    status: String,
+    #[tabled(rename = "Priority")]
+    priority: String,
+    #[tabled(rename = "Due")]
+    due: String,
+    #[tabled(rename = "Urgency")]
+    urgency: String,
+}
+
+/// Display tasks with their urgency scores, in the order given
+pub fn display_ranked_task_list(tasks: &[(Task, f64)]) {
+    if tasks.is_empty() {
+        log::info!("No tasks found.");
+        return;
+    }
+
+    let rows: Vec<RankedTaskRow> = tasks
+        .iter()
+        .map(|(task, urgency)| RankedTaskRow {
+            id: task.id.to_string(),
+            title: pin_marker(task) + &truncate(&task.title, 40) + &checklist_marker(task),
+            status: status_label(task),
+            priority: task.priority.to_string(),
+            due: due_label(task),
+            urgency: format!("{:.1}", urgency),
+        })
+        .collect();
+    let table = Table::new(rows)
+        .with(Style::rounded())
+        .with(Modify::new(Columns::single(0)).with(Alignment::right()))
+        .with(Modify::new(Columns::single(5)).with(Alignment::right()))
+        .to_string();
+
+    println!("{}", table);
+}
+
 /// Aggregated task row for table display (includes project column)
 #[derive(Tabled)]
 struct AggregatedTaskRow {
@@ -78,10 +183,16 @@ impl From<&AggregatedTask> for AggregatedTaskRow {
             id: agg.qualified_id(),
             project: agg.project.clone(),
             kind: agg.task.kind.to_string(),
-            title: truncate(&agg.task.title, 35),
-            status: agg.task.status.to_string(),
+            title: pin_marker(&agg.task)
+                + &truncate(&agg.task.title, 35)
+                + &checklist_marker(&agg.task),
+            status: status_label(&agg.task),
             priority: agg.task.priority.to_string(),
-            due: agg.task.due.map(|d| d.to_string()).unwrap_or_default(),
+            due: agg
+                .task
+                .due
+                .map(|d| dates().format_due(&d))
+                .unwrap_or_default(),
         }
     }
 }
@@ -94,14 +205,49 @@ pub fn display_aggregated_task_list(tasks: &[AggregatedTask]) {
     }
 
     let rows: Vec<AggregatedTaskRow> = tasks.iter().map(AggregatedTaskRow::from).collect();
-    let table = Table::new(rows)
+    let mut table = Table::new(rows);
+    table
         .with(Style::rounded())
-        .with(Modify::new(Columns::single(0)).with(Alignment::right()))
-        .to_string();
+        .with(Modify::new(Columns::single(0)).with(Alignment::right()));
+    tint_column(&mut table, 1, tasks.iter().map(|t| t.color));
 
     println!("{}", table);
 }
 
+/// Whether to color output: stdout is a terminal and `NO_COLOR` is unset
+fn use_color() -> bool {
+    std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+/// Terminal color of a project color
+fn project_color(color: ProjectColor) -> Color {
+    match color {
+        ProjectColor::Red => Color::FG_RED,
+        ProjectColor::Green => Color::FG_GREEN,
+        ProjectColor::Yellow => Color::FG_YELLOW,
+        ProjectColor::Blue => Color::FG_BLUE,
+        ProjectColor::Magenta => Color::FG_MAGENTA,
+        ProjectColor::Cyan => Color::FG_CYAN,
+        ProjectColor::Gray => Color::FG_BRIGHT_BLACK,
+    }
+}
+
+/// Color the cells of `column` below the header, one color per row
+fn tint_column(
+    table: &mut Table,
+    column: usize,
+    colors: impl Iterator<Item = Option<ProjectColor>>,
+) {
+    if !use_color() {
+        return;
+    }
+    for (row, color) in colors.enumerate() {
+        if let Some(color) = color {
+            table.with(Modify::new(Cell::new(row + 1, column)).with(project_color(color)));
+        }
+    }
+}
+
 /// Display detailed task information
 pub fn display_task_detail(task: &Task) {
     println!("ID:       {}", task.id);
@@ -110,26 +256,445 @@ pub fn display_task_detail(task: &Task) {
     println!("Status:   {}", task.status);
     println!("Priority: {}", task.priority);
 
+    if task.pinned {
+        println!("Pinned:   yes");
+    }
+
     if !task.tags.is_empty() {
         println!("Tags:     {}", task.tags.join(", "));
     }
 
+    if let Some(ref context) = task.context {
+        println!("Context:  {}", context);
+    }
+
     if let Some(due) = task.due {
-        println!("Due:      {}", due);
+        println!("Due:      {}", dates().format_due(&due));
+    }
+
+    if let Some(scheduled) = task.scheduled {
+        println!("Scheduled: {}", dates().format_date(scheduled));
+    }
+
+    if !task.remind.is_empty() {
+        let times: Vec<String> = task
+            .reminder_times()
+            .iter()
+            .map(|(at, offset)| format!("{} ({})", dates().format_timestamp(*at), offset))
+            .collect();
+        let remind: Vec<String> = task.remind.iter().map(|r| r.to_string()).collect();
+        if times.is_empty() {
+            println!("Remind:   {} (no due date)", remind.join(", "));
+        } else {
+            println!("Remind:   {}", times.join(", "));
+        }
+    }
+
+    if let Some(estimate) = task.estimate {
+        println!("Estimate: {}h", estimate);
+    }
+
+    let checklist = task.checklist();
+    if let Some(progress) = checklist.progress() {
+        println!(
+            "Checklist: {}/{} ({}%)",
+            checklist.done(),
+            checklist.items.len(),
+            progress
+        );
+    }
+
+    if let Some(ref external_id) = task.external_id {
+        println!("External: {}", external_id);
+    }
+
+    if let Some(ref source) = task.delegated_from {
+        println!("Delegated from: {}", source);
     }
 
-    println!("Created:  {}", task.created.format("%Y-%m-%d %H:%M:%S"));
-    println!("Updated:  {}", task.updated.format("%Y-%m-%d %H:%M:%S"));
+    if let Some(ref assignee) = task.assignee {
+        println!("Assignee: {}", assignee);
+    }
+
+    if let Some(ref claimant) = task.claimed_by {
+        match task.claimed_at {
+            Some(at) => println!(
+                "Claimed:  {} (since {})",
+                claimant,
+                dates().format_timestamp(at)
+            ),
+            None => println!("Claimed:  {}", claimant),
+        }
+    }
+
+    if let Some(parent) = task.parent {
+        println!("Parent:   #{}", parent);
+    }
+
+    if !task.blocked_by.is_empty() {
+        let ids: Vec<String> = task
+            .blocked_by
+            .iter()
+            .map(|id| format!("#{}", id))
+            .collect();
+        println!("Blocked by: {}", ids.join(", "));
+    }
+
+    if let Some(ref branch) = task.branch {
+        println!("Branch:   {}", branch);
+    }
+
+    if !task.refs.is_empty() {
+        println!("Refs:     {}", task.refs.join(", "));
+    }
+
+    if !task.time_entries.is_empty() {
+        let spent = format_duration(task.time_spent(chrono::Utc::now()));
+        if task.active_session().is_some() {
+            println!("Time:     {} (running)", spent);
+        } else {
+            println!("Time:     {}", spent);
+        }
+    }
+
+    println!("Created:  {}", dates().format_timestamp(task.created));
+    println!("Updated:  {}", dates().format_timestamp(task.updated));
+
+    if let Some(reviewed) = task.last_reviewed {
+        println!("Reviewed: {}", dates().format_timestamp(reviewed));
+    }
+
+    if let Some(completed) = task.completed {
+        println!("Completed: {}", dates().format_timestamp(completed));
+    }
 
     if let Some(ref commit) = task.closed_commit {
         println!("Closed:   {}", commit);
     }
 
+    if let Some(resolution) = task.resolution {
+        println!("Resolution: {}", resolution);
+    }
+
+    for reopening in &task.reopened {
+        let mut was = reopening.status.to_string();
+        if let Some(resolution) = reopening.resolution {
+            was.push_str(&format!(" as {}", resolution));
+        }
+        if let Some(ref commit) = reopening.closed_commit {
+            was.push_str(&format!(" in {}", commit));
+        }
+        println!(
+            "Reopened: {} (was {})",
+            dates().format_timestamp(reopening.at),
+            was
+        );
+    }
+
     if !task.description.is_empty() {
         println!();
         println!("Description:");
         println!("{}", task.description);
     }
+
+    if !task.comments.is_empty() {
+        println!();
+        println!("Comments:");
+        for comment in &task.comments {
+            match &comment.author {
+                Some(author) => {
+                    println!("  {} {}", dates().format_timestamp(comment.at), author)
+                }
+                None => println!("  {}", dates().format_timestamp(comment.at)),
+            }
+            for line in comment.text.lines() {
+                println!("    {}", line);
+            }
+        }
+    }
+}
+
+/// Display the file changes a `--dry-run` held back
+///
+/// Paths inside the current directory are shown relative to it.
+pub fn display_planned_changes(changes: &[PlannedChange]) {
+    if changes.is_empty() {
+        log::info!("Dry run: no files would change.");
+        return;
+    }
+
+    let cwd = std::env::current_dir().unwrap_or_default();
+    let relative = |path: &std::path::Path| path.strip_prefix(&cwd).unwrap_or(path).to_path_buf();
+    println!();
+    println!("Dry run, nothing was written. Would:");
+    for change in changes {
+        let shown = match change {
+            PlannedChange::Create(path) => PlannedChange::Create(relative(path)),
+            PlannedChange::Modify(path) => PlannedChange::Modify(relative(path)),
+            PlannedChange::Delete(path) => PlannedChange::Delete(relative(path)),
+            PlannedChange::Move { from, to } => PlannedChange::Move {
+                from: relative(from),
+                to: relative(to),
+            },
+        };
+        println!("  {}", shown);
+    }
+}
+
+/// Display the tasks a description refers to, below `display_task_detail`
+pub fn display_task_links(links: &[ResolvedLink]) {
+    if links.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("References:");
+    for link in links {
+        match &link.task {
+            Some(task) => println!("  {}  {} ({})", link.link, task.title, status_label(task)),
+            None => println!("  {}  (not found)", link.link),
+        }
+    }
+}
+
+/// List a task's subtasks under the task's details
+pub fn display_subtasks(children: &[Task]) {
+    if children.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("Subtasks:");
+    for child in children {
+        println!("  #{}  {} ({})", child.id, child.title, status_label(child));
+    }
+}
+
+/// Display the tasks referring to one task
+pub fn display_backlinks(backlinks: &[Backlink]) {
+    if backlinks.is_empty() {
+        log::info!("No tasks refer to this one.");
+        return;
+    }
+
+    for backlink in backlinks {
+        println!(
+            "{}  {} ({})",
+            backlink.display_id(),
+            backlink.task.title,
+            status_label(&backlink.task)
+        );
+    }
+}
+
+/// Display grep matches in `git grep` style (`#id title:line:text`)
+pub fn display_grep_matches(matches: &[GrepMatch]) {
+    if matches.is_empty() {
+        log::info!("No matches found.");
+        return;
+    }
+
+    for m in matches {
+        match m.line_number {
+            Some(n) => println!("#{} {}:{}:{}", m.task_id, m.title, n, m.line),
+            None => println!("#{} {}:title", m.task_id, m.title),
+        }
+    }
+}
+
+/// Display search matches from several projects, with qualified IDs
+pub fn display_aggregated_matches(matches: &[AggregatedMatch]) {
+    if matches.is_empty() {
+        log::info!("No matches found.");
+        return;
+    }
+
+    for m in matches {
+        match m.found.line_number {
+            Some(n) => println!(
+                "{} {}:{}:{}",
+                m.qualified_id(),
+                m.found.title,
+                n,
+                m.found.line
+            ),
+            None => println!("{} {}:title", m.qualified_id(), m.found.title),
+        }
+    }
+}
+
+/// Display an activity feed, one task per block
+pub fn display_activity(entries: &[ActivityEntry]) {
+    if entries.is_empty() {
+        log::info!("No activity found.");
+        return;
+    }
+
+    for entry in entries {
+        let when = match entry.when {
+            Some(when) => dates().format_timestamp(when),
+            None => "not committed yet".to_string(),
+        };
+        println!("{}  #{} {}", when, entry.task.id, entry.task.title);
+        for change in &entry.changes {
+            println!("    {}", change);
+        }
+    }
+}
+
+/// Display a task's commit history, oldest first
+pub fn display_history(entries: &[HistoryEntry]) {
+    if entries.is_empty() {
+        log::info!("No committed history found.");
+        return;
+    }
+
+    for entry in entries {
+        println!(
+            "{}  {}  {}",
+            entry.commit,
+            dates().format_timestamp(entry.when),
+            entry.author
+        );
+        for change in &entry.changes {
+            println!("    {}", change);
+        }
+    }
+}
+
+/// Display the Eisenhower matrix, one block per quadrant
+pub fn display_matrix(matrix: &EisenhowerMatrix) {
+    if matrix.is_empty() {
+        log::info!("No open tasks found.");
+        return;
+    }
+
+    for (i, quadrant) in Quadrant::ALL.into_iter().enumerate() {
+        let tasks = matrix.get(quadrant);
+        if i > 0 {
+            println!();
+        }
+        println!("{} [{}]", quadrant, tasks.len());
+        for task in tasks {
+            let due = task
+                .due
+                .map(|d| format!(", due {}", dates().format_due(&d)))
+                .unwrap_or_default();
+            println!(
+                "    #{} {} ({}{})",
+                task.id,
+                truncate(&task.title, 50),
+                task.priority,
+                due
+            );
+        }
+    }
+}
+
+/// Display a kanban board, one column after another
+pub fn display_board(board: &[(TaskStatus, Vec<Task>)]) {
+    for (i, (status, tasks)) in board.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!("{} [{}]", status, tasks.len());
+        for task in tasks {
+            println!(
+                "    #{} {} ({})",
+                task.id,
+                truncate(&task.title, 50),
+                task.priority
+            );
+        }
+    }
+}
+
+/// Capacity plan row for table display
+#[derive(Tabled)]
+struct PlanRow {
+    #[tabled(rename = "Who")]
+    who: String,
+    #[tabled(rename = "Tasks")]
+    tasks: String,
+    #[tabled(rename = "Estimated")]
+    estimated: String,
+    #[tabled(rename = "Capacity")]
+    capacity: String,
+    #[tabled(rename = "Load")]
+    load: String,
+}
+
+impl PlanRow {
+    fn new(who: &str, line: &PlanLine) -> Self {
+        let unestimated = if line.unestimated > 0 {
+            format!(" (+{} unestimated)", line.unestimated)
+        } else {
+            String::new()
+        };
+        PlanRow {
+            who: who.to_string(),
+            tasks: line.tasks.to_string(),
+            estimated: format!("{:.1}h{}", line.estimated_hours, unestimated),
+            capacity: line
+                .capacity_hours
+                .map(|c| format!("{:.1}h", c))
+                .unwrap_or_else(|| "-".to_string()),
+            load: line
+                .capacity_hours
+                .filter(|c| *c > 0.0)
+                .map(|c| format!("{:.0}%", line.estimated_hours / c * 100.0))
+                .unwrap_or_else(|| "-".to_string()),
+        }
+    }
+}
+
+/// Display a capacity plan, optionally with a row per assignee
+pub fn display_plan(plan: &CapacityPlan, by_assignee: bool) {
+    println!(
+        "Until {} ({:.1} weeks): open tasks due by then and tasks in progress",
+        dates().format_date(plan.until),
+        plan.weeks
+    );
+
+    let mut rows = vec![PlanRow::new("Project", &plan.total)];
+    if by_assignee {
+        rows.extend(
+            plan.by_assignee
+                .iter()
+                .map(|line| PlanRow::new(line.assignee.as_deref().unwrap_or("(unassigned)"), line)),
+        );
+    }
+    let table = Table::new(rows)
+        .with(Style::rounded())
+        .with(Modify::new(Columns::new(1..)).with(Alignment::right()))
+        .to_string();
+
+    println!("{}", table);
+}
+
+/// Display upcoming reminders, one per line
+pub fn display_reminders(reminders: &[UpcomingReminder]) {
+    if reminders.is_empty() {
+        log::info!("No upcoming reminders.");
+        return;
+    }
+
+    for reminder in reminders {
+        let due = reminder
+            .task
+            .task
+            .due
+            .map(|d| dates().format_due(&d))
+            .unwrap_or_default();
+        println!(
+            "{}  {} {} (due {}, {})",
+            dates().format_timestamp(reminder.at),
+            reminder.task.qualified_id(),
+            truncate(&reminder.task.task.title, 50),
+            due,
+            reminder.offset
+        );
+    }
 }
 
 /// Stats row for table display
@@ -143,7 +708,7 @@ struct StatsRow {
 
 /// Display task statistics
 pub fn display_stats(stats: &TaskStats) {
-    let rows = vec![
+    let mut rows = vec![
         StatsRow {
             metric: "Total".to_string(),
             count: stats.total.to_string(),
@@ -160,6 +725,10 @@ pub fn display_stats(stats: &TaskStats) {
             metric: "Completed".to_string(),
             count: stats.completed.to_string(),
         },
+        StatsRow {
+            metric: "Closed (not done)".to_string(),
+            count: stats.closed_not_done.to_string(),
+        },
         StatsRow {
             metric: "Archived".to_string(),
             count: stats.archived.to_string(),
@@ -168,6 +737,33 @@ pub fn display_stats(stats: &TaskStats) {
             metric: "Overdue".to_string(),
             count: stats.overdue.to_string(),
         },
+        StatsRow {
+            metric: "Due Soon".to_string(),
+            count: stats.due_soon.to_string(),
+        },
+        StatsRow {
+            metric: "Avg Cycle Time".to_string(),
+            count: stats
+                .avg_cycle_time
+                .map(format_duration)
+                .unwrap_or_else(|| "-".to_string()),
+        },
+        StatsRow {
+            metric: "Time Spent".to_string(),
+            count: format_hours(stats.time_spent.num_minutes() as f64 / 60.0),
+        },
+        StatsRow {
+            metric: "Estimated".to_string(),
+            count: format_hours(stats.estimated_hours),
+        },
+        StatsRow {
+            metric: "Done: Estimated / Actual".to_string(),
+            count: format!(
+                "{} / {}",
+                format_hours(stats.done_estimated_hours),
+                format_hours(stats.done_actual.num_minutes() as f64 / 60.0)
+            ),
+        },
         StatsRow {
             metric: "---".to_string(),
             count: "---".to_string(),
@@ -185,7 +781,53 @@ pub fn display_stats(stats: &TaskStats) {
             count: stats.ideas.to_string(),
         },
     ];
+    // Custom statuses go after In Progress
+    let custom = stats.custom.iter().map(|(status, count)| StatsRow {
+        metric: status.clone(),
+        count: count.to_string(),
+    });
+    rows.splice(3..3, custom);
+
+    let table = Table::new(rows)
+        .with(Style::rounded())
+        .with(Modify::new(Columns::single(1)).with(Alignment::right()))
+        .to_string();
 
+    println!("{}", table);
+    display_age_histogram(&stats.open_by_age);
+}
+
+/// Age row for the open task histogram
+#[derive(Tabled)]
+struct AgeRow {
+    #[tabled(rename = "Open for")]
+    age: String,
+    #[tabled(rename = "Tasks")]
+    count: String,
+    #[tabled(rename = "")]
+    bar: String,
+}
+
+/// Widest bar in the age histogram, in characters
+const AGE_BAR_WIDTH: usize = 30;
+
+/// Show how long open tasks have been waiting, as a bar per age bracket
+fn display_age_histogram(open_by_age: &[usize]) {
+    let most = open_by_age.iter().copied().max().unwrap_or(0);
+    if most == 0 {
+        return;
+    }
+
+    let rows: Vec<AgeRow> = AGE_BUCKETS
+        .iter()
+        .zip(open_by_age)
+        .map(|(age, &count)| AgeRow {
+            age: age.to_string(),
+            count: count.to_string(),
+            // Any non-empty bracket gets at least one block
+            bar: "█".repeat((count * AGE_BAR_WIDTH).div_ceil(most)),
+        })
+        .collect();
     let table = Table::new(rows)
         .with(Style::rounded())
         .with(Modify::new(Columns::single(1)).with(Alignment::right()))
@@ -194,6 +836,142 @@ pub fn display_stats(stats: &TaskStats) {
     println!("{}", table);
 }
 
+/// Context row for table display
+#[derive(Tabled)]
+struct ContextRow {
+    #[tabled(rename = "Context")]
+    context: String,
+    #[tabled(rename = "Open")]
+    open: String,
+}
+
+/// Display open task counts per context
+pub fn display_contexts(contexts: &[(String, usize)]) {
+    if contexts.is_empty() {
+        log::info!("No contexts found.");
+        return;
+    }
+
+    let rows: Vec<ContextRow> = contexts
+        .iter()
+        .map(|(context, open)| ContextRow {
+            context: context.clone(),
+            open: open.to_string(),
+        })
+        .collect();
+    let table = Table::new(rows)
+        .with(Style::rounded())
+        .with(Modify::new(Columns::single(1)).with(Alignment::right()))
+        .to_string();
+
+    println!("{}", table);
+}
+
+/// Tag row for table display
+#[derive(Tabled)]
+struct TagRow {
+    #[tabled(rename = "Tag")]
+    tag: String,
+    #[tabled(rename = "Open")]
+    open: String,
+}
+
+/// Display open task counts per tag
+pub fn display_tags(tags: &[(String, usize)]) {
+    if tags.is_empty() {
+        log::info!("No tags found.");
+        return;
+    }
+
+    let rows: Vec<TagRow> = tags
+        .iter()
+        .map(|(tag, open)| TagRow {
+            tag: tag.clone(),
+            open: open.to_string(),
+        })
+        .collect();
+    let table = Table::new(rows)
+        .with(Style::rounded())
+        .with(Modify::new(Columns::single(1)).with(Alignment::right()))
+        .to_string();
+
+    println!("{}", table);
+}
+
+/// Display the tag hierarchy with open task counts
+pub fn display_tag_tree(nodes: &[TagNode]) {
+    if nodes.is_empty() {
+        log::info!("No tags found.");
+        return;
+    }
+
+    fn print_nodes(nodes: &[TagNode], indent: &str) {
+        for (i, node) in nodes.iter().enumerate() {
+            let last = i + 1 == nodes.len();
+            println!(
+                "{}{} {} ({})",
+                indent,
+                if last { "└─" } else { "├─" },
+                node.name,
+                node.count
+            );
+            let child_indent = format!("{}{}", indent, if last { "   " } else { "│  " });
+            print_nodes(&node.children, &child_indent);
+        }
+    }
+    print_nodes(nodes, "");
+}
+
+/// Display tasks in groups, each under its own heading
+pub fn display_grouped_task_list(groups: &[(Option<String>, Vec<Task>)], namespace: &str) {
+    if groups.is_empty() {
+        log::info!("No tasks found.");
+        return;
+    }
+
+    for (name, tasks) in groups {
+        match name {
+            Some(name) => println!("{}/{}", namespace.trim_end_matches('/'), name),
+            None => println!("(no {} tag)", namespace.trim_end_matches('/')),
+        }
+        display_task_list(tasks);
+    }
+}
+
+/// Template row for table display
+#[derive(Tabled)]
+struct TemplateRow {
+    #[tabled(rename = "Template")]
+    name: String,
+    #[tabled(rename = "Priority")]
+    priority: String,
+    #[tabled(rename = "Tags")]
+    tags: String,
+    #[tabled(rename = "Due")]
+    due: String,
+}
+
+/// Display available task templates
+pub fn display_templates(templates: &[TaskTemplate]) {
+    if templates.is_empty() {
+        log::info!("No templates found. Add Markdown files to .tasks/templates/.");
+        return;
+    }
+
+    let rows: Vec<TemplateRow> = templates
+        .iter()
+        .map(|t| TemplateRow {
+            name: t.name.clone(),
+            priority: t.priority.map(|p| p.to_string()).unwrap_or_default(),
+            tags: t.tags.join(", "),
+            due: t.due.clone().unwrap_or_default(),
+        })
+        .collect();
+    let table = Table::new(rows).with(Style::rounded()).to_string();
+
+    println!("{}", table);
+}
+
 /// Project row for table display
 #[derive(Tabled)]
 struct ProjectRow {
@@ -207,6 +985,8 @@ struct ProjectRow {
     open: String,
     #[tabled(rename = "Total")]
     total: String,
+    #[tabled(rename = "Sync")]
+    sync: String,
 }
 
 impl From<&ProjectStatus> for ProjectRow {
@@ -220,11 +1000,19 @@ impl From<&ProjectStatus> for ProjectRow {
         };
 
         ProjectRow {
-            name: status.name.clone(),
+            name: if status.aliases.is_empty() {
+                status.name.clone()
+            } else {
+                format!("{} ({})", status.name, status.aliases.join(", "))
+            },
             path: truncate(&status.path.to_string_lossy(), 50),
             status: status_str,
-            open: status.open_tasks.to_string(),
-            total: status.total_tasks.to_string(),
+            open: status.open_tasks().to_string(),
+            total: status.total_tasks().to_string(),
+            sync: status
+                .sync_summary()
+                .map(|s| truncate(&s, 40))
+                .unwrap_or_default(),
         }
     }
 }
@@ -237,14 +1025,65 @@ pub fn display_projects(projects: &[ProjectStatus]) {
     }
 
     let rows: Vec<ProjectRow> = projects.iter().map(ProjectRow::from).collect();
-    let table = Table::new(rows)
+    let mut table = Table::new(rows);
+    table
         .with(Style::rounded())
-        .with(Modify::new(Columns::new(3..=4)).with(Alignment::right()))
-        .to_string();
+        .with(Modify::new(Columns::new(3..=4)).with(Alignment::right()));
+    tint_column(&mut table, 0, projects.iter().map(|p| p.color));
 
     println!("{}", table);
 }
 
+/// Status text for table rows, noting non-done resolutions
+fn status_label(task: &Task) -> String {
+    match task.resolution {
+        Some(r) if r != Resolution::Done => format!("{} ({})", task.status, r),
+        _ => task.status.to_string(),
+    }
+}
+
+/// `* ` in front of the titles of pinned tasks
+fn pin_marker(task: &Task) -> String {
+    if task.pinned { "* " } else { "" }.to_string()
+}
+
+/// ` [2/5]` after the titles of tasks with a checklist
+fn checklist_marker(task: &Task) -> String {
+    let checklist = task.checklist();
+    if checklist.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}/{}]", checklist.done(), checklist.items.len())
+    }
+}
+
+/// Due date for a table cell, marked when it falls in the "due soon" window
+fn due_label(task: &Task) -> String {
+    let Some(due) = task.due else {
+        return String::new();
+    };
+    let label = dates().format_due(&due);
+    if task.is_due_soon(chrono::Utc::now(), dates().due_soon_days()) {
+        format!("{} (soon)", label)
+    } else {
+        label
+    }
+}
+
+/// Format a duration as days or hours
+fn format_duration(d: chrono::Duration) -> String {
+    let hours = d.num_minutes() as f64 / 60.0;
+    if hours >= 24.0 {
+        format!("{:.1}d", hours / 24.0)
+    } else {
+        format!("{:.1}h", hours)
+    }
+}
+
+fn format_hours(hours: f64) -> String {
+    format!("{:.1}h", hours)
+}
+
 /// Truncate a string to a maximum length
 fn truncate(s: &str, max: usize) -> String {
     if s.len() <= max {
@@ -259,7 +1098,23 @@ pub fn success(msg: &str) {
     println!("{}", msg);
 }
 
+/// Format for warnings that don't stop the command
+pub fn warning(msg: &str) {
+    eprintln!("Warning: {}", msg);
+}
+
 /// Format for error messages
 pub fn error(msg: &str) {
     eprintln!("Error: {}", msg);
 }
+
+/// Report a task rejected by the project's validation rules, one rule per line
+pub fn display_validation_errors(title: &str, errors: &[ValidationError]) {
+    error(&format!(
+        "'{}' breaks the project's validation rules:",
+        title
+    ));
+    for e in errors {
+        eprintln!("  {:<12} {}", e.field, e.message);
+    }
+}
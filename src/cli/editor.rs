@@ -0,0 +1,105 @@
+//! Launching the user's editor for `gittask open --editor`
+//!
+//! `$VISUAL` and `$EDITOR` may carry arguments (`code -w`, `emacsclient -t`),
+//! so they are split into words the way a POSIX shell would: on whitespace,
+//! with single quotes, double quotes and backslashes escaping.
+
+use std::process::Command;
+
+/// Editor used when neither `$VISUAL` nor `$EDITOR` is set
+pub const DEFAULT_EDITOR: &str = "vi";
+
+/// The configured editor command line, unsplit
+pub fn configured_editor() -> String {
+    std::env::var("VISUAL")
+        .or_else(|_| std::env::var("EDITOR"))
+        .ok()
+        .filter(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EDITOR.to_string())
+}
+
+/// A command running the editor line `editor`, ready for the file argument
+///
+/// Fails on an unclosed quote or an empty line.
+pub fn editor_command(editor: &str) -> Result<Command, String> {
+    let words = split_words(editor)?;
+    let (program, args) = words
+        .split_first()
+        .ok_or_else(|| "Editor command is empty".to_string())?;
+    let mut command = Command::new(program);
+    command.args(args);
+    Ok(command)
+}
+
+/// Split a command line into words, honouring shell quoting
+pub fn split_words(line: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(format!("Unclosed ' in '{}'", line)),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        // Inside double quotes a backslash only escapes these
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err(format!("Unclosed \" in '{}'", line)),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err(format!("Unclosed \" in '{}'", line)),
+                    }
+                }
+            }
+            '\\' => {
+                let word = word.get_or_insert_with(String::new);
+                if let Some(c) = chars.next() {
+                    word.push(c);
+                }
+            }
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_words() {
+        assert_eq!(split_words("vi").unwrap(), vec!["vi"]);
+        assert_eq!(split_words("  code   -w ").unwrap(), vec!["code", "-w"]);
+        assert_eq!(
+            split_words(r#"'/Applications/My Editor' --wait "a \"b\"" c\ d ''"#).unwrap(),
+            vec!["/Applications/My Editor", "--wait", "a \"b\"", "c d", ""]
+        );
+        assert_eq!(
+            split_words(r#""C:\Tools\ed.exe""#).unwrap(),
+            vec![r"C:\Tools\ed.exe"]
+        );
+        assert!(split_words("code 'unclosed").is_err());
+        assert!(split_words("").unwrap().is_empty());
+        assert!(editor_command("   ").is_err());
+    }
+}
@@ -0,0 +1,354 @@
+//! Worked examples and help topics for `gittask help` and `gittask man`
+//!
+//! Examples live here rather than in the doc comments of `commands.rs` so
+//! the same text ends up under `--help`, in `gittask help` and in the man
+//! pages, and so the tests can check that every example still parses.
+
+use crate::cli::Cli;
+use crate::storage::{TaskFilter, writes};
+use clap::{Command, CommandFactory};
+use std::path::Path;
+
+/// A worked example: what it does, then the command line
+pub struct Example {
+    pub about: &'static str,
+    pub command: &'static str,
+}
+
+const fn example(about: &'static str, command: &'static str) -> Example {
+    Example { about, command }
+}
+
+/// Examples shown after each subcommand's `--help`
+pub const COMMAND_EXAMPLES: &[(&str, &[Example])] = &[
+    (
+        "add",
+        &[
+            example("Add a task", "gittask add task \"Fix login redirect\""),
+            example(
+                "Add a todo with a priority, tags and a due date",
+                "gittask add todo \"Rotate keys\" -p high -t security,ops --due friday",
+            ),
+            example(
+                "Start from a template and estimate the effort",
+                "gittask add task \"Crash on save\" -T bug --estimate 2h",
+            ),
+        ],
+    ),
+    (
+        "list",
+        &[
+            example("Open high-priority bugs", "gittask list -p high -t bug"),
+            example(
+                "Ready to start, most urgent first",
+                "gittask list --unblocked --by-urgency",
+            ),
+            example(
+                "One line per task for scripts",
+                "gittask list --format template --template \"{{id}}\\t{{title}}\"",
+            ),
+        ],
+    ),
+    (
+        "show",
+        &[
+            example("Show a task", "gittask show 3"),
+            example("Show a task of another project", "gittask show webapp:3"),
+        ],
+    ),
+    (
+        "update",
+        &[
+            example(
+                "Move the due date and add a tag",
+                "gittask update 3 --due +1w --add-tag blocked",
+            ),
+            example(
+                "Clear the assignee of another project's task",
+                "gittask update api:12 --assignee none",
+            ),
+        ],
+    ),
+    (
+        "close",
+        &[example(
+            "Close two tasks as won't fix",
+            "gittask close 4 5 --reason wontfix",
+        )],
+    ),
+    (
+        "grep",
+        &[example(
+            "Search every registered project, ignoring case",
+            "gittask -g grep -i \"timeout\"",
+        )],
+    ),
+    (
+        "check",
+        &[
+            example("Toggle the second checklist item", "gittask check 4 2"),
+            example(
+                "Toggle the item mentioning the changelog",
+                "gittask check 4 changelog",
+            ),
+        ],
+    ),
+    (
+        "comment",
+        &[example(
+            "Comment on a task",
+            "gittask comment 3 \"Reproduced on staging\"",
+        )],
+    ),
+    (
+        "fmt",
+        &[example(
+            "Fail CI when a hand-edited task file isn't formatted",
+            "gittask fmt --check",
+        )],
+    ),
+    (
+        "embed",
+        &[example(
+            "Keep the README roadmap in sync with pending tasks",
+            "gittask embed README.md --section \"## Roadmap\" --filter \"status:pending kind:task\"",
+        )],
+    ),
+];
+
+/// A guide printed by `gittask help <topic>`
+pub struct Topic {
+    pub name: &'static str,
+    pub about: &'static str,
+    intro: &'static str,
+    examples: &'static [Example],
+}
+
+pub const TOPICS: &[Topic] = &[
+    Topic {
+        name: "ids",
+        about: "Refer to tasks in other projects as project:id",
+        intro: "Wherever a command takes a task ID it also takes project:id, for a task in a\n\
+                registered project (see `gittask link`). The project is matched, ignoring\n\
+                case, by directory name or alias: exactly, then as a unique prefix, then\n\
+                fuzzily. When several projects match the error lists them. Commands that\n\
+                change a task don't match fuzzily, only `show`, `history` and `backlinks`.",
+        examples: &[
+            example("Show a task of the webapp project", "gittask show webapp:1"),
+            example(
+                "Complete tasks in two projects at once",
+                "gittask complete api:2 webapp:7",
+            ),
+            example(
+                "A unique prefix of the project name works too",
+                "gittask status web:3 in-progress",
+            ),
+            example("Reading commands match fuzzily", "gittask show wbp:3"),
+        ],
+    },
+    Topic {
+        name: "filters",
+        about: "Options for narrowing `list` and `count`",
+        intro: "Filter options combine: a task is listed only if it matches all of them.\n\
+                `count` takes the kind, status, priority, tag, context and assignee ones.",
+        examples: &[
+            example(
+                "Pending todos in the @errands context",
+                "gittask list -k todo -s pending -c @errands",
+            ),
+            example(
+                "Everything assigned to you, archived included",
+                "gittask list --mine -a",
+            ),
+            example(
+                "Fail a CI step while critical tasks are pending",
+                "gittask count -s pending -p critical --fail-if-any",
+            ),
+        ],
+    },
+    Topic {
+        name: "query",
+        about: "The key:value filter language of `embed` and config files",
+        intro: "A query is a space-separated list of key:value terms, all of which must\n\
+                match. It is used by `gittask embed --filter`, `filter` in [[embed]]\n\
+                sections of config.toml and the MCP task resources.",
+        examples: &[example(
+            "Pending backend tasks nobody is blocked on",
+            "gittask embed ROADMAP.md --filter \"status:pending tag:backend unblocked:true\"",
+        )],
+    },
+];
+
+/// Examples formatted as a help section
+fn render_examples(examples: &[Example]) -> String {
+    let mut out = String::from("Examples:\n");
+    for (i, example) in examples.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        out.push_str(&format!("  # {}\n  {}\n", example.about, example.command));
+    }
+    out
+}
+
+/// The full text of a topic, including the parts generated from the CLI
+fn render_topic(topic: &Topic) -> String {
+    let mut out = format!("{}\n\n{}\n\n", topic.about, topic.intro);
+    match topic.name {
+        "filters" => {
+            out.push_str("Options of `list`:\n");
+            let cmd = Cli::command();
+            if let Some(list) = cmd.find_subcommand("list") {
+                for arg in list.get_arguments().filter(|a| !a.is_global_set()) {
+                    let Some(long) = arg.get_long() else { continue };
+                    let help = arg.get_help().map(|h| h.to_string()).unwrap_or_default();
+                    out.push_str(&format!("  --{:<20} {}\n", long, help));
+                }
+            }
+            out.push('\n');
+        }
+        "query" => {
+            out.push_str("Keys:\n");
+            for (key, about) in TaskFilter::KEYS {
+                out.push_str(&format!("  {:<10} {}\n", key, about));
+            }
+            out.push('\n');
+        }
+        _ => {}
+    }
+    out.push_str(&render_examples(topic.examples));
+    out
+}
+
+/// One line per topic, for listings
+fn topic_list() -> String {
+    let mut out = String::from("Topics (gittask help <topic>):\n");
+    for topic in TOPICS {
+        out.push_str(&format!("  {:<10} {}\n", topic.name, topic.about));
+    }
+    out
+}
+
+/// The CLI definition with the worked examples attached to each subcommand
+pub fn command() -> Command {
+    let mut cmd = Cli::command().after_long_help(topic_list());
+    for (name, examples) in COMMAND_EXAMPLES {
+        cmd = cmd.mut_subcommand(*name, |sub| sub.after_long_help(render_examples(examples)));
+    }
+    cmd
+}
+
+/// Text for `gittask help [topic]`: a subcommand's help, a topic, or the overview
+pub fn help_text(topic: Option<&str>) -> Result<String, String> {
+    let mut cmd = command();
+    cmd.build();
+    let Some(name) = topic else {
+        return Ok(cmd.render_long_help().to_string());
+    };
+
+    if let Some(topic) = TOPICS.iter().find(|t| t.name == name) {
+        return Ok(render_topic(topic));
+    }
+    if let Some(sub) = cmd.find_subcommand_mut(name) {
+        return Ok(sub.render_long_help().to_string());
+    }
+    Err(format!(
+        "No help for '{}': expected a command or one of the topics {}",
+        name,
+        TOPICS.iter().map(|t| t.name).collect::<Vec<_>>().join(", ")
+    ))
+}
+
+/// The CLI definition for man pages, with every topic as an extra section
+fn man_command() -> Command {
+    let topics: Vec<String> = TOPICS
+        .iter()
+        .map(|t| format!("{}: {}", t.name.to_uppercase(), render_topic(t)))
+        .collect();
+    command().after_long_help(topics.join("\n"))
+}
+
+/// The `gittask(1)` man page
+pub fn man_page() -> std::io::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    clap_mangen::Man::new(man_command()).render(&mut out)?;
+    Ok(out)
+}
+
+/// Write `gittask.1` and a `gittask-<command>.1` page per subcommand into `dir`
+///
+/// The pages are the ones `clap_mangen::generate_to` writes, written through
+/// `storage::writes` so `--dry-run` holds them back.
+pub fn write_man_pages(dir: &Path) -> std::io::Result<()> {
+    fn write(cmd: Command, dir: &Path) -> std::io::Result<()> {
+        for sub in cmd.get_subcommands().filter(|s| !s.is_hide_set()).cloned() {
+            write(sub, dir)?;
+        }
+        let man = clap_mangen::Man::new(cmd);
+        let mut page = Vec::new();
+        man.render(&mut page)?;
+        writes::write(&dir.join(man.get_filename()), page)
+    }
+
+    let mut cmd = man_command().disable_help_subcommand(true);
+    cmd.build();
+    write(cmd, dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Split a command line on spaces, keeping double-quoted words together
+    fn split(line: &str) -> Vec<String> {
+        let mut words = Vec::new();
+        let mut word = String::new();
+        let mut quoted = false;
+        for c in line.chars() {
+            match c {
+                '"' => quoted = !quoted,
+                ' ' if !quoted => {
+                    if !word.is_empty() {
+                        words.push(std::mem::take(&mut word));
+                    }
+                }
+                _ => word.push(c),
+            }
+        }
+        if !word.is_empty() {
+            words.push(word);
+        }
+        words
+    }
+
+    #[test]
+    fn test_examples_parse() {
+        let examples = COMMAND_EXAMPLES
+            .iter()
+            .flat_map(|(_, examples)| examples.iter())
+            .chain(TOPICS.iter().flat_map(|t| t.examples.iter()));
+        for example in examples {
+            let args = split(example.command);
+            assert!(
+                command().try_get_matches_from(&args).is_ok(),
+                "example doesn't parse: {}",
+                example.command
+            );
+        }
+    }
+
+    #[test]
+    fn test_help_text() {
+        let list = help_text(Some("list")).unwrap();
+        assert!(list.contains("Examples:"));
+        assert!(list.contains("gittask list --unblocked --by-urgency"));
+
+        let filters = help_text(Some("filters")).unwrap();
+        assert!(filters.contains("--unblocked"));
+        assert!(!filters.contains("--dry-run"));
+        assert!(help_text(Some("query")).unwrap().contains("archived"));
+
+        assert!(help_text(None).unwrap().contains("Topics"));
+        assert!(help_text(Some("nope")).is_err());
+    }
+}
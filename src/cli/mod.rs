@@ -2,5 +2,11 @@
 
 pub mod commands;
 pub mod display;
+pub mod editor;
+pub mod help;
+pub mod output_template;
 
-pub use commands::{Cli, Commands};
+pub use commands::{
+    Clearable, Cli, Commands, DepsAction, GraphFormat, HooksAction, ListFormat, OutputFormat,
+    RefAction, TagsAction,
+};
@@ -0,0 +1,171 @@
+//! One-line output templates for `gittask list --format template`
+//!
+//! A template is plain text with `{{field}}` placeholders, e.g.
+//! `{{id}}\t{{title}} ({{status}})`. `\t`, `\n` and `\\` are unescaped so
+//! templates can be written without shell quoting tricks. Fields without a
+//! value render as nothing.
+
+use crate::models::Task;
+use chrono::{DateTime, SecondsFormat, Utc};
+use std::str::FromStr;
+
+/// Placeholders a template can use
+pub const FIELDS: &[&str] = &[
+    "id",
+    "kind",
+    "title",
+    "status",
+    "priority",
+    "due",
+    "scheduled",
+    "tags",
+    "context",
+    "assignee",
+    "external_id",
+    "parent",
+    "blocked_by",
+    "estimate",
+    "created",
+    "updated",
+    "completed",
+    "project",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Text(String),
+    Field(String),
+}
+
+/// A parsed output template
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputTemplate {
+    parts: Vec<Part>,
+}
+
+impl FromStr for OutputTemplate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = Vec::new();
+        let mut rest = s;
+        while let Some(start) = rest.find("{{") {
+            if start > 0 {
+                parts.push(Part::Text(unescape(&rest[..start])));
+            }
+            let after = &rest[start + 2..];
+            let end = after
+                .find("}}")
+                .ok_or_else(|| format!("Unclosed '{{{{' in template: {}", s))?;
+            let name = after[..end].trim();
+            if !FIELDS.contains(&name) {
+                return Err(format!(
+                    "Unknown template field '{}' (expected one of {})",
+                    name,
+                    FIELDS.join(", ")
+                ));
+            }
+            parts.push(Part::Field(name.to_string()));
+            rest = &after[end + 2..];
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Text(unescape(rest)));
+        }
+        Ok(OutputTemplate { parts })
+    }
+}
+
+impl OutputTemplate {
+    /// Fill in the template for `task`, which lives in `project` if known
+    pub fn render(&self, task: &Task, project: Option<&str>) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Text(text) => text.clone(),
+                Part::Field(name) => field(task, project, name),
+            })
+            .collect()
+    }
+}
+
+fn timestamp(at: &DateTime<Utc>) -> String {
+    at.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn join<T: ToString>(items: &[T]) -> String {
+    items.iter().map(T::to_string).collect::<Vec<_>>().join(",")
+}
+
+/// The value of one of `FIELDS`
+fn field(task: &Task, project: Option<&str>, name: &str) -> String {
+    match name {
+        "id" => task.id.to_string(),
+        "kind" => task.kind.to_string(),
+        "title" => task.title.clone(),
+        "status" => task.status.to_string(),
+        "priority" => task.priority.to_string(),
+        "due" => task.due.map(|d| d.to_string()).unwrap_or_default(),
+        "scheduled" => task.scheduled.map(|d| d.to_string()).unwrap_or_default(),
+        "tags" => join(&task.tags),
+        "context" => task.context.clone().unwrap_or_default(),
+        "assignee" => task.assignee.clone().unwrap_or_default(),
+        "external_id" => task.external_id.clone().unwrap_or_default(),
+        "parent" => task.parent.map(|p| p.to_string()).unwrap_or_default(),
+        "blocked_by" => join(&task.blocked_by),
+        "estimate" => task.estimate.map(|e| e.to_string()).unwrap_or_default(),
+        "created" => timestamp(&task.created),
+        "updated" => timestamp(&task.updated),
+        "completed" => task.completed.as_ref().map(timestamp).unwrap_or_default(),
+        "project" => project.unwrap_or_default().to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Replace `\t`, `\n` and `\\`; other backslashes are kept as written
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('\\') => out.push('\\'),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TaskKind;
+
+    #[test]
+    fn test_render_template() {
+        let mut task = Task::new(12, TaskKind::Task, "Fix login");
+        task.tags = vec!["bug".to_string(), "area/auth".to_string()];
+
+        let template: OutputTemplate = r"{{id}}\t{{ title }} ({{status}}) [{{tags}}]{{due}}"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            template.render(&task, None),
+            "12\tFix login (pending) [bug,area/auth]"
+        );
+
+        let template: OutputTemplate = r"{{project}}:{{id}} C:\dir".parse().unwrap();
+        assert_eq!(template.render(&task, Some("api")), r"api:12 C:\dir");
+
+        assert!("{{nope}}".parse::<OutputTemplate>().is_err());
+        assert!("{{id".parse::<OutputTemplate>().is_err());
+    }
+}
@@ -0,0 +1,355 @@
+//! Committing task changes on their own, separate from code changes
+
+use crate::git::operations::GitError;
+use crate::models::{Task, TaskStatus, parse_task};
+use git2::{Repository, Status, StatusOptions};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// What happened to a task since the last commit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChangeKind {
+    Created,
+    Completed,
+    Archived,
+    Updated,
+    Deleted,
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChangeKind::Created => write!(f, "created"),
+            ChangeKind::Completed => write!(f, "completed"),
+            ChangeKind::Archived => write!(f, "archived"),
+            ChangeKind::Updated => write!(f, "updated"),
+            ChangeKind::Deleted => write!(f, "deleted"),
+        }
+    }
+}
+
+/// A change to one task, possibly spanning several files (e.g. a rename)
+#[derive(Debug, Clone)]
+pub struct TaskChange {
+    pub id: u64,
+    pub title: String,
+    pub kind: ChangeKind,
+    /// Changed paths holding this task, old and new
+    pub paths: Vec<PathBuf>,
+}
+
+/// Uncommitted changes under a tasks directory
+#[derive(Debug, Default)]
+pub struct PendingChanges {
+    /// Changes to task files, ordered by ID
+    pub tasks: Vec<TaskChange>,
+    /// Every changed path (relative to the repository root), tasks or not
+    pub paths: Vec<PathBuf>,
+}
+
+impl PendingChanges {
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Only the changes to the given tasks, leaving out everything else
+    pub fn for_tasks(self, ids: &[u64]) -> PendingChanges {
+        let tasks: Vec<TaskChange> = self
+            .tasks
+            .into_iter()
+            .filter(|change| ids.contains(&change.id))
+            .collect();
+        let paths = tasks.iter().flat_map(|c| c.paths.clone()).collect();
+        PendingChanges { tasks, paths }
+    }
+
+    /// Conventional commit message summarizing the changes
+    ///
+    /// e.g. `chore(tasks): created #12, #13; completed #9`, followed by one
+    /// line per task.
+    pub fn message(&self) -> String {
+        let mut by_kind: BTreeMap<ChangeKind, Vec<String>> = BTreeMap::new();
+        for change in &self.tasks {
+            by_kind
+                .entry(change.kind)
+                .or_default()
+                .push(format!("#{}", change.id));
+        }
+
+        let summary = if by_kind.is_empty() {
+            "update task settings".to_string()
+        } else {
+            by_kind
+                .iter()
+                .map(|(kind, ids)| format!("{} {}", kind, ids.join(", ")))
+                .collect::<Vec<_>>()
+                .join("; ")
+        };
+
+        let mut message = format!("chore(tasks): {}\n", summary);
+        if !self.tasks.is_empty() {
+            message.push('\n');
+            for change in &self.tasks {
+                message.push_str(&format!(
+                    "- {} #{}: {}\n",
+                    change.kind, change.id, change.title
+                ));
+            }
+        }
+        message
+    }
+}
+
+/// Path of `dir` relative to the repository's working directory
+fn relative_to_workdir(repo: &Repository, dir: &Path) -> Result<PathBuf, GitError> {
+    let workdir = repo.workdir().ok_or(GitError::NotInRepo)?;
+    let root = workdir.canonicalize().map_err(|_| GitError::NotInRepo)?;
+    let dir = dir.canonicalize().map_err(|_| GitError::NotInRepo)?;
+    dir.strip_prefix(&root)
+        .map(|p| p.to_path_buf())
+        .map_err(|_| GitError::NotInRepo)
+}
+
+/// Collect uncommitted changes (staged or not) under `tasks_dir`
+pub fn pending_changes(tasks_dir: &Path) -> Result<PendingChanges, GitError> {
+    let repo = Repository::discover(tasks_dir)?;
+    let rel_dir = relative_to_workdir(&repo, tasks_dir)?;
+    let workdir = repo.workdir().ok_or(GitError::NotInRepo)?.to_path_buf();
+    let head_tree = repo.head().ok().and_then(|h| h.peel_to_tree().ok());
+
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .pathspec(&rel_dir);
+
+    // Old and new versions of each task, keyed by ID so renames pair up
+    let mut versions: BTreeMap<u64, (Option<Task>, Option<Task>)> = BTreeMap::new();
+    let mut task_paths: BTreeMap<u64, Vec<PathBuf>> = BTreeMap::new();
+    let mut pending = PendingChanges::default();
+
+    for entry in repo.statuses(Some(&mut options))?.iter() {
+        let Ok(path) = entry.path().map(PathBuf::from) else {
+            continue;
+        };
+        if entry.status().contains(Status::IGNORED) || !path.starts_with(&rel_dir) {
+            continue;
+        }
+
+        let old = head_tree
+            .as_ref()
+            .and_then(|tree| tree.get_path(&path).ok())
+            .and_then(|entry| entry.to_object(&repo).ok())
+            .and_then(|object| {
+                let blob = object.as_blob()?;
+                parse_task(&String::from_utf8_lossy(blob.content())).ok()
+            });
+        let new = std::fs::read_to_string(workdir.join(&path))
+            .ok()
+            .and_then(|content| parse_task(&content).ok());
+
+        for task in old.iter().chain(new.iter()) {
+            let paths = task_paths.entry(task.id).or_default();
+            if !paths.contains(&path) {
+                paths.push(path.clone());
+            }
+        }
+        if let Some(task) = &old {
+            versions.entry(task.id).or_default().0 = old.clone();
+        }
+        if let Some(task) = &new {
+            versions.entry(task.id).or_default().1 = new.clone();
+        }
+        pending.paths.push(path);
+    }
+
+    for (id, (old, new)) in versions {
+        let kind = match (&old, &new) {
+            (None, Some(_)) => ChangeKind::Created,
+            (Some(_), None) => ChangeKind::Deleted,
+            (Some(o), Some(n)) if n.status != o.status && n.status == TaskStatus::Completed => {
+                ChangeKind::Completed
+            }
+            (Some(o), Some(n)) if n.status != o.status && n.status == TaskStatus::Archived => {
+                ChangeKind::Archived
+            }
+            (Some(o), Some(n)) if o == n => continue,
+            _ => ChangeKind::Updated,
+        };
+        let title = new.or(old).map(|t| t.title).unwrap_or_default();
+        let paths = task_paths.remove(&id).unwrap_or_default();
+        pending.tasks.push(TaskChange {
+            id,
+            title,
+            kind,
+            paths,
+        });
+    }
+
+    Ok(pending)
+}
+
+/// Commit the given paths on top of HEAD, leaving any other staged changes staged
+///
+/// Returns the new commit's full hash.
+pub fn commit_paths(
+    repo_path: &Path,
+    paths: &[PathBuf],
+    message: &str,
+) -> Result<String, GitError> {
+    let repo = Repository::discover(repo_path)?;
+    let workdir = repo.workdir().ok_or(GitError::NotInRepo)?.to_path_buf();
+    let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+
+    // Build the tree from HEAD plus these paths only, not from the index
+    let mut tree_index = git2::Index::new()?;
+    if let Some(parent) = &parent {
+        tree_index.read_tree(&parent.tree()?)?;
+    }
+    let mut index = repo.index()?;
+    for path in paths {
+        if workdir.join(path).exists() {
+            index.add_path(path)?;
+            let entry = index
+                .get_path(path, 0)
+                .ok_or_else(|| git2::Error::from_str("path missing from index"))?;
+            tree_index.add(&entry)?;
+        } else {
+            index.remove_path(path)?;
+            tree_index.remove_path(path)?;
+        }
+    }
+    index.write()?;
+    let tree = repo.find_tree(tree_index.write_tree_to(&repo)?)?;
+
+    let signature = repo.signature()?;
+    let parents: Vec<&git2::Commit> = parent.iter().collect();
+    let oid = repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parents,
+    )?;
+    Ok(oid.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TaskKind, serialize_task};
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+    }
+
+    fn setup() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        git(temp.path(), &["init"]);
+        git(temp.path(), &["config", "user.email", "test@test.com"]);
+        git(temp.path(), &["config", "user.name", "Test User"]);
+        std::fs::create_dir(temp.path().join(".tasks")).unwrap();
+        temp
+    }
+
+    fn write(dir: &Path, task: &Task) {
+        std::fs::write(
+            dir.join(".tasks").join(task.filename()),
+            serialize_task(task).unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_message() {
+        let pending = PendingChanges {
+            tasks: vec![
+                TaskChange {
+                    id: 9,
+                    title: "Fix login".to_string(),
+                    kind: ChangeKind::Completed,
+                    paths: Vec::new(),
+                },
+                TaskChange {
+                    id: 12,
+                    title: "Add docs".to_string(),
+                    kind: ChangeKind::Created,
+                    paths: Vec::new(),
+                },
+                TaskChange {
+                    id: 13,
+                    title: "Add tests".to_string(),
+                    kind: ChangeKind::Created,
+                    paths: Vec::new(),
+                },
+            ],
+            paths: Vec::new(),
+        };
+        assert_eq!(
+            pending.message(),
+            "chore(tasks): created #12, #13; completed #9\n\n\
+             - completed #9: Fix login\n- created #12: Add docs\n- created #13: Add tests\n"
+        );
+    }
+
+    #[test]
+    fn test_commit_only_task_changes() {
+        let temp = setup();
+        let tasks_dir = temp.path().join(".tasks");
+
+        let mut done = Task::new(1, TaskKind::Task, "Fix login");
+        write(temp.path(), &done);
+        git(temp.path(), &["add", "."]);
+        git(temp.path(), &["commit", "-m", "init"]);
+
+        // Complete and retitle #1, which also renames its file
+        done.complete(None);
+        done.title = "Fix login flow".to_string();
+        std::fs::remove_file(tasks_dir.join("fix-login-001.md")).unwrap();
+        write(temp.path(), &done);
+        write(temp.path(), &Task::new(2, TaskKind::Idea, "Dark mode"));
+
+        // An unrelated staged code change must stay out of the commit
+        std::fs::write(temp.path().join("main.rs"), "fn main() {}").unwrap();
+        git(temp.path(), &["add", "main.rs"]);
+
+        let pending = pending_changes(&tasks_dir).unwrap();
+        assert_eq!(pending.paths.len(), 3);
+        let kinds: Vec<(u64, ChangeKind)> = pending.tasks.iter().map(|c| (c.id, c.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![(1, ChangeKind::Completed), (2, ChangeKind::Created)]
+        );
+
+        // Narrowed to #2, only its file is committed
+        let only_new = pending_changes(&tasks_dir).unwrap().for_tasks(&[2]);
+        assert_eq!(
+            only_new.paths,
+            vec![PathBuf::from(".tasks/dark-mode-002.md")]
+        );
+        commit_paths(temp.path(), &only_new.paths, &only_new.message()).unwrap();
+        let pending = pending_changes(&tasks_dir).unwrap();
+        assert_eq!(pending.paths.len(), 2);
+        assert_eq!(pending.tasks[0].paths.len(), 2);
+
+        commit_paths(temp.path(), &pending.paths, &pending.message()).unwrap();
+
+        assert!(pending_changes(&tasks_dir).unwrap().is_empty());
+        let repo = Repository::open(temp.path()).unwrap();
+        let tree = repo.head().unwrap().peel_to_tree().unwrap();
+        assert!(tree.get_path(Path::new("main.rs")).is_err());
+        assert!(
+            repo.status_file(Path::new("main.rs"))
+                .unwrap()
+                .contains(Status::INDEX_NEW)
+        );
+    }
+}
@@ -0,0 +1,292 @@
+//! Per-file history that follows renames
+
+use crate::git::operations::GitError;
+use chrono::{DateTime, TimeZone, Utc};
+use git2::{Delta, DiffFindOptions, DiffOptions, Repository, Sort};
+use std::path::{Path, PathBuf};
+
+/// One committed version of a file
+#[derive(Debug, Clone)]
+pub struct FileVersion {
+    /// Short hash of the commit that produced this version
+    pub commit: String,
+    pub when: DateTime<Utc>,
+    pub author: String,
+    /// Path of the file in that commit (relative to the repository root)
+    pub path: PathBuf,
+    pub content: String,
+}
+
+/// Versions of `file` on HEAD's first-parent history, newest first
+///
+/// Renames are followed like `git log --follow`, with detection limited to
+/// changes under `within` (e.g. the tasks directory) to keep diffs cheap.
+/// Both paths may be absolute or relative to the repository root.
+pub fn file_history(within: &Path, file: &Path) -> Result<Vec<FileVersion>, GitError> {
+    let repo = Repository::discover(within)?;
+    let workdir = repo.workdir().ok_or(GitError::NotInRepo)?;
+    let relative = |path: &Path| -> Result<PathBuf, GitError> {
+        if path.is_relative() {
+            return Ok(path.to_path_buf());
+        }
+        let root = workdir.canonicalize()?;
+        let path = path.canonicalize()?;
+        path.strip_prefix(&root)
+            .map(|p| p.to_path_buf())
+            .map_err(|_| GitError::NotInRepo)
+    };
+    let scope = relative(within)?;
+    let mut current = relative(file)?;
+
+    let mut walk = repo.revwalk()?;
+    if walk.push_head().is_err() {
+        return Ok(Vec::new());
+    }
+    walk.simplify_first_parent()?;
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+
+    let mut versions = Vec::new();
+    for oid in walk {
+        let commit = repo.find_commit(oid?)?;
+        let tree = commit.tree()?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+
+        let mut options = DiffOptions::new();
+        options.pathspec(&scope);
+        let mut diff =
+            repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut options))?;
+        diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+
+        let Some(delta) = diff
+            .deltas()
+            .find(|d| d.new_file().path() == Some(current.as_path()))
+        else {
+            continue;
+        };
+        if delta.status() == Delta::Deleted {
+            continue;
+        }
+
+        let content = tree
+            .get_path(&current)?
+            .to_object(&repo)?
+            .as_blob()
+            .map(|b| String::from_utf8_lossy(b.content()).to_string())
+            .unwrap_or_default();
+        versions.push(FileVersion {
+            commit: format!("{:.7}", commit.id()),
+            when: Utc
+                .timestamp_opt(commit.time().seconds(), 0)
+                .single()
+                .unwrap_or_default(),
+            author: commit.author().name().unwrap_or_default().to_string(),
+            path: current.clone(),
+            content,
+        });
+
+        match delta.status() {
+            Delta::Added => break,
+            Delta::Renamed => {
+                if let Some(old) = delta.old_file().path() {
+                    current = old.to_path_buf();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(versions)
+}
+
+/// Files added under `within` on HEAD's history, oldest first, with the
+/// email of the author who added them
+pub fn file_authors(within: &Path) -> Result<Vec<(PathBuf, String)>, GitError> {
+    let repo = Repository::discover(within)?;
+    let workdir = repo.workdir().ok_or(GitError::NotInRepo)?;
+    let scope = within
+        .canonicalize()?
+        .strip_prefix(workdir.canonicalize()?)
+        .map(|p| p.to_path_buf())
+        .map_err(|_| GitError::NotInRepo)?;
+
+    let mut walk = repo.revwalk()?;
+    if walk.push_head().is_err() {
+        return Ok(Vec::new());
+    }
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME | Sort::REVERSE)?;
+
+    let mut added = Vec::new();
+    for oid in walk {
+        let commit = repo.find_commit(oid?)?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+
+        let mut options = DiffOptions::new();
+        options.pathspec(&scope);
+        let diff = repo.diff_tree_to_tree(
+            parent_tree.as_ref(),
+            Some(&commit.tree()?),
+            Some(&mut options),
+        )?;
+        let email = commit.author().email().unwrap_or_default().to_string();
+        for delta in diff.deltas().filter(|d| d.status() == Delta::Added) {
+            if let Some(path) = delta.new_file().path() {
+                added.push((path.to_path_buf(), email.clone()));
+            }
+        }
+    }
+
+    Ok(added)
+}
+
+/// Files deleted under `within` by commits made since `since`, newest
+/// first, with the time of the deleting commit
+pub fn file_deletions(
+    within: &Path,
+    since: DateTime<Utc>,
+) -> Result<Vec<(PathBuf, DateTime<Utc>)>, GitError> {
+    let repo = Repository::discover(within)?;
+    let workdir = repo.workdir().ok_or(GitError::NotInRepo)?;
+    let scope = within
+        .canonicalize()?
+        .strip_prefix(workdir.canonicalize()?)
+        .map(|p| p.to_path_buf())
+        .map_err(|_| GitError::NotInRepo)?;
+
+    let mut walk = repo.revwalk()?;
+    if walk.push_head().is_err() {
+        return Ok(Vec::new());
+    }
+    walk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)?;
+
+    let mut deleted = Vec::new();
+    for oid in walk {
+        let commit = repo.find_commit(oid?)?;
+        if commit.time().seconds() < since.timestamp() {
+            continue;
+        }
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+
+        let mut options = DiffOptions::new();
+        options.pathspec(&scope);
+        let diff = repo.diff_tree_to_tree(
+            parent_tree.as_ref(),
+            Some(&commit.tree()?),
+            Some(&mut options),
+        )?;
+        let when = Utc
+            .timestamp_opt(commit.time().seconds(), 0)
+            .single()
+            .unwrap_or_default();
+        for delta in diff.deltas().filter(|d| d.status() == Delta::Deleted) {
+            if let Some(path) = delta.old_file().path() {
+                deleted.push((path.to_path_buf(), when));
+            }
+        }
+    }
+
+    Ok(deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_file_history_follows_renames() {
+        let temp = TempDir::new().unwrap();
+        git(temp.path(), &["init"]);
+        git(temp.path(), &["config", "user.email", "test@test.com"]);
+        git(temp.path(), &["config", "user.name", "Test User"]);
+        let dir = temp.path().join(".tasks");
+        std::fs::create_dir(&dir).unwrap();
+
+        let body = "id: 1\nkind: task\nstatus: pending\npriority: medium\ncreated: 2026-01-01\n";
+        std::fs::write(
+            dir.join("old-title-001.md"),
+            format!("title: Old\n{}", body),
+        )
+        .unwrap();
+        git(temp.path(), &["add", "."]);
+        git(temp.path(), &["commit", "-m", "create"]);
+
+        std::fs::remove_file(dir.join("old-title-001.md")).unwrap();
+        std::fs::write(
+            dir.join("new-title-001.md"),
+            format!("title: New\n{}", body),
+        )
+        .unwrap();
+        git(temp.path(), &["add", "-A"]);
+        git(temp.path(), &["commit", "-m", "rename"]);
+
+        std::fs::write(temp.path().join("other.txt"), "unrelated").unwrap();
+        git(temp.path(), &["add", "-A"]);
+        git(temp.path(), &["commit", "-m", "unrelated"]);
+
+        let history = file_history(&dir, &dir.join("new-title-001.md")).unwrap();
+        let paths: Vec<&Path> = history.iter().map(|v| v.path.as_path()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                Path::new(".tasks/new-title-001.md"),
+                Path::new(".tasks/old-title-001.md")
+            ]
+        );
+        assert!(history[1].content.starts_with("title: Old"));
+        assert_eq!(history[0].author, "Test User");
+    }
+
+    #[test]
+    fn test_file_authors() {
+        let temp = TempDir::new().unwrap();
+        git(temp.path(), &["init"]);
+        git(temp.path(), &["config", "user.name", "Test User"]);
+        let dir = temp.path().join(".tasks");
+        std::fs::create_dir(&dir).unwrap();
+
+        git(temp.path(), &["config", "user.email", "alice@example.com"]);
+        std::fs::write(dir.join("a-001.md"), "a").unwrap();
+        std::fs::write(temp.path().join("code.rs"), "").unwrap();
+        git(temp.path(), &["add", "."]);
+        git(temp.path(), &["commit", "-m", "alice"]);
+
+        git(temp.path(), &["config", "user.email", "bob@example.com"]);
+        std::fs::write(dir.join("a-001.md"), "edited").unwrap();
+        std::fs::write(dir.join("b-002.md"), "b").unwrap();
+        git(temp.path(), &["add", "."]);
+        git(temp.path(), &["commit", "-m", "bob"]);
+
+        let authors = file_authors(&dir).unwrap();
+        assert_eq!(
+            authors,
+            vec![
+                (
+                    PathBuf::from(".tasks/a-001.md"),
+                    "alice@example.com".to_string()
+                ),
+                (
+                    PathBuf::from(".tasks/b-002.md"),
+                    "bob@example.com".to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,142 @@
+//! Git hooks that keep tasks in step with the repository
+
+use crate::git::operations::GitError;
+use git2::Repository;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Hooks installed by `gittask hooks install`
+pub const HOOKS: &[&str] = &["post-merge", "post-rewrite", "pre-push"];
+
+/// Hooks whose failure must stop the git command, rather than be ignored
+const BLOCKING_HOOKS: &[&str] = &["pre-push"];
+
+/// Line identifying a hook script as ours
+const MARKER: &str = "# Installed by gittask";
+
+/// Directory git runs hooks from, honouring `core.hooksPath`
+fn hooks_dir(repo: &Repository) -> PathBuf {
+    let configured = repo
+        .config()
+        .ok()
+        .and_then(|c| c.get_path("core.hooksPath").ok());
+    match configured {
+        Some(path) if path.is_absolute() => path,
+        Some(path) => repo.workdir().unwrap_or(repo.path()).join(path),
+        None => repo.path().join("hooks"),
+    }
+}
+
+/// Shell script for a hook, delegating to `gittask hooks run`
+fn script(name: &str) -> String {
+    let on_failure = if BLOCKING_HOOKS.contains(&name) {
+        ""
+    } else {
+        " || true"
+    };
+    format!(
+        "#!/bin/sh\n{}\ncommand -v gittask >/dev/null 2>&1 || exit 0\ngittask hooks run {} \"$@\"{}\n",
+        MARKER, name, on_failure
+    )
+}
+
+/// Whether the file at `path` is a hook we installed
+fn is_ours(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|content| content.contains(MARKER))
+}
+
+/// Install the gittask hooks, returning the paths written
+///
+/// Existing hooks from other tools are left alone unless `force` is set.
+pub fn install(path: &Path, force: bool) -> Result<Vec<PathBuf>, GitError> {
+    let repo = Repository::discover(path)?;
+    let dir = hooks_dir(&repo);
+    fs::create_dir_all(&dir)?;
+
+    if !force
+        && let Some(existing) = HOOKS
+            .iter()
+            .find(|name| dir.join(name).exists() && !is_ours(&dir.join(name)))
+    {
+        return Err(GitError::HookExists(existing.to_string()));
+    }
+
+    let mut written = Vec::new();
+    for name in HOOKS {
+        let hook = dir.join(name);
+        fs::write(&hook, script(name))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&hook, fs::Permissions::from_mode(0o755))?;
+        }
+        written.push(hook);
+    }
+    Ok(written)
+}
+
+/// Remove the gittask hooks, returning the paths removed
+pub fn uninstall(path: &Path) -> Result<Vec<PathBuf>, GitError> {
+    let repo = Repository::discover(path)?;
+    let dir = hooks_dir(&repo);
+
+    let mut removed = Vec::new();
+    for name in HOOKS {
+        let hook = dir.join(name);
+        if is_ours(&hook) {
+            fs::remove_file(&hook)?;
+            removed.push(hook);
+        }
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_install_uninstall() {
+        let temp = TempDir::new().unwrap();
+        Repository::init(temp.path()).unwrap();
+        let dir = temp.path().join(".git").join("hooks");
+
+        let written = install(temp.path(), false).unwrap();
+        assert_eq!(written.len(), HOOKS.len());
+        let content = fs::read_to_string(dir.join("post-merge")).unwrap();
+        assert!(content.contains("gittask hooks run post-merge \"$@\" || true"));
+        let content = fs::read_to_string(dir.join("pre-push")).unwrap();
+        assert!(
+            content
+                .trim_end()
+                .ends_with("gittask hooks run pre-push \"$@\"")
+        );
+
+        // Reinstalling over our own hooks is fine
+        install(temp.path(), false).unwrap();
+
+        let removed = uninstall(temp.path()).unwrap();
+        assert_eq!(removed.len(), HOOKS.len());
+        assert!(!dir.join("post-merge").exists());
+    }
+
+    #[test]
+    fn test_install_keeps_foreign_hooks() {
+        let temp = TempDir::new().unwrap();
+        Repository::init(temp.path()).unwrap();
+        let dir = temp.path().join(".git").join("hooks");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("post-rewrite"), "#!/bin/sh\necho mine\n").unwrap();
+
+        assert!(matches!(
+            install(temp.path(), false),
+            Err(GitError::HookExists(name)) if name == "post-rewrite"
+        ));
+        assert!(uninstall(temp.path()).unwrap().is_empty());
+        assert!(dir.join("post-rewrite").exists());
+
+        install(temp.path(), true).unwrap();
+        assert!(is_ours(&dir.join("post-rewrite")));
+    }
+}
@@ -0,0 +1,292 @@
+//! Three-way merging of task files, backing the `gittask` merge driver
+//!
+//! Git hands the driver the common ancestor and both sides of a conflicting
+//! file. Frontmatter fields are merged one at a time: a field changed on one
+//! side only takes that change, and a field changed on both sides takes the
+//! value from the side updated last. List fields such as `tags` merge as sets,
+//! so additions from both sides are kept, as are comments. Only the
+//! description can end up with conflict markers.
+
+use crate::git::operations::GitError;
+use crate::models::{Task, parse_task, update_task_content};
+use crate::storage::scaffold::MERGE_DRIVER;
+use git2::{MergeFileInput, MergeFileOptions, Repository};
+use serde_json::{Map, Value};
+use std::path::Path;
+
+/// Fields merged as sets rather than taken whole from one side
+const SET_FIELDS: &[&str] = &[
+    "tags",
+    "blocked_by",
+    "remind",
+    "time_entries",
+    "refs",
+    "reopened",
+];
+
+/// Merged file content and whether it still needs manual resolution
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeResult {
+    pub content: String,
+    pub conflicts: bool,
+}
+
+/// Merge two versions of a task file against their common ancestor
+///
+/// Files that don't parse as tasks fall back to a plain text merge.
+pub fn merge_task_files(base: &str, ours: &str, theirs: &str) -> MergeResult {
+    let parsed = (parse_task(base), parse_task(ours), parse_task(theirs));
+    let (Ok(base_task), Ok(our_task), Ok(their_task)) = parsed else {
+        let (content, conflicts) = merge_text(base, ours, theirs);
+        return MergeResult { content, conflicts };
+    };
+
+    let (merged, conflicts) = merge_tasks(&base_task, &our_task, &their_task);
+    match update_task_content(ours, &merged) {
+        Ok(content) => MergeResult { content, conflicts },
+        Err(_) => {
+            let (content, _) = merge_text(base, ours, theirs);
+            MergeResult {
+                content,
+                conflicts: true,
+            }
+        }
+    }
+}
+
+/// Merge two versions of a task, returning whether the description conflicts
+pub fn merge_tasks(base: &Task, ours: &Task, theirs: &Task) -> (Task, bool) {
+    let theirs_newer = theirs.updated > ours.updated;
+    let fields = merge_fields(
+        &to_object(base),
+        &to_object(ours),
+        &to_object(theirs),
+        theirs_newer,
+    );
+    let mut merged: Task =
+        serde_json::from_value(Value::Object(fields)).unwrap_or_else(|_| ours.clone());
+
+    let (description, conflicts) =
+        merge_text(&base.description, &ours.description, &theirs.description);
+    merged.description = description;
+
+    // Comments are append-only: keep those from both sides, in time order
+    merged.comments = ours.comments.clone();
+    for comment in &theirs.comments {
+        if !merged.comments.contains(comment) {
+            merged.comments.push(comment.clone());
+        }
+    }
+    merged.comments.sort_by_key(|c| c.at);
+    (merged, conflicts)
+}
+
+fn to_object(task: &Task) -> Map<String, Value> {
+    match serde_json::to_value(task) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+fn merge_fields(
+    base: &Map<String, Value>,
+    ours: &Map<String, Value>,
+    theirs: &Map<String, Value>,
+    theirs_newer: bool,
+) -> Map<String, Value> {
+    let mut keys: Vec<&String> = ours.keys().chain(theirs.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    let mut merged = Map::new();
+    for key in keys {
+        let (b, o, t) = (base.get(key), ours.get(key), theirs.get(key));
+        let value = if o == t || t == b {
+            o
+        } else if o == b {
+            t
+        } else if SET_FIELDS.contains(&key.as_str()) {
+            merged.insert(key.clone(), merge_sets(b, o, t));
+            continue;
+        } else if theirs_newer {
+            t
+        } else {
+            o
+        };
+        if let Some(value) = value {
+            merged.insert(key.clone(), value.clone());
+        }
+    }
+    merged
+}
+
+/// Items from either side, minus those either side removed from the base
+fn merge_sets(base: Option<&Value>, ours: Option<&Value>, theirs: Option<&Value>) -> Value {
+    let items = |v: Option<&Value>| v.and_then(|v| v.as_array()).cloned().unwrap_or_default();
+    let (base, ours, theirs) = (items(base), items(ours), items(theirs));
+
+    let mut merged = ours.clone();
+    merged.extend(theirs.iter().filter(|item| !ours.contains(item)).cloned());
+    merged.retain(|item| !base.contains(item) || (ours.contains(item) && theirs.contains(item)));
+    Value::Array(merged)
+}
+
+/// Line-based three-way merge (diff3), marking conflicting hunks
+///
+/// Edits to separate parts of the text from each side both apply; only
+/// overlapping edits end up between conflict markers.
+pub fn merge_text(base: &str, ours: &str, theirs: &str) -> (String, bool) {
+    if ours == theirs || theirs == base {
+        return (ours.to_string(), false);
+    }
+    if ours == base {
+        return (theirs.to_string(), false);
+    }
+
+    // git2 only initialises libgit2 for calls that go through a repository
+    // or config, and merge_file reports whole-file conflicts without it
+    let _ = git2::Config::new();
+    let mut options = MergeFileOptions::new();
+    options.our_label("ours").their_label("theirs");
+    match git2::merge_file(
+        &input(base),
+        &input(ours),
+        &input(theirs),
+        Some(&mut options),
+    ) {
+        Ok(result) => (
+            String::from_utf8_lossy(result.content()).into_owned(),
+            !result.is_automergeable(),
+        ),
+        Err(e) => {
+            log::warn!("Text merge failed: {}", e);
+            let mut merged = String::from("<<<<<<< ours\n");
+            push_text(&mut merged, ours);
+            merged.push_str("=======\n");
+            push_text(&mut merged, theirs);
+            merged.push_str(">>>>>>> theirs\n");
+            (merged, true)
+        }
+    }
+}
+
+fn input(content: &str) -> MergeFileInput<'_> {
+    let mut input = MergeFileInput::new();
+    input.content(content.as_bytes());
+    input
+}
+
+fn push_text(out: &mut String, text: &str) {
+    out.push_str(text);
+    if !text.ends_with('\n') {
+        out.push('\n');
+    }
+}
+
+/// Register the `gittask` merge driver in the repository's local config
+///
+/// `.gitattributes` only names the driver; git needs this to know how to run it.
+pub fn install_driver(path: &Path) -> Result<(), GitError> {
+    let repo = Repository::discover(path)?;
+    let mut config = repo.config()?.open_level(git2::ConfigLevel::Local)?;
+    let section = format!("merge.{}", MERGE_DRIVER);
+    config.set_str(&format!("{}.name", section), "gittask task file merge")?;
+    config.set_str(
+        &format!("{}.driver", section),
+        "gittask merge-file %O %A %B",
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Comment, Priority, TaskKind, serialize_task};
+    use chrono::Duration;
+
+    fn base_task() -> Task {
+        let mut task = Task::new(1, TaskKind::Task, "Ship it");
+        task.tags = vec!["a".to_string(), "b".to_string()];
+        task.description = "Intro\n\nDetails\n\nOutro".to_string();
+        task
+    }
+
+    #[test]
+    fn test_merge_fields_and_tags() {
+        let base = base_task();
+        let mut ours = base.clone();
+        ours.priority = Priority::High;
+        ours.tags = vec!["a".to_string(), "c".to_string()];
+        ours.updated = base.updated + Duration::minutes(1);
+        let mut theirs = base.clone();
+        theirs.title = "Ship it now".to_string();
+        theirs.priority = Priority::Low;
+        theirs.tags.push("d".to_string());
+        theirs.updated = base.updated + Duration::minutes(2);
+        let comment = |minutes, text: &str| Comment {
+            at: base.updated + Duration::minutes(minutes),
+            author: None,
+            text: text.to_string(),
+        };
+        ours.comments = vec![comment(2, "Ours")];
+        theirs.comments = vec![comment(1, "Theirs")];
+
+        let (merged, conflicts) = merge_tasks(&base, &ours, &theirs);
+        assert!(!conflicts);
+        assert_eq!(merged.title, "Ship it now");
+        // Changed on both sides: the later update wins
+        assert_eq!(merged.priority, Priority::Low);
+        assert_eq!(merged.tags, vec!["a", "c", "d"]);
+        assert_eq!(merged.updated, theirs.updated);
+        assert_eq!(merged.description, base.description);
+        let comments: Vec<&str> = merged.comments.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(comments, ["Theirs", "Ours"]);
+    }
+
+    #[test]
+    fn test_merge_description_conflict() {
+        let base = base_task();
+        let mut ours = base.clone();
+        ours.description = "Intro\n\nOur details\n\nOutro".to_string();
+        let mut theirs = base.clone();
+        theirs.description = "Intro\n\nTheir details\n\nOutro".to_string();
+
+        let result = merge_task_files(
+            &serialize_task(&base).unwrap(),
+            &serialize_task(&ours).unwrap(),
+            &serialize_task(&theirs).unwrap(),
+        );
+        assert!(result.conflicts);
+        assert!(result.content.ends_with(
+            "Intro\n\n<<<<<<< ours\nOur details\n=======\nTheir details\n>>>>>>> theirs\n\nOutro\n"
+        ));
+        assert!(result.content.starts_with("---\nid: 1\n"));
+    }
+
+    #[test]
+    fn test_merge_text_separate_edits() {
+        let base = "Intro\n\nDetails\n\nMore\n\nOutro\n";
+        let ours = "Our intro\n\nDetails\n\nMore\n\nOutro\n";
+        let theirs = "Intro\n\nDetails\n\nMore\n\nTheir outro\n";
+        assert_eq!(
+            merge_text(base, ours, theirs),
+            (
+                "Our intro\n\nDetails\n\nMore\n\nTheir outro\n".to_string(),
+                false
+            )
+        );
+    }
+
+    #[test]
+    fn test_merge_text_one_sided() {
+        assert_eq!(merge_text("a\n", "a\n", "b\n"), ("b\n".to_string(), false));
+        assert_eq!(merge_text("a\n", "b\n", "a\n"), ("b\n".to_string(), false));
+        assert_eq!(
+            merge_text("a", "b", "c"),
+            (
+                "<<<<<<< ours\nb\n=======\nc\n>>>>>>> theirs\n".to_string(),
+                true
+            )
+        );
+    }
+}
@@ -1,5 +1,10 @@
 //! Git integration
 
+pub mod commit;
+pub mod history;
+pub mod hooks;
+pub mod merge;
 pub mod operations;
+pub mod release_notes;
 
 pub use operations::{GitError, GitOperations};
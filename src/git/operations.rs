@@ -1,7 +1,8 @@
 //! Git operations for task management
 
+use chrono::{DateTime, Utc};
 use git2::Repository;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 /// Errors related to git operations
@@ -13,6 +14,10 @@ pub enum GitError {
     NotInRepo,
     #[error("No HEAD commit found")]
     NoHead,
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("A {0} hook already exists (use --force to replace it)")]
+    HookExists(String),
 }
 
 /// Git operations helper
@@ -30,7 +35,7 @@ impl GitOperations {
     }
 
     /// Get the root directory of the git repository
-    pub fn repo_root(path: &Path) -> Result<std::path::PathBuf, GitError> {
+    pub fn repo_root(path: &Path) -> Result<PathBuf, GitError> {
         let repo = Repository::discover(path)?;
         repo.workdir()
             .map(|p| p.to_path_buf())
@@ -58,6 +63,199 @@ impl GitOperations {
     pub fn head_commit_optional(path: &Path) -> Option<String> {
         Self::head_commit_short(path).ok()
     }
+
+    /// The `user.email` git would commit with in this repository
+    pub fn user_email(path: &Path) -> Option<String> {
+        let repo = Repository::discover(path).ok()?;
+        let config = repo.config().ok()?;
+        config.get_string("user.email").ok()
+    }
+
+    /// The `user.name` git would commit with in this repository
+    pub fn user_name(path: &Path) -> Option<String> {
+        let repo = Repository::discover(path).ok()?;
+        let config = repo.config().ok()?;
+        config.get_string("user.name").ok()
+    }
+
+    /// Who is working in this repository: `user.email`, falling back to `user.name`
+    pub fn current_user(path: &Path) -> Option<String> {
+        Self::user_email(path).or_else(|| Self::user_name(path))
+    }
+
+    /// Read every file in `dir` as of the newest commit on HEAD made before `before`
+    ///
+    /// Returns (file name, content) pairs. Empty if no such commit exists or the
+    /// directory was not tracked at that point.
+    pub fn dir_snapshot_before(
+        dir: &Path,
+        before: DateTime<Utc>,
+    ) -> Result<Vec<(String, String)>, GitError> {
+        let repo = Repository::discover(dir)?;
+        let rel = Self::relative_to_workdir(&repo, dir)?;
+
+        let mut revwalk = repo.revwalk()?;
+        if revwalk.push_head().is_err() {
+            return Ok(Vec::new());
+        }
+        revwalk.set_sorting(git2::Sort::TIME)?;
+
+        for oid in revwalk {
+            let commit = repo.find_commit(oid?)?;
+            if commit.time().seconds() >= before.timestamp() {
+                continue;
+            }
+
+            let tree = commit.tree()?;
+            let subtree = match tree.get_path(&rel) {
+                Ok(entry) => entry.to_object(&repo)?.peel_to_tree()?,
+                Err(_) => return Ok(Vec::new()),
+            };
+
+            // Walk into subdirectories too so sharded layouts are covered
+            let mut files = Vec::new();
+            subtree.walk(git2::TreeWalkMode::PreOrder, |_, entry| {
+                if let (Ok(name), Ok(object)) = (entry.name(), entry.to_object(&repo))
+                    && let Some(blob) = object.as_blob()
+                {
+                    files.push((
+                        name.to_string(),
+                        String::from_utf8_lossy(blob.content()).to_string(),
+                    ));
+                }
+                git2::TreeWalkResult::Ok
+            })?;
+            return Ok(files);
+        }
+
+        Ok(Vec::new())
+    }
+
+    /// Read every file under `dir` as of commit `rev`
+    ///
+    /// Returns (path relative to `dir`, content) pairs, or `None` if the
+    /// directory wasn't tracked in that commit.
+    pub fn dir_at(dir: &Path, rev: &str) -> Result<Option<Vec<(PathBuf, String)>>, GitError> {
+        let repo = Repository::discover(dir)?;
+        let rel = Self::relative_to_workdir(&repo, dir)?;
+        let tree = repo.revparse_single(rev)?.peel_to_tree()?;
+        let subtree = match tree.get_path(&rel) {
+            Ok(entry) => entry.to_object(&repo)?.peel_to_tree()?,
+            Err(_) => return Ok(None),
+        };
+
+        let mut files = Vec::new();
+        subtree.walk(git2::TreeWalkMode::PreOrder, |parent, entry| {
+            if let (Ok(name), Ok(object)) = (entry.name(), entry.to_object(&repo))
+                && let Some(blob) = object.as_blob()
+            {
+                files.push((
+                    Path::new(parent).join(name),
+                    String::from_utf8_lossy(blob.content()).to_string(),
+                ));
+            }
+            git2::TreeWalkResult::Ok
+        })?;
+        Ok(Some(files))
+    }
+
+    /// `dir` relative to the repository's working directory
+    fn relative_to_workdir(repo: &Repository, dir: &Path) -> Result<PathBuf, GitError> {
+        let workdir = repo.workdir().ok_or(GitError::NotInRepo)?;
+        dir.canonicalize()
+            .ok()
+            .and_then(|d| {
+                let root = workdir.canonicalize().ok()?;
+                d.strip_prefix(root).ok().map(|p| p.to_path_buf())
+            })
+            .ok_or(GitError::NotInRepo)
+    }
+
+    /// Full hashes of the commits reachable from `to` but not from `from`
+    ///
+    /// With no `from`, every commit reachable from `to` is returned.
+    pub fn commits_between(
+        path: &Path,
+        from: Option<&str>,
+        to: &str,
+    ) -> Result<Vec<String>, GitError> {
+        let repo = Repository::discover(path)?;
+        let mut walk = repo.revwalk()?;
+        walk.push(repo.revparse_single(to)?.peel_to_commit()?.id())?;
+        if let Some(from) = from {
+            walk.hide(repo.revparse_single(from)?.peel_to_commit()?.id())?;
+        }
+
+        let mut commits = Vec::new();
+        for oid in walk {
+            commits.push(oid?.to_string());
+        }
+        Ok(commits)
+    }
+
+    /// Check whether a local branch has been merged into HEAD
+    ///
+    /// False while the branch is checked out, or if it doesn't exist. A
+    /// branch only counts once it has commits of its own: its tip must
+    /// differ from where it was created and from its merge base with the
+    /// HEAD before the latest commit, so a fresh branch isn't "merged" just
+    /// because something else was.
+    pub fn is_merged(path: &Path, branch: &str) -> Result<bool, GitError> {
+        let repo = Repository::discover(path)?;
+        let head = repo.head().map_err(|_| GitError::NoHead)?;
+        if head.is_branch() && head.shorthand().ok() == Some(branch) {
+            return Ok(false);
+        }
+        let Ok(branch) = repo.find_branch(branch, git2::BranchType::Local) else {
+            return Ok(false);
+        };
+
+        let head = head.peel_to_commit()?;
+        let tip = branch.get().peel_to_commit()?.id();
+        if head.id() != tip && !repo.graph_descendant_of(head.id(), tip)? {
+            return Ok(false);
+        }
+
+        // Never moved since `start --branch` created it
+        let refname = branch.get().name().unwrap_or_default().to_string();
+        if let Ok(reflog) = repo.reflog(&refname)
+            && let Some(created) = reflog.iter().next_back()
+            && created.id_new() == tip
+        {
+            return Ok(false);
+        }
+
+        // Already contained in HEAD before the merge that made it
+        if let Ok(before) = head.parent_id(0)
+            && repo.merge_base(before, tip)? == tip
+        {
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Switch the working tree to a local branch, creating it from HEAD if needed
+    ///
+    /// Returns true if the branch was created.
+    pub fn switch_branch(path: &Path, name: &str) -> Result<bool, GitError> {
+        let repo = Repository::discover(path)?;
+        let created = if repo.find_branch(name, git2::BranchType::Local).is_ok() {
+            false
+        } else {
+            let head = repo
+                .head()
+                .and_then(|h| h.peel_to_commit())
+                .map_err(|_| GitError::NoHead)?;
+            repo.branch(name, &head, false)?;
+            true
+        };
+
+        let refname = format!("refs/heads/{}", name);
+        let target = repo.revparse_single(&refname)?;
+        repo.checkout_tree(&target, Some(git2::build::CheckoutBuilder::new().safe()))?;
+        repo.set_head(&refname)?;
+        Ok(created)
+    }
 }
 
 #[cfg(test)]
@@ -159,4 +357,191 @@ mod tests {
 
         assert!(GitOperations::head_commit_optional(temp.path()).is_some());
     }
+
+    #[test]
+    fn test_dir_snapshot_before() {
+        let temp = setup_git_repo();
+        let dir = temp.path().join(".tasks");
+        std::fs::create_dir(&dir).unwrap();
+
+        // Nothing committed yet
+        let snapshot = GitOperations::dir_snapshot_before(&dir, Utc::now()).unwrap();
+        assert!(snapshot.is_empty());
+
+        std::fs::write(dir.join("task-001.md"), "v1").unwrap();
+        Command::new("git")
+            .args(["add", "."])
+            .current_dir(temp.path())
+            .output()
+            .unwrap();
+        Command::new("git")
+            .args(["commit", "-m", "Add task"])
+            .current_dir(temp.path())
+            .output()
+            .unwrap();
+
+        let later = Utc::now() + chrono::Duration::seconds(5);
+        let snapshot = GitOperations::dir_snapshot_before(&dir, later).unwrap();
+        assert_eq!(
+            snapshot,
+            vec![("task-001.md".to_string(), "v1".to_string())]
+        );
+
+        let earlier = Utc::now() - chrono::Duration::days(1);
+        assert!(
+            GitOperations::dir_snapshot_before(&dir, earlier)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_dir_at() {
+        let temp = setup_git_repo();
+        let dir = temp.path().join(".tasks");
+        std::fs::create_dir_all(dir.join("0a")).unwrap();
+        std::fs::write(temp.path().join("readme.txt"), "hi").unwrap();
+        let commit = |message: &str| {
+            Command::new("git")
+                .args(["add", "."])
+                .current_dir(temp.path())
+                .output()
+                .unwrap();
+            Command::new("git")
+                .args(["commit", "-m", message])
+                .current_dir(temp.path())
+                .output()
+                .unwrap();
+        };
+        commit("Initial commit");
+        assert_eq!(GitOperations::dir_at(&dir, "HEAD").unwrap(), None);
+
+        std::fs::write(dir.join("0a").join("task-010.md"), "v1").unwrap();
+        commit("Add task");
+        let first = GitOperations::head_commit(temp.path()).unwrap();
+        std::fs::write(dir.join("0a").join("task-010.md"), "v2").unwrap();
+        commit("Edit task");
+
+        // The committed version, not the working tree's
+        std::fs::write(dir.join("0a").join("task-010.md"), "v3").unwrap();
+        assert_eq!(
+            GitOperations::dir_at(&dir, &first).unwrap(),
+            Some(vec![(PathBuf::from("0a/task-010.md"), "v1".to_string())])
+        );
+        assert_eq!(
+            GitOperations::dir_at(&dir, "HEAD").unwrap(),
+            Some(vec![(PathBuf::from("0a/task-010.md"), "v2".to_string())])
+        );
+    }
+
+    #[test]
+    fn test_switch_branch() {
+        let temp = setup_git_repo();
+        assert!(matches!(
+            GitOperations::switch_branch(temp.path(), "task/1-test"),
+            Err(GitError::NoHead)
+        ));
+
+        std::fs::write(temp.path().join("test.txt"), "content").unwrap();
+        Command::new("git")
+            .args(["add", "test.txt"])
+            .current_dir(temp.path())
+            .output()
+            .unwrap();
+        Command::new("git")
+            .args(["commit", "-m", "Initial commit"])
+            .current_dir(temp.path())
+            .output()
+            .unwrap();
+
+        assert!(GitOperations::switch_branch(temp.path(), "task/1-test").unwrap());
+        let repo = GitOperations::repo(temp.path()).unwrap();
+        assert_eq!(repo.head().unwrap().shorthand().ok(), Some("task/1-test"));
+
+        // Switching again reuses the existing branch
+        assert!(!GitOperations::switch_branch(temp.path(), "task/1-test").unwrap());
+    }
+
+    #[test]
+    fn test_commits_between() {
+        let temp = setup_git_repo();
+        let commit = |msg: &str| {
+            std::fs::write(temp.path().join("test.txt"), msg).unwrap();
+            Command::new("git")
+                .args(["commit", "-am", msg])
+                .current_dir(temp.path())
+                .output()
+                .unwrap();
+        };
+        std::fs::write(temp.path().join("test.txt"), "").unwrap();
+        Command::new("git")
+            .args(["add", "test.txt"])
+            .current_dir(temp.path())
+            .output()
+            .unwrap();
+        commit("first");
+        let first = GitOperations::head_commit(temp.path()).unwrap();
+        commit("second");
+        commit("third");
+
+        let range = GitOperations::commits_between(temp.path(), Some(&first), "HEAD").unwrap();
+        assert_eq!(range.len(), 2);
+        assert!(!range.contains(&first));
+
+        let all = GitOperations::commits_between(temp.path(), None, "HEAD").unwrap();
+        assert_eq!(all.len(), 3);
+    }
+
+    #[test]
+    fn test_is_merged() {
+        let temp = setup_git_repo();
+        let git = |args: &[&str]| {
+            Command::new("git")
+                .args(args)
+                .current_dir(temp.path())
+                .output()
+                .unwrap();
+        };
+        std::fs::write(temp.path().join("test.txt"), "base").unwrap();
+        git(&["add", "test.txt"]);
+        git(&["commit", "-m", "base"]);
+        let main = GitOperations::repo(temp.path())
+            .unwrap()
+            .head()
+            .unwrap()
+            .shorthand()
+            .unwrap()
+            .to_string();
+
+        // A branch with no commits of its own isn't merged by merging
+        // something else
+        git(&["branch", "task/2-empty"]);
+        git(&["checkout", "-b", "other"]);
+        std::fs::write(temp.path().join("other.txt"), "other").unwrap();
+        git(&["add", "other.txt"]);
+        git(&["commit", "-m", "other"]);
+        git(&["checkout", &main]);
+        assert!(!GitOperations::is_merged(temp.path(), "task/2-empty").unwrap());
+        git(&["merge", "--no-ff", "-m", "merge other", "other"]);
+        assert!(!GitOperations::is_merged(temp.path(), "task/2-empty").unwrap());
+
+        git(&["checkout", "-b", "task/1-feature"]);
+        std::fs::write(temp.path().join("test.txt"), "feature").unwrap();
+        git(&["commit", "-am", "feature"]);
+        assert!(!GitOperations::is_merged(temp.path(), "task/1-feature").unwrap());
+
+        git(&["checkout", &main]);
+        assert!(!GitOperations::is_merged(temp.path(), "task/1-feature").unwrap());
+        git(&["merge", "--no-ff", "-m", "merge", "task/1-feature"]);
+        assert!(GitOperations::is_merged(temp.path(), "task/1-feature").unwrap());
+        assert!(!GitOperations::is_merged(temp.path(), "missing").unwrap());
+
+        // Fast-forward merges count too
+        git(&["checkout", "-b", "task/3-fast"]);
+        std::fs::write(temp.path().join("test.txt"), "fast").unwrap();
+        git(&["commit", "-am", "fast"]);
+        git(&["checkout", &main]);
+        git(&["merge", "--ff-only", "task/3-fast"]);
+        assert!(GitOperations::is_merged(temp.path(), "task/3-fast").unwrap());
+    }
 }
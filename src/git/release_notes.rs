@@ -0,0 +1,123 @@
+//! Release notes built from the tasks closed between two git refs
+
+use crate::git::operations::{GitError, GitOperations};
+use crate::models::{Resolution, Task};
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+/// Heading for tasks without tags
+const UNTAGGED: &str = "Other";
+
+/// Done tasks whose closing commit lies in `from..to`, oldest completion first
+pub fn closed_between<'a>(
+    repo_path: &Path,
+    tasks: &'a [Task],
+    from: Option<&str>,
+    to: &str,
+) -> Result<Vec<&'a Task>, GitError> {
+    let commits = GitOperations::commits_between(repo_path, from, to)?;
+    Ok(closed_in(tasks, &commits))
+}
+
+/// Done tasks closed by one of the given commits, oldest completion first
+pub fn closed_in<'a>(tasks: &'a [Task], commits: &[String]) -> Vec<&'a Task> {
+    // closed_commit holds an abbreviated hash, so compare on the short form
+    let short: HashSet<&str> = commits.iter().map(|c| abbreviate(c)).collect();
+
+    let mut closed: Vec<&Task> = tasks
+        .iter()
+        .filter(|t| t.completed.is_some() && t.resolution.is_none_or(|r| r == Resolution::Done))
+        .filter(|t| {
+            t.closed_commit
+                .as_deref()
+                .is_some_and(|c| short.contains(abbreviate(c)))
+        })
+        .collect();
+    closed.sort_by_key(|t| (t.completed, t.id));
+    closed
+}
+
+/// Render tasks as markdown, grouped under a heading per first tag
+pub fn render(title: &str, tasks: &[&Task]) -> String {
+    let mut groups: BTreeMap<&str, Vec<&Task>> = BTreeMap::new();
+    let mut untagged = Vec::new();
+    for task in tasks {
+        match task.tags.first() {
+            Some(tag) => groups.entry(tag.as_str()).or_default().push(task),
+            None => untagged.push(*task),
+        }
+    }
+
+    let mut out = format!("# {}\n", title);
+    if tasks.is_empty() {
+        out.push_str("\nNo completed tasks.\n");
+        return out;
+    }
+
+    let sections = groups
+        .into_iter()
+        .chain((!untagged.is_empty()).then_some((UNTAGGED, untagged)));
+    for (heading, tasks) in sections {
+        out.push_str(&format!("\n## {}\n\n", heading));
+        for task in tasks {
+            out.push_str(&format!("- {} (#{})\n", task.title, task.id));
+        }
+    }
+    out
+}
+
+fn abbreviate(hash: &str) -> &str {
+    hash.get(..7).unwrap_or(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TaskKind;
+
+    fn closed(id: u64, title: &str, tags: &[&str], commit: &str) -> Task {
+        let mut task = Task::new(id, TaskKind::Task, title);
+        task.tags = tags.iter().map(|t| t.to_string()).collect();
+        task.complete(Some(commit.to_string()));
+        task
+    }
+
+    #[test]
+    fn test_closed_in_matches_short_hashes() {
+        let mut wontfix = closed(3, "Rewrite in COBOL", &[], "abcdef1");
+        wontfix.resolution = Some(Resolution::Wontfix);
+        let tasks = vec![
+            closed(1, "Fix login", &["bug"], "abcdef1"),
+            closed(2, "Old work", &[], "1234567"),
+            wontfix,
+            Task::new(4, TaskKind::Task, "Still open"),
+        ];
+        let commits = vec!["abcdef1234567890abcdef1234567890abcdef12".to_string()];
+
+        let ids: Vec<u64> = closed_in(&tasks, &commits).iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![1]);
+    }
+
+    #[test]
+    fn test_render_groups_by_first_tag() {
+        let tasks = [
+            closed(1, "Fix login", &["bug", "auth"], "a"),
+            closed(2, "Dark mode", &["feature"], "a"),
+            closed(3, "Tidy docs", &[], "a"),
+            closed(4, "Fix crash", &["bug"], "a"),
+        ];
+        let refs: Vec<&Task> = tasks.iter().collect();
+
+        let notes = render("v1.2.0", &refs);
+        assert_eq!(
+            notes,
+            "# v1.2.0\n\n## bug\n\n- Fix login (#1)\n- Fix crash (#4)\n\n\
+             ## feature\n\n- Dark mode (#2)\n\n## Other\n\n- Tidy docs (#3)\n"
+        );
+    }
+
+    #[test]
+    fn test_render_empty() {
+        assert_eq!(render("Notes", &[]), "# Notes\n\nNo completed tasks.\n");
+    }
+}
@@ -2,12 +2,25 @@
 //!
 //! This library provides the core functionality for managing tasks stored as
 //! Markdown files with YAML frontmatter in a git repository.
+//!
+//! With default features off, only the models and storage (minus git
+//! history, code scanning, watching and importers) are built, which is
+//! enough to embed `FileStore` in another program, or to read tasks through
+//! `storage::MemoryStore` on targets like wasm32.
+
+// The MCP tool list is one large `json!` literal
+#![recursion_limit = "256"]
 
+#[cfg(feature = "cli")]
 pub mod cli;
+#[cfg(feature = "git")]
 pub mod git;
+#[cfg(feature = "mcp")]
 pub mod mcp;
 pub mod models;
 pub mod storage;
+#[cfg(feature = "web")]
+pub mod web;
 
-pub use models::{Priority, Task, TaskKind, TaskStatus};
+pub use models::{Priority, Resolution, Task, TaskKind, TaskStatus};
 pub use storage::{FileStore, ProjectRegistry, ProjectStatus, TaskFilter, TaskLocation, TaskStats};
@@ -1,51 +1,364 @@
 //! gittask CLI - Git-versioned task management
 
 use anyhow::Result;
-use clap::Parser;
+use clap::FromArgMatches;
 use gittask::cli::display::{
-    display_aggregated_task_list, display_projects, display_stats, display_task_detail,
-    display_task_list, error, success,
+    display_activity, display_aggregated_matches, display_aggregated_task_list, display_backlinks,
+    display_board, display_contexts, display_grep_matches, display_grouped_task_list,
+    display_history, display_matrix, display_plan, display_planned_changes, display_projects,
+    display_ranked_task_list, display_reminders, display_stats, display_subtasks, display_tag_tree,
+    display_tags, display_task_detail, display_task_links, display_task_list, display_task_tree,
+    display_templated, display_templates, display_validation_errors, error, set_date_display,
+    success, warning,
 };
-use gittask::cli::{Cli, Commands};
-use gittask::git::GitOperations;
+use gittask::cli::editor::{configured_editor, editor_command};
+use gittask::cli::help;
+use gittask::cli::{
+    Clearable, Cli, Commands, DepsAction, GraphFormat, HooksAction, ListFormat, OutputFormat,
+    RefAction, TagsAction,
+};
+use gittask::git::{GitOperations, commit, hooks, merge, release_notes};
 use gittask::models::Task;
 use gittask::storage::{
-    FileStore, ProjectRegistry, TaskFilter, TaskLocation, list_aggregated, resolve_qualified_id,
+    AggregatedTask, DependencyGraph, EisenhowerMatrix, EmbedConfig, ExportManifest, FileStore,
+    FileStoreError, ImportFormat, OrderMove, ProjectConfig, ProjectRegistry, ReviewAction,
+    SyncStatus, TaskFilter, TaskIndex, TaskLayout, TaskLocation, TaskPatch, TaskTemplate,
+    TaskWatcher, TimeWindow, activity_log, backlinks, board_columns, check_wip, delegate, doctor,
+    embed_section, export_tasks, find_projects, grep, group_by_namespace, import_github_project,
+    import_json, is_dry_run, is_mine, layout, list_aggregated, list_scopes, location,
+    plan_capacity, plan_prune, project_name, prune, rank_by_urgency, render_checklist,
+    resolve_links, resolve_qualified_id, review_queue, scaffold, scan_code, search_aggregated,
+    set_dry_run, set_read_only, set_strict, sort_by_order, sync, sync_once, sync_round, tag_counts,
+    tag_tree, take_planned_changes, task_creators, task_history, task_tree, upcoming_reminders,
+    urgency, wip_overloads, writes,
 };
-use std::io::{self, Write};
+use gittask::web::{WebOptions, run_web_server};
+use rand::seq::IndexedRandom;
+use regex::RegexBuilder;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Exit status when a command ran fine but its check failed (`--fail-if-any`,
+/// problems found by `doctor`); errors exit with 1 and usage errors with 2
+const EXIT_CHECK_FAILED: i32 = 3;
+
+/// A failed check, as opposed to an error running the command
+#[derive(Debug)]
+struct CheckFailed(String);
+
+impl std::fmt::Display for CheckFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for CheckFailed {}
+
+/// Report every problem `doctor::validate` finds, failing if there are any
+fn validate_tasks(store: &FileStore) -> Result<()> {
+    report_problems(&doctor::validate(store)?)
+}
+
+/// Validate the tasks directory of every commit a push sends
+///
+/// git passes `<local ref> <local sha> <remote ref> <remote sha>` lines on
+/// stdin, so the check sees `.tasks/` as committed rather than the working
+/// tree. Deleted refs (an all-zero sha) are skipped. Run by hand, with no
+/// input piped in, it validates the working tree instead.
+fn validate_pushed(location: &TaskLocation) -> Result<()> {
+    if io::stdin().is_terminal() {
+        return validate_tasks(&FileStore::new(location.clone()));
+    }
+    let tasks_dir = location
+        .tasks_dir
+        .strip_prefix(&location.root)
+        .unwrap_or(&location.tasks_dir);
+    let config_file = ProjectConfig::path(Path::new(""));
+
+    let mut pushed: Vec<String> = Vec::new();
+    for line in io::stdin().lines() {
+        if let Some(sha) = line?.split_whitespace().nth(1)
+            && !sha.bytes().all(|b| b == b'0')
+            && !pushed.iter().any(|s| s == sha)
+        {
+            pushed.push(sha.to_string());
+        }
+    }
+
+    let mut problems = Vec::new();
+    for sha in &pushed {
+        let Some(files) = GitOperations::dir_at(&location.tasks_dir, sha)? else {
+            continue;
+        };
+        let workflow = match files.iter().find(|(path, _)| *path == config_file) {
+            Some((_, content)) => ProjectConfig::parse(content)?.workflow,
+            None => Default::default(),
+        };
+        let files = files
+            .into_iter()
+            .filter(|(path, _)| layout::is_task_path(path))
+            .map(|(path, content)| (tasks_dir.join(path), content))
+            .collect();
+        let short = &sha[..sha.len().min(7)];
+        for mut problem in doctor::validate_files(files, &workflow) {
+            problem.path = PathBuf::from(format!("{}:{}", short, problem.path.display()));
+            problems.push(problem);
+        }
+    }
+    report_problems(&problems)
+}
+
+/// Print validation problems, failing the check if there are any
+fn report_problems(problems: &[doctor::FileProblem]) -> Result<()> {
+    if problems.is_empty() {
+        log::info!("All task files are valid");
+        return Ok(());
+    }
+    for problem in problems {
+        error(&problem.to_string());
+    }
+    Err(CheckFailed(format!("{} problem(s) in task files", problems.len())).into())
+}
+
+/// Hide tasks scheduled after today from a listing, unless `include_scheduled`
+fn available_on(include_scheduled: bool) -> Option<chrono::NaiveDate> {
+    (!include_scheduled).then(gittask::models::today)
+}
+
+/// Parse a status given on the command line, rejecting custom statuses that
+/// neither the project nor, with `global`, any registered project defines
+fn parse_status(name: &str, location: &TaskLocation, global: bool) -> Result<gittask::TaskStatus> {
+    let mut workflow = ProjectConfig::load(&location.tasks_dir)?.workflow;
+    if global {
+        workflow
+            .statuses
+            .extend(ProjectRegistry::load()?.workflow().statuses);
+    }
+    workflow.parse_status(name).map_err(|e| anyhow::anyhow!(e))
+}
+
+/// Fail the check with `--fail-if-any` when any task matched
+fn fail_if_any(enabled: bool, count: usize) -> Result<()> {
+    if enabled && count > 0 {
+        return Err(CheckFailed(format!("{} matching task(s)", count)).into());
+    }
+    Ok(())
+}
 
 fn main() -> Result<()> {
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
         .format(|buf, record| writeln!(buf, "{}", record.args()))
         .init();
 
-    let cli = Cli::parse();
+    let cli = Cli::from_arg_matches(&help::command().get_matches()).unwrap_or_else(|e| e.exit());
 
+    let dry_run = cli.dry_run;
     let result = run(cli);
+    if dry_run && result.is_ok() {
+        display_planned_changes(&take_planned_changes());
+    }
 
     if let Err(e) = &result {
-        error(&e.to_string());
+        match e.downcast_ref::<FileStoreError>() {
+            Some(FileStoreError::Invalid { title, errors }) => {
+                display_validation_errors(title, errors)
+            }
+            _ => error(&e.to_string()),
+        }
+        if e.is::<CheckFailed>() {
+            std::process::exit(EXIT_CHECK_FAILED);
+        }
         std::process::exit(1);
     }
 
     Ok(())
 }
 
-fn run(cli: Cli) -> Result<()> {
-    let location = if cli.global {
-        TaskLocation::global()?
+/// Fail for commands whose writes `--dry-run` can't hold back
+fn refuse_dry_run(command: &str) -> Result<()> {
+    if is_dry_run() {
+        anyhow::bail!("`gittask {}` can't be previewed with --dry-run", command);
+    }
+    Ok(())
+}
+
+/// Resolve the task location from the global targeting options
+fn resolve_location(cli: &Cli) -> Result<TaskLocation> {
+    if cli.global {
+        return Ok(TaskLocation::global()?);
+    }
+
+    if let Some(ref name) = cli.project {
+        let registry = ProjectRegistry::load()?;
+        let path = registry.resolve_project_strict(name)?;
+        return Ok(TaskLocation::find_project_from(&path)?);
+    }
+
+    if let Some(ref dir) = cli.dir {
+        // Absolute, so paths stored or shown later don't depend on the cwd
+        let dir = std::fs::canonicalize(dir)
+            .map_err(|e| anyhow::anyhow!("Can't use -C {}: {}", dir.display(), e))?;
+        return Ok(TaskLocation::find_project_from(&dir)?);
+    }
+
+    Ok(TaskLocation::find_project()?)
+}
+
+/// Resolve a task ID, either local to `location` or qualified (`project:3`)
+///
+/// The project registry is only read for qualified IDs. Their project must
+/// be named exactly or by a unique prefix; commands that only read use
+/// `resolve_read_id`, which also takes fuzzy names.
+fn resolve_id(id: &str, location: &TaskLocation) -> Result<(TaskLocation, u64)> {
+    resolve_id_matching(id, location, false)
+}
+
+/// Resolve a task ID for a command that only reads, like `resolve_id`
+fn resolve_read_id(id: &str, location: &TaskLocation) -> Result<(TaskLocation, u64)> {
+    resolve_id_matching(id, location, true)
+}
+
+fn resolve_id_matching(
+    id: &str,
+    location: &TaskLocation,
+    fuzzy: bool,
+) -> Result<(TaskLocation, u64)> {
+    let registry = if id.contains(':') {
+        ProjectRegistry::load()?
     } else {
-        TaskLocation::find_project()?
+        ProjectRegistry::fixed(Vec::new())
+    };
+    resolve_qualified_id(id, &registry, Some(location), fuzzy).map_err(|e| anyhow::anyhow!(e))
+}
+
+fn run(cli: Cli) -> Result<()> {
+    set_read_only(cli.read_only);
+    set_strict(cli.strict);
+    set_dry_run(cli.dry_run);
+    let dry_run = cli.dry_run;
+
+    // Help and man pages work outside any project
+    match cli.command {
+        Commands::Help { ref topic } => {
+            print!(
+                "{}",
+                help::help_text(topic.as_deref()).map_err(|e| anyhow::anyhow!(e))?
+            );
+            return Ok(());
+        }
+        Commands::Man { ref out_dir } => {
+            match out_dir {
+                Some(dir) => {
+                    help::write_man_pages(dir)?;
+                    if !dry_run {
+                        success(&format!("Wrote man pages to {}", dir.display()));
+                    }
+                }
+                None => std::io::stdout().write_all(&help::man_page()?)?,
+            }
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let location = resolve_location(&cli)?;
+    // A broken config.toml shouldn't lock the user out of every command,
+    // including the ones that would fix it
+    let display = match ProjectConfig::load(&location.tasks_dir) {
+        Ok(config) => {
+            for name in config.unknown_kinds() {
+                warning(&format!(
+                    "Ignoring [kind.{}] in config.toml: kinds are task, todo and idea",
+                    name
+                ));
+            }
+            config.display
+        }
+        Err(e) => {
+            warning(&format!("Ignoring config.toml: {}", e));
+            Default::default()
+        }
     };
+    gittask::models::set_timezone(display.offset().unwrap_or_default());
+    set_date_display(display);
 
     match cli.command {
-        Commands::Init => {
+        Commands::Init {
+            here,
+            templates,
+            commit,
+            scan,
+        } => {
+            refuse_dry_run("init")?;
+            if scan.is_some() && !cli.global {
+                anyhow::bail!("--scan registers projects, so it needs --global");
+            }
+            let location = if here {
+                match cli.dir {
+                    Some(ref dir) => TaskLocation::at(dir)?,
+                    None => TaskLocation::here()?,
+                }
+            } else if cli.global {
+                location
+            } else {
+                // Plain init always targets the repository root scope
+                TaskLocation::at(&TaskLocation::find_git_root(&location.root)?)?
+            };
+
             if location.exists() {
                 log::info!("Task directory already exists: {:?}", location.tasks_dir);
             } else {
+                FileStore::new(location.clone()).ensure_writable()?;
                 location.ensure_exists()?;
                 log::info!("Created task directory: {:?}", location.tasks_dir);
             }
+
+            FileStore::new(location.clone()).ensure_writable()?;
+            let created = scaffold(&location, templates)?;
+            for path in &created {
+                log::info!("Created {}", path.display());
+            }
+            if !location.is_global
+                && let Err(e) = merge::install_driver(&location.root)
+            {
+                warning(&format!("could not register the merge driver: {}", e));
+            }
+
+            if !created.is_empty() && !location.is_global {
+                let commit =
+                    commit || (io::stdin().is_terminal() && confirm("Commit the task scaffold?")?);
+                if commit {
+                    // Only the scaffold, not unrelated task edits
+                    let mut pending = commit::pending_changes(&location.tasks_dir)?;
+                    pending
+                        .paths
+                        .retain(|p| created.iter().any(|c| c.ends_with(p)));
+                    let hash = commit::commit_paths(
+                        &location.root,
+                        &pending.paths,
+                        "chore(tasks): initialize task tracking",
+                    )?;
+                    success(&format!("[{:.7}] Committed the task scaffold", hash));
+                }
+            }
+
+            if let Some(dir) = scan {
+                let mut registry = ProjectRegistry::load()?;
+                let found = find_projects(&dir)?;
+                if found.is_empty() {
+                    log::info!(
+                        "No projects with a .tasks directory under {}",
+                        dir.display()
+                    );
+                }
+                for project in found {
+                    if registry.link(&project)? {
+                        success(&format!("Linked project: {}", project.display()));
+                    }
+                }
+            }
         }
 
         Commands::Add {
@@ -54,7 +367,15 @@ fn run(cli: Cli) -> Result<()> {
             description,
             priority,
             due,
+            scheduled,
+            remind,
+            estimate,
             tags,
+            external_id,
+            assignee,
+            context,
+            template,
+            parent,
         } => {
             let store = FileStore::new(location.clone());
 
@@ -62,7 +383,7 @@ fn run(cli: Cli) -> Result<()> {
                 location.ensure_exists()?;
             }
 
-            let mut task = Task::new(0, kind, &title);
+            let mut task = store.new_task(kind, &title, template.as_deref())?;
 
             if let Some(desc) = description {
                 task.description = desc;
@@ -72,14 +393,49 @@ fn run(cli: Cli) -> Result<()> {
                 task.priority = p;
             }
 
-            task.due = due;
-            task.tags = tags;
+            if due.is_some() {
+                task.due = due;
+            }
+
+            if scheduled.is_some() {
+                task.scheduled = scheduled;
+            }
+
+            if !remind.is_empty() {
+                task.remind = remind;
+                if task.due.is_none() {
+                    warning("reminders only fire once the task has a due date");
+                }
+            }
+
+            if estimate.is_some() {
+                task.estimate = estimate;
+            }
+
+            for tag in &tags {
+                task.add_tag(tag);
+            }
+
+            task.external_id = external_id;
+            task.assignee = assignee;
+
+            if context.is_some() {
+                task.context = context;
+            }
+            task.parent = parent;
 
             let created = store.create(task)?;
-            success(&format!(
-                "Created {} #{}: {}",
-                created.kind, created.id, created.title
-            ));
+            if dry_run {
+                println!(
+                    "Would create {} #{}: {}",
+                    created.kind, created.id, created.title
+                );
+            } else {
+                success(&format!(
+                    "Created {} #{}: {}",
+                    created.kind, created.id, created.title
+                ));
+            }
         }
 
         Commands::List {
@@ -87,80 +443,449 @@ fn run(cli: Cli) -> Result<()> {
             status,
             priority,
             tags,
+            context,
+            group_by,
             include_archived,
+            completed_since,
+            external_id,
+            assignee,
+            unblocked,
+            scheduled_before,
+            scheduled_after,
+            include_scheduled,
+            due_soon,
+            all_scopes,
+            mine,
+            by_urgency,
+            ordered,
+            tree,
+            watch,
+            format,
+            template,
+            fail_if_any: fail,
         } => {
+            let template = match format {
+                ListFormat::Table => None,
+                ListFormat::Template => Some(template.ok_or_else(|| {
+                    anyhow::anyhow!("--format template needs a --template to print")
+                })?),
+            };
+            let show_aggregated = |tasks: &[AggregatedTask]| match &template {
+                Some(template) => display_templated(
+                    template,
+                    tasks.iter().map(|t| (t.project.as_str(), &t.task)),
+                ),
+                None => display_aggregated_task_list(tasks),
+            };
+            let status = status
+                .map(|s| parse_status(&s, &location, cli.global))
+                .transpose()?;
             let filter = TaskFilter {
                 kind,
                 status,
                 priority,
                 tags,
+                context,
                 include_archived,
+                completed_since,
+                external_id,
+                assignee,
+                unblocked,
+                scheduled_before,
+                scheduled_after,
+                available_on: available_on(
+                    include_scheduled || scheduled_before.is_some() || scheduled_after.is_some(),
+                ),
+            };
+
+            let me = if mine {
+                Some(GitOperations::user_email(&location.root).ok_or_else(|| {
+                    anyhow::anyhow!("--mine needs git config user.email to be set")
+                })?)
+            } else {
+                None
+            };
+            let due_soon_days = if due_soon {
+                Some(
+                    config_or_default(&location.tasks_dir)
+                        .display
+                        .due_soon_days(),
+                )
+            } else {
+                None
+            };
+            let keep_due_soon = |task: &Task| {
+                due_soon_days.is_none_or(|days| task.is_due_soon(chrono::Utc::now(), days))
             };
 
             // If global mode and registry has projects, use aggregated view
             if cli.global {
                 let registry = ProjectRegistry::load()?;
                 if !registry.is_empty() {
-                    let tasks = list_aggregated(&registry, &filter)?;
-                    display_aggregated_task_list(&tasks);
-                    return Ok(());
+                    let mut tasks = list_aggregated(&registry, &filter)?;
+                    if let Some(ref me) = me {
+                        retain_mine_aggregated(&mut tasks, me);
+                    }
+                    tasks.retain(|t| keep_due_soon(&t.task));
+                    if by_urgency {
+                        let config = config_or_default(&location.tasks_dir).urgency;
+                        let now = chrono::Utc::now();
+                        tasks.sort_by(|a, b| {
+                            urgency(&b.task, &config, now)
+                                .total_cmp(&urgency(&a.task, &config, now))
+                        });
+                    }
+                    pinned_first(&mut tasks, |t| &t.task);
+                    show_aggregated(&tasks);
+                    return fail_if_any(fail, tasks.len());
+                }
+            }
+
+            if all_scopes {
+                let mut tasks = list_scopes(&location, &filter)?;
+                if let Some(ref me) = me {
+                    retain_mine_aggregated(&mut tasks, me);
                 }
+                tasks.retain(|t| keep_due_soon(&t.task));
+                pinned_first(&mut tasks, |t| &t.task);
+                show_aggregated(&tasks);
+                return fail_if_any(fail, tasks.len());
             }
 
+            let list_filtered = |store: &FileStore| -> Result<Vec<Task>> {
+                let mut tasks = store.list(&filter)?;
+                if let Some(ref me) = me {
+                    let creators = task_creators(store);
+                    tasks.retain(|t| is_mine(&creators, t, me));
+                }
+                tasks.retain(keep_due_soon);
+                if ordered {
+                    sort_by_order(&mut tasks);
+                }
+                pinned_first(&mut tasks, |t| t);
+                Ok(tasks)
+            };
+
             // Otherwise, use regular listing
+            auto_prune(&location)?;
+            if watch {
+                let mut watcher = TaskWatcher::new(&location)?;
+                let store = FileStore::new(location);
+                loop {
+                    // Clear the screen and move the cursor home before redrawing
+                    print!("\x1B[2J\x1B[H");
+                    let tasks = list_filtered(&store)?;
+                    if tree {
+                        display_task_tree(&task_tree(tasks));
+                    } else {
+                        display_task_list(&tasks);
+                    }
+                    watcher.recv()?;
+                }
+            }
+
+            let store = FileStore::new(location);
+            let tasks = list_filtered(&store)?;
+            let count = tasks.len();
+            let project = project_name(&store.location().root);
+            if by_urgency {
+                let config = ProjectConfig::load(&store.location().tasks_dir)?.urgency;
+                let mut ranked = rank_by_urgency(tasks, &config, chrono::Utc::now());
+                pinned_first(&mut ranked, |(t, _)| t);
+                match &template {
+                    Some(template) => display_templated(
+                        template,
+                        ranked.iter().map(|(t, _)| (project.as_str(), t)),
+                    ),
+                    None => display_ranked_task_list(&ranked),
+                }
+            } else if let Some(namespace) = group_by {
+                let groups = group_by_namespace(tasks, &namespace);
+                display_grouped_task_list(&groups, &namespace);
+            } else if tree {
+                display_task_tree(&task_tree(tasks));
+            } else if let Some(template) = &template {
+                display_templated(template, tasks.iter().map(|t| (project.as_str(), t)));
+            } else {
+                display_task_list(&tasks);
+            }
+            fail_if_any(fail, count)?;
+        }
+
+        Commands::Count {
+            kind,
+            status,
+            priority,
+            tags,
+            context,
+            include_archived,
+            assignee,
+            unblocked,
+            include_scheduled,
+            fail_if_any: fail,
+        } => {
+            let status = status
+                .map(|s| parse_status(&s, &location, cli.global))
+                .transpose()?;
+            let filter = TaskFilter {
+                kind,
+                status,
+                priority,
+                tags,
+                context,
+                include_archived,
+                assignee,
+                unblocked,
+                available_on: available_on(include_scheduled),
+                ..Default::default()
+            };
+
+            let registry = if cli.global {
+                Some(ProjectRegistry::load()?)
+            } else {
+                None
+            };
+            let count = match registry.filter(|r| !r.is_empty()) {
+                Some(registry) => list_aggregated(&registry, &filter)?.len(),
+                None => FileStore::new(location).iter(&filter)?.count(),
+            };
+            println!("{}", count);
+            fail_if_any(fail, count)?;
+        }
+
+        Commands::Next {
+            count,
+            kind,
+            tags,
+            context,
+        } => {
+            let store = FileStore::new(location);
+            let graph = DependencyGraph::new(store.list(&TaskFilter {
+                include_archived: true,
+                ..Default::default()
+            })?);
+            let filter = TaskFilter {
+                kind,
+                tags,
+                context,
+                available_on: available_on(false),
+                ..Default::default()
+            };
+            let ready: Vec<Task> = graph
+                .ready()
+                .into_iter()
+                .filter(|t| filter.matches(t))
+                .cloned()
+                .collect();
+
+            let config = ProjectConfig::load(&store.location().tasks_dir)?.urgency;
+            let mut ranked = rank_by_urgency(ready, &config, chrono::Utc::now());
+            ranked.truncate(count);
+            display_ranked_task_list(&ranked);
+        }
+
+        Commands::Random {
+            kind,
+            priority,
+            tags,
+            context,
+        } => {
+            let filter = TaskFilter {
+                kind,
+                priority,
+                tags,
+                context,
+                available_on: available_on(false),
+                ..Default::default()
+            };
+
+            let store = FileStore::new(location);
+            let open: Vec<Task> = store.iter(&filter)?.filter(|t| t.is_open()).collect();
+
+            match open.choose(&mut rand::rng()) {
+                Some(task) => display_task_detail(task),
+                None => log::info!("No open tasks found."),
+            }
+        }
+
+        Commands::Grep {
+            pattern,
+            ignore_case,
+            tags,
+            status,
+        } => {
+            let regex = RegexBuilder::new(&pattern)
+                .case_insensitive(ignore_case)
+                .build()?;
+            let status = status
+                .map(|s| parse_status(&s, &location, cli.global))
+                .transpose()?;
+            let filter = TaskFilter {
+                status,
+                tags,
+                include_archived: status == Some(gittask::TaskStatus::Archived),
+                ..Default::default()
+            };
+
+            if cli.global {
+                let registry = ProjectRegistry::load()?;
+                if !registry.is_empty() {
+                    let matches = search_aggregated(&registry, &regex, &filter)?;
+                    display_aggregated_matches(&matches);
+                    return Ok(());
+                }
+            }
+
             let store = FileStore::new(location);
-            let tasks = store.list(&filter)?;
-            display_task_list(&tasks);
+            let matches = grep(&store, &regex, &filter)?;
+            display_grep_matches(&matches);
         }
 
         Commands::Show { id } => {
-            let registry = ProjectRegistry::load().ok();
-            let (resolved_location, task_id) = resolve_qualified_id(
-                &id,
-                registry.as_ref().unwrap_or(&ProjectRegistry::load()?),
-                Some(&location),
-            )
-            .map_err(|e| anyhow::anyhow!(e))?;
+            let (resolved_location, task_id) = resolve_read_id(&id, &location)?;
 
+            // Without the registry only links within the project resolve
+            let registry = ProjectRegistry::load().unwrap_or_else(|e| {
+                warning(&format!("Not resolving links to other projects: {}", e));
+                ProjectRegistry::fixed(Vec::new())
+            });
             let store = FileStore::new(resolved_location);
             let task = store.read(task_id)?;
             display_task_detail(&task);
+            display_subtasks(&store.children(task.id)?);
+            display_task_links(&resolve_links(&task.description, &store, &registry));
+        }
+
+        Commands::Backlinks { id } => {
+            let registry = ProjectRegistry::load()?;
+            let (resolved_location, task_id) =
+                resolve_qualified_id(&id, &registry, Some(&location), true)
+                    .map_err(|e| anyhow::anyhow!(e))?;
+
+            let store = FileStore::new(resolved_location);
+            store.read(task_id)?;
+            display_backlinks(&backlinks(&store, task_id, &registry)?);
+        }
+
+        Commands::Open { id, editor, reveal } => {
+            let (resolved_location, task_id) = resolve_id(&id, &location)?;
+
+            let store = FileStore::new(resolved_location);
+            let path = store.find_task_file(task_id)?;
+
+            if editor {
+                let editor = configured_editor();
+                let status = editor_command(&editor)
+                    .map_err(|e| anyhow::anyhow!(e))?
+                    .arg(&path)
+                    .status()
+                    .map_err(|e| anyhow::anyhow!("Could not run editor '{}': {}", editor, e))?;
+                if !status.success() {
+                    anyhow::bail!("Editor '{}' exited with {}", editor, status);
+                }
+            } else if reveal {
+                reveal_in_file_manager(&path)?;
+            } else {
+                println!("{}", path.display());
+            }
         }
 
         Commands::Complete { ids } => {
-            let registry = ProjectRegistry::load().ok();
+            // One store per project so ID lookups are cached across the batch
+            let mut stores: HashMap<PathBuf, FileStore> = HashMap::new();
+            let mut completed = Vec::new();
 
             for id_str in ids {
-                let (resolved_location, task_id) = resolve_qualified_id(
-                    &id_str,
-                    registry.as_ref().unwrap_or(&ProjectRegistry::load()?),
-                    Some(&location),
-                )
-                .map_err(|e| anyhow::anyhow!(e))?;
+                let (resolved_location, task_id) = resolve_id(&id_str, &location)?;
 
-                let store = FileStore::new(resolved_location.clone());
+                let store = stores
+                    .entry(resolved_location.tasks_dir.clone())
+                    .or_insert_with(|| FileStore::new(resolved_location.clone()));
 
                 // Get current git commit from the resolved project
                 let commit = GitOperations::head_commit_optional(&resolved_location.root);
 
                 let mut task = store.read(task_id)?;
+                warn_if_claimed(store, &task);
                 task.complete(commit);
                 store.update(&task)?;
                 success(&format!("Completed #{}: {}", task.id, task.title));
+                offer_to_complete_source(&task)?;
+                completed.push((resolved_location.tasks_dir, task.id));
+            }
+
+            // After the whole batch, so completing a parent with its subtasks doesn't warn
+            for (tasks_dir, id) in completed {
+                warn_open_subtasks(&stores[&tasks_dir], id)?;
+            }
+        }
+
+        Commands::Close {
+            ids,
+            reason,
+            commit: commit_after,
+        } => {
+            if commit_after {
+                refuse_dry_run("close --commit")?;
+            }
+            // One store per project, remembering which tasks were closed there
+            let mut closed: BTreeMap<PathBuf, (FileStore, Vec<u64>)> = BTreeMap::new();
+
+            for id_str in ids {
+                let (resolved_location, task_id) = resolve_id(&id_str, &location)?;
+
+                let (store, closed_ids) = closed
+                    .entry(resolved_location.tasks_dir.clone())
+                    .or_insert_with(|| (FileStore::new(resolved_location.clone()), Vec::new()));
+                let commit = GitOperations::head_commit_optional(&resolved_location.root);
+
+                let task = store.read(task_id)?;
+                warn_if_claimed(store, &task);
+                let patch = TaskPatch {
+                    close: Some((commit, reason)),
+                    ..Default::default()
+                };
+                let task = store.patch(task_id, &patch, Some(task.updated))?;
+                closed_ids.push(task.id);
+                success(&format!("Closed #{} ({}): {}", task.id, reason, task.title));
+                offer_to_complete_source(&task)?;
+            }
+
+            for (store, closed_ids) in closed.values() {
+                for &id in closed_ids {
+                    warn_open_subtasks(store, id)?;
+                }
+            }
+
+            if commit_after {
+                for (tasks_dir, (store, closed_ids)) in &closed {
+                    let pending = commit::pending_changes(tasks_dir)?.for_tasks(closed_ids);
+                    if pending.is_empty() {
+                        continue;
+                    }
+                    let message = pending.message();
+                    let hash =
+                        commit::commit_paths(&store.location().root, &pending.paths, &message)?;
+                    success(&format!(
+                        "[{:.7}] {}",
+                        hash,
+                        message.lines().next().unwrap_or_default()
+                    ));
+                }
             }
         }
 
-        Commands::Status { id, status } => {
-            let registry = ProjectRegistry::load().ok();
-            let (resolved_location, task_id) = resolve_qualified_id(
-                &id,
-                registry.as_ref().unwrap_or(&ProjectRegistry::load()?),
-                Some(&location),
-            )
-            .map_err(|e| anyhow::anyhow!(e))?;
+        Commands::Status { id, status, strict } => {
+            let (resolved_location, task_id) = resolve_id(&id, &location)?;
 
+            let status = parse_status(&status, &resolved_location, false)?;
             let store = FileStore::new(resolved_location.clone());
             let mut task = store.read(task_id)?;
+            warn_if_claimed(&store, &task);
+
+            if status == gittask::TaskStatus::InProgress {
+                enforce_wip(&store, &task, strict)?;
+            }
 
             // If completing, capture git commit from the resolved project
             if status == gittask::TaskStatus::Completed
@@ -168,78 +893,326 @@ fn run(cli: Cli) -> Result<()> {
             {
                 let commit = GitOperations::head_commit_optional(&resolved_location.root);
                 task.closed_commit = commit;
+                task.completed = Some(chrono::Utc::now());
+            } else if status.is_open() {
+                task.reopen(status, chrono::Utc::now());
             }
 
+            let completing = status == gittask::TaskStatus::Completed
+                && task.status != gittask::TaskStatus::Completed;
             task.status = status;
             task.touch();
             store.update(&task)?;
             success(&format!("Set #{} status to {}", task.id, task.status));
+            if completing {
+                offer_to_complete_source(&task)?;
+                warn_open_subtasks(&store, task.id)?;
+            }
         }
 
-        Commands::Update {
-            id,
-            title,
-            description,
-            priority,
-            due,
-            tags,
-        } => {
-            let registry = ProjectRegistry::load().ok();
-            let (resolved_location, task_id) = resolve_qualified_id(
-                &id,
-                registry.as_ref().unwrap_or(&ProjectRegistry::load()?),
-                Some(&location),
-            )
-            .map_err(|e| anyhow::anyhow!(e))?;
+        Commands::Reopen { id, in_progress } => {
+            let (resolved_location, task_id) = resolve_id(&id, &location)?;
 
             let store = FileStore::new(resolved_location);
             let mut task = store.read(task_id)?;
+            let status = if in_progress {
+                enforce_wip(&store, &task, false)?;
+                gittask::TaskStatus::InProgress
+            } else {
+                gittask::TaskStatus::Pending
+            };
 
-            if let Some(t) = title {
-                task.title = t;
+            if !task.reopen(status, chrono::Utc::now()) {
+                anyhow::bail!("#{} is already open ({})", task.id, task.status);
             }
+            store.update(&task)?;
+            success(&format!(
+                "Reopened #{} as {}: {}",
+                task.id, task.status, task.title
+            ));
+        }
 
-            if let Some(d) = description {
-                task.description = d;
-            }
+        Commands::Pin { id } => set_pinned(&location, &id, true)?,
 
-            if let Some(p) = priority {
-                task.priority = p;
-            }
+        Commands::Unpin { id } => set_pinned(&location, &id, false)?,
+
+        Commands::MoveUp { id } => move_in_order(&location, &id, OrderMove::Up)?,
 
-            if let Some(d) = due {
-                task.due = Some(d);
+        Commands::MoveDown { id } => move_in_order(&location, &id, OrderMove::Down)?,
+
+        Commands::Start { id, branch, strict } => {
+            if branch {
+                refuse_dry_run("start --branch")?;
             }
+            let (resolved_location, task_id) = resolve_id(&id, &location)?;
+
+            let store = FileStore::new(resolved_location.clone());
+            let task = store.read(task_id)?;
+            warn_if_claimed(&store, &task);
+            enforce_wip(&store, &task, strict)?;
 
-            if let Some(t) = tags {
-                task.tags = t;
+            let mut patch = TaskPatch {
+                start: Some(chrono::Utc::now()),
+                ..Default::default()
+            };
+            if branch {
+                let name = task.branch.clone().unwrap_or_else(|| task.branch_name());
+                let created = GitOperations::switch_branch(&resolved_location.root, &name)?;
+                if created {
+                    success(&format!("Created and switched to branch {}", name));
+                } else {
+                    success(&format!("Switched to branch {}", name));
+                }
+                patch.branch = Some(Some(name));
             }
 
-            task.touch();
-            store.update(&task)?;
-            success(&format!("Updated #{}: {}", task.id, task.title));
+            let started = task.active_session().is_none();
+            let task = store.patch(task_id, &patch, Some(task.updated))?;
+
+            if started {
+                success(&format!("Started #{}: {}", task.id, task.title));
+            } else {
+                success(&format!("#{} is already running", task.id));
+            }
         }
 
-        Commands::Delete { id, force } => {
-            let registry = ProjectRegistry::load().ok();
-            let (resolved_location, task_id) = resolve_qualified_id(
-                &id,
-                registry.as_ref().unwrap_or(&ProjectRegistry::load()?),
-                Some(&location),
-            )
-            .map_err(|e| anyhow::anyhow!(e))?;
+        Commands::Claim { id, release, force } => {
+            let (resolved_location, task_id) = resolve_id(&id, &location)?;
 
+            let me = GitOperations::current_user(&resolved_location.root).ok_or_else(|| {
+                anyhow::anyhow!("claiming needs git config user.email or user.name to be set")
+            })?;
             let store = FileStore::new(resolved_location);
+            let mut task = store.read(task_id)?;
 
-            if !force {
-                let task = store.read(task_id)?;
-                print!("Delete #{} '{}'? [y/N] ", task.id, task.title);
-                io::stdout().flush()?;
+            if !force && let Some(claimant) = task.claimed_by_other(Some(&me)) {
+                anyhow::bail!(
+                    "#{} is claimed by {}; use --force to {}",
+                    task.id,
+                    claimant,
+                    if release {
+                        "release it"
+                    } else {
+                        "take it over"
+                    }
+                );
+            }
+
+            if release {
+                if !task.release_claim() {
+                    anyhow::bail!("#{} is not claimed", task.id);
+                }
+                task.touch();
+                store.update(&task)?;
+                success(&format!("Released #{}: {}", task.id, task.title));
+            } else {
+                task.claim(me, chrono::Utc::now());
+                task.touch();
+                store.update(&task)?;
+                success(&format!("Claimed #{}: {}", task.id, task.title));
+            }
+        }
+
+        Commands::Ref { action } => {
+            let (id, patch) = match action {
+                RefAction::Add { id, reference } => (
+                    id,
+                    TaskPatch {
+                        add_refs: vec![reference],
+                        ..Default::default()
+                    },
+                ),
+                RefAction::Remove { id, reference } => (
+                    id,
+                    TaskPatch {
+                        remove_refs: vec![reference],
+                        ..Default::default()
+                    },
+                ),
+            };
+            let (resolved_location, task_id) = resolve_id(&id, &location)?;
+
+            for reference in &patch.add_refs {
+                if let Some(problem) = doctor::check_ref(&resolved_location.root, reference) {
+                    warning(&format!("{}: {}", reference, problem));
+                }
+            }
+            let store = FileStore::new(resolved_location);
+            let mut task = store.read(task_id)?;
+            let before = task.refs.clone();
+            patch.apply(&mut task);
+            if task.refs == before {
+                log::info!("#{} refs unchanged", task.id);
+                return Ok(());
+            }
+            let task = store.patch(task_id, &patch, Some(task.updated))?;
+            success(&format!("Refs of #{}: {}", task.id, task.refs.join(", ")));
+        }
+
+        Commands::Delegate { id, target } => {
+            let registry = ProjectRegistry::load()?;
+            let (source_location, task_id) =
+                resolve_qualified_id(&id, &registry, Some(&location), false)
+                    .map_err(|e| anyhow::anyhow!(e))?;
+            let target_location =
+                TaskLocation::find_project_from(&registry.resolve_project_strict(&target)?)?;
+
+            let mirror = delegate(
+                &FileStore::new(source_location),
+                task_id,
+                &FileStore::new(target_location.clone()),
+            )?;
+            success(&format!(
+                "Delegated #{} to {}:{}: {}",
+                task_id,
+                project_name(&target_location.root),
+                mirror.id,
+                mirror.title
+            ));
+        }
+
+        Commands::Deps { action } => match action {
+            DepsAction::Add { id, blocker } => {
+                let (resolved_location, task_id) = resolve_id(&id, &location)?;
+                let task = FileStore::new(resolved_location).add_blocker(task_id, blocker)?;
+                success(&format!("#{} now waits on #{}", task.id, blocker));
+            }
+            DepsAction::Remove { id, blocker } => {
+                let (resolved_location, task_id) = resolve_id(&id, &location)?;
+                let patch = TaskPatch {
+                    remove_blockers: vec![blocker],
+                    ..Default::default()
+                };
+                let task = FileStore::new(resolved_location).patch(task_id, &patch, None)?;
+                success(&format!("#{} no longer waits on #{}", task.id, blocker));
+            }
+            DepsAction::Graph { format } => {
+                let store = FileStore::new(location);
+                let graph = DependencyGraph::new(store.list(&TaskFilter {
+                    include_archived: true,
+                    ..Default::default()
+                })?);
+                match format {
+                    GraphFormat::Dot => print!("{}", graph.to_dot()),
+                    GraphFormat::Text => {
+                        for (blocker, task) in graph.edges() {
+                            println!(
+                                "#{} {} -> #{} {}",
+                                blocker.id, blocker.title, task.id, task.title
+                            );
+                        }
+                    }
+                }
+            }
+        },
+
+        Commands::Stop { id } => {
+            let (resolved_location, task_id) = resolve_id(&id, &location)?;
+
+            let store = FileStore::new(resolved_location);
+            let task = store.read(task_id)?;
+            let now = chrono::Utc::now();
+            let Some(session) = task.active_session().map(|s| s.duration(now)) else {
+                anyhow::bail!("#{} has no running session", task.id);
+            };
+            let patch = TaskPatch {
+                stop: Some(now),
+                ..Default::default()
+            };
+            let task = store.patch(task_id, &patch, Some(task.updated))?;
+            success(&format!(
+                "Stopped #{} after {}m ({}m total)",
+                task.id,
+                session.num_minutes(),
+                task.time_spent(now).num_minutes()
+            ));
+        }
 
-                let mut input = String::new();
-                io::stdin().read_line(&mut input)?;
+        Commands::Update {
+            id,
+            title,
+            description,
+            append_description,
+            prepend_description,
+            priority,
+            due,
+            scheduled,
+            remind,
+            clear_reminders,
+            estimate,
+            tags,
+            clear_tags,
+            add_tags,
+            remove_tags,
+            external_id,
+            assignee,
+            context,
+            parent,
+        } => {
+            let (resolved_location, task_id) = resolve_id(&id, &location)?;
 
-                if !input.trim().eq_ignore_ascii_case("y") {
+            let patch = TaskPatch {
+                title,
+                description,
+                append_description,
+                prepend_description,
+                priority,
+                due: due.map(Clearable::into_option),
+                scheduled: scheduled.map(Clearable::into_option),
+                remind: remind.or(clear_reminders.then(Vec::new)),
+                estimate: estimate.map(Clearable::into_option),
+                tags: tags.or(clear_tags.then(Vec::new)),
+                add_tags,
+                remove_tags,
+                external_id: external_id.map(Clearable::into_option),
+                assignee: assignee.map(Clearable::into_option),
+                context: context.map(Clearable::into_option),
+                parent: parent.map(Clearable::into_option),
+                ..Default::default()
+            };
+
+            let store = FileStore::new(resolved_location);
+            warn_if_claimed(&store, &store.read(task_id)?);
+            let task = store.patch(task_id, &patch, None)?;
+            success(&format!("Updated #{}: {}", task.id, task.title));
+        }
+
+        Commands::Comment { id, text, author } => {
+            let (resolved_location, task_id) = resolve_id(&id, &location)?;
+
+            if text.trim().is_empty() {
+                anyhow::bail!("Comment text is empty");
+            }
+            let store = FileStore::new(resolved_location);
+            let task = store.add_comment(task_id, &text, author)?;
+            success(&format!("Commented on #{}: {}", task.id, task.title));
+        }
+
+        Commands::Check { id, item } => {
+            let (resolved_location, task_id) = resolve_id(&id, &location)?;
+
+            let store = FileStore::new(resolved_location);
+            let (task, index) = store.toggle_check(task_id, &item)?;
+            let checklist = task.checklist();
+            let toggled = &checklist.items[index];
+            success(&format!(
+                "{} '{}' on #{} ({}/{} done)",
+                if toggled.done { "Checked" } else { "Unchecked" },
+                toggled.text,
+                task.id,
+                checklist.done(),
+                checklist.items.len()
+            ));
+        }
+
+        Commands::Delete { id, force } => {
+            let (resolved_location, task_id) = resolve_id(&id, &location)?;
+
+            let store = FileStore::new(resolved_location);
+
+            if !force {
+                let task = store.read(task_id)?;
+                if !confirm(&format!("Delete #{} '{}'?", task.id, task.title))? {
                     log::info!("Cancelled.");
                     return Ok(());
                 }
@@ -249,13 +1222,754 @@ fn run(cli: Cli) -> Result<()> {
             success(&format!("Deleted #{}", task_id));
         }
 
-        Commands::Stats => {
+        Commands::Log { since } => {
+            let store = FileStore::new(location);
+            let entries = activity_log(&store, since)?;
+            display_activity(&entries);
+        }
+
+        Commands::History { id } => {
+            let (resolved_location, task_id) = resolve_read_id(&id, &location)?;
+
+            let store = FileStore::new(resolved_location);
+            let entries = task_history(&store, task_id)?;
+            display_history(&entries);
+        }
+
+        Commands::Commit { preview } => {
+            let dry_run = dry_run || preview;
+            let pending = commit::pending_changes(&location.tasks_dir)?;
+            if pending.is_empty() {
+                log::info!("No task changes to commit.");
+                return Ok(());
+            }
+
+            let message = pending.message();
+            if dry_run {
+                print!("{}", message);
+                println!();
+                for path in &pending.paths {
+                    println!("  {}", path.display());
+                }
+            } else {
+                let hash = commit::commit_paths(&location.root, &pending.paths, &message)?;
+                success(&format!(
+                    "[{:.7}] {}",
+                    hash,
+                    message.lines().next().unwrap_or_default()
+                ));
+            }
+        }
+
+        Commands::MergeFile { base, ours, theirs } => {
+            refuse_dry_run("merge-file")?;
+            let result = merge::merge_task_files(
+                &std::fs::read_to_string(&base)?,
+                &std::fs::read_to_string(&ours)?,
+                &std::fs::read_to_string(&theirs)?,
+            );
+            writes::write(&ours, result.content)?;
+            if result.conflicts {
+                anyhow::bail!("Conflicting descriptions in {}", ours.display());
+            }
+        }
+
+        Commands::Hooks { action } => match action {
+            HooksAction::Install { force } => {
+                refuse_dry_run("hooks install")?;
+                for hook in hooks::install(&location.root, force)? {
+                    success(&format!("Installed {}", hook.display()));
+                }
+            }
+            HooksAction::Uninstall => {
+                refuse_dry_run("hooks uninstall")?;
+                let removed = hooks::uninstall(&location.root)?;
+                if removed.is_empty() {
+                    log::info!("No gittask hooks installed.");
+                }
+                for hook in removed {
+                    success(&format!("Removed {}", hook.display()));
+                }
+            }
+            HooksAction::Run { hook, .. } => {
+                log::debug!("Running {} hook", hook);
+                if !location.exists() {
+                    return Ok(());
+                }
+                if hook == "pre-push" {
+                    return validate_pushed(&location);
+                }
+
+                // Complete open tasks whose branch is now part of HEAD
+                let store = FileStore::new(location.clone());
+                let commit = GitOperations::head_commit_optional(&location.root);
+                for mut task in store.list(&TaskFilter::default())? {
+                    let Some(branch) = task.branch.clone() else {
+                        continue;
+                    };
+                    if task.is_open() && GitOperations::is_merged(&location.root, &branch)? {
+                        task.stop(chrono::Utc::now());
+                        task.complete(commit.clone());
+                        store.update(&task)?;
+                        success(&format!(
+                            "Completed #{}: {} ({} merged)",
+                            task.id, task.title, branch
+                        ));
+                    }
+                }
+            }
+        },
+
+        Commands::ReleaseNotes { from, to, title } => {
+            let store = FileStore::new(location.clone());
+            let tasks = store.list(&TaskFilter {
+                include_archived: true,
+                ..Default::default()
+            })?;
+            let closed =
+                release_notes::closed_between(&location.root, &tasks, from.as_deref(), &to)?;
+            print!(
+                "{}",
+                release_notes::render(title.as_deref().unwrap_or(&to), &closed)
+            );
+        }
+
+        Commands::Export {
+            output,
+            kind,
+            status,
+            tags,
+            include_archived,
+            since,
+            until,
+            by,
+        } => {
+            let status = status
+                .map(|s| parse_status(&s, &location, false))
+                .transpose()?;
+            let filter = TaskFilter {
+                kind,
+                status,
+                tags,
+                include_archived,
+                ..Default::default()
+            };
+            let window = TimeWindow {
+                field: by,
+                since,
+                until,
+            };
+
+            let store = FileStore::new(location);
+            let tasks = export_tasks(&store, &filter, &window)?;
+            let json = gittask::storage::export::to_json(&tasks)?;
+
+            match output {
+                Some(path) => {
+                    let content = json + "\n";
+                    writes::write(&path, &content)?;
+                    let manifest = ExportManifest::new(
+                        path.file_name().unwrap_or_default().to_string_lossy(),
+                        content.as_bytes(),
+                        tasks.len(),
+                        chrono::Utc::now(),
+                    );
+                    let manifest_path = ExportManifest::path_for(&path);
+                    writes::write(
+                        &manifest_path,
+                        serde_json::to_string_pretty(&manifest)? + "\n",
+                    )?;
+                    success(&format!(
+                        "Exported {} tasks to {} (manifest: {})",
+                        tasks.len(),
+                        path.display(),
+                        manifest_path.display()
+                    ));
+                }
+                None => println!("{}", json),
+            }
+        }
+
+        Commands::VerifyExport { file, manifest } => {
+            let manifest_path = manifest.unwrap_or_else(|| ExportManifest::path_for(&file));
+            let count = verify_export(&file, &manifest_path)?.ok_or_else(|| {
+                anyhow::anyhow!("No manifest found at {}", manifest_path.display())
+            })?;
+            success(&format!(
+                "{}: {} tasks, size and checksum match",
+                file.display(),
+                count
+            ));
+        }
+
+        Commands::Import {
+            input,
+            format,
+            preview,
+            update,
+        } => {
+            // With --dry-run the import goes through as usual, its writes held back
+            let dry_run = preview;
+            if !location.exists() {
+                anyhow::bail!("Task directory does not exist. Run 'gittask init' first.");
+            }
+            // Refuse a damaged export before anything is written
+            if input != "-" {
+                let path = Path::new(&input);
+                if verify_export(path, &ExportManifest::path_for(path))?.is_some() {
+                    log::info!("{} matches its manifest", input);
+                }
+            }
+
+            let content = if input == "-" {
+                io::read_to_string(io::stdin())?
+            } else if input.ends_with(".gz") {
+                // e.g. a snapshot written by `gittask prune`
+                io::read_to_string(flate2::read::GzDecoder::new(std::fs::File::open(&input)?))?
+            } else {
+                std::fs::read_to_string(&input)?
+            };
+
+            let store = FileStore::new(location);
+            let report = match format {
+                ImportFormat::Json => import_json(&store, &content, dry_run, update)?,
+                ImportFormat::GithubProject => {
+                    import_github_project(&store, &content, dry_run, update)?
+                }
+            };
+
+            for status in &report.new_statuses {
+                if dry_run {
+                    println!("Would add status {} to [workflow]", status);
+                } else {
+                    println!("Added status {} to [workflow]", status);
+                }
+            }
+            for task in &report.created {
+                if dry_run {
+                    println!("Would create {}: {}", task.kind, task.title);
+                } else {
+                    println!("Created {} #{}: {}", task.kind, task.id, task.title);
+                }
+            }
+            for task in &report.updated {
+                if dry_run {
+                    println!("Would update {} #{}: {}", task.kind, task.id, task.title);
+                } else {
+                    println!("Updated {} #{}: {}", task.kind, task.id, task.title);
+                }
+            }
+            for (index, reason) in &report.skipped {
+                log::warn!("Record {}: skipped: {}", index, reason);
+            }
+            for (index, e) in &report.errors {
+                error(&format!("Record {}: {}", index, e));
+            }
+
+            success(&format!(
+                "{} {}, {} {}, {} skipped, {} failed",
+                report.created.len(),
+                if dry_run {
+                    "would be created"
+                } else {
+                    "created"
+                },
+                report.updated.len(),
+                if dry_run {
+                    "would be updated"
+                } else {
+                    "updated"
+                },
+                report.skipped.len(),
+                report.errors.len()
+            ));
+
+            if !report.errors.is_empty() {
+                anyhow::bail!("{} records failed to import", report.errors.len());
+            }
+        }
+
+        Commands::Sync {
+            repo,
+            interval,
+            daemon,
+            stop,
+        } => {
+            if stop {
+                refuse_dry_run("sync --stop")?;
+            } else if interval.is_some() {
+                refuse_dry_run("sync --interval")?;
+            }
+            // The status file, log and .gitignore entry are writes too
+            let store = FileStore::new(location.clone());
+            store.ensure_writable()?;
+            if !location.exists() {
+                location.ensure_exists()?;
+            }
+            let tasks_dir = store.location().tasks_dir.clone();
+            let mut status = SyncStatus::load(&tasks_dir).unwrap_or_default();
+
+            if stop {
+                let Some(pid) = status.pid else {
+                    log::info!("No sync loop is running.");
+                    return Ok(());
+                };
+                if let Err(e) = stop_process(pid) {
+                    warning(&format!("Could not stop pid {}: {}", pid, e));
+                }
+                status.pid = None;
+                status.next_run = None;
+                status.save(&tasks_dir)?;
+                success(&format!("Stopped the sync loop (pid {})", pid));
+                return Ok(());
+            }
+
+            let Some(interval) = interval else {
+                let result = sync_once(&store, repo.as_deref());
+                status.record(chrono::Utc::now(), &result);
+                status.pid = None;
+                status.next_run = None;
+                status.save(&tasks_dir)?;
+                let report = result?;
+                let (created, updated) = if dry_run {
+                    ("Would create", "Would update")
+                } else {
+                    ("Created", "Updated")
+                };
+                for task in &report.created {
+                    println!("{} {} #{}: {}", created, task.kind, task.id, task.title);
+                }
+                for task in &report.updated {
+                    println!("{} {} #{}: {}", updated, task.kind, task.id, task.title);
+                }
+                for (index, e) in &report.errors {
+                    error(&format!("Issue {}: {}", index, e));
+                }
+                success(&format!(
+                    "{} created, {} updated",
+                    report.created.len(),
+                    report.updated.len()
+                ));
+                return Ok(());
+            };
+
+            if daemon {
+                let pid = spawn_sync_daemon(store.location(), repo.as_deref(), interval)?;
+                success(&format!(
+                    "Syncing every {} in the background (pid {}), logging to {}",
+                    format_interval(interval),
+                    pid,
+                    tasks_dir.join(sync::SYNC_LOG_FILE).display()
+                ));
+                return Ok(());
+            }
+
+            status.pid = Some(std::process::id());
+            loop {
+                let delay = sync_round(&store, repo.as_deref(), interval, &mut status)?;
+                std::thread::sleep(delay.to_std().unwrap_or_default());
+            }
+        }
+
+        Commands::Scan { path, preview } => {
+            let dry_run = preview;
+            if !dry_run && !location.exists() {
+                location.ensure_exists()?;
+            }
+
+            let store = FileStore::new(location);
+            let report = scan_code(&store, &path, dry_run)?;
+
+            for (todo, task) in &report.created {
+                if dry_run {
+                    println!("Would create todo: {} ({})", task.title, todo.source_ref());
+                } else {
+                    println!(
+                        "Created todo #{}: {} ({})",
+                        task.id,
+                        task.title,
+                        todo.source_ref()
+                    );
+                }
+            }
+
+            success(&format!(
+                "{} {}, {} already tracked",
+                report.created.len(),
+                if dry_run {
+                    "would be created"
+                } else {
+                    "created"
+                },
+                report.skipped.len()
+            ));
+        }
+
+        Commands::Index { remove } => {
+            refuse_dry_run("index")?;
+            if !location.exists() {
+                anyhow::bail!("Task directory does not exist. Run 'gittask init' first.");
+            }
+            FileStore::new(location.clone()).ensure_writable()?;
+
+            if remove {
+                TaskIndex::remove(&location.tasks_dir)?;
+                success("Removed task index");
+            } else {
+                let index = TaskIndex::build(&location.tasks_dir)?;
+                location::ensure_gitignored(&location.tasks_dir, ".index.json*")?;
+                success(&format!("Indexed {} tasks", index.len()));
+            }
+        }
+
+        Commands::Validate => validate_tasks(&FileStore::new(location))?,
+
+        Commands::Doctor { fix, quarantine } => {
+            let store = FileStore::new(location);
+
+            if quarantine {
+                for q in doctor::quarantine(&store)? {
+                    warning(&format!(
+                        "Moved {} to {}: {}",
+                        q.from.display(),
+                        q.to.display(),
+                        q.error
+                    ));
+                }
+            }
+
+            if fix {
+                for r in doctor::fix_duplicate_ids(&store)? {
+                    success(&format!(
+                        "Renumbered {} from #{} to #{}",
+                        r.old_path.display(),
+                        r.old_id,
+                        r.new_id
+                    ));
+                }
+            }
+
+            let issues = doctor::diagnose(&store)?;
+            if issues.is_empty() {
+                success("No problems found");
+            } else {
+                for issue in &issues {
+                    error(&issue.to_string());
+                }
+                return Err(CheckFailed(format!("{} problem(s) found", issues.len())).into());
+            }
+        }
+
+        Commands::Fmt { check } => {
+            let rules = ProjectConfig::load(&location.tasks_dir)?.fmt;
+            let root = location.root.clone();
+            let report = doctor::format_files(&FileStore::new(location), &rules, !check)?;
+            let relative = |path: &std::path::Path| {
+                path.strip_prefix(&root)
+                    .unwrap_or(path)
+                    .display()
+                    .to_string()
+            };
+
+            for (path, reason) in &report.skipped {
+                warning(&format!("Skipped {}: {}", relative(path), reason));
+            }
+            for path in &report.changed {
+                if check {
+                    println!("{}", relative(path));
+                } else {
+                    success(&format!("Formatted {}", relative(path)));
+                }
+            }
+            if check && !report.changed.is_empty() {
+                return Err(CheckFailed(format!(
+                    "{} task file(s) not formatted",
+                    report.changed.len()
+                ))
+                .into());
+            }
+            if report.changed.is_empty() {
+                log::info!("All task files are formatted");
+            }
+        }
+
+        Commands::Layout { layout } => {
+            if !location.exists() {
+                anyhow::bail!("Task directory does not exist. Run 'gittask init' first.");
+            }
+
+            match layout {
+                Some(layout) => {
+                    refuse_dry_run("layout")?;
+                    FileStore::new(location.clone()).ensure_writable()?;
+                    let moved = layout::migrate(&location.tasks_dir, layout)?;
+                    success(&format!(
+                        "Moved {} task files to the {} layout",
+                        moved, layout
+                    ));
+                }
+                None => println!("{}", TaskLayout::detect(&location.tasks_dir)),
+            }
+        }
+
+        Commands::Stats { format } => {
             let store = FileStore::new(location);
             let stats = store.stats()?;
-            display_stats(&stats);
+            let config = ProjectConfig::load(&store.location().tasks_dir)?;
+            let in_progress = store.list(&TaskFilter {
+                status: Some(gittask::TaskStatus::InProgress),
+                ..Default::default()
+            })?;
+            let violations = wip_overloads(&config.wip, &in_progress);
+
+            match format {
+                OutputFormat::Json => {
+                    let mut output = stats.to_json();
+                    output["wip_exceeded"] = violations
+                        .iter()
+                        .map(|v| v.to_string())
+                        .collect::<Vec<_>>()
+                        .into();
+                    println!("{}", serde_json::to_string_pretty(&output)?);
+                }
+                OutputFormat::Table => {
+                    display_stats(&stats);
+                    for violation in violations {
+                        warning(&violation.to_string());
+                    }
+                }
+            }
+        }
+
+        Commands::Templates => {
+            display_templates(&TaskTemplate::list(&location)?);
         }
 
-        Commands::Link { path } => {
+        Commands::Prune {
+            archive_after,
+            purge_after,
+            preview,
+        } => {
+            let dry_run = preview;
+            let store = FileStore::new(location);
+            let mut config = ProjectConfig::load(&store.location().tasks_dir)?.retention;
+            config.archive_after_days = archive_after.or(config.archive_after_days);
+            config.purge_after_days = purge_after.or(config.purge_after_days);
+            if config.archive_after_days.is_none() && config.purge_after_days.is_none() {
+                anyhow::bail!(
+                    "Nothing to prune: set [retention] archive_after_days or purge_after_days in config.toml, or pass --archive-after / --purge-after"
+                );
+            }
+
+            let all = store.list(&TaskFilter {
+                include_archived: true,
+                ..Default::default()
+            })?;
+            let now = chrono::Utc::now();
+            let plan = plan_prune(&all, &config, now);
+            if plan.is_empty() {
+                log::info!("Nothing to prune");
+                return Ok(());
+            }
+
+            if dry_run {
+                for task in &plan.archive {
+                    println!("Would archive #{}: {}", task.id, task.title);
+                }
+                for task in &plan.purge {
+                    println!("Would purge #{}: {}", task.id, task.title);
+                }
+                return Ok(());
+            }
+
+            let snapshot = prune(&store, &plan, now)?;
+            for task in &plan.archive {
+                println!("Archived #{}: {}", task.id, task.title);
+            }
+            for task in &plan.purge {
+                println!("Purged #{}: {}", task.id, task.title);
+            }
+            if let Some(snapshot) = snapshot {
+                success(&format!(
+                    "Saved {} purged task(s) to {}",
+                    plan.purge.len(),
+                    snapshot.display()
+                ));
+            }
+        }
+
+        Commands::Review { stale_days } => {
+            let store = FileStore::new(location);
+            let queue = review_queue(
+                store.list(&TaskFilter::default())?,
+                chrono::Utc::now(),
+                stale_days,
+            );
+            if queue.is_empty() {
+                log::info!("Nothing to review.");
+                return Ok(());
+            }
+
+            let total = queue.len();
+            let mut reviewed = 0;
+            for (i, (mut task, reason)) in queue.into_iter().enumerate() {
+                println!();
+                println!("[{}/{}] {}", i + 1, total, reason);
+                display_task_detail(&task);
+
+                let Some(action) = prompt_review_action()? else {
+                    break;
+                };
+                if let Some(action) = action {
+                    action.apply(&mut task, chrono::Utc::now());
+                    store.update(&task)?;
+                    reviewed += 1;
+                }
+            }
+            success(&format!("Reviewed {} of {} tasks", reviewed, total));
+        }
+
+        Commands::Reminders { days } => {
+            let registry = if cli.global {
+                Some(ProjectRegistry::load()?)
+            } else {
+                None
+            };
+            let mut tasks = Vec::new();
+            if let Some(registry) = registry.filter(|r| !r.is_empty()) {
+                tasks = list_aggregated(&registry, &TaskFilter::default())?;
+            } else if location.exists() {
+                let project = location
+                    .root
+                    .file_name()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let store = FileStore::new(location.clone());
+                for task in store.list(&TaskFilter::default())? {
+                    tasks.push(AggregatedTask {
+                        task,
+                        project: project.clone(),
+                        project_path: location.root.clone(),
+                        color: None,
+                    });
+                }
+            }
+
+            let now = chrono::Utc::now();
+            display_reminders(&upcoming_reminders(
+                tasks,
+                now,
+                now + chrono::Duration::days(days),
+            ));
+        }
+
+        Commands::Plan { until, by_assignee } => {
+            let store = FileStore::new(location);
+            let config = ProjectConfig::load(&store.location().tasks_dir)?.capacity;
+            let today = gittask::models::today();
+            if until < today {
+                anyhow::bail!("--until {} is in the past", until);
+            }
+            let plan = plan_capacity(&store.list(&TaskFilter::default())?, &config, today, until);
+            display_plan(&plan, by_assignee);
+
+            if config.weekly_hours.is_none() && config.assignees.is_empty() {
+                warning("No capacity configured; set [capacity] weekly_hours in config.toml");
+            }
+            if plan.total.unestimated > 0 {
+                warning(&format!(
+                    "{} task(s) have no estimate and count as 0h",
+                    plan.total.unestimated
+                ));
+            }
+            let lines =
+                std::iter::once(&plan.total).chain(plan.by_assignee.iter().filter(|_| by_assignee));
+            for line in lines {
+                if let Some(over) = line.overcommitted_by() {
+                    warning(&format!(
+                        "{} overcommitted by {:.1}h",
+                        line.assignee.as_deref().unwrap_or("Project"),
+                        over
+                    ));
+                }
+            }
+        }
+
+        Commands::Matrix { within } => {
+            let store = FileStore::new(location);
+            let tasks = store.list(&TaskFilter::default())?;
+            let today = gittask::models::today();
+            display_matrix(&EisenhowerMatrix::new(tasks, today, within));
+        }
+
+        Commands::Board => {
+            let store = FileStore::new(location);
+            let columns = ProjectConfig::load(&store.location().tasks_dir)?
+                .workflow
+                .columns();
+            let tasks = store.list(&TaskFilter::default())?;
+            display_board(&board_columns(tasks, &columns));
+        }
+
+        Commands::Embed {
+            file,
+            section,
+            filter,
+        } => {
+            let store = FileStore::new(location);
+            let embeds = match file {
+                // Relative to where the command runs, unlike config entries
+                Some(file) => vec![EmbedConfig {
+                    file: std::env::current_dir()?.join(file),
+                    section,
+                    filter,
+                }],
+                None => ProjectConfig::load(&store.location().tasks_dir)?.embed,
+            };
+            if embeds.is_empty() {
+                log::info!("No [[embed]] entries in config.toml; pass a file to embed into.");
+            }
+            for embed in embeds {
+                let filter = TaskFilter::parse(&embed.filter).map_err(anyhow::Error::msg)?;
+                let checklist = render_checklist(&store.list(&filter)?);
+                let root = &store.location().root;
+                let path = root.join(&embed.file);
+                let content = match std::fs::read_to_string(&path) {
+                    Ok(content) => content,
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+                    Err(e) => return Err(e.into()),
+                };
+                let updated = embed_section(&content, &embed.section, &checklist);
+                if updated != content {
+                    writes::write(&path, updated)?;
+                    if !is_dry_run() {
+                        let shown = path.strip_prefix(root).unwrap_or(&path);
+                        success(&format!("Updated {}", shown.display()));
+                    }
+                }
+            }
+        }
+
+        Commands::Serve { port, ui } => {
+            tokio::runtime::Runtime::new()?
+                .block_on(run_web_server(location, WebOptions { port, ui }))?;
+        }
+
+        Commands::Tags { action } => {
+            let tasks = FileStore::new(location).list(&TaskFilter::default())?;
+            match action {
+                Some(TagsAction::Tree) => display_tag_tree(&tag_tree(&tasks)),
+                None => display_tags(&tag_counts(&tasks)),
+            }
+        }
+
+        Commands::Contexts => {
+            let store = FileStore::new(location);
+            let contexts = store.contexts()?;
+            display_contexts(&contexts);
+        }
+
+        Commands::Link { path, alias, color } => {
             let mut registry = ProjectRegistry::load()?;
 
             let project_path = if let Some(p) = path {
@@ -271,6 +1985,23 @@ fn run(cli: Cli) -> Result<()> {
             } else {
                 log::info!("Project already linked: {}", project_path.display());
             }
+            if let Some(alias) = alias {
+                registry.set_alias(&alias, &project_path)?;
+                success(&format!(
+                    "{} is now also known as {}",
+                    project_path.display(),
+                    alias
+                ));
+            }
+            if let Some(color) = color.map(Clearable::into_option) {
+                registry.set_color(&project_path, color)?;
+                match color {
+                    Some(color) => {
+                        success(&format!("{} is shown in {}", project_path.display(), color))
+                    }
+                    None => success(&format!("{} is shown uncolored", project_path.display())),
+                }
+            }
         }
 
         Commands::Unlink { path } => {
@@ -291,12 +2022,327 @@ fn run(cli: Cli) -> Result<()> {
             }
         }
 
-        Commands::Projects => {
+        Commands::Projects { names } => {
             let registry = ProjectRegistry::load()?;
+            if names {
+                for name in registry.names() {
+                    println!("{}", name);
+                }
+                return Ok(());
+            }
             let statuses = registry.project_statuses();
             display_projects(&statuses);
         }
+
+        Commands::Help { .. } | Commands::Man { .. } => {
+            unreachable!("handled before resolving the task location")
+        }
+    }
+
+    Ok(())
+}
+
+/// Offer to complete the task a just-completed mirror was delegated from
+///
+/// Asks on a terminal; otherwise only mentions that the source is still open.
+fn offer_to_complete_source(task: &Task) -> Result<()> {
+    let Some(ref source) = task.delegated_from else {
+        return Ok(());
+    };
+    let registry = ProjectRegistry::load()?;
+    let (source_location, source_id) = match resolve_qualified_id(source, &registry, None, false) {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            warning(&format!(
+                "#{} was delegated from {}: {}",
+                task.id, source, e
+            ));
+            return Ok(());
+        }
+    };
+
+    let store = FileStore::new(source_location.clone());
+    let mut original = store.read(source_id)?;
+    if !original.is_open() {
+        return Ok(());
+    }
+    let question = format!(
+        "#{} was delegated from {} ({}). Complete that too?",
+        task.id, source, original.title
+    );
+    if io::stdin().is_terminal() && confirm(&question)? {
+        original.complete(GitOperations::head_commit_optional(&source_location.root));
+        store.update(&original)?;
+        success(&format!("Completed {}: {}", source, original.title));
+    } else {
+        log::info!("{} ({}) is still open", source, original.title);
+    }
+    Ok(())
+}
+
+/// Start `gittask sync --interval` for a project as a detached process
+///
+/// Its output goes to `.tasks/.sync.log`; returns the process ID.
+fn spawn_sync_daemon(
+    location: &TaskLocation,
+    repo: Option<&str>,
+    interval: chrono::Duration,
+) -> Result<u32> {
+    location::ensure_gitignored(&location.tasks_dir, sync::SYNC_LOG_FILE)?;
+    let log = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(location.tasks_dir.join(sync::SYNC_LOG_FILE))?;
+
+    let mut command = Command::new(std::env::current_exe()?);
+    command
+        .arg("-C")
+        .arg(&location.root)
+        .args(["sync", "--interval", &format_interval(interval)])
+        .stdin(std::process::Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log);
+    if let Some(repo) = repo {
+        command.args(["--repo", repo]);
+    }
+    // Keep running when the terminal's process group is signalled
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(&mut command, 0);
+
+    Ok(command.spawn()?.id())
+}
+
+/// Terminate a process started by `spawn_sync_daemon` or a foreground loop
+fn stop_process(pid: u32) -> Result<()> {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut c = Command::new("taskkill");
+        c.args(["/PID", &pid.to_string(), "/F"]);
+        c
+    } else {
+        let mut c = Command::new("kill");
+        c.arg(pid.to_string());
+        c
+    };
+    let output = command.stderr(std::process::Stdio::piped()).output()?;
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// An interval in the `--interval` syntax, in whole seconds
+fn format_interval(interval: chrono::Duration) -> String {
+    format!("{}s", interval.num_seconds())
+}
+
+/// Show a file in the platform's file manager
+fn reveal_in_file_manager(path: &Path) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut c = Command::new("open");
+        c.arg("-R").arg(path);
+        c
+    } else if cfg!(target_os = "windows") {
+        let mut c = Command::new("explorer");
+        c.arg(format!("/select,{}", path.display()));
+        c
+    } else {
+        let mut c = Command::new("xdg-open");
+        c.arg(path.parent().unwrap_or(path));
+        c
+    };
+
+    let status = command
+        .status()
+        .map_err(|e| anyhow::anyhow!("Could not open the file manager: {}", e))?;
+    // Explorer exits with 1 even when it selected the file
+    if !status.success() && !cfg!(target_os = "windows") {
+        anyhow::bail!("File manager exited with {}", status);
+    }
+    Ok(())
+}
+
+/// Check an export file against a manifest, returning its number of tasks,
+/// or `None` when there is no manifest
+fn verify_export(file: &Path, manifest_path: &Path) -> Result<Option<usize>> {
+    let manifest = match std::fs::read_to_string(manifest_path) {
+        Ok(manifest) => manifest,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let manifest: ExportManifest = serde_json::from_str(&manifest)
+        .map_err(|e| anyhow::anyhow!("Invalid manifest {}: {}", manifest_path.display(), e))?;
+    let content = std::fs::read(file)?;
+    let count = manifest.verify(&content).map_err(|e| {
+        anyhow::anyhow!(
+            "{} does not match {}: {}",
+            file.display(),
+            manifest_path.display(),
+            e
+        )
+    })?;
+    Ok(Some(count))
+}
+
+/// A project's config for reading tasks, or the defaults when config.toml
+/// is broken (`run` warns about that once)
+fn config_or_default(tasks_dir: &Path) -> ProjectConfig {
+    ProjectConfig::load(tasks_dir).unwrap_or_default()
+}
+
+/// Run the `[retention]` prune before listing when `auto` is set
+fn auto_prune(location: &TaskLocation) -> Result<()> {
+    let config = config_or_default(&location.tasks_dir).retention;
+    if !config.auto {
+        return Ok(());
+    }
+    let store = FileStore::new(location.clone());
+    if store.is_read_only()? {
+        return Ok(());
+    }
+    let all = store.list(&TaskFilter {
+        include_archived: true,
+        ..Default::default()
+    })?;
+    let now = chrono::Utc::now();
+    let plan = plan_prune(&all, &config, now);
+    if !plan.is_empty() {
+        prune(&store, &plan, now)?;
+        log::info!(
+            "Pruned: archived {}, purged {} task(s)",
+            plan.archive.len(),
+            plan.purge.len()
+        );
+    }
+    Ok(())
+}
+
+/// Pin or unpin a task, leaving it untouched if it already is
+fn set_pinned(location: &TaskLocation, id: &str, pinned: bool) -> Result<()> {
+    let (resolved_location, task_id) = resolve_id(id, location)?;
+
+    let store = FileStore::new(resolved_location);
+    let task = store.read(task_id)?;
+    let verb = if pinned { "pinned" } else { "unpinned" };
+    if task.pinned == pinned {
+        log::info!("#{} is already {}", task.id, verb);
+        return Ok(());
     }
+    let patch = TaskPatch {
+        pinned: Some(pinned),
+        ..Default::default()
+    };
+    let task = store.patch(task_id, &patch, Some(task.updated))?;
+    success(&format!("#{} {}: {}", task.id, verb, task.title));
+    Ok(())
+}
 
+/// Move a task up or down among the tasks sharing its status
+fn move_in_order(location: &TaskLocation, id: &str, to: OrderMove) -> Result<()> {
+    let (resolved_location, task_id) = resolve_id(id, location)?;
+
+    let store = FileStore::new(resolved_location);
+    let position = store.move_task(task_id, to)?;
+    let task = store.read(task_id)?;
+    success(&format!(
+        "Moved #{} to position {} among {} tasks: {}",
+        task.id,
+        position + 1,
+        task.status,
+        task.title
+    ));
     Ok(())
 }
+
+/// Keep the pinned tasks at the top, otherwise preserving the order
+fn pinned_first<T>(tasks: &mut [T], task: impl Fn(&T) -> &Task) {
+    tasks.sort_by_key(|t| !task(t).pinned);
+}
+
+/// Keep only tasks created by or assigned to `me`, looking up creators once per project
+fn retain_mine_aggregated(tasks: &mut Vec<gittask::storage::AggregatedTask>, me: &str) {
+    let mut creators: HashMap<PathBuf, HashMap<u64, String>> = HashMap::new();
+    tasks.retain(|t| {
+        let project_creators = creators.entry(t.project_path.clone()).or_insert_with(|| {
+            TaskLocation::find_project_from(&t.project_path)
+                .map(|location| task_creators(&FileStore::new(location)))
+                .unwrap_or_default()
+        });
+        is_mine(project_creators, &t.task, me)
+    });
+}
+
+/// Warn when `task` is claimed by someone other than the current git user
+fn warn_if_claimed(store: &FileStore, task: &Task) {
+    let me = GitOperations::current_user(&store.location().root);
+    if let Some(claimant) = task.claimed_by_other(me.as_deref()) {
+        warning(&format!("#{} is claimed by {}", task.id, claimant));
+    }
+}
+
+/// Warn if task `id` was closed while some of its subtasks are still open
+fn warn_open_subtasks(store: &FileStore, id: u64) -> Result<()> {
+    let open: Vec<String> = store
+        .children(id)?
+        .iter()
+        .filter(|t| t.is_open())
+        .map(|t| format!("#{}", t.id))
+        .collect();
+    if !open.is_empty() {
+        warning(&format!(
+            "#{} still has open subtasks: {}",
+            id,
+            open.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Warn about WIP limits that starting `task` would exceed, or refuse if `strict`
+fn enforce_wip(store: &FileStore, task: &Task, strict: bool) -> Result<()> {
+    let violations = check_wip(store, task)?;
+    if strict && let Some(violation) = violations.first() {
+        anyhow::bail!("{}; not starting #{}", violation, task.id);
+    }
+    for violation in &violations {
+        warning(&violation.to_string());
+    }
+    Ok(())
+}
+
+/// Ask a yes/no question, defaulting to no
+fn confirm(question: &str) -> Result<bool> {
+    print!("{} [y/N] ", question);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(input.trim().eq_ignore_ascii_case("y"))
+}
+
+/// Ask for a review decision until the answer is valid
+///
+/// Returns `None` to stop reviewing (`q` or end of input) and `Some(None)`
+/// to skip the task without recording a review.
+fn prompt_review_action() -> Result<Option<Option<ReviewAction>>> {
+    loop {
+        print!("[k]eep [s]nooze [a]rchive [b]ump [q]uit, then Enter (Enter alone skips): ");
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input)? == 0 {
+            println!();
+            return Ok(None);
+        }
+        let input = input.trim();
+        match input.chars().next() {
+            None => return Ok(Some(None)),
+            Some('q' | 'Q') => return Ok(None),
+            Some(key) if input.len() == 1 => {
+                if let Some(action) = ReviewAction::from_key(key) {
+                    return Ok(Some(Some(action)));
+                }
+            }
+            Some(_) => {}
+        }
+    }
+}
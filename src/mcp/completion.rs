@@ -0,0 +1,169 @@
+//! Argument completion for `completion/complete`
+//!
+//! Completion is driven by the argument name, so the same suggestions are
+//! offered for tool arguments and resource template variables.
+
+use crate::models::{Priority, Resolution, Task, TaskKind, TaskStatus};
+use std::collections::BTreeSet;
+
+/// Maximum number of values returned in one response (MCP limit)
+pub const MAX_VALUES: usize = 100;
+
+/// Suggest values for an argument given what has been typed so far
+///
+/// Task IDs match on an ID prefix or on words in the title, open tasks
+/// first, and are suggested as `<id> <title>` so clients show what each one
+/// is; ID arguments accept that form (see [`strip_title`]). For
+/// comma-separated tag lists only the last entry is completed.
+pub fn complete_argument(
+    argument: &str,
+    value: &str,
+    tasks: &[Task],
+    projects: &[String],
+) -> Vec<String> {
+    match argument {
+        "id" | "ids" | "blocked_by" => complete_task_id(value, tasks),
+        "project" => filter_prefix(value, projects.iter().cloned()),
+        "status" => filter_prefix(
+            value,
+            [
+                TaskStatus::Pending,
+                TaskStatus::InProgress,
+                TaskStatus::Completed,
+                TaskStatus::Archived,
+            ]
+            .iter()
+            // Custom statuses in use
+            .chain(tasks.iter().map(|t| &t.status))
+            .map(|s| s.to_string()),
+        ),
+        "kind" => filter_prefix(
+            value,
+            [TaskKind::Task, TaskKind::Todo, TaskKind::Idea]
+                .iter()
+                .map(|k| k.to_string()),
+        ),
+        "priority" => filter_prefix(
+            value,
+            [
+                Priority::Low,
+                Priority::Medium,
+                Priority::High,
+                Priority::Critical,
+            ]
+            .iter()
+            .map(|p| p.to_string()),
+        ),
+        "resolution" | "reason" => filter_prefix(
+            value,
+            [
+                Resolution::Done,
+                Resolution::Wontfix,
+                Resolution::Duplicate,
+                Resolution::Invalid,
+            ]
+            .iter()
+            .map(|r| r.to_string()),
+        ),
+        "context" => filter_prefix(value, tasks.iter().filter_map(|t| t.context.clone())),
+        "tag" | "tags" | "add_tags" | "remove_tags" => {
+            // Complete the last entry of a comma-separated list
+            let (done, last) = match value.rsplit_once(',') {
+                Some((done, last)) => (format!("{},", done), last.trim_start()),
+                None => (String::new(), value),
+            };
+            filter_prefix(last, tasks.iter().flat_map(|t| t.tags.iter().cloned()))
+                .into_iter()
+                .map(|tag| format!("{}{}", done, tag))
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Sorted, de-duplicated candidates starting with `prefix` (case-insensitive)
+fn filter_prefix(prefix: &str, candidates: impl Iterator<Item = String>) -> Vec<String> {
+    let prefix = prefix.to_lowercase();
+    candidates
+        .filter(|c| c.to_lowercase().starts_with(&prefix))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// The ID part of a task ID argument, which may be a completed `<id> <title>`
+pub fn strip_title(value: &str) -> &str {
+    value.split_whitespace().next().unwrap_or_default()
+}
+
+fn complete_task_id(value: &str, tasks: &[Task]) -> Vec<String> {
+    let needle = value.trim().to_lowercase();
+    let mut matches: Vec<&Task> = tasks
+        .iter()
+        .filter(|t| {
+            needle.is_empty()
+                || t.id.to_string().starts_with(&needle)
+                || t.title.to_lowercase().contains(&needle)
+        })
+        .collect();
+    matches.sort_by_key(|t| (!t.is_open(), t.id));
+    matches
+        .iter()
+        .map(|t| format!("{} {}", t.id, t.title))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tasks() -> Vec<Task> {
+        let mut auth = Task::new(12, TaskKind::Task, "Fix auth bug");
+        auth.tags = vec!["auth".to_string(), "backend".to_string()];
+        auth.context = Some("@office".to_string());
+        let mut done = Task::new(1, TaskKind::Task, "Old auth work");
+        done.status = TaskStatus::Completed;
+        vec![done, auth, Task::new(13, TaskKind::Idea, "Dark mode")]
+    }
+
+    #[test]
+    fn test_complete_task_ids() {
+        assert_eq!(
+            complete_argument("id", "1", &tasks(), &[]),
+            vec!["12 Fix auth bug", "13 Dark mode", "1 Old auth work"]
+        );
+        assert_eq!(
+            complete_argument("id", "AUTH", &tasks(), &[]),
+            vec!["12 Fix auth bug", "1 Old auth work"]
+        );
+        assert_eq!(strip_title("12 Fix auth bug"), "12");
+        assert_eq!(strip_title("api:12"), "api:12");
+    }
+
+    #[test]
+    fn test_complete_enums_and_tags() {
+        assert_eq!(
+            complete_argument("status", "in", &[], &[]),
+            vec!["in-progress"]
+        );
+        assert_eq!(complete_argument("priority", "", &[], &[]).len(), 4);
+        assert_eq!(
+            complete_argument("tags", "auth, b", &tasks(), &[]),
+            vec!["auth,backend"]
+        );
+        assert_eq!(
+            complete_argument("context", "@", &tasks(), &[]),
+            vec!["@office"]
+        );
+        assert_eq!(
+            complete_argument(
+                "project",
+                "we",
+                &[],
+                &["api".to_string(), "web".to_string()]
+            ),
+            vec!["web"]
+        );
+        assert!(complete_argument("title", "x", &tasks(), &[]).is_empty());
+    }
+}
@@ -1,5 +1,7 @@
 //! MCP server implementation
 
+pub mod completion;
+pub mod resources;
 pub mod server;
 
-pub use server::run_mcp_server;
+pub use server::{ServerOptions, run_mcp_server};
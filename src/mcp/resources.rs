@@ -0,0 +1,178 @@
+//! `gittask://` resource URIs for the MCP server
+//!
+//! - `gittask://{project}/tasks{?status,kind,priority,tags,context,include_archived}`
+//! - `gittask://{project}/tasks/{id}`
+//!
+//! `{project}` is a registered project name, or `.` for the server's own
+//! project.
+
+use crate::mcp::completion::strip_title;
+use crate::storage::TaskFilter;
+
+/// URI scheme for task resources
+pub const SCHEME: &str = "gittask://";
+
+/// What a resource URI points at
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResourceTarget {
+    /// A filtered task collection
+    Tasks(TaskFilter),
+    /// A single task
+    Task(u64),
+}
+
+/// A parsed resource URI
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceUri {
+    pub project: String,
+    pub target: ResourceTarget,
+}
+
+/// Resource templates advertised via `resources/templates/list`
+pub fn templates() -> serde_json::Value {
+    serde_json::json!([
+        {
+            "uriTemplate": "gittask://{project}/tasks{?status,kind,priority,tags,context,include_archived}",
+            "name": "Tasks",
+            "description": "Tasks in a project ('.' for the current one), filtered like list_tasks; tags are comma-separated",
+            "mimeType": "application/json"
+        },
+        {
+            "uriTemplate": "gittask://{project}/tasks/{id}",
+            "name": "Task",
+            "description": "A single task by ID",
+            "mimeType": "application/json"
+        }
+    ])
+}
+
+/// URI of the unfiltered task collection of a project
+pub fn tasks_uri(project: &str) -> String {
+    format!("{}{}/tasks", SCHEME, project)
+}
+
+/// Parse a `gittask://` URI
+pub fn parse_uri(uri: &str) -> Result<ResourceUri, String> {
+    let rest = uri
+        .strip_prefix(SCHEME)
+        .ok_or_else(|| format!("Unsupported resource URI: {}", uri))?;
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+    let segments: Vec<String> = path.split('/').map(percent_decode).collect();
+    let (project, target) = match segments.as_slice() {
+        [project, tasks] if tasks == "tasks" => {
+            (project.clone(), ResourceTarget::Tasks(parse_filter(query)?))
+        }
+        [project, tasks, id] if tasks == "tasks" => {
+            let id = strip_title(id)
+                .parse()
+                .map_err(|_| format!("Invalid task ID in resource URI: {}", id))?;
+            (project.clone(), ResourceTarget::Task(id))
+        }
+        _ => return Err(format!("Unknown resource: {}", uri)),
+    };
+
+    if project.is_empty() {
+        return Err(format!("Missing project in resource URI: {}", uri));
+    }
+
+    Ok(ResourceUri { project, target })
+}
+
+/// Build a task filter from URI query parameters
+fn parse_filter(query: &str) -> Result<TaskFilter, String> {
+    let mut filter = TaskFilter::default();
+
+    for pair in query.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = percent_decode(value);
+
+        match key {
+            "status" => filter.status = Some(value.parse()?),
+            "kind" => filter.kind = Some(value.parse()?),
+            "priority" => filter.priority = Some(value.parse()?),
+            "tags" | "tag" => filter.tags.extend(
+                value
+                    .split(',')
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty()),
+            ),
+            "context" => filter.context = Some(value),
+            "include_archived" => filter.include_archived = value != "false",
+            _ => return Err(format!("Unknown resource parameter: {}", key)),
+        }
+    }
+
+    Ok(filter)
+}
+
+/// Decode `%XX` escapes and `+` in a URI component
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len()
+                && bytes[i + 1].is_ascii_hexdigit()
+                && bytes[i + 2].is_ascii_hexdigit() =>
+            {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("00");
+                out.push(u8::from_str_radix(hex, 16).unwrap_or(0));
+                i += 3;
+                continue;
+            }
+            b'+' => out.push(b' '),
+            b => out.push(b),
+        }
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&out).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TaskKind, TaskStatus};
+
+    #[test]
+    fn test_parse_task_collection_uri() {
+        let uri = parse_uri(
+            "gittask://api/tasks?status=in-progress&kind=todo&tags=auth,ui&context=%40office",
+        )
+        .unwrap();
+        assert_eq!(uri.project, "api");
+        let ResourceTarget::Tasks(filter) = uri.target else {
+            panic!("expected a task collection");
+        };
+        assert_eq!(filter.status, Some(TaskStatus::InProgress));
+        assert_eq!(filter.kind, Some(TaskKind::Todo));
+        assert_eq!(filter.tags, vec!["auth", "ui"]);
+        assert_eq!(filter.context.as_deref(), Some("@office"));
+    }
+
+    #[test]
+    fn test_parse_single_task_uri() {
+        assert_eq!(
+            parse_uri("gittask://./tasks/42").unwrap(),
+            ResourceUri {
+                project: ".".to_string(),
+                target: ResourceTarget::Task(42),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_uris() {
+        assert!(parse_uri("file:///tmp/tasks").is_err());
+        assert!(parse_uri("gittask://api/projects").is_err());
+        assert!(parse_uri("gittask://api/tasks/abc").is_err());
+        // A completed `<id> <title>`
+        let uri = parse_uri("gittask://./tasks/42%20Fix%20auth").unwrap();
+        assert_eq!(uri.target, ResourceTarget::Task(42));
+        assert!(parse_uri("gittask://api/tasks?colour=red").is_err());
+        assert!(parse_uri("gittask:///tasks").is_err());
+    }
+}
@@ -3,17 +3,63 @@
 //! This is a manual implementation of the MCP protocol for maximum control
 //! and simpler debugging.
 
-use crate::git::GitOperations;
-use crate::models::{Task, TaskKind, TaskStatus};
+use crate::git::{GitOperations, release_notes};
+use crate::mcp::completion;
+use crate::mcp::resources::{self, ResourceTarget};
+use crate::models::{
+    ChecklistItem, Comment, ReminderOffset, Task, TaskKind, TaskStatus, parse_due_date,
+    parse_due_now, set_timezone, today,
+};
 use crate::storage::{
-    AggregatedTask, FileStore, ProjectRegistry, TaskFilter, TaskLocation, list_aggregated,
-    resolve_qualified_id,
+    AggregatedTask, DependencyGraph, FileStore, FileStoreError, OrderMove, ProjectConfig,
+    ProjectRegistry, SyncedFields, TaskFilter, TaskLocation, TaskPatch, TaskTemplate,
+    ValidationError, WorkflowConfig, check_wip, list_aggregated, project_name, resolve_links,
+    resolve_qualified_id, scan_code, set_read_only, sort_by_order, wip_overloads,
 };
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
 
+/// Why a tool call failed
+#[derive(Debug)]
+struct ToolError {
+    message: String,
+    /// Rules a task broke, returned as structured content
+    validation_errors: Vec<ValidationError>,
+}
+
+impl From<String> for ToolError {
+    fn from(message: String) -> Self {
+        ToolError {
+            message,
+            validation_errors: Vec::new(),
+        }
+    }
+}
+
+impl From<&str> for ToolError {
+    fn from(message: &str) -> Self {
+        message.to_string().into()
+    }
+}
+
+impl From<FileStoreError> for ToolError {
+    fn from(e: FileStoreError) -> Self {
+        let message = e.to_string();
+        match e {
+            FileStoreError::Invalid { errors, .. } => ToolError {
+                message,
+                validation_errors: errors,
+            },
+            _ => message.into(),
+        }
+    }
+}
+
 /// JSON-RPC request
 #[derive(Debug, Deserialize)]
 #[allow(dead_code)]
@@ -77,28 +123,101 @@ struct TaskOutput {
     kind: String,
     status: String,
     priority: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pinned: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    order: Option<u64>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     tags: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    context: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     due: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    scheduled: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    remind: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    external_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delegated_from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    assignee: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    claimed_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    claimed_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent: Option<u64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    blocked_by: Vec<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    branch: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    refs: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    estimate: Option<f64>,
+    updated: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    completed: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     closed_commit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resolution: Option<String>,
+    /// Total recorded work time, present once a session has been started
+    #[serde(skip_serializing_if = "Option::is_none")]
+    time_spent_minutes: Option<i64>,
+    /// Start of the running work session
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_started: Option<String>,
     #[serde(skip_serializing_if = "String::is_empty")]
     description: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    checklist: Vec<ChecklistItem>,
+    /// Percentage of checklist items checked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    progress: Option<u8>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    comments: Vec<Comment>,
 }
 
 impl From<&Task> for TaskOutput {
     fn from(task: &Task) -> Self {
+        let checklist = task.checklist();
         TaskOutput {
             id: task.id,
             title: task.title.clone(),
             kind: task.kind.to_string(),
             status: task.status.to_string(),
             priority: task.priority.to_string(),
+            pinned: task.pinned,
+            order: task.order,
             tags: task.tags.clone(),
+            context: task.context.clone(),
             due: task.due.map(|d| d.to_string()),
+            scheduled: task.scheduled.map(|d| d.to_string()),
+            remind: task.remind.iter().map(|r| r.to_string()).collect(),
+            external_id: task.external_id.clone(),
+            delegated_from: task.delegated_from.clone(),
+            assignee: task.assignee.clone(),
+            claimed_by: task.claimed_by.clone(),
+            claimed_at: task.claimed_at.map(|c| c.to_rfc3339()),
+            parent: task.parent,
+            blocked_by: task.blocked_by.clone(),
+            branch: task.branch.clone(),
+            refs: task.refs.clone(),
+            estimate: task.estimate,
+            updated: task.updated.to_rfc3339(),
+            completed: task.completed.map(|c| c.to_rfc3339()),
             closed_commit: task.closed_commit.clone(),
+            resolution: task.resolution.map(|r| r.to_string()),
+            time_spent_minutes: (!task.time_entries.is_empty())
+                .then(|| task.time_spent(chrono::Utc::now()).num_minutes()),
+            session_started: task.active_session().map(|e| e.start.to_rfc3339()),
             description: task.description.clone(),
+            checklist: checklist.items.clone(),
+            progress: checklist.progress(),
+            comments: task.comments.clone(),
         }
     }
 }
@@ -116,9 +235,29 @@ struct AggregatedTaskOutput {
     #[serde(skip_serializing_if = "Vec::is_empty")]
     tags: Vec<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    context: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     due: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    scheduled: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    remind: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    external_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delegated_from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    assignee: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    claimed_by: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    parent: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    completed: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     closed_commit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resolution: Option<String>,
     #[serde(skip_serializing_if = "String::is_empty")]
     description: String,
 }
@@ -133,13 +272,37 @@ impl From<&AggregatedTask> for AggregatedTaskOutput {
             status: agg.task.status.to_string(),
             priority: agg.task.priority.to_string(),
             tags: agg.task.tags.clone(),
+            context: agg.task.context.clone(),
             due: agg.task.due.map(|d| d.to_string()),
+            scheduled: agg.task.scheduled.map(|d| d.to_string()),
+            remind: agg.task.remind.iter().map(|r| r.to_string()).collect(),
+            external_id: agg.task.external_id.clone(),
+            delegated_from: agg.task.delegated_from.clone(),
+            assignee: agg.task.assignee.clone(),
+            claimed_by: agg.task.claimed_by.clone(),
+            parent: agg.task.parent,
+            completed: agg.task.completed.map(|c| c.to_rfc3339()),
             closed_commit: agg.task.closed_commit.clone(),
+            resolution: agg.task.resolution.map(|r| r.to_string()),
             description: agg.task.description.clone(),
         }
     }
 }
 
+/// A task referred to in a description, for MCP responses
+#[derive(Serialize)]
+struct LinkOutput {
+    /// The reference as written (`#2`, `api:14`)
+    reference: String,
+    /// Resource URI of the task
+    uri: String,
+    /// Title and status, absent if there is no such task
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<String>,
+}
+
 /// Project output for MCP responses
 #[derive(Serialize)]
 struct ProjectOutput {
@@ -149,29 +312,227 @@ struct ProjectOutput {
     has_tasks_dir: bool,
     open_tasks: usize,
     total_tasks: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    color: Option<String>,
+    /// Summary of the last GitHub sync, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sync: Option<String>,
+}
+
+/// Tools that never modify tasks or the project registry
+const READ_ONLY_TOOLS: &[&str] = &[
+    "list_tasks",
+    "get_task",
+    "get_stats",
+    "release_notes",
+    "list_projects",
+    "list_templates",
+    "get_blocked_tasks",
+];
+
+/// Tools that change which projects are registered
+const REGISTRY_TOOLS: &[&str] = &["link_project", "unlink_project"];
+
+/// Write tools that delete or overwrite data rather than only adding to it
+///
+/// `add_task` is one because with an `external_id` it updates the task
+/// synced from that record in place.
+const DESTRUCTIVE_TOOLS: &[&str] = &[
+    "add_task",
+    "delete_task",
+    "update_task",
+    "set_task_status",
+    "set_due",
+    "remove_dependency",
+    "unlink_project",
+];
+
+/// Write tools that have no further effect when repeated with the same arguments
+const IDEMPOTENT_TOOLS: &[&str] = &[
+    "complete_task",
+    "claim_task",
+    "reopen_task",
+    "move_task",
+    "set_due",
+    "update_task",
+    "delete_task",
+    "set_task_status",
+    "link_project",
+    "unlink_project",
+    "add_dependency",
+    "remove_dependency",
+];
+
+/// MCP tool annotations, so clients can auto-approve queries and confirm deletes
+fn tool_annotations(name: &str) -> Value {
+    let read_only = READ_ONLY_TOOLS.contains(&name);
+    json!({
+        "readOnlyHint": read_only,
+        "destructiveHint": DESTRUCTIVE_TOOLS.contains(&name),
+        "idempotentHint": read_only || IDEMPOTENT_TOOLS.contains(&name),
+        "openWorldHint": false
+    })
+}
+
+/// The `blocked_by` argument: a task ID, or a completed `<id> <title>`
+fn blocker_arg(args: &Value) -> Result<u64, ToolError> {
+    let value = args.get("blocked_by").ok_or("Missing 'blocked_by'")?;
+    value
+        .as_u64()
+        .or_else(|| completion::strip_title(value.as_str()?).parse().ok())
+        .ok_or_else(|| "Invalid 'blocked_by'".into())
+}
+
+/// How the MCP server was started
+#[derive(Debug, Clone, Default)]
+pub struct ServerOptions {
+    /// Use the global tasks directory instead of the current project
+    pub global: bool,
+    /// Only offer query tools and reject anything that would write
+    pub read_only: bool,
+    /// Serve only these project roots, routing each call by its `project` argument
+    pub allow: Vec<PathBuf>,
 }
 
 /// MCP Server state
+#[derive(Clone)]
 pub struct McpServer {
     global: bool,
+    read_only: bool,
+    /// Canonical roots of the allowed projects (empty: serve the current project)
+    allowed: Vec<PathBuf>,
+    /// Project a routed tool call is pinned to
+    location: Option<TaskLocation>,
 }
 
 impl McpServer {
-    pub fn new(global: bool) -> Self {
-        Self { global }
+    pub fn new(options: ServerOptions) -> Result<Self, String> {
+        let mut allowed = Vec::new();
+        for path in &options.allow {
+            let root = path
+                .canonicalize()
+                .map_err(|e| format!("Cannot allow {}: {}", path.display(), e))?;
+            let location = TaskLocation::find_project_from(&root)
+                .map_err(|e| format!("Cannot allow {}: {}", path.display(), e))?;
+            if location.root != root {
+                return Err(format!(
+                    "Cannot allow {}: not a project root (the project is at {})",
+                    path.display(),
+                    location.root.display()
+                ));
+            }
+            allowed.push(root);
+        }
+
+        Ok(Self {
+            global: options.global,
+            read_only: options.read_only,
+            allowed,
+            location: None,
+        })
+    }
+
+    /// Whether the server routes calls over an allowlist of projects
+    fn is_routed(&self) -> bool {
+        !self.allowed.is_empty()
+    }
+
+    /// Whether a tool may be listed and called with the server's options
+    fn tool_allowed(&self, name: &str) -> bool {
+        if self.is_routed() && REGISTRY_TOOLS.contains(&name) {
+            return false;
+        }
+        !self.read_only || READ_ONLY_TOOLS.contains(&name)
+    }
+
+    /// Projects visible to the server: the allowlist if given, else the registry
+    fn registry(&self) -> Result<ProjectRegistry, String> {
+        if self.is_routed() {
+            Ok(ProjectRegistry::fixed(self.allowed.iter().cloned()))
+        } else {
+            ProjectRegistry::load().map_err(|e| e.to_string())
+        }
+    }
+
+    /// Pin a tool call to the allowed project named by its `project` argument
+    fn route(&self, args: &Value) -> Result<McpServer, String> {
+        let names = || {
+            self.registry()
+                .map(|r| {
+                    r.project_statuses()
+                        .into_iter()
+                        .map(|s| s.name)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+                .unwrap_or_default()
+        };
+        let project = args
+            .get("project")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| format!("Missing 'project' (one of: {})", names()))?;
+
+        // Accept an allowed root given as a path, or a project name
+        let root = PathBuf::from(project)
+            .canonicalize()
+            .ok()
+            .filter(|p| self.allowed.contains(p))
+            .or_else(|| self.registry().ok()?.resolve_project_strict(project).ok())
+            .ok_or_else(|| format!("Project not allowed: {} (one of: {})", project, names()))?;
+        let location = TaskLocation::find_project_from(&root).map_err(|e| e.to_string())?;
+
+        Ok(McpServer {
+            location: Some(location),
+            ..self.clone()
+        })
     }
 
     fn get_store(&self) -> Result<FileStore, String> {
-        let location = if self.global {
+        let location = if let Some(location) = &self.location {
+            location.clone()
+        } else if self.is_routed() {
+            return Err("No project selected".to_string());
+        } else if self.global {
             TaskLocation::global().map_err(|e| e.to_string())?
         } else {
             TaskLocation::find_project().map_err(|e| e.to_string())?
         };
-        Ok(FileStore::new(location))
+        Ok(Self::open_store(location))
+    }
+
+    /// Open a project's store, counting days in its `[display] timezone`
+    ///
+    /// Calls can touch different projects, so this is set per store rather
+    /// than once at startup.
+    fn open_store(location: TaskLocation) -> FileStore {
+        let store = FileStore::new(location);
+        set_timezone(
+            store
+                .config()
+                .ok()
+                .and_then(|config| config.display.offset().ok().flatten()),
+        );
+        store
     }
 
     /// Resolve an ID that can be either a numeric ID or a qualified ID string
+    ///
+    /// A qualified ID's project must be named exactly or by a unique prefix;
+    /// `resolve_read_id` also takes fuzzy names, for tools that only read.
     fn resolve_id(&self, id_value: &Value) -> Result<(FileStore, u64), String> {
+        self.resolve_id_matching(id_value, false)
+    }
+
+    /// Resolve an ID for a tool that only reads, like `resolve_id`
+    fn resolve_read_id(&self, id_value: &Value) -> Result<(FileStore, u64), String> {
+        self.resolve_id_matching(id_value, true)
+    }
+
+    fn resolve_id_matching(
+        &self,
+        id_value: &Value,
+        fuzzy: bool,
+    ) -> Result<(FileStore, u64), String> {
         // Try to get as u64 first (backward compatible)
         if let Some(id) = id_value.as_u64() {
             let store = self.get_store()?;
@@ -180,18 +541,17 @@ impl McpServer {
 
         // Try to get as string (qualified ID support)
         if let Some(id_str) = id_value.as_str() {
-            let registry = ProjectRegistry::load().ok();
+            let registry = self.registry()?;
             let default_location = self.get_store().ok().map(|s| s.location().clone());
 
             let (location, task_id) = resolve_qualified_id(
-                id_str,
-                registry
-                    .as_ref()
-                    .unwrap_or(&ProjectRegistry::load().map_err(|e| e.to_string())?),
+                completion::strip_title(id_str),
+                &registry,
                 default_location.as_ref(),
+                fuzzy,
             )?;
 
-            return Ok((FileStore::new(location), task_id));
+            return Ok((Self::open_store(location), task_id));
         }
 
         Err("Invalid ID: expected number or string".to_string())
@@ -206,6 +566,12 @@ impl McpServer {
             "initialized" => JsonRpcResponse::success(id, json!({})),
             "tools/list" => self.handle_tools_list(id),
             "tools/call" => self.handle_tools_call(id, request.params.as_ref()),
+            "resources/list" => self.handle_resources_list(id),
+            "resources/templates/list" => {
+                JsonRpcResponse::success(id, json!({"resourceTemplates": resources::templates()}))
+            }
+            "resources/read" => self.handle_resources_read(id, request.params.as_ref()),
+            "completion/complete" => self.handle_completion(id, request.params.as_ref()),
             _ => {
                 JsonRpcResponse::error(id, -32601, format!("Method not found: {}", request.method))
             }
@@ -218,7 +584,9 @@ impl McpServer {
             json!({
                 "protocolVersion": "2024-11-05",
                 "capabilities": {
-                    "tools": {}
+                    "tools": {},
+                    "resources": {},
+                    "completions": {}
                 },
                 "serverInfo": {
                     "name": "gittask",
@@ -229,46 +597,71 @@ impl McpServer {
     }
 
     fn handle_tools_list(&self, id: Value) -> JsonRpcResponse {
-        let tools = json!({
+        let mut tools = json!({
             "tools": [
                 {
                     "name": "add_task",
-                    "description": "Create a new task, todo, or idea",
+                    "description": "Create a new task, todo, or idea. A task matching its external_id is updated instead; 'action' in the result says which happened (created, updated or unchanged)",
                     "inputSchema": {
                         "type": "object",
                         "properties": {
                             "kind": {"type": "string", "description": "Type: task, todo, or idea"},
                             "title": {"type": "string", "description": "Task title"},
                             "description": {"type": "string", "description": "Optional description"},
-                            "priority": {"type": "string", "description": "Priority: low, medium, high, critical"},
-                            "due": {"type": "string", "description": "Due date YYYY-MM-DD"},
-                            "tags": {"type": "array", "items": {"type": "string"}}
+                            "priority": {"type": "string", "description": "Priority: low, medium, high, critical (or p3, p2, p1, p0)"},
+                            "due": {"type": "string", "description": "Due date: YYYY-MM-DD, today, tomorrow, a weekday or an offset like +3d, 2w or 1m (m is months), optionally with a time (2026-03-04T17:00, fri 9:30)"},
+                            "scheduled": {"type": "string", "description": "Date work can start (as for due, without a time); list_tasks hides the task until then"},
+                            "remind": {"type": "array", "items": {"type": "string"}, "description": "Reminders relative to the due date, e.g. [\"-1d\", \"-2h\"]"},
+                            "estimate": {"type": "number", "description": "Estimated effort in hours"},
+                            "tags": {"type": "array", "items": {"type": "string"}},
+                            "external_id": {"type": "string", "description": "External tracker ID (e.g. GH#123). If a task already has it, that task is updated instead, so syncs can be re-run"},
+                            "assignee": {"type": "string", "description": "Who is responsible for the task; defaults to the git user when enabled in config.toml"},
+                            "context": {"type": "string", "description": "GTD context (e.g. @computer)"},
+                            "template": {"type": "string", "description": "Template name from list_templates; its defaults and body apply unless given explicitly. Defaults to the template configured for the kind"},
+                            "parent": {"type": "integer", "description": "ID of the task this one is a subtask of"}
                         },
                         "required": ["kind", "title"]
                     }
                 },
+                {
+                    "name": "list_templates",
+                    "description": "List task templates with their default fields and body",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
+                },
                 {
                     "name": "list_tasks",
-                    "description": "List tasks with optional filters",
+                    "description": "List tasks with optional filters, pinned tasks first",
                     "inputSchema": {
                         "type": "object",
                         "properties": {
                             "kind": {"type": "string"},
                             "status": {"type": "string"},
                             "priority": {"type": "string"},
-                            "tags": {"type": "array", "items": {"type": "string"}},
+                            "tags": {"type": "array", "items": {"type": "string"}, "description": "Tasks must match every entry; area/* matches all tags under area/"},
+                            "context": {"type": "string"},
                             "include_archived": {"type": "boolean"},
+                            "completed_since": {"type": "string", "description": "Only tasks completed on or after this date (YYYY-MM-DD)"},
+                            "external_id": {"type": "string", "description": "Only tasks with this external tracker ID"},
+                            "assignee": {"type": "string", "description": "Only tasks assigned to this person (case-insensitive)"},
+                            "unblocked": {"type": "boolean", "description": "Only tasks that aren't waiting on an open task"},
+                            "scheduled_before": {"type": "string", "description": "Only tasks scheduled on or before this date (YYYY-MM-DD)"},
+                            "scheduled_after": {"type": "string", "description": "Only tasks scheduled on or after this date (YYYY-MM-DD)"},
+                            "include_scheduled": {"type": "boolean", "description": "Also list tasks scheduled to start after today, which are hidden by default"},
+                            "ordered": {"type": "boolean", "description": "Group by status, each in the order set with move_task"},
                             "aggregate": {"type": "boolean", "description": "If true, aggregate tasks from all registered projects"}
                         }
                     }
                 },
                 {
                     "name": "get_task",
-                    "description": "Get task details by ID",
+                    "description": "Get task details by ID, with the tasks its description refers to (#N, project:N) under 'links' and its subtasks under 'subtasks'",
                     "inputSchema": {
                         "type": "object",
                         "properties": {
-                            "id": {"type": "integer", "description": "Task ID"}
+                            "id": {"type": ["integer", "string"], "description": "Task ID"}
                         },
                         "required": ["id"]
                     }
@@ -279,34 +672,132 @@ impl McpServer {
                     "inputSchema": {
                         "type": "object",
                         "properties": {
-                            "ids": {"type": "array", "items": {"type": "integer"}}
+                            "ids": {"type": "array", "items": {"type": ["integer", "string"]}}
                         },
                         "required": ["ids"]
                     }
                 },
+                {
+                    "name": "start_task",
+                    "description": "Start working on a task: set it in-progress and start a work session, optionally switching to a task branch",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "id": {"type": ["integer", "string"]},
+                            "branch": {"type": "boolean", "description": "Create and check out a task/<id>-<slug> branch"},
+                            "strict": {"type": "boolean", "description": "Fail instead of warning when a WIP limit would be exceeded"}
+                        },
+                        "required": ["id"]
+                    }
+                },
+                {
+                    "name": "claim_task",
+                    "description": "Claim a task for the current git user so others know it is being worked on, or release the claim",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "id": {"type": ["integer", "string"]},
+                            "release": {"type": "boolean", "description": "Release the claim instead"},
+                            "force": {"type": "boolean", "description": "Take over or release a claim held by someone else"}
+                        },
+                        "required": ["id"]
+                    }
+                },
+                {
+                    "name": "reopen_task",
+                    "description": "Reopen a completed or archived task as pending (or in-progress); its completion time, commit and resolution are kept in the task's 'reopened' history",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "id": {"type": ["integer", "string"]},
+                            "in_progress": {"type": "boolean", "description": "Reopen as in-progress instead of pending"}
+                        },
+                        "required": ["id"]
+                    }
+                },
+                {
+                    "name": "move_task",
+                    "description": "Move a task to a position in the hand-curated order of the tasks sharing its status, renumbering their 'order' as needed",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "id": {"type": ["integer", "string"]},
+                            "position": {"type": "integer", "minimum": 1, "description": "1 for the top; past the end moves it last"}
+                        },
+                        "required": ["id", "position"]
+                    }
+                },
+                {
+                    "name": "stop_task",
+                    "description": "End the running work session on a task",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "id": {"type": ["integer", "string"]}
+                        },
+                        "required": ["id"]
+                    }
+                },
+                {
+                    "name": "set_due",
+                    "description": "Set or clear the due date of one or more tasks",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "ids": {"type": "array", "items": {"type": ["integer", "string"]}},
+                            "due": {"type": ["string", "null"], "description": "YYYY-MM-DD, today, tomorrow, a weekday, an offset like +3d/2w/1m (m is months), optionally with a time (2026-03-04T17:00, fri 9:30), or null to clear"}
+                        },
+                        "required": ["ids", "due"]
+                    }
+                },
                 {
                     "name": "update_task",
-                    "description": "Update task properties",
+                    "description": "Update task properties (pass null for description, due or tags to clear them)",
                     "inputSchema": {
                         "type": "object",
                         "properties": {
-                            "id": {"type": "integer"},
+                            "id": {"type": ["integer", "string"]},
                             "title": {"type": "string"},
-                            "description": {"type": "string"},
+                            "description": {"type": ["string", "null"]},
                             "priority": {"type": "string"},
-                            "due": {"type": "string"},
-                            "tags": {"type": "array", "items": {"type": "string"}}
+                            "pinned": {"type": "boolean", "description": "Pin the task to the top of task lists, or unpin it"},
+                            "due": {"type": ["string", "null"], "description": "Due date (as for add_task), or null to clear"},
+                            "scheduled": {"type": ["string", "null"], "description": "Start date (as for add_task), or null to clear"},
+                            "remind": {"type": ["array", "null"], "items": {"type": "string"}, "description": "Replaces existing reminders (e.g. [\"-1d\"]), or null to clear"},
+                            "estimate": {"type": ["number", "null"], "description": "Estimated effort in hours, or null to clear"},
+                            "tags": {"type": ["array", "null"], "items": {"type": "string"}, "description": "Replaces existing tags, or null to clear"},
+                            "add_tags": {"type": "array", "items": {"type": "string"}, "description": "Tags to add"},
+                            "remove_tags": {"type": "array", "items": {"type": "string"}, "description": "Tags to remove"},
+                            "external_id": {"type": ["string", "null"], "description": "External tracker ID, or null to clear"},
+                            "assignee": {"type": ["string", "null"], "description": "New assignee, or null to clear"},
+                            "context": {"type": ["string", "null"], "description": "GTD context, or null to clear"},
+                            "parent": {"type": ["integer", "null"], "description": "ID of the task this one is a subtask of, or null to make it top-level"},
+                            "expected_updated": {"type": "string", "description": "The task's 'updated' value from when it was read; the update fails if the task changed since"}
                         },
                         "required": ["id"]
                     }
                 },
+                {
+                    "name": "add_comment",
+                    "description": "Append a comment to a task's discussion; comments are returned with the task by get_task",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "id": {"type": ["integer", "string"]},
+                            "text": {"type": "string", "description": "Comment text (Markdown)"},
+                            "author": {"type": "string", "description": "Who wrote it; defaults to the git user"}
+                        },
+                        "required": ["id", "text"]
+                    }
+                },
                 {
                     "name": "delete_task",
-                    "description": "Delete a task",
+                    "description": "Delete a task in two steps: without 'confirm' it only returns the task and a confirmation token; call again with that token to delete",
                     "inputSchema": {
                         "type": "object",
                         "properties": {
-                            "id": {"type": "integer"}
+                            "id": {"type": ["integer", "string"]},
+                            "confirm": {"type": "string", "description": "Token returned by the first call"}
                         },
                         "required": ["id"]
                     }
@@ -317,27 +808,52 @@ impl McpServer {
                     "inputSchema": {
                         "type": "object",
                         "properties": {
-                            "id": {"type": "integer"},
-                            "status": {"type": "string", "description": "pending, in-progress, completed, archived"}
+                            "id": {"type": ["integer", "string"]},
+                            "status": {"type": "string", "description": "pending, in-progress, completed, archived, or a custom status from [workflow] in config.toml"},
+                            "strict": {"type": "boolean", "description": "Fail instead of warning when a WIP limit would be exceeded"}
                         },
                         "required": ["id", "status"]
                     }
                 },
                 {
                     "name": "get_stats",
-                    "description": "Get task statistics",
+                    "description": "Get task statistics: counts by status and kind, overdue tasks by priority, tasks created and completed per week, open tasks by age (<1w, 1-4w, 1-3mo, >3mo) and the oldest open task",
                     "inputSchema": {
                         "type": "object",
                         "properties": {}
                     }
                 },
+                {
+                    "name": "release_notes",
+                    "description": "Markdown release notes for tasks closed by commits in from_ref..to_ref, grouped by tag",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "from_ref": {"type": "string", "description": "Previous release tag, branch or commit (omit to include all history)"},
+                            "to_ref": {"type": "string", "description": "Ref the release is cut from (default HEAD)"},
+                            "title": {"type": "string", "description": "Heading for the notes (defaults to to_ref)"}
+                        }
+                    }
+                },
+                {
+                    "name": "scan_code",
+                    "description": "Create todos from TODO/FIXME/XXX/HACK comments under a path, skipping comments already tracked; returns each task with its file and line",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "path": {"type": "string", "description": "File or directory relative to the project root (default: whole project)"},
+                            "dry_run": {"type": "boolean", "description": "Report what would be created without creating tasks"}
+                        }
+                    }
+                },
                 {
                     "name": "link_project",
                     "description": "Register a project for global task aggregation",
                     "inputSchema": {
                         "type": "object",
                         "properties": {
-                            "path": {"type": "string", "description": "Project path to register"}
+                            "path": {"type": "string", "description": "Project path to register"},
+                            "alias": {"type": "string", "description": "Another name to refer to the project by, e.g. in qualified IDs"}
                         },
                         "required": ["path"]
                     }
@@ -360,13 +876,259 @@ impl McpServer {
                         "type": "object",
                         "properties": {}
                     }
+                },
+                {
+                    "name": "add_dependency",
+                    "description": "Mark a task as blocked by another task in the same project",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "id": {"type": ["integer", "string"], "description": "Task that is blocked"},
+                            "blocked_by": {"type": ["integer", "string"], "description": "Task that must be finished first"}
+                        },
+                        "required": ["id", "blocked_by"]
+                    }
+                },
+                {
+                    "name": "remove_dependency",
+                    "description": "Remove a blocked-by dependency between two tasks",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "id": {"type": ["integer", "string"], "description": "Task that is blocked"},
+                            "blocked_by": {"type": ["integer", "string"], "description": "Task it no longer waits on"}
+                        },
+                        "required": ["id", "blocked_by"]
+                    }
+                },
+                {
+                    "name": "get_blocked_tasks",
+                    "description": "List open tasks waiting on other open tasks, plus the tasks ready to work on in dependency order",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {}
+                    }
                 }
             ]
         });
 
+        if let Some(list) = tools["tools"].as_array_mut() {
+            list.retain(|t| t["name"].as_str().is_some_and(|n| self.tool_allowed(n)));
+            for tool in list.iter_mut() {
+                let annotations = tool_annotations(tool["name"].as_str().unwrap_or_default());
+                tool["annotations"] = annotations;
+            }
+
+            if self.is_routed() {
+                let names: Vec<String> = self
+                    .registry()
+                    .map(|r| r.project_statuses().into_iter().map(|s| s.name).collect())
+                    .unwrap_or_default();
+                for tool in list.iter_mut().filter(|t| t["name"] != "list_projects") {
+                    let schema = &mut tool["inputSchema"];
+                    schema["properties"]["project"] = json!({
+                        "type": "string",
+                        "enum": names,
+                        "description": "Project to act on"
+                    });
+                    match schema["required"].as_array_mut() {
+                        Some(required) => required.push(json!("project")),
+                        None => schema["required"] = json!(["project"]),
+                    }
+                }
+            }
+        }
+
         JsonRpcResponse::success(id, tools)
     }
 
+    /// Concrete task collections: the server's project plus registered ones
+    fn handle_resources_list(&self, id: Value) -> JsonRpcResponse {
+        let mut resources = Vec::new();
+
+        if self.get_store().is_ok_and(|s| s.location().exists()) {
+            resources.push(json!({
+                "uri": resources::tasks_uri("."),
+                "name": "Tasks (current project)",
+                "mimeType": "application/json"
+            }));
+        }
+
+        if let Ok(registry) = self.registry() {
+            let mut statuses = registry.project_statuses();
+            statuses.sort_by(|a, b| a.name.cmp(&b.name));
+            for status in statuses.iter().filter(|s| s.has_tasks_dir) {
+                resources.push(json!({
+                    "uri": resources::tasks_uri(&status.name),
+                    "name": format!("Tasks ({})", status.name),
+                    "mimeType": "application/json"
+                }));
+            }
+        }
+
+        JsonRpcResponse::success(id, json!({"resources": resources}))
+    }
+
+    fn handle_resources_read(&self, id: Value, params: Option<&Value>) -> JsonRpcResponse {
+        let Some(uri) = params.and_then(|p| p.get("uri")).and_then(|v| v.as_str()) else {
+            return JsonRpcResponse::error(id, -32602, "Missing 'uri'".to_string());
+        };
+
+        let result = resources::parse_uri(uri).and_then(|resource| {
+            let store = self.project_store(&resource.project)?;
+            match resource.target {
+                ResourceTarget::Tasks(filter) => {
+                    let tasks = store.list(&filter).map_err(|e| e.to_string())?;
+                    let output: Vec<TaskOutput> = tasks.iter().map(TaskOutput::from).collect();
+                    Ok(json!(output))
+                }
+                ResourceTarget::Task(task_id) => {
+                    let task = store.read(task_id).map_err(|e| e.to_string())?;
+                    Ok(self.task_with_links(&store, &task))
+                }
+            }
+        });
+
+        match result {
+            Ok(content) => JsonRpcResponse::success(
+                id,
+                json!({
+                    "contents": [{
+                        "uri": uri,
+                        "mimeType": "application/json",
+                        "text": serde_json::to_string_pretty(&content).unwrap_or_default()
+                    }]
+                }),
+            ),
+            // MCP's "resource not found" code
+            Err(e) => JsonRpcResponse::error(id, -32002, e),
+        }
+    }
+
+    /// Suggest values for a tool argument or resource template variable
+    ///
+    /// Besides the standard `ref/prompt` and `ref/resource` references, a
+    /// `ref/tool` reference is accepted; suggestions depend only on the
+    /// argument name.
+    fn handle_completion(&self, id: Value, params: Option<&Value>) -> JsonRpcResponse {
+        let Some(argument) = params.and_then(|p| p.get("argument")) else {
+            return JsonRpcResponse::error(id, -32602, "Missing 'argument'".to_string());
+        };
+        let name = argument.get("name").and_then(|v| v.as_str()).unwrap_or("");
+        let value = argument.get("value").and_then(|v| v.as_str()).unwrap_or("");
+
+        let registry = self.registry().ok();
+        let projects: Vec<String> = registry.as_ref().map(|r| r.names()).unwrap_or_default();
+
+        // Completing IDs within a registered project's resource URI
+        let store = params
+            .and_then(|p| p.get("ref"))
+            .and_then(|r| r.get("uri"))
+            .and_then(|v| v.as_str())
+            .and_then(|uri| resources::parse_uri(uri).ok())
+            .and_then(|r| self.project_store(&r.project).ok())
+            .or_else(|| self.get_store().ok());
+        let tasks = store
+            .map(|s| {
+                s.list(&TaskFilter {
+                    include_archived: true,
+                    ..Default::default()
+                })
+                .unwrap_or_default()
+            })
+            .unwrap_or_default();
+
+        let values = completion::complete_argument(name, value, &tasks, &projects);
+        let total = values.len();
+
+        JsonRpcResponse::success(
+            id,
+            json!({
+                "completion": {
+                    "values": values.into_iter().take(completion::MAX_VALUES).collect::<Vec<_>>(),
+                    "total": total,
+                    "hasMore": total > completion::MAX_VALUES
+                }
+            }),
+        )
+    }
+
+    /// A task as `TaskOutput`, plus the tasks its description refers to
+    fn task_with_links(&self, store: &FileStore, task: &Task) -> Value {
+        let mut output = json!(TaskOutput::from(task));
+        let registry = self
+            .registry()
+            .unwrap_or_else(|_| ProjectRegistry::fixed(Vec::new()));
+        let own_root = self.get_store().ok().map(|s| s.location().root.clone());
+
+        let links: Vec<LinkOutput> = resolve_links(&task.description, store, &registry)
+            .into_iter()
+            .map(|link| {
+                let project = if Some(&link.project_path) == own_root.as_ref() {
+                    ".".to_string()
+                } else {
+                    project_name(&link.project_path)
+                };
+                LinkOutput {
+                    reference: link.link.to_string(),
+                    uri: format!("{}/{}", resources::tasks_uri(&project), link.link.id),
+                    title: link.task.as_ref().map(|t| t.title.clone()),
+                    status: link.task.as_ref().map(|t| t.status.to_string()),
+                }
+            })
+            .collect();
+        if !links.is_empty() {
+            output["links"] = json!(links);
+        }
+        output
+    }
+
+    /// Store for a resource's project: '.' is the server's own project
+    fn project_store(&self, project: &str) -> Result<FileStore, String> {
+        if project == "." {
+            return self.get_store();
+        }
+
+        let registry = self.registry()?;
+        let path = registry
+            .resolve_project(project)
+            .map_err(|e| e.to_string())?;
+        let location = TaskLocation::find_project_from(&path)
+            .map_err(|e| format!("Failed to find project: {}", e))?;
+
+        Ok(Self::open_store(location))
+    }
+
+    fn call_tool(&self, name: &str, args: &Value) -> Result<Value, ToolError> {
+        match name {
+            "add_task" => self.tool_add_task(args),
+            "list_tasks" => self.tool_list_tasks(args),
+            "get_task" => self.tool_get_task(args),
+            "complete_task" => self.tool_complete_task(args),
+            "start_task" => self.tool_start_task(args),
+            "claim_task" => self.tool_claim_task(args),
+            "reopen_task" => self.tool_reopen_task(args),
+            "move_task" => self.tool_move_task(args),
+            "stop_task" => self.tool_stop_task(args),
+            "set_due" => self.tool_set_due(args),
+            "update_task" => self.tool_update_task(args),
+            "add_comment" => self.tool_add_comment(args),
+            "delete_task" => self.tool_delete_task(args),
+            "set_task_status" => self.tool_set_task_status(args),
+            "get_stats" => self.tool_get_stats(args),
+            "release_notes" => self.tool_release_notes(args),
+            "scan_code" => self.tool_scan_code(args),
+            "link_project" => self.tool_link_project(args),
+            "unlink_project" => self.tool_unlink_project(args),
+            "list_projects" => self.tool_list_projects(args),
+            "list_templates" => self.tool_list_templates(args),
+            "add_dependency" => self.tool_add_dependency(args),
+            "remove_dependency" => self.tool_remove_dependency(args),
+            "get_blocked_tasks" => self.tool_get_blocked_tasks(args),
+            _ => Err(format!("Unknown tool: {}", name).into()),
+        }
+    }
+
     fn handle_tools_call(&self, id: Value, params: Option<&Value>) -> JsonRpcResponse {
         let params = match params {
             Some(p) => p,
@@ -376,19 +1138,26 @@ impl McpServer {
         let name = params.get("name").and_then(|v| v.as_str()).unwrap_or("");
         let args = params.get("arguments").cloned().unwrap_or(json!({}));
 
-        let result = match name {
-            "add_task" => self.tool_add_task(&args),
-            "list_tasks" => self.tool_list_tasks(&args),
-            "get_task" => self.tool_get_task(&args),
-            "complete_task" => self.tool_complete_task(&args),
-            "update_task" => self.tool_update_task(&args),
-            "delete_task" => self.tool_delete_task(&args),
-            "set_task_status" => self.tool_set_task_status(&args),
-            "get_stats" => self.tool_get_stats(&args),
-            "link_project" => self.tool_link_project(&args),
-            "unlink_project" => self.tool_unlink_project(&args),
-            "list_projects" => self.tool_list_projects(&args),
-            _ => Err(format!("Unknown tool: {}", name)),
+        let result = if !self.tool_allowed(name) {
+            if self.is_routed() && REGISTRY_TOOLS.contains(&name) {
+                Err(format!(
+                    "Tool '{}' is not available: the server only serves its allowed projects",
+                    name
+                )
+                .into())
+            } else {
+                Err(format!(
+                    "Tool '{}' is not available: the server is running in read-only mode",
+                    name
+                )
+                .into())
+            }
+        } else if self.is_routed() && name != "list_projects" {
+            self.route(&args)
+                .map_err(ToolError::from)
+                .and_then(|server| server.call_tool(name, &args))
+        } else {
+            self.call_tool(name, &args)
         };
 
         match result {
@@ -401,20 +1170,23 @@ impl McpServer {
                     }]
                 }),
             ),
-            Err(e) => JsonRpcResponse::success(
-                id,
-                json!({
+            Err(e) => {
+                let mut result = json!({
                     "content": [{
                         "type": "text",
-                        "text": format!("Error: {}", e)
+                        "text": format!("Error: {}", e.message)
                     }],
                     "isError": true
-                }),
-            ),
+                });
+                if !e.validation_errors.is_empty() {
+                    result["structuredContent"] = json!({"validation_errors": e.validation_errors});
+                }
+                JsonRpcResponse::success(id, result)
+            }
         }
     }
 
-    fn tool_add_task(&self, args: &Value) -> Result<Value, String> {
+    fn tool_add_task(&self, args: &Value) -> Result<Value, ToolError> {
         let kind: TaskKind = args
             .get("kind")
             .and_then(|v| v.as_str())
@@ -426,7 +1198,11 @@ impl McpServer {
             .and_then(|v| v.as_str())
             .ok_or("Missing 'title'")?;
 
-        let mut task = Task::new(0, kind, title);
+        let store = self.get_store()?;
+        let template = args.get("template").and_then(|v| v.as_str());
+        let mut task = store
+            .new_task(kind, title, template)
+            .map_err(|e| e.to_string())?;
 
         if let Some(desc) = args.get("description").and_then(|v| v.as_str()) {
             task.description = desc.to_string();
@@ -437,39 +1213,72 @@ impl McpServer {
         }
 
         if let Some(due) = args.get("due").and_then(|v| v.as_str()) {
-            task.due = Some(
-                NaiveDate::parse_from_str(due, "%Y-%m-%d")
-                    .map_err(|e| format!("Invalid date: {}", e))?,
-            );
+            task.due = Some(parse_due_now(due)?);
+        }
+
+        if let Some(scheduled) = args.get("scheduled").and_then(|v| v.as_str()) {
+            task.scheduled = Some(parse_due_date(scheduled, today())?);
+        }
+
+        if let Some(remind) = args.get("remind") {
+            task.remind = parse_reminders(remind)?;
+        }
+
+        if let Some(estimate) = args.get("estimate") {
+            task.estimate = Some(parse_estimate(estimate)?);
         }
 
         if let Some(tags) = args.get("tags").and_then(|v| v.as_array()) {
-            task.tags = tags
-                .iter()
-                .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                .collect();
+            for tag in tags.iter().filter_map(|v| v.as_str()) {
+                task.add_tag(tag);
+            }
+        }
+
+        if let Some(external_id) = args.get("external_id").and_then(|v| v.as_str()) {
+            task.external_id = Some(external_id.to_string());
+        }
+
+        if let Some(assignee) = args.get("assignee").and_then(|v| v.as_str()) {
+            task.assignee = Some(assignee.to_string());
+        }
+
+        if let Some(context) = args.get("context").and_then(|v| v.as_str()) {
+            task.context = Some(context.to_string());
+        }
+
+        if let Some(parent) = args.get("parent") {
+            task.parent = Some(parent.as_u64().ok_or("Invalid 'parent'")?);
         }
 
-        let store = self.get_store()?;
         store
             .location()
             .ensure_exists()
             .map_err(|e| e.to_string())?;
 
-        let created = store.create(task).map_err(|e| e.to_string())?;
-        Ok(json!(TaskOutput::from(&created)))
+        // Re-running a sync must not duplicate tasks it already created, nor
+        // reset fields the call leaves out
+        let fields = SyncedFields {
+            kind: true,
+            status: false,
+            priority: args.get("priority").is_some(),
+            description: args.get("description").is_some(),
+            tags: args.get("tags").is_some(),
+            context: args.get("context").is_some(),
+            due: args.get("due").is_some(),
+        };
+        let upsert = store.create_or_update_by_external_id(task, fields)?;
+        let mut result = json!(TaskOutput::from(upsert.task()));
+        result["action"] = json!(upsert.action());
+        Ok(result)
     }
 
-    fn tool_list_tasks(&self, args: &Value) -> Result<Value, String> {
-        let filter = TaskFilter {
+    fn tool_list_tasks(&self, args: &Value) -> Result<Value, ToolError> {
+        let mut filter = TaskFilter {
             kind: args
                 .get("kind")
                 .and_then(|v| v.as_str())
                 .and_then(|s| s.parse().ok()),
-            status: args
-                .get("status")
-                .and_then(|v| v.as_str())
-                .and_then(|s| s.parse().ok()),
+            status: None,
             priority: args
                 .get("priority")
                 .and_then(|v| v.as_str())
@@ -483,21 +1292,51 @@ impl McpServer {
                         .collect()
                 })
                 .unwrap_or_default(),
+            context: args
+                .get("context")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
             include_archived: args
                 .get("include_archived")
                 .and_then(|v| v.as_bool())
                 .unwrap_or(false),
+            completed_since: date_arg(args, "completed_since")?,
+            external_id: args
+                .get("external_id")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            assignee: args
+                .get("assignee")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            unblocked: args
+                .get("unblocked")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+            scheduled_before: date_arg(args, "scheduled_before")?,
+            scheduled_after: date_arg(args, "scheduled_after")?,
+            available_on: None,
         };
+        if filter.scheduled_before.is_none()
+            && filter.scheduled_after.is_none()
+            && args.get("include_scheduled").and_then(|v| v.as_bool()) != Some(true)
+        {
+            filter.available_on = Some(today());
+        }
 
         // Check if aggregation is requested
         let aggregate = args
             .get("aggregate")
             .and_then(|v| v.as_bool())
             .unwrap_or(false);
+        let status = args.get("status").and_then(|v| v.as_str());
 
         if aggregate {
-            let registry = ProjectRegistry::load().map_err(|e| e.to_string())?;
+            let registry = self.registry()?;
             if !registry.is_empty() {
+                if let Some(status) = status {
+                    filter.status = Some(registry.workflow().parse_status(status)?);
+                }
                 let tasks = list_aggregated(&registry, &filter).map_err(|e| e.to_string())?;
                 let output: Vec<AggregatedTaskOutput> =
                     tasks.iter().map(AggregatedTaskOutput::from).collect();
@@ -506,136 +1345,616 @@ impl McpServer {
         }
 
         let store = self.get_store()?;
-        let tasks = store.list(&filter).map_err(|e| e.to_string())?;
+        if let Some(status) = status {
+            filter.status = Some(Self::workflow(&store)?.parse_status(status)?);
+        }
+        let mut tasks = store.list(&filter).map_err(|e| e.to_string())?;
+        if args.get("ordered").and_then(|v| v.as_bool()) == Some(true) {
+            sort_by_order(&mut tasks);
+        }
+        tasks.sort_by_key(|t| !t.pinned);
 
         let output: Vec<TaskOutput> = tasks.iter().map(TaskOutput::from).collect();
         Ok(json!(output))
     }
 
-    fn tool_get_task(&self, args: &Value) -> Result<Value, String> {
+    fn tool_get_task(&self, args: &Value) -> Result<Value, ToolError> {
         let id_value = args.get("id").ok_or("Missing 'id'")?;
-        let (store, task_id) = self.resolve_id(id_value)?;
+        let (store, task_id) = self.resolve_read_id(id_value)?;
 
         let task = store.read(task_id).map_err(|e| e.to_string())?;
 
-        Ok(json!(TaskOutput::from(&task)))
+        let mut output = self.task_with_links(&store, &task);
+        let subtasks: Vec<TaskOutput> = store
+            .children(task_id)?
+            .iter()
+            .map(TaskOutput::from)
+            .collect();
+        if !subtasks.is_empty() {
+            output["subtasks"] = json!(subtasks);
+        }
+        Ok(output)
     }
 
-    fn tool_complete_task(&self, args: &Value) -> Result<Value, String> {
+    fn tool_complete_task(&self, args: &Value) -> Result<Value, ToolError> {
         let ids_array = args
             .get("ids")
             .and_then(|v| v.as_array())
             .ok_or("Missing 'ids'")?;
 
         let mut completed = Vec::new();
+        // One store per project so ID lookups are cached across the batch
+        let mut stores: HashMap<PathBuf, FileStore> = HashMap::new();
 
         for id_value in ids_array {
             let (store, task_id) = self.resolve_id(id_value)?;
+            let store = stores
+                .entry(store.location().tasks_dir.clone())
+                .or_insert(store);
 
             // Get git commit from the resolved project
             let commit = GitOperations::head_commit_optional(&store.location().root);
 
             let mut task = store.read(task_id).map_err(|e| e.to_string())?;
+            let claimed = Self::claim_warnings(store, &task);
             task.complete(commit);
-            store.update(&task).map_err(|e| e.to_string())?;
-            completed.push(TaskOutput::from(&task));
+            store.update(&task)?;
+            completed.push((store.location().tasks_dir.clone(), task, claimed));
         }
 
-        Ok(json!(completed))
+        // Checked after the whole batch, which may complete the subtasks too
+        let mut output = Vec::new();
+        for (tasks_dir, task, claimed) in completed {
+            let mut result = json!(TaskOutput::from(&task));
+            let mut warnings = claimed;
+            warnings.extend(Self::subtask_warnings(&stores[&tasks_dir], &task)?);
+            if !warnings.is_empty() {
+                result["warnings"] = json!(warnings);
+            }
+            output.push(result);
+        }
+        Ok(json!(output))
     }
 
-    fn tool_update_task(&self, args: &Value) -> Result<Value, String> {
+    fn tool_start_task(&self, args: &Value) -> Result<Value, ToolError> {
         let id_value = args.get("id").ok_or("Missing 'id'")?;
         let (store, task_id) = self.resolve_id(id_value)?;
+        let branch = args
+            .get("branch")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let task = store.read(task_id).map_err(|e| e.to_string())?;
+        let mut warnings = Self::claim_warnings(&store, &task);
+        warnings.extend(Self::wip_warnings(&store, &task, args)?);
+        let mut patch = TaskPatch {
+            start: Some(chrono::Utc::now()),
+            ..Default::default()
+        };
+        let branch_created = if branch {
+            let name = task.branch.clone().unwrap_or_else(|| task.branch_name());
+            let created = GitOperations::switch_branch(&store.location().root, &name)
+                .map_err(|e| e.to_string())?;
+            patch.branch = Some(Some(name));
+            Some(created)
+        } else {
+            None
+        };
+        let started = task.active_session().is_none();
+        let task = store.patch(task_id, &patch, Some(task.updated))?;
+
+        let mut result = json!({
+            "task": TaskOutput::from(&task),
+            "started": started,
+        });
+        if let Some(created) = branch_created {
+            result["branch_created"] = json!(created);
+        }
+        if !warnings.is_empty() {
+            result["warnings"] = json!(warnings);
+        }
+
+        Ok(result)
+    }
+
+    fn tool_reopen_task(&self, args: &Value) -> Result<Value, ToolError> {
+        let id_value = args.get("id").ok_or("Missing 'id'")?;
+        let (store, task_id) = self.resolve_id(id_value)?;
+        let in_progress = args
+            .get("in_progress")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
 
         let mut task = store.read(task_id).map_err(|e| e.to_string())?;
+        let mut warnings = Vec::new();
+        let status = if in_progress {
+            warnings.extend(Self::wip_warnings(&store, &task, args)?);
+            TaskStatus::InProgress
+        } else {
+            TaskStatus::Pending
+        };
 
-        if let Some(title) = args.get("title").and_then(|v| v.as_str()) {
-            task.title = title.to_string();
+        if !task.reopen(status, chrono::Utc::now()) {
+            return Err(format!("#{} is already open ({})", task.id, task.status).into());
         }
+        store.update(&task)?;
 
-        if let Some(desc) = args.get("description").and_then(|v| v.as_str()) {
-            task.description = desc.to_string();
+        let mut result = json!(TaskOutput::from(&task));
+        if !warnings.is_empty() {
+            result["warnings"] = json!(warnings);
+        }
+        Ok(result)
+    }
+
+    fn tool_move_task(&self, args: &Value) -> Result<Value, ToolError> {
+        let id_value = args.get("id").ok_or("Missing 'id'")?;
+        let (store, task_id) = self.resolve_id(id_value)?;
+        let position = args
+            .get("position")
+            .and_then(|v| v.as_u64())
+            .filter(|&p| p >= 1)
+            .ok_or("Missing or invalid 'position' (1 for the top)")?;
+
+        store
+            .move_task(task_id, OrderMove::To(position as usize - 1))
+            .map_err(|e| e.to_string())?;
+        let task = store.read(task_id).map_err(|e| e.to_string())?;
+        Ok(json!(TaskOutput::from(&task)))
+    }
+
+    fn tool_claim_task(&self, args: &Value) -> Result<Value, ToolError> {
+        let id_value = args.get("id").ok_or("Missing 'id'")?;
+        let (store, task_id) = self.resolve_id(id_value)?;
+        let release = args
+            .get("release")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let force = args.get("force").and_then(|v| v.as_bool()).unwrap_or(false);
+
+        let me = GitOperations::current_user(&store.location().root)
+            .ok_or("Claiming needs git config user.email or user.name to be set")?;
+        let mut task = store.read(task_id).map_err(|e| e.to_string())?;
+
+        if !force && let Some(claimant) = task.claimed_by_other(Some(&me)) {
+            return Err(format!("#{} is claimed by {}", task.id, claimant).into());
+        }
+        if release {
+            if !task.release_claim() {
+                return Err(format!("#{} is not claimed", task.id).into());
+            }
+        } else {
+            task.claim(me, chrono::Utc::now());
+        }
+        task.touch();
+        store.update(&task)?;
+
+        Ok(json!(TaskOutput::from(&task)))
+    }
+
+    fn tool_add_comment(&self, args: &Value) -> Result<Value, ToolError> {
+        let id_value = args.get("id").ok_or("Missing 'id'")?;
+        let (store, task_id) = self.resolve_id(id_value)?;
+        let text = args
+            .get("text")
+            .and_then(|v| v.as_str())
+            .filter(|t| !t.trim().is_empty())
+            .ok_or("Missing 'text'")?;
+        let author = args
+            .get("author")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let task = store.add_comment(task_id, text, author)?;
+        Ok(json!(TaskOutput::from(&task)))
+    }
+
+    fn tool_stop_task(&self, args: &Value) -> Result<Value, ToolError> {
+        let id_value = args.get("id").ok_or("Missing 'id'")?;
+        let (store, task_id) = self.resolve_id(id_value)?;
+
+        let task = store.read(task_id).map_err(|e| e.to_string())?;
+        let now = chrono::Utc::now();
+        let session = task
+            .active_session()
+            .map(|s| s.duration(now))
+            .ok_or_else(|| format!("#{} has no running session", task.id))?;
+        let patch = TaskPatch {
+            stop: Some(now),
+            ..Default::default()
+        };
+        let task = store.patch(task_id, &patch, Some(task.updated))?;
+
+        Ok(json!({
+            "task": TaskOutput::from(&task),
+            "session_minutes": session.num_minutes(),
+        }))
+    }
+
+    fn tool_set_due(&self, args: &Value) -> Result<Value, ToolError> {
+        let ids_array = args
+            .get("ids")
+            .and_then(|v| v.as_array())
+            .ok_or("Missing 'ids'")?;
+
+        let due = match args.get("due") {
+            Some(Value::Null) => None,
+            Some(v) => Some(parse_due_now(v.as_str().ok_or("Invalid 'due'")?)?),
+            None => return Err("Missing 'due'".into()),
+        };
+        let patch = TaskPatch {
+            due: Some(due),
+            ..Default::default()
+        };
+
+        // Check every ID before changing anything, so a bad one leaves all
+        // tasks as they were
+        let mut targets = Vec::new();
+        let mut stores: HashMap<PathBuf, FileStore> = HashMap::new();
+        for id_value in ids_array {
+            let (store, task_id) = self.resolve_id(id_value)?;
+            let tasks_dir = store.location().tasks_dir.clone();
+            let store = stores.entry(tasks_dir.clone()).or_insert(store);
+            store.read(task_id)?;
+            store.ensure_writable()?;
+            targets.push((tasks_dir, task_id));
+        }
+
+        let mut updated = Vec::new();
+        for (tasks_dir, task_id) in targets {
+            let task = stores[&tasks_dir].patch(task_id, &patch, None)?;
+            updated.push(TaskOutput::from(&task));
+        }
+
+        Ok(json!(updated))
+    }
+
+    fn tool_update_task(&self, args: &Value) -> Result<Value, ToolError> {
+        let id_value = args.get("id").ok_or("Missing 'id'")?;
+        let (store, task_id) = self.resolve_id(id_value)?;
+
+        let mut patch = TaskPatch {
+            title: args
+                .get("title")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            ..Default::default()
+        };
+
+        // An explicit null clears optional fields; an absent key leaves them untouched
+        match args.get("description") {
+            Some(Value::Null) => patch.description = Some(String::new()),
+            Some(v) => {
+                patch.description = Some(v.as_str().ok_or("Invalid 'description'")?.to_string());
+            }
+            None => {}
         }
 
         if let Some(p) = args.get("priority").and_then(|v| v.as_str()) {
-            task.priority = p.parse()?;
+            patch.priority = Some(p.parse()?);
         }
 
-        if let Some(due) = args.get("due").and_then(|v| v.as_str()) {
-            task.due = Some(
-                NaiveDate::parse_from_str(due, "%Y-%m-%d")
-                    .map_err(|e| format!("Invalid date: {}", e))?,
-            );
+        patch.pinned = args.get("pinned").and_then(|v| v.as_bool());
+
+        match args.get("due") {
+            Some(Value::Null) => patch.due = Some(None),
+            Some(v) => {
+                let due = v.as_str().ok_or("Invalid 'due'")?;
+                patch.due = Some(Some(parse_due_now(due)?));
+            }
+            None => {}
         }
 
-        if let Some(tags) = args.get("tags").and_then(|v| v.as_array()) {
-            task.tags = tags
+        match args.get("scheduled") {
+            Some(Value::Null) => patch.scheduled = Some(None),
+            Some(v) => {
+                let scheduled = v.as_str().ok_or("Invalid 'scheduled'")?;
+                patch.scheduled = Some(Some(parse_due_date(scheduled, today())?));
+            }
+            None => {}
+        }
+
+        match args.get("remind") {
+            Some(Value::Null) => patch.remind = Some(Vec::new()),
+            Some(v) => patch.remind = Some(parse_reminders(v)?),
+            None => {}
+        }
+
+        match args.get("estimate") {
+            Some(Value::Null) => patch.estimate = Some(None),
+            Some(v) => patch.estimate = Some(Some(parse_estimate(v)?)),
+            None => {}
+        }
+
+        match args.get("tags") {
+            Some(Value::Null) => patch.tags = Some(Vec::new()),
+            Some(v) => {
+                patch.tags = Some(
+                    v.as_array()
+                        .ok_or("Invalid 'tags'")?
+                        .iter()
+                        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                        .collect(),
+                );
+            }
+            None => {}
+        }
+
+        if let Some(tags) = args.get("add_tags").and_then(|v| v.as_array()) {
+            patch.add_tags = tags
                 .iter()
                 .filter_map(|v| v.as_str().map(|s| s.to_string()))
                 .collect();
         }
 
-        task.touch();
-        store.update(&task).map_err(|e| e.to_string())?;
+        if let Some(tags) = args.get("remove_tags").and_then(|v| v.as_array()) {
+            patch.remove_tags = tags
+                .iter()
+                .filter_map(|v| v.as_str().map(|s| s.to_string()))
+                .collect();
+        }
 
-        Ok(json!(TaskOutput::from(&task)))
+        match args.get("external_id") {
+            Some(Value::Null) => patch.external_id = Some(None),
+            Some(v) => {
+                patch.external_id =
+                    Some(Some(v.as_str().ok_or("Invalid 'external_id'")?.to_string()));
+            }
+            None => {}
+        }
+
+        match args.get("assignee") {
+            Some(Value::Null) => patch.assignee = Some(None),
+            Some(v) => {
+                patch.assignee = Some(Some(v.as_str().ok_or("Invalid 'assignee'")?.to_string()));
+            }
+            None => {}
+        }
+
+        match args.get("context") {
+            Some(Value::Null) => patch.context = Some(None),
+            Some(v) => {
+                patch.context = Some(Some(v.as_str().ok_or("Invalid 'context'")?.to_string()));
+            }
+            None => {}
+        }
+
+        match args.get("parent") {
+            Some(Value::Null) => patch.parent = Some(None),
+            Some(v) => patch.parent = Some(Some(v.as_u64().ok_or("Invalid 'parent'")?)),
+            None => {}
+        }
+
+        let expected_updated = match args.get("expected_updated").and_then(|v| v.as_str()) {
+            Some(s) => Some(
+                chrono::DateTime::parse_from_rfc3339(s)
+                    .map_err(|e| format!("Invalid 'expected_updated': {}", e))?
+                    .with_timezone(&chrono::Utc),
+            ),
+            None => None,
+        };
+
+        let warnings =
+            Self::claim_warnings(&store, &store.read(task_id).map_err(|e| e.to_string())?);
+        let task = store.patch(task_id, &patch, expected_updated)?;
+
+        let mut result = json!(TaskOutput::from(&task));
+        if !warnings.is_empty() {
+            result["warnings"] = json!(warnings);
+        }
+        Ok(result)
     }
 
-    fn tool_delete_task(&self, args: &Value) -> Result<Value, String> {
+    fn tool_delete_task(&self, args: &Value) -> Result<Value, ToolError> {
         let id_value = args.get("id").ok_or("Missing 'id'")?;
         let (store, task_id) = self.resolve_id(id_value)?;
+        let task = store.read(task_id).map_err(|e| e.to_string())?;
 
-        store.delete(task_id).map_err(|e| e.to_string())?;
+        match args.get("confirm").and_then(|v| v.as_str()) {
+            None => Ok(json!({
+                "task": TaskOutput::from(&task),
+                "confirm": Self::confirmation_token(&store, &task),
+                "message": "Nothing deleted yet. Call delete_task again with this 'confirm' token to delete the task."
+            })),
+            // Checked under the store's lock, so the task can't change
+            // between the check and the delete
+            Some(given) => {
+                let deleted = store
+                    .delete_if(task_id, |task| {
+                        Self::confirmation_token(&store, task) == given
+                    })
+                    .map_err(|e| e.to_string())?;
+                if !deleted {
+                    return Err("Confirmation token doesn't match: the task changed or the token is for another task. Call delete_task without 'confirm' for a new one.".into());
+                }
+                Ok(json!({"deleted": task_id}))
+            }
+        }
+    }
 
-        Ok(json!({"deleted": task_id}))
+    /// The `[workflow]` section of a project, for checking statuses given for it
+    fn workflow(store: &FileStore) -> Result<WorkflowConfig, ToolError> {
+        Ok(ProjectConfig::load(&store.location().tasks_dir)
+            .map_err(|e| e.to_string())?
+            .workflow)
     }
 
-    fn tool_set_task_status(&self, args: &Value) -> Result<Value, String> {
+    /// Token a destructive call must echo back, tied to the task as it is now
+    ///
+    /// It is derived from the project, ID and last update, so it goes stale
+    /// when the task changes and needs no state kept between calls.
+    fn confirmation_token(store: &FileStore, task: &Task) -> String {
+        let digest = Sha256::digest(format!(
+            "{}\n{}\n{}",
+            store.location().root.display(),
+            task.id,
+            task.updated.to_rfc3339()
+        ));
+        digest[..6].iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn tool_set_task_status(&self, args: &Value) -> Result<Value, ToolError> {
         let id_value = args.get("id").ok_or("Missing 'id'")?;
         let (store, task_id) = self.resolve_id(id_value)?;
 
-        let status: TaskStatus = args
-            .get("status")
-            .and_then(|v| v.as_str())
-            .ok_or("Missing 'status'")?
-            .parse()?;
+        let status = Self::workflow(&store)?.parse_status(
+            args.get("status")
+                .and_then(|v| v.as_str())
+                .ok_or("Missing 'status'")?,
+        )?;
 
         let mut task = store.read(task_id).map_err(|e| e.to_string())?;
+        let mut warnings = Self::claim_warnings(&store, &task);
+        if status == TaskStatus::InProgress {
+            warnings.extend(Self::wip_warnings(&store, &task, args)?);
+        }
 
         // If completing, capture git commit from the resolved project
         if status == TaskStatus::Completed && task.status != TaskStatus::Completed {
             let commit = GitOperations::head_commit_optional(&store.location().root);
             task.closed_commit = commit;
+            task.completed = Some(chrono::Utc::now());
+        } else if status.is_open() {
+            task.reopen(status, chrono::Utc::now());
         }
 
+        let completing = status == TaskStatus::Completed && task.status != TaskStatus::Completed;
         task.status = status;
         task.touch();
-        store.update(&task).map_err(|e| e.to_string())?;
+        store.update(&task)?;
+        if completing {
+            warnings.extend(Self::subtask_warnings(&store, &task)?);
+        }
 
-        Ok(json!(TaskOutput::from(&task)))
+        let mut result = json!(TaskOutput::from(&task));
+        if !warnings.is_empty() {
+            result["warnings"] = json!(warnings);
+        }
+        Ok(result)
+    }
+
+    /// A warning when `task` is claimed by someone other than the current git user
+    fn claim_warnings(store: &FileStore, task: &Task) -> Vec<String> {
+        let me = GitOperations::current_user(&store.location().root);
+        task.claimed_by_other(me.as_deref())
+            .map(|claimant| format!("#{} is claimed by {}", task.id, claimant))
+            .into_iter()
+            .collect()
+    }
+
+    /// A warning when `task` is closed while some of its subtasks are still open
+    fn subtask_warnings(store: &FileStore, task: &Task) -> Result<Vec<String>, FileStoreError> {
+        let open: Vec<String> = store
+            .children(task.id)?
+            .iter()
+            .filter(|t| t.is_open())
+            .map(|t| format!("#{}", t.id))
+            .collect();
+        Ok(if open.is_empty() {
+            Vec::new()
+        } else {
+            vec![format!(
+                "#{} still has open subtasks: {}",
+                task.id,
+                open.join(", ")
+            )]
+        })
+    }
+
+    /// WIP limits that starting `task` would exceed, as messages
+    ///
+    /// Fails instead when the `strict` argument is set.
+    fn wip_warnings(store: &FileStore, task: &Task, args: &Value) -> Result<Vec<String>, String> {
+        let violations = check_wip(store, task).map_err(|e| e.to_string())?;
+        let strict = args
+            .get("strict")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if strict && let Some(violation) = violations.first() {
+            return Err(format!("{}; not starting #{}", violation, task.id));
+        }
+        Ok(violations.iter().map(|v| v.to_string()).collect())
     }
 
-    fn tool_get_stats(&self, _args: &Value) -> Result<Value, String> {
+    fn tool_get_stats(&self, _args: &Value) -> Result<Value, ToolError> {
         let store = self.get_store()?;
         let stats = store.stats().map_err(|e| e.to_string())?;
+        let config = ProjectConfig::load(&store.location().tasks_dir).map_err(|e| e.to_string())?;
+        let in_progress = store
+            .list(&TaskFilter {
+                status: Some(TaskStatus::InProgress),
+                ..Default::default()
+            })
+            .map_err(|e| e.to_string())?;
+        let wip_exceeded: Vec<String> = wip_overloads(&config.wip, &in_progress)
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
+
+        let mut output = stats.to_json();
+        output["wip_exceeded"] = json!(wip_exceeded);
+        Ok(output)
+    }
+
+    fn tool_release_notes(&self, args: &Value) -> Result<Value, ToolError> {
+        let store = self.get_store()?;
+        let from = args.get("from_ref").and_then(|v| v.as_str());
+        let to = args
+            .get("to_ref")
+            .and_then(|v| v.as_str())
+            .unwrap_or("HEAD");
+        let title = args.get("title").and_then(|v| v.as_str()).unwrap_or(to);
+
+        let tasks = store
+            .list(&TaskFilter {
+                include_archived: true,
+                ..Default::default()
+            })
+            .map_err(|e| e.to_string())?;
+        let closed = release_notes::closed_between(&store.location().root, &tasks, from, to)
+            .map_err(|e| e.to_string())?;
 
         Ok(json!({
-            "total": stats.total,
-            "pending": stats.pending,
-            "in_progress": stats.in_progress,
-            "completed": stats.completed,
-            "archived": stats.archived,
-            "overdue": stats.overdue,
-            "by_kind": {
-                "tasks": stats.tasks,
-                "todos": stats.todos,
-                "ideas": stats.ideas
-            }
+            "markdown": release_notes::render(title, &closed),
+            "tasks": closed.iter().map(|t| TaskOutput::from(*t)).collect::<Vec<_>>(),
         }))
     }
 
-    fn tool_link_project(&self, args: &Value) -> Result<Value, String> {
+    fn tool_scan_code(&self, args: &Value) -> Result<Value, ToolError> {
+        let store = self.get_store()?;
+        let path = args.get("path").and_then(|v| v.as_str()).unwrap_or(".");
+        let dry_run = args
+            .get("dry_run")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        if !dry_run && !store.location().exists() {
+            store
+                .location()
+                .ensure_exists()
+                .map_err(|e| e.to_string())?;
+        }
+
+        let dir = store.location().root.join(path);
+        let report = scan_code(&store, &dir, dry_run).map_err(|e| e.to_string())?;
+
+        let created: Vec<Value> = report
+            .created
+            .iter()
+            .map(|(todo, task)| {
+                json!({
+                    "file": todo.path.to_string_lossy(),
+                    "line": todo.line,
+                    "marker": todo.marker,
+                    "task": TaskOutput::from(task),
+                })
+            })
+            .collect();
+        let skipped: Vec<String> = report.skipped.iter().map(|t| t.source_ref()).collect();
+
+        Ok(json!({"created": created, "already_tracked": skipped, "dry_run": dry_run}))
+    }
+
+    fn tool_link_project(&self, args: &Value) -> Result<Value, ToolError> {
         let path = args
             .get("path")
             .and_then(|v| v.as_str())
@@ -645,8 +1964,15 @@ impl McpServer {
         let mut registry = ProjectRegistry::load().map_err(|e| e.to_string())?;
 
         let inserted = registry.link(&path).map_err(|e| e.to_string())?;
+        let alias = args.get("alias").and_then(|v| v.as_str());
+        if let Some(alias) = alias {
+            registry
+                .set_alias(alias, &path)
+                .map_err(|e| e.to_string())?;
+        }
 
         Ok(json!({
+            "alias": alias,
             "path": path.to_string_lossy(),
             "linked": inserted,
             "message": if inserted {
@@ -657,7 +1983,7 @@ impl McpServer {
         }))
     }
 
-    fn tool_unlink_project(&self, args: &Value) -> Result<Value, String> {
+    fn tool_unlink_project(&self, args: &Value) -> Result<Value, ToolError> {
         let path = args
             .get("path")
             .and_then(|v| v.as_str())
@@ -679,8 +2005,8 @@ impl McpServer {
         }))
     }
 
-    fn tool_list_projects(&self, _args: &Value) -> Result<Value, String> {
-        let registry = ProjectRegistry::load().map_err(|e| e.to_string())?;
+    fn tool_list_projects(&self, _args: &Value) -> Result<Value, ToolError> {
+        let registry = self.registry()?;
         let statuses = registry.project_statuses();
 
         let output: Vec<ProjectOutput> = statuses
@@ -690,18 +2016,102 @@ impl McpServer {
                 path: s.path.to_string_lossy().to_string(),
                 exists: s.exists,
                 has_tasks_dir: s.has_tasks_dir,
-                open_tasks: s.open_tasks,
-                total_tasks: s.total_tasks,
+                open_tasks: s.open_tasks(),
+                total_tasks: s.total_tasks(),
+                color: s.color.map(|c| c.to_string()),
+                sync: s.sync_summary(),
             })
             .collect();
 
         Ok(json!(output))
     }
+
+    fn tool_list_templates(&self, _args: &Value) -> Result<Value, ToolError> {
+        let store = self.get_store()?;
+        let templates = TaskTemplate::list(store.location()).map_err(|e| e.to_string())?;
+        Ok(json!(templates))
+    }
+
+    fn tool_add_dependency(&self, args: &Value) -> Result<Value, ToolError> {
+        let id_value = args.get("id").ok_or("Missing 'id'")?;
+        let (store, task_id) = self.resolve_id(id_value)?;
+        let blocker = blocker_arg(args)?;
+
+        if blocker == task_id {
+            return Err("A task cannot block itself".into());
+        }
+        let task = store.add_blocker(task_id, blocker)?;
+
+        Ok(json!(TaskOutput::from(&task)))
+    }
+
+    fn tool_remove_dependency(&self, args: &Value) -> Result<Value, ToolError> {
+        let id_value = args.get("id").ok_or("Missing 'id'")?;
+        let (store, task_id) = self.resolve_id(id_value)?;
+        let blocker = blocker_arg(args)?;
+
+        let patch = TaskPatch {
+            remove_blockers: vec![blocker],
+            ..Default::default()
+        };
+        let task = store.patch(task_id, &patch, None)?;
+
+        Ok(json!(TaskOutput::from(&task)))
+    }
+
+    fn tool_get_blocked_tasks(&self, _args: &Value) -> Result<Value, ToolError> {
+        let store = self.get_store()?;
+        let tasks = store
+            .list(&TaskFilter {
+                include_archived: true,
+                ..Default::default()
+            })
+            .map_err(|e| e.to_string())?;
+        let graph = DependencyGraph::new(tasks);
+
+        let blocked: Vec<Value> = graph
+            .blocked()
+            .into_iter()
+            .map(|(task, waiting_on)| json!({"task": TaskOutput::from(task), "waiting_on": waiting_on}))
+            .collect();
+        let ready: Vec<TaskOutput> = graph.ready().into_iter().map(TaskOutput::from).collect();
+
+        Ok(json!({"blocked": blocked, "ready": ready}))
+    }
+}
+
+/// Parse a JSON array of reminder offsets such as `["-1d", "-2h"]`
+fn parse_reminders(value: &Value) -> Result<Vec<ReminderOffset>, String> {
+    value
+        .as_array()
+        .ok_or("Invalid 'remind'")?
+        .iter()
+        .map(|v| v.as_str().ok_or("Invalid 'remind'")?.parse())
+        .collect()
+}
+
+/// Optional `YYYY-MM-DD` argument `key`
+fn date_arg(args: &Value, key: &str) -> Result<Option<NaiveDate>, String> {
+    args.get(key)
+        .and_then(|v| v.as_str())
+        .map(|s| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .map_err(|e| format!("Invalid '{}': {}", key, e))
+        })
+        .transpose()
+}
+
+fn parse_estimate(value: &Value) -> Result<f64, String> {
+    value
+        .as_f64()
+        .filter(|hours| *hours >= 0.0)
+        .ok_or_else(|| "Invalid 'estimate' (expected hours, at least 0)".to_string())
 }
 
 /// Run the MCP server (async stdio)
-pub async fn run_mcp_server(global: bool) -> anyhow::Result<()> {
-    let server = McpServer::new(global);
+pub async fn run_mcp_server(options: ServerOptions) -> anyhow::Result<()> {
+    set_read_only(options.read_only);
+    let server = McpServer::new(options).map_err(|e| anyhow::anyhow!(e))?;
 
     let stdin = tokio::io::stdin();
     let mut stdout = tokio::io::stdout();
@@ -753,3 +2163,163 @@ pub async fn run_mcp_server(global: bool) -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Call a tool, returning its result text and whether it failed
+    fn call(server: &McpServer, name: &str, args: Value) -> (String, bool) {
+        let params = json!({"name": name, "arguments": args});
+        let result = server
+            .handle_tools_call(json!(1), Some(&params))
+            .result
+            .unwrap();
+        let text = result["content"][0]["text"].as_str().unwrap().to_string();
+        (text, result["isError"] == json!(true))
+    }
+
+    /// A project root with an initialised tasks directory
+    fn project() -> TempDir {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir(temp.path().join(".git")).unwrap();
+        TaskLocation::find_project_from(temp.path())
+            .unwrap()
+            .ensure_exists()
+            .unwrap();
+        temp
+    }
+
+    /// Annotations of each tool `tools/list` offers, by name
+    fn listed_annotations(server: &McpServer) -> HashMap<String, Value> {
+        let response = server.handle_tools_list(json!(1));
+        response.result.unwrap()["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| {
+                let name = tool["name"].as_str().unwrap().to_string();
+                (name, tool["annotations"].clone())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_tools_list_annotations() {
+        let server = McpServer::new(ServerOptions::default()).unwrap();
+        let annotations = listed_annotations(&server);
+
+        let hints = |name: &str| {
+            let tool = &annotations[name];
+            (
+                tool["readOnlyHint"].as_bool().unwrap(),
+                tool["destructiveHint"].as_bool().unwrap(),
+                tool["idempotentHint"].as_bool().unwrap(),
+            )
+        };
+        assert_eq!(hints("list_tasks"), (true, false, true));
+        assert_eq!(hints("add_task"), (false, true, false));
+        assert_eq!(hints("delete_task"), (false, true, true));
+        assert_eq!(hints("set_task_status"), (false, true, true));
+    }
+
+    #[test]
+    fn test_read_only_hides_and_rejects_write_tools() {
+        let server = McpServer::new(ServerOptions {
+            read_only: true,
+            ..Default::default()
+        })
+        .unwrap();
+        let mut listed: Vec<String> = listed_annotations(&server).into_keys().collect();
+        listed.sort();
+        let mut expected: Vec<String> = READ_ONLY_TOOLS.iter().map(|t| t.to_string()).collect();
+        expected.sort();
+        assert_eq!(listed, expected);
+
+        assert!(server.tool_allowed("list_tasks"));
+        assert!(!server.tool_allowed("add_task"));
+        let (text, failed) = call(&server, "add_task", json!({"title": "Nope"}));
+        assert!(failed);
+        assert!(text.contains("read-only mode"), "{}", text);
+    }
+
+    #[test]
+    fn test_complete_task_warns_about_claims() {
+        let root = project();
+        let server = McpServer::new(ServerOptions {
+            allow: vec![root.path().to_path_buf()],
+            ..Default::default()
+        })
+        .unwrap();
+        let project = root.path().to_str().unwrap();
+        let store = server
+            .route(&json!({"project": project}))
+            .unwrap()
+            .get_store()
+            .unwrap();
+        let mut task = Task::new(0, TaskKind::Task, "Claimed");
+        task.claimed_by = Some("someone-else".to_string());
+        store.create(task).unwrap();
+
+        let args = json!({"ids": [1], "project": project});
+        let (text, failed) = call(&server, "complete_task", args);
+        assert!(!failed, "{}", text);
+        assert!(text.contains("#1 is claimed by someone-else"), "{}", text);
+        assert!(store.read(1).unwrap().is_done());
+    }
+
+    #[test]
+    fn test_allowlist_routes_by_project() {
+        let (api, web, other) = (project(), project(), project());
+        let server = McpServer::new(ServerOptions {
+            allow: vec![api.path().to_path_buf(), web.path().to_path_buf()],
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(server.is_routed());
+        assert!(!server.tool_allowed("link_project"));
+
+        // Every routed tool requires a project
+        let listed = server.handle_tools_list(json!(1)).result.unwrap();
+        let add_task = listed["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|t| t["name"] == "add_task")
+            .unwrap();
+        let required = add_task["inputSchema"]["required"].as_array().unwrap();
+        assert!(required.contains(&json!("project")));
+
+        // Calls land in the project they name
+        let web_root = web.path().to_str().unwrap();
+        let (text, failed) = call(
+            &server,
+            "add_task",
+            json!({"kind": "task", "title": "Header", "project": web_root}),
+        );
+        assert!(!failed, "{}", text);
+        let routed = server.route(&json!({"project": web_root})).unwrap();
+        let tasks = routed.get_store().unwrap().list(&TaskFilter::default());
+        assert_eq!(tasks.unwrap()[0].title, "Header");
+        let api_root = api.path().to_str().unwrap();
+        let routed = server.route(&json!({"project": api_root})).unwrap();
+        let tasks = routed.get_store().unwrap().list(&TaskFilter::default());
+        assert!(tasks.unwrap().is_empty());
+
+        // Projects outside the allowlist, and calls naming none, are refused
+        let outside = other.path().to_str().unwrap();
+        let (text, failed) = call(
+            &server,
+            "add_task",
+            json!({"kind": "task", "title": "Sneaky", "project": outside}),
+        );
+        assert!(failed);
+        assert!(text.contains("Project not allowed"), "{}", text);
+        let (text, failed) = call(&server, "list_tasks", json!({}));
+        assert!(failed);
+        assert!(text.contains("Missing 'project'"), "{}", text);
+        let store = FileStore::new(TaskLocation::find_project_from(other.path()).unwrap());
+        assert!(store.list(&TaskFilter::default()).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,199 @@
+//! Checklists written as Markdown task lists in the description
+//!
+//! Any `- [ ] item` or `- [x] item` line (also with `*` or `+` bullets, and
+//! indented) counts as a checklist item, except inside fenced code blocks.
+//! The description stays the source of truth; toggling an item only flips
+//! the character between its brackets.
+
+use serde::Serialize;
+
+/// One `- [ ]` line of a description
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChecklistItem {
+    pub text: String,
+    pub done: bool,
+    /// Line of the description the item is on, 0-based
+    #[serde(skip)]
+    line: usize,
+    /// Byte offset of the box character within that line
+    #[serde(skip)]
+    mark: usize,
+}
+
+/// The checklist items of a description, in order
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Checklist {
+    pub items: Vec<ChecklistItem>,
+}
+
+impl Checklist {
+    /// Collect the checklist items of `description`
+    pub fn parse(description: &str) -> Self {
+        let mut items = Vec::new();
+        let mut fenced = false;
+        for (line_no, line) in description.lines().enumerate() {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                fenced = !fenced;
+                continue;
+            }
+            if fenced {
+                continue;
+            }
+            if let Some((done, text)) = parse_item(trimmed) {
+                items.push(ChecklistItem {
+                    text: text.to_string(),
+                    done,
+                    line: line_no,
+                    mark: line.len() - trimmed.len() + 3,
+                });
+            }
+        }
+        Checklist { items }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Number of checked items
+    pub fn done(&self) -> usize {
+        self.items.iter().filter(|i| i.done).count()
+    }
+
+    /// Percentage of items checked, or `None` without a checklist
+    pub fn progress(&self) -> Option<u8> {
+        if self.items.is_empty() {
+            return None;
+        }
+        Some((self.done() * 100 / self.items.len()) as u8)
+    }
+
+    /// Find an item by its 1-based number or by a unique piece of its text
+    ///
+    /// Text matches ignore case; an exact match wins over partial ones.
+    pub fn find(&self, item: &str) -> Result<usize, String> {
+        let item = item.trim();
+        if let Ok(n) = item.parse::<usize>() {
+            if (1..=self.items.len()).contains(&n) {
+                return Ok(n - 1);
+            }
+            return Err(format!(
+                "No checklist item {} (there are {})",
+                n,
+                self.items.len()
+            ));
+        }
+
+        let needle = item.to_lowercase();
+        if let Some(index) = self
+            .items
+            .iter()
+            .position(|i| i.text.to_lowercase() == needle)
+        {
+            return Ok(index);
+        }
+        let matches: Vec<usize> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| i.text.to_lowercase().contains(&needle))
+            .map(|(index, _)| index)
+            .collect();
+        match matches.as_slice() {
+            [index] => Ok(*index),
+            [] => Err(format!("No checklist item matches '{}'", item)),
+            _ => Err(format!(
+                "'{}' matches {} checklist items; use the item number instead",
+                item,
+                matches.len()
+            )),
+        }
+    }
+}
+
+/// `(done, text)` if `line` (without indentation) is a checklist item
+fn parse_item(line: &str) -> Option<(bool, &str)> {
+    let rest = line
+        .strip_prefix("- ")
+        .or_else(|| line.strip_prefix("* "))
+        .or_else(|| line.strip_prefix("+ "))?;
+    let done = match rest.get(..3)? {
+        "[ ]" => false,
+        "[x]" | "[X]" => true,
+        _ => return None,
+    };
+    let text = &rest[3..];
+    if !text.is_empty() && !text.starts_with(' ') {
+        return None;
+    }
+    Some((done, text.trim()))
+}
+
+/// Flip item `index` of the checklist in `description`
+///
+/// Returns `None` if there is no such item. Everything else in the
+/// description, line endings included, is left as it was.
+pub fn toggle_item(description: &str, index: usize) -> Option<String> {
+    let item = Checklist::parse(description).items.into_iter().nth(index)?;
+    let mut out = String::with_capacity(description.len());
+    for (line_no, line) in description.split_inclusive('\n').enumerate() {
+        if line_no == item.line {
+            out.push_str(&line[..item.mark]);
+            out.push(if item.done { ' ' } else { 'x' });
+            out.push_str(&line[item.mark + 1..]);
+        } else {
+            out.push_str(line);
+        }
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DESCRIPTION: &str = "Steps:\n\n- [x] Write the migration\n  * [ ] Run it on staging\n- [X] Tell the team\n- [] not an item\n\n```\n- [ ] in a code block\n```\n- [ ] Run it on production\n";
+
+    #[test]
+    fn test_parse_checklist() {
+        let checklist = Checklist::parse(DESCRIPTION);
+        let items: Vec<(&str, bool)> = checklist
+            .items
+            .iter()
+            .map(|i| (i.text.as_str(), i.done))
+            .collect();
+        assert_eq!(
+            items,
+            [
+                ("Write the migration", true),
+                ("Run it on staging", false),
+                ("Tell the team", true),
+                ("Run it on production", false),
+            ]
+        );
+        assert_eq!(checklist.done(), 2);
+        assert_eq!(checklist.progress(), Some(50));
+        assert_eq!(Checklist::parse("No list").progress(), None);
+
+        assert_eq!(checklist.find("2"), Ok(1));
+        assert_eq!(checklist.find("team"), Ok(2));
+        assert!(checklist.find("run it").is_err());
+        assert!(checklist.find("5").is_err());
+        assert!(checklist.find("0").is_err());
+    }
+
+    #[test]
+    fn test_toggle_item() {
+        let toggled = toggle_item(DESCRIPTION, 1).unwrap();
+        assert!(toggled.contains("  * [x] Run it on staging\n"));
+        let toggled = toggle_item(&toggled, 0).unwrap();
+        assert!(toggled.contains("- [ ] Write the migration\n"));
+        assert_eq!(toggled.len(), DESCRIPTION.len());
+        assert!(toggled.contains("- [ ] in a code block"));
+
+        let back = toggle_item(&toggle_item(&toggled, 0).unwrap(), 1).unwrap();
+        assert_eq!(back, DESCRIPTION);
+        assert_eq!(toggle_item(DESCRIPTION, 4), None);
+    }
+}
@@ -0,0 +1,556 @@
+//! Due dates, with an optional time of day, and their parsing
+
+use chrono::{
+    DateTime, Datelike, Days, Duration, FixedOffset, Local, Months, NaiveDate, NaiveDateTime,
+    NaiveTime, TimeZone, Utc, Weekday,
+};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::RwLock;
+
+/// Local hour that reminders for date-only due dates are counted from
+pub const REMINDER_HOUR: u32 = 9;
+
+static TIMEZONE: RwLock<Option<FixedOffset>> = RwLock::new(None);
+
+/// Decide what day it is in a fixed offset instead of the system timezone
+///
+/// Set from `[display] timezone` of the project being worked on: by the CLI
+/// at startup, and by the MCP server for each project a call touches.
+pub fn set_timezone(offset: Option<FixedOffset>) {
+    *TIMEZONE.write().unwrap_or_else(|e| e.into_inner()) = offset;
+}
+
+fn timezone() -> Option<FixedOffset> {
+    *TIMEZONE.read().unwrap_or_else(|e| e.into_inner())
+}
+
+/// The calendar day `at` falls on in the configured timezone
+///
+/// Overdue, due-soon, date filters and stats all count days with this, so
+/// they agree with each other and with what the CLI prints.
+pub fn local_date(at: DateTime<Utc>) -> NaiveDate {
+    date_in(at, timezone())
+}
+
+/// Today's date in the configured timezone
+pub fn today() -> NaiveDate {
+    local_date(Utc::now())
+}
+
+fn date_in(at: DateTime<Utc>, offset: Option<FixedOffset>) -> NaiveDate {
+    match offset {
+        Some(offset) => at.with_timezone(&offset).date_naive(),
+        None => at.with_timezone(&Local).date_naive(),
+    }
+}
+
+/// A local date and time in `offset`, or the system timezone without one
+///
+/// A time skipped by a DST change falls back to reading it as UTC.
+fn local_in(local: NaiveDateTime, offset: Option<FixedOffset>) -> DateTime<FixedOffset> {
+    let at = match offset {
+        Some(offset) => offset.from_local_datetime(&local).earliest(),
+        None => Local
+            .from_local_datetime(&local)
+            .earliest()
+            .map(|at| at.fixed_offset()),
+    };
+    at.unwrap_or_else(|| local.and_utc().fixed_offset())
+}
+
+/// When a task is due: a whole day, or a moment on a day
+///
+/// Stored as `YYYY-MM-DD` or as an RFC 3339 timestamp with its offset
+/// (`2026-03-04T17:00:00+01:00`), so date-only files keep working. A time
+/// written by hand without an offset is `Local` until the project's
+/// timezone is applied with [`Due::resolve`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Due {
+    Date(NaiveDate),
+    At(DateTime<FixedOffset>),
+    Local(NaiveDateTime),
+}
+
+impl Due {
+    /// The calendar day the task is due, in the timezone it was given in
+    pub fn date(&self) -> NaiveDate {
+        match self {
+            Due::Date(date) => *date,
+            Due::At(at) => at.date_naive(),
+            Due::Local(local) => local.date(),
+        }
+    }
+
+    /// The time of day, if one was given
+    pub fn time(&self) -> Option<NaiveTime> {
+        match self {
+            Due::Date(_) => None,
+            Due::At(at) => Some(at.time()),
+            Due::Local(local) => Some(local.time()),
+        }
+    }
+
+    /// Pin a `Local` time to `offset` (the system timezone for `None`)
+    ///
+    /// Stores apply their project's `[display] timezone` before saving.
+    pub fn resolve(self, offset: Option<FixedOffset>) -> Due {
+        match self {
+            Due::Local(local) => Due::At(local_in(local, offset)),
+            due => due,
+        }
+    }
+
+    /// The moment reminders are counted from
+    ///
+    /// Date-only due dates count from [`REMINDER_HOUR`] local time that day.
+    pub fn anchor(&self) -> DateTime<Utc> {
+        match self {
+            Due::Date(date) => {
+                let morning = date.and_time(NaiveTime::from_hms_opt(REMINDER_HOUR, 0, 0).unwrap());
+                local_in(morning, timezone()).to_utc()
+            }
+            Due::At(at) => at.to_utc(),
+            Due::Local(local) => local_in(*local, timezone()).to_utc(),
+        }
+    }
+
+    /// Whether the deadline has passed at `now`
+    ///
+    /// A date-only due date passes once that day is over in local time.
+    pub fn is_past(&self, now: DateTime<Utc>) -> bool {
+        match self {
+            Due::Date(date) => *date < local_date(now),
+            Due::At(at) => *at < now,
+            Due::Local(_) => self.anchor() < now,
+        }
+    }
+
+    /// Whether the deadline is still ahead but no more than `days` away
+    ///
+    /// Date-only due dates count whole days: due today or within the next
+    /// `days` local days.
+    pub fn is_within(&self, now: DateTime<Utc>, days: u32) -> bool {
+        if self.is_past(now) {
+            return false;
+        }
+        match self {
+            Due::Date(date) => *date <= local_date(now) + Days::new(days as u64),
+            Due::At(at) => *at <= now + Duration::days(days as i64),
+            Due::Local(_) => self.anchor() <= now + Duration::days(days as i64),
+        }
+    }
+}
+
+impl From<NaiveDate> for Due {
+    fn from(date: NaiveDate) -> Self {
+        Due::Date(date)
+    }
+}
+
+/// Earlier days first; within a day, timed deadlines come before date-only ones
+impl Ord for Due {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.date()
+            .cmp(&other.date())
+            .then_with(|| match (self, other) {
+                (Due::Date(_), Due::Date(_)) => Ordering::Equal,
+                (_, Due::Date(_)) => Ordering::Less,
+                (Due::Date(_), _) => Ordering::Greater,
+                (Due::At(a), Due::At(b)) => a.cmp(b),
+                (Due::Local(a), Due::Local(b)) => a.cmp(b),
+                _ => self.anchor().cmp(&other.anchor()),
+            })
+    }
+}
+
+impl PartialOrd for Due {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Due {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Due::Date(date) => write!(f, "{}", date.format("%Y-%m-%d")),
+            Due::At(at) => write!(f, "{}", at.to_rfc3339()),
+            Due::Local(local) => write!(f, "{}", local.format("%Y-%m-%dT%H:%M:%S")),
+        }
+    }
+}
+
+impl std::str::FromStr for Due {
+    type Err = String;
+
+    /// Parse the stored form: `YYYY-MM-DD` or an RFC 3339 timestamp
+    ///
+    /// A hand-written `YYYY-MM-DDTHH:MM` (or with a space or seconds) is
+    /// accepted too, as a [`Due::Local`] time: parsing doesn't know which
+    /// project's timezone it is in.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+            return Ok(Due::Date(date));
+        }
+        if let Ok(at) = DateTime::parse_from_rfc3339(s) {
+            return Ok(Due::At(at));
+        }
+        [
+            "%Y-%m-%dT%H:%M",
+            "%Y-%m-%dT%H:%M:%S",
+            "%Y-%m-%d %H:%M",
+            "%Y-%m-%d %H:%M:%S",
+        ]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+        .map(Due::Local)
+        .ok_or_else(|| format!("Invalid due date: {}", s))
+    }
+}
+
+impl Serialize for Due {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Due {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// When to be reminded of a task, relative to its due date
+///
+/// Written as a signed offset in minutes, hours, days or weeks (`-1d`,
+/// `-2h`, `+30m`); without a sign the offset counts back from the due date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ReminderOffset(Duration);
+
+impl ReminderOffset {
+    /// When this reminder fires for `due`, `None` if that is beyond the
+    /// representable dates
+    pub fn fire_time(&self, due: &Due) -> Option<DateTime<Utc>> {
+        due.anchor().checked_add_signed(self.0)
+    }
+}
+
+impl fmt::Display for ReminderOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < Duration::zero() { '-' } else { '+' };
+        let minutes = self.0.num_minutes().abs();
+        let (amount, unit) = [(7 * 24 * 60, 'w'), (24 * 60, 'd'), (60, 'h')]
+            .into_iter()
+            .find(|&(size, _)| minutes != 0 && minutes % size == 0)
+            .map(|(size, unit)| (minutes / size, unit))
+            .unwrap_or((minutes, 'm'));
+        write!(f, "{}{}{}", sign, amount, unit)
+    }
+}
+
+impl std::str::FromStr for ReminderOffset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || format!("Invalid reminder: {} (expected e.g. -1d, -2h, -30m)", s);
+        let (after, rest) = match s.strip_prefix('+') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('-').unwrap_or(s)),
+        };
+        let split = rest
+            .char_indices()
+            .last()
+            .map(|(i, _)| i)
+            .ok_or_else(invalid)?;
+        let (amount, unit) = rest.split_at(split);
+        let amount: i64 = amount.parse().map_err(|_| invalid())?;
+        let offset = match unit {
+            "m" => Duration::try_minutes(amount),
+            "h" => Duration::try_hours(amount),
+            "d" => Duration::try_days(amount),
+            "w" => Duration::try_weeks(amount),
+            _ => return Err(invalid()),
+        }
+        .ok_or_else(invalid)?;
+        Ok(ReminderOffset(if after { offset } else { -offset }))
+    }
+}
+
+impl Serialize for ReminderOffset {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ReminderOffset {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Parse a due date with an optional time of day, relative to `now`
+///
+/// Accepts a full RFC 3339 timestamp, or anything [`parse_due_date`]
+/// understands optionally followed by a time, separated by `T` or a space
+/// (`2026-03-04T17:00`, `tomorrow 9:30`). Times without an offset are taken
+/// in the timezone of `now`.
+pub fn parse_due<Tz: TimeZone>(s: &str, now: DateTime<Tz>) -> Result<Due, String> {
+    let s = s.trim();
+    if let Ok(at) = DateTime::parse_from_rfc3339(s) {
+        return Ok(Due::At(at));
+    }
+
+    // Weekday names contain `t` too ("SAT", "Thursday"), so only split off
+    // a time that starts with a digit
+    let lower = s.to_lowercase();
+    let Some((day, time)) = lower
+        .rsplit_once(['t', ' '])
+        .filter(|(day, time)| !day.is_empty() && time.starts_with(|c: char| c.is_ascii_digit()))
+    else {
+        return parse_due_date(s, now.date_naive()).map(Due::Date);
+    };
+
+    let date = parse_due_date(day, now.date_naive())?;
+    let time = NaiveTime::parse_from_str(time, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M:%S"))
+        .map_err(|_| format!("Invalid time: {} (expected HH:MM)", time))?;
+    let at = now
+        .timezone()
+        .from_local_datetime(&date.and_time(time))
+        .earliest()
+        .ok_or_else(|| format!("{} {} doesn't exist in the local timezone", date, time))?;
+    Ok(Due::At(at.fixed_offset()))
+}
+
+/// Parse a due date relative to the current time in the configured timezone
+pub fn parse_due_now(s: &str) -> Result<Due, String> {
+    match timezone() {
+        Some(offset) => parse_due(s, Utc::now().with_timezone(&offset)),
+        None => parse_due(s, Local::now()),
+    }
+}
+
+/// Parse a due date, resolving relative expressions against `today`
+///
+/// Accepts `YYYY-MM-DD`, `today`, `tomorrow`, a weekday name (`fri`,
+/// `monday`: the next such day after today) or an offset such as `3d`,
+/// `+2w`, `-1d` or `1m` (calendar months; time windows such as
+/// `log --since 30m` read `m` as minutes instead).
+pub fn parse_due_date(s: &str, today: NaiveDate) -> Result<NaiveDate, String> {
+    let s = s.trim().to_lowercase();
+
+    if let Ok(date) = NaiveDate::parse_from_str(&s, "%Y-%m-%d") {
+        return Ok(date);
+    }
+
+    match s.as_str() {
+        "today" => return Ok(today),
+        "tomorrow" => return Ok(today + Days::new(1)),
+        _ => {}
+    }
+
+    if let Ok(weekday) = s.parse::<Weekday>() {
+        let ahead =
+            (7 + weekday.num_days_from_monday() - today.weekday().num_days_from_monday()) % 7;
+        return Ok(today + Days::new(if ahead == 0 { 7 } else { ahead.into() }));
+    }
+
+    let invalid = || {
+        format!(
+            "Invalid date: {} (expected YYYY-MM-DD, today, tomorrow, a weekday or e.g. +3d)",
+            s
+        )
+    };
+
+    let (negative, rest) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s.strip_prefix('+').unwrap_or(&s)),
+    };
+    let split = rest
+        .char_indices()
+        .last()
+        .map(|(i, _)| i)
+        .ok_or_else(invalid)?;
+    let (amount, unit) = rest.split_at(split);
+    let amount: u32 = amount.parse().map_err(|_| invalid())?;
+
+    let shifted = match (unit, negative) {
+        ("d", false) => today.checked_add_days(Days::new(amount.into())),
+        ("d", true) => today.checked_sub_days(Days::new(amount.into())),
+        ("w", false) => today.checked_add_days(Days::new(u64::from(amount) * 7)),
+        ("w", true) => today.checked_sub_days(Days::new(u64::from(amount) * 7)),
+        ("m", false) => today.checked_add_months(Months::new(amount)),
+        ("m", true) => today.checked_sub_months(Months::new(amount)),
+        _ => return Err(invalid()),
+    };
+
+    shifted.ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_due_storage_format() {
+        let day: Due = "2026-03-04".parse().unwrap();
+        assert_eq!(day, Due::Date(date(2026, 3, 4)));
+        assert_eq!(day.to_string(), "2026-03-04");
+
+        let at: Due = "2026-03-04T17:00:00+01:00".parse().unwrap();
+        assert_eq!(at.date(), date(2026, 3, 4));
+        assert_eq!(at.to_string(), "2026-03-04T17:00:00+01:00");
+        assert!(at < day);
+        assert!("tomorrow".parse::<Due>().is_err());
+
+        // Written by hand without an offset: kept local until a store pins
+        // it to its project's timezone
+        for written in ["2026-03-04T17:00", "2026-03-04 17:00:00"] {
+            let local: Due = written.parse().unwrap();
+            assert_eq!(local.date(), date(2026, 3, 4));
+            assert_eq!(local.time(), NaiveTime::from_hms_opt(17, 0, 0));
+            assert_eq!(local.to_string(), "2026-03-04T17:00:00");
+            assert_eq!(local.to_string().parse::<Due>(), Ok(local));
+            assert!(local < day);
+
+            let offset = FixedOffset::east_opt(5 * 3600);
+            assert_eq!(
+                local.resolve(offset).to_string(),
+                "2026-03-04T17:00:00+05:00"
+            );
+            assert_eq!(at.resolve(offset), at);
+        }
+        assert!("tomorrow 9:00".parse::<Due>().is_err());
+        assert!("2026-03-04T25:00".parse::<Due>().is_err());
+    }
+
+    #[test]
+    fn test_parse_due_with_time() {
+        let now = Local.with_ymd_and_hms(2026, 2, 18, 12, 0, 0).unwrap();
+
+        assert_eq!(parse_due("fri", now), Ok(Due::Date(date(2026, 2, 20))));
+        let at = parse_due("2026-03-04T17:00", now).unwrap();
+        assert_eq!(at.date(), date(2026, 3, 4));
+        assert_eq!(at.time(), NaiveTime::from_hms_opt(17, 0, 0));
+        let at = parse_due("tomorrow 9:30", now).unwrap();
+        assert_eq!(at.date(), date(2026, 2, 19));
+        assert_eq!(at.time(), NaiveTime::from_hms_opt(9, 30, 0));
+        assert_eq!(
+            parse_due("2026-03-04T17:00:00+05:30", now)
+                .unwrap()
+                .to_string(),
+            "2026-03-04T17:00:00+05:30"
+        );
+        assert!(parse_due("tomorrow 25:00", now).is_err());
+
+        assert_eq!(parse_due("SAT", now), Ok(Due::Date(date(2026, 2, 21))));
+        assert_eq!(parse_due("Saturday", now), Ok(Due::Date(date(2026, 2, 21))));
+        assert_eq!(parse_due("THURSDAY", now), Ok(Due::Date(date(2026, 2, 19))));
+        let at = parse_due("SAT 10:00", now).unwrap();
+        assert_eq!(at.date(), date(2026, 2, 21));
+        assert_eq!(at.time(), NaiveTime::from_hms_opt(10, 0, 0));
+        let at = parse_due("2026-03-04t08:15", now).unwrap();
+        assert_eq!(at.time(), NaiveTime::from_hms_opt(8, 15, 0));
+    }
+
+    #[test]
+    fn test_reminder_offsets() {
+        let offset: ReminderOffset = "-1d".parse().unwrap();
+        assert_eq!(offset.to_string(), "-1d");
+        assert_eq!("2h".parse::<ReminderOffset>().unwrap().to_string(), "-2h");
+        assert_eq!(
+            "+90m".parse::<ReminderOffset>().unwrap().to_string(),
+            "+90m"
+        );
+        assert_eq!("-14d".parse::<ReminderOffset>().unwrap().to_string(), "-2w");
+        assert!("-1y".parse::<ReminderOffset>().is_err());
+        assert!("soon".parse::<ReminderOffset>().is_err());
+        assert!("-999999999999999w".parse::<ReminderOffset>().is_err());
+
+        let due: Due = "2026-03-04T17:00:00+01:00".parse().unwrap();
+        assert_eq!(
+            offset.fire_time(&due),
+            Utc.with_ymd_and_hms(2026, 3, 3, 16, 0, 0).single()
+        );
+        let far: ReminderOffset = "-99999999w".parse().unwrap();
+        assert_eq!(far.fire_time(&due), None);
+        let day = Due::Date(date(2026, 3, 4));
+        assert_eq!(
+            offset
+                .fire_time(&day)
+                .unwrap()
+                .with_timezone(&Local)
+                .naive_local()
+                .to_string(),
+            "2026-03-03 09:00:00"
+        );
+    }
+
+    #[test]
+    fn test_is_past() {
+        let now = Utc::now();
+        assert!(Due::At((now - chrono::Duration::minutes(1)).fixed_offset()).is_past(now));
+        assert!(!Due::At((now + chrono::Duration::minutes(1)).fixed_offset()).is_past(now));
+        let today = now.with_timezone(&Local).date_naive();
+        assert!(!Due::Date(today).is_past(now));
+        assert!(Due::Date(today - Days::new(1)).is_past(now));
+    }
+
+    #[test]
+    fn test_is_within() {
+        let now = Utc::now();
+        let today = now.with_timezone(&Local).date_naive();
+        assert!(Due::Date(today).is_within(now, 3));
+        assert!(Due::Date(today + Days::new(3)).is_within(now, 3));
+        assert!(!Due::Date(today + Days::new(4)).is_within(now, 3));
+        assert!(!Due::Date(today - Days::new(1)).is_within(now, 3));
+        assert!(Due::At((now + chrono::Duration::hours(47)).fixed_offset()).is_within(now, 2));
+        assert!(!Due::At((now + chrono::Duration::hours(49)).fixed_offset()).is_within(now, 2));
+    }
+
+    #[test]
+    fn test_date_in_timezone() {
+        let late = Utc.with_ymd_and_hms(2026, 3, 4, 23, 30, 0).unwrap();
+        assert_eq!(date_in(late, FixedOffset::east_opt(0)), date(2026, 3, 4));
+        assert_eq!(
+            date_in(late, FixedOffset::east_opt(2 * 3600)),
+            date(2026, 3, 5)
+        );
+        let early = Utc.with_ymd_and_hms(2026, 3, 4, 0, 30, 0).unwrap();
+        assert_eq!(
+            date_in(early, FixedOffset::west_opt(5 * 3600)),
+            date(2026, 3, 3)
+        );
+        assert_eq!(
+            date_in(early, None),
+            early.with_timezone(&Local).date_naive()
+        );
+    }
+
+    #[test]
+    fn test_parse_due_date() {
+        // A Wednesday
+        let today = date(2026, 2, 18);
+
+        assert_eq!(parse_due_date("2026-03-01", today), Ok(date(2026, 3, 1)));
+        assert_eq!(parse_due_date("today", today), Ok(today));
+        assert_eq!(parse_due_date("Tomorrow", today), Ok(date(2026, 2, 19)));
+        assert_eq!(parse_due_date("fri", today), Ok(date(2026, 2, 20)));
+        assert_eq!(parse_due_date("wednesday", today), Ok(date(2026, 2, 25)));
+        assert_eq!(parse_due_date("3d", today), Ok(date(2026, 2, 21)));
+        assert_eq!(parse_due_date("+2w", today), Ok(date(2026, 3, 4)));
+        assert_eq!(parse_due_date("-1d", today), Ok(date(2026, 2, 17)));
+        assert_eq!(parse_due_date("1m", today), Ok(date(2026, 3, 18)));
+        assert!(parse_due_date("soon", today).is_err());
+        assert!(parse_due_date("", today).is_err());
+    }
+}
@@ -1,11 +1,18 @@
 //! YAML frontmatter parsing and serialization
 
-use crate::models::task::Task;
+use crate::models::task::{Comment, Task, TaskStatus};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+use std::collections::{HashMap, HashSet};
 use thiserror::Error;
 
 /// Frontmatter delimiter
 const FRONTMATTER_DELIMITER: &str = "---";
 
+/// Heading of the body section holding a task's comments
+const COMMENTS_HEADING: &str = "## Comments";
+
 /// Errors that can occur during frontmatter operations
 #[derive(Debug, Error)]
 pub enum FrontmatterError {
@@ -21,10 +28,109 @@ pub enum FrontmatterError {
 pub fn parse_task(content: &str) -> Result<Task, FrontmatterError> {
     let (frontmatter, body) = split_frontmatter(content)?;
     let mut task: Task = serde_yaml::from_str(&frontmatter)?;
-    task.description = body.trim().to_string();
+    (task.description, task.comments) = split_comments(body.trim());
     Ok(task)
 }
 
+/// Split a body into the description and the comments section after it
+///
+/// A `## Comments` heading only starts the section if everything after it
+/// reads as comments; otherwise it is part of the description.
+fn split_comments(body: &str) -> (String, Vec<Comment>) {
+    let lines: Vec<&str> = body.lines().collect();
+    for (i, line) in lines.iter().enumerate() {
+        if line.trim_end() != COMMENTS_HEADING {
+            continue;
+        }
+        if let Some(comments) = parse_comments(&lines[i + 1..]) {
+            return (lines[..i].join("\n").trim_end().to_string(), comments);
+        }
+    }
+    (body.to_string(), Vec::new())
+}
+
+/// Parse the lines of a comments section, or `None` if they aren't one
+fn parse_comments(lines: &[&str]) -> Option<Vec<Comment>> {
+    let mut comments: Vec<Comment> = Vec::new();
+    for line in lines {
+        if let Some((at, author)) = comment_heading(line) {
+            comments.push(Comment {
+                at,
+                author,
+                text: String::new(),
+            });
+            continue;
+        }
+        match comments.last_mut() {
+            Some(comment) => {
+                comment.text.push_str(line);
+                comment.text.push('\n');
+            }
+            None if line.trim().is_empty() => {}
+            None => return None,
+        }
+    }
+    for comment in &mut comments {
+        comment.text = comment.text.trim().to_string();
+    }
+    (!comments.is_empty()).then_some(comments)
+}
+
+/// Parse a `### <RFC 3339 timestamp> [author]` comment heading
+fn comment_heading(line: &str) -> Option<(DateTime<Utc>, Option<String>)> {
+    let heading = line.strip_prefix("### ")?.trim();
+    let (at, author) = heading.split_once(' ').unwrap_or((heading, ""));
+    let at = DateTime::parse_from_rfc3339(at).ok()?.to_utc();
+    let author = author.trim();
+    Some((at, (!author.is_empty()).then(|| author.to_string())))
+}
+
+/// The body of a task file: the description, then any comments
+fn render_body(task: &Task) -> String {
+    let mut body = task.description.clone();
+    if task.comments.is_empty() {
+        return body;
+    }
+    if !body.is_empty() {
+        body.push_str("\n\n");
+    }
+    body.push_str(COMMENTS_HEADING);
+    body.push('\n');
+    for comment in &task.comments {
+        body.push_str("\n### ");
+        body.push_str(&comment.at.to_rfc3339_opts(SecondsFormat::Secs, true));
+        if let Some(author) = &comment.author {
+            body.push(' ');
+            body.push_str(author);
+        }
+        body.push_str("\n\n");
+        body.push_str(&comment.text);
+        body.push('\n');
+    }
+    body.trim_end().to_string()
+}
+
+/// Read only the status of a task file, without parsing the rest
+///
+/// Much cheaper than `parse_task` when all that's needed is whether a task
+/// is open. A missing `status` key means `pending`, as it does for
+/// `parse_task`.
+pub fn peek_status(content: &str) -> Result<TaskStatus, FrontmatterError> {
+    let (frontmatter, _) = split_frontmatter(content)?;
+    let Some(line) = frontmatter
+        .lines()
+        .find(|line| top_level_key(line) == Some("status"))
+    else {
+        return Ok(TaskStatus::default());
+    };
+    let (_, value) = line
+        .split_once(':')
+        .ok_or(FrontmatterError::InvalidFormat)?;
+    let value = value.split(" #").next().unwrap_or_default().trim();
+    TaskStatus::parse_stored(value.trim_matches(|c| c == '"' || c == '\''))
+        .map_err(|_| FrontmatterError::InvalidFormat)
+}
+
 /// Serialize a Task to a markdown file with YAML frontmatter
 pub fn serialize_task(task: &Task) -> Result<String, FrontmatterError> {
     let frontmatter = serde_yaml::to_string(&task)?;
@@ -35,48 +141,508 @@ pub fn serialize_task(task: &Task) -> Result<String, FrontmatterError> {
     result.push_str(FRONTMATTER_DELIMITER);
     result.push('\n');
 
-    if !task.description.is_empty() {
+    let body = render_body(task);
+    if !body.is_empty() {
         result.push('\n');
-        result.push_str(&task.description);
+        result.push_str(&body);
         result.push('\n');
     }
 
     Ok(result)
 }
 
+/// Rewrite an existing task file so it matches `task`, keeping everything else
+///
+/// Only frontmatter keys whose values changed are re-rendered; comments, key
+/// order, unknown keys and formatting of untouched keys are kept, as is the
+/// body when the description is unchanged. Newly set keys are appended.
+/// Falls back to `serialize_task` if `original` can't be parsed or the
+/// patched text doesn't read back as `task`.
+pub fn update_task_content(original: &str, task: &Task) -> Result<String, FrontmatterError> {
+    match patch_frontmatter(original, task) {
+        Ok(Some(content)) => Ok(content),
+        _ => serialize_task(task),
+    }
+}
+
+fn patch_frontmatter(original: &str, task: &Task) -> Result<Option<String>, FrontmatterError> {
+    let old_task = parse_task(original)?;
+    let (frontmatter, body) = split_frontmatter(original)?;
+    let old = to_mapping(&old_task)?;
+    let new = to_mapping(task)?;
+
+    let source: Vec<&str> = if frontmatter.is_empty() {
+        Vec::new()
+    } else {
+        frontmatter.split('\n').collect()
+    };
+    let mut lines: Vec<String> = Vec::new();
+    let mut seen = HashSet::new();
+    // Where each key's entry ends in `lines`
+    let mut ends: HashMap<Value, usize> = HashMap::new();
+    let mut i = 0;
+
+    while i < source.len() {
+        let Some(key) = top_level_key(source[i]) else {
+            lines.push(source[i].to_string());
+            i += 1;
+            continue;
+        };
+
+        let mut end = i + 1;
+        while end < source.len() && is_continuation(&source[end..]) {
+            end += 1;
+        }
+
+        let key = Value::String(key.to_string());
+        match (old.get(&key), new.get(&key)) {
+            (Some(before), Some(after)) if before != after => {
+                lines.extend(render_entry(&key, after)?)
+            }
+            // Field was cleared
+            (Some(_), None) => {}
+            // Unchanged, or a key gittask doesn't know about
+            _ => lines.extend(source[i..end].iter().map(|l| l.to_string())),
+        }
+
+        ends.insert(key.clone(), lines.len());
+        seen.insert(key);
+        i = end;
+    }
+
+    // Keys left implicit in the original (defaults) stay implicit unless
+    // changed, and go after the key that precedes them in `serialize_task`
+    let mut inserts: Vec<(usize, Vec<String>)> = Vec::new();
+    let mut anchor = lines.len();
+    for (key, value) in &new {
+        if let Some(&end) = ends.get(key) {
+            anchor = end;
+        } else if !seen.contains(key) && old.get(key) != Some(value) {
+            inserts.push((anchor, render_entry(key, value)?));
+        }
+    }
+    inserts.sort_by_key(|(at, _)| *at);
+    for (at, entry) in inserts.into_iter().rev() {
+        lines.splice(at..at, entry);
+    }
+
+    let mut result = String::new();
+    result.push_str(FRONTMATTER_DELIMITER);
+    result.push('\n');
+    for line in &lines {
+        result.push_str(line);
+        result.push('\n');
+    }
+    result.push_str(FRONTMATTER_DELIMITER);
+    result.push('\n');
+
+    if old_task.description == task.description && old_task.comments == task.comments {
+        if !body.trim().is_empty() {
+            result.push_str(&body);
+            result.push('\n');
+        }
+    } else {
+        let body = render_body(task);
+        if !body.is_empty() {
+            result.push('\n');
+            result.push_str(&body);
+            result.push('\n');
+        }
+    }
+
+    // Only trust the patch if it reads back as the intended task
+    let check = parse_task(&result)?;
+    if to_mapping(&check)? == new
+        && check.description == task.description.trim()
+        && check.comments == task.comments
+    {
+        Ok(Some(result))
+    } else {
+        Ok(None)
+    }
+}
+
+/// Serialize a task's frontmatter fields to a YAML mapping
+fn to_mapping(task: &Task) -> Result<Mapping, FrontmatterError> {
+    match serde_yaml::to_value(task)? {
+        Value::Mapping(mapping) => Ok(mapping),
+        _ => Err(FrontmatterError::InvalidFormat),
+    }
+}
+
+/// Render a single `key: value` entry as frontmatter lines
+fn render_entry(key: &Value, value: &Value) -> Result<Vec<String>, FrontmatterError> {
+    let mut entry = Mapping::new();
+    entry.insert(key.clone(), value.clone());
+    let yaml = serde_yaml::to_string(&entry)?;
+    Ok(yaml.trim_end().lines().map(str::to_string).collect())
+}
+
+/// What `format_task_content` normalizes (`[fmt]` in config.toml)
+///
+/// Every rule is on by default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FormatRules {
+    /// Put frontmatter keys in the order gittask writes them
+    #[serde(skip_serializing_if = "is_true")]
+    pub key_order: bool,
+    /// Write dates and timestamps the way gittask does, except times
+    /// written without an offset
+    #[serde(skip_serializing_if = "is_true")]
+    pub dates: bool,
+    /// Lowercase tags, dropping duplicates that leaves
+    #[serde(skip_serializing_if = "is_true")]
+    pub lowercase_tags: bool,
+    /// Strip trailing whitespace from the frontmatter and blank lines
+    /// around the body
+    #[serde(skip_serializing_if = "is_true")]
+    pub trailing_whitespace: bool,
+}
+
+fn is_true(value: &bool) -> bool {
+    *value
+}
+
+impl Default for FormatRules {
+    fn default() -> Self {
+        FormatRules {
+            key_order: true,
+            dates: true,
+            lowercase_tags: true,
+            trailing_whitespace: true,
+        }
+    }
+}
+
+/// Frontmatter keys holding dates or timestamps
+const DATE_KEYS: &[&str] = &[
+    "due",
+    "scheduled",
+    "claimed_at",
+    "created",
+    "updated",
+    "last_reviewed",
+    "completed",
+    "reopened",
+    "time_entries",
+];
+
+/// A top-level frontmatter key with its value lines and the comment or
+/// blank lines above it (`key` is `None` for lines after the last key)
+struct Entry {
+    key: Option<String>,
+    lines: Vec<String>,
+    /// Index in `lines` of the `key:` line
+    start: usize,
+}
+
+fn frontmatter_entries(frontmatter: &str) -> Vec<Entry> {
+    let source: Vec<&str> = if frontmatter.is_empty() {
+        Vec::new()
+    } else {
+        frontmatter.split('\n').collect()
+    };
+    let mut entries = Vec::new();
+    let mut leading: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < source.len() {
+        let Some(key) = top_level_key(source[i]) else {
+            leading.push(source[i].to_string());
+            i += 1;
+            continue;
+        };
+        let mut end = i + 1;
+        while end < source.len() && is_continuation(&source[end..]) {
+            end += 1;
+        }
+        let start = leading.len();
+        let mut lines = std::mem::take(&mut leading);
+        lines.extend(source[i..end].iter().map(|l| l.to_string()));
+        entries.push(Entry {
+            key: Some(key.to_string()),
+            lines,
+            start,
+        });
+        i = end;
+    }
+    if !leading.is_empty() {
+        entries.push(Entry {
+            key: None,
+            lines: leading,
+            start: 0,
+        });
+    }
+    entries
+}
+
+/// Normalize a task file by `rules`, leaving what they don't cover alone
+///
+/// Comments and unknown keys are kept (moving with the key below them when
+/// keys are reordered). Fails if the file can't be parsed, or if the
+/// result wouldn't read back as the same task.
+pub fn format_task_content(content: &str, rules: &FormatRules) -> Result<String, FrontmatterError> {
+    let mut task = parse_task(content)?;
+    let (frontmatter, body) = split_frontmatter(content)?;
+
+    let mut tags_changed = false;
+    if rules.lowercase_tags {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in &task.tags {
+            let tag = tag.to_lowercase();
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+        tags_changed = tags != task.tags;
+        task.tags = tags;
+    }
+    let mapping = to_mapping(&task)?;
+
+    let mut entries = frontmatter_entries(&frontmatter);
+    for entry in &mut entries {
+        let Some(key) = entry.key.as_deref() else {
+            continue;
+        };
+        // A time without an offset means the project's timezone; rendering it
+        // would write the offset of whoever runs fmt
+        let rerender =
+            (rules.dates && DATE_KEYS.contains(&key) && !is_local_time(&entry.lines[entry.start]))
+                || (tags_changed && key == "tags");
+        let key = Value::String(key.to_string());
+        if let (true, Some(value)) = (rerender, mapping.get(&key)) {
+            entry.lines.truncate(entry.start);
+            entry.lines.extend(render_entry(&key, value)?);
+        }
+    }
+    if rules.key_order {
+        // Unknown keys, and known ones only written out with their default
+        // value, stay after the rest in their original order
+        entries.sort_by_key(|entry| {
+            entry
+                .key
+                .as_deref()
+                .and_then(|key| mapping.keys().position(|k| k.as_str() == Some(key)))
+                .unwrap_or(usize::MAX)
+        });
+    }
+
+    let mut result = String::new();
+    result.push_str(FRONTMATTER_DELIMITER);
+    result.push('\n');
+    for line in entries.iter().flat_map(|e| &e.lines) {
+        result.push_str(if rules.trailing_whitespace {
+            line.trim_end()
+        } else {
+            line
+        });
+        result.push('\n');
+    }
+    result.push_str(FRONTMATTER_DELIMITER);
+    result.push('\n');
+
+    if rules.trailing_whitespace {
+        // Only whole blank lines: trailing spaces in the body can be
+        // Markdown line breaks or part of a code block
+        let mut body: Vec<&str> = body.lines().collect();
+        while body.last().is_some_and(|line| line.trim().is_empty()) {
+            body.pop();
+        }
+        let start = body
+            .iter()
+            .position(|line| !line.trim().is_empty())
+            .unwrap_or(body.len());
+        let body = body[start..].join("\n");
+        if !body.is_empty() {
+            result.push('\n');
+            result.push_str(&body);
+            result.push('\n');
+        }
+    } else if !body.trim().is_empty() {
+        result.push_str(&body);
+        result.push('\n');
+    }
+
+    if to_mapping(&parse_task(&result)?)? != mapping {
+        return Err(FrontmatterError::InvalidFormat);
+    }
+    Ok(result)
+}
+
+/// Whether a `key: value` line holds a timestamp without an offset, such
+/// as a hand-written `due: 2026-03-01T17:00`
+fn is_local_time(line: &str) -> bool {
+    let Some((_, value)) = line.split_once(':') else {
+        return false;
+    };
+    let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+    [
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%d %H:%M:%S",
+    ]
+    .iter()
+    .any(|format| chrono::NaiveDateTime::parse_from_str(value, format).is_ok())
+}
+
+/// 1-based line of the opening `---` in a task file
+fn opening_line(content: &str) -> Option<usize> {
+    let (i, line) = content
+        .lines()
+        .enumerate()
+        .find(|(_, line)| !line.trim().is_empty())?;
+    (line.trim_end() == FRONTMATTER_DELIMITER).then_some(i + 1)
+}
+
+/// 1-based line in a task file where top-level frontmatter `key` is set
+pub fn key_line(content: &str, key: &str) -> Option<usize> {
+    let start = opening_line(content)?;
+    content
+        .lines()
+        .enumerate()
+        .skip(start)
+        .take_while(|(_, line)| line.trim_end() != FRONTMATTER_DELIMITER)
+        .find(|(_, line)| top_level_key(line) == Some(key))
+        .map(|(i, _)| i + 1)
+}
+
+impl FrontmatterError {
+    /// 1-based line in the task file `content` the error points at, if known
+    pub fn line(&self, content: &str) -> Option<usize> {
+        match self {
+            FrontmatterError::YamlParse(e) => Some(opening_line(content)? + e.location()?.line()),
+            FrontmatterError::MissingDelimiters => Some(1),
+            FrontmatterError::InvalidFormat => None,
+        }
+    }
+
+    /// The error without serde_yaml's position, for callers that report
+    /// the file line from [`FrontmatterError::line`] instead
+    pub fn message(&self) -> String {
+        let message = self.to_string();
+        match self {
+            FrontmatterError::YamlParse(e) => match e.location() {
+                Some(at) => {
+                    let position = format!(" at line {} column {}", at.line(), at.column());
+                    message.replacen(&position, "", 1)
+                }
+                None => message,
+            },
+            _ => message,
+        }
+    }
+}
+
+/// The key of a top-level `key: ...` line
+fn top_level_key(line: &str) -> Option<&str> {
+    if line.starts_with(char::is_whitespace) || line.starts_with('#') || line.starts_with('-') {
+        return None;
+    }
+    let (key, _) = line.split_once(':')?;
+    let key = key.trim_end();
+    (!key.is_empty()).then_some(key)
+}
+
+/// Whether the first line continues the value of the key above it
+///
+/// Indented lines and block sequence items do; blank lines do only when the
+/// value carries on after them (e.g. inside a block scalar).
+fn is_continuation(lines: &[&str]) -> bool {
+    let indented =
+        |line: &str| line.starts_with([' ', '\t']) || line.starts_with("- ") || line == "-";
+
+    if lines[0].trim().is_empty() {
+        lines
+            .iter()
+            .find(|l| !l.trim().is_empty())
+            .is_some_and(|l| l.starts_with([' ', '\t']))
+    } else {
+        indented(lines[0])
+    }
+}
+
+/// Strip a UTF-8 byte order mark and convert CRLF/CR line endings to LF
+///
+/// Parsing normalizes this way and `serialize_task` always writes LF, so a
+/// file saved by a Windows editor is rewritten in the canonical form.
+fn normalize_line_endings(content: &str) -> String {
+    content
+        .trim_start_matches('\u{feff}')
+        .replace("\r\n", "\n")
+        .replace('\r', "\n")
+}
+
 /// Split content into frontmatter and body
-fn split_frontmatter(content: &str) -> Result<(String, String), FrontmatterError> {
-    let content = content.trim();
+///
+/// Delimiter lines may carry trailing whitespace.
+pub(crate) fn split_frontmatter(content: &str) -> Result<(String, String), FrontmatterError> {
+    let content = normalize_line_endings(content);
+    let mut lines = content.trim().split('\n');
 
     // Must start with delimiter
-    if !content.starts_with(FRONTMATTER_DELIMITER) {
+    if lines.next().map(str::trim_end) != Some(FRONTMATTER_DELIMITER) {
         return Err(FrontmatterError::MissingDelimiters);
     }
 
     // Find the closing delimiter
-    let after_first = &content[FRONTMATTER_DELIMITER.len()..];
-    let after_first = after_first.trim_start_matches('\n');
-
-    if let Some(end_pos) = after_first.find(&format!("\n{}", FRONTMATTER_DELIMITER)) {
-        let frontmatter = &after_first[..end_pos];
-        let body_start = end_pos + 1 + FRONTMATTER_DELIMITER.len();
-        let body = if body_start < after_first.len() {
-            &after_first[body_start..]
-        } else {
-            ""
-        };
-        Ok((frontmatter.to_string(), body.to_string()))
-    } else {
-        Err(FrontmatterError::MissingDelimiters)
+    let mut frontmatter = Vec::new();
+    for line in lines.by_ref() {
+        if line.trim_end() == FRONTMATTER_DELIMITER {
+            let body: Vec<&str> = lines.collect();
+            return Ok((frontmatter.join("\n"), body.join("\n")));
+        }
+        frontmatter.push(line);
     }
+
+    Err(FrontmatterError::MissingDelimiters)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::Due;
     use crate::models::task::{Priority, TaskKind, TaskStatus};
     use chrono::NaiveDate;
 
+    #[test]
+    fn test_peek_status() {
+        let status = |content: &str| peek_status(content).ok();
+        assert_eq!(
+            status("---\nid: 1\nstatus: in-progress  # started\n---\nstatus: completed\n"),
+            Some(TaskStatus::InProgress)
+        );
+        assert_eq!(
+            status("---\nid: 2\nstatus: 'completed'\n---\n"),
+            Some(TaskStatus::Completed)
+        );
+        assert_eq!(
+            status("---\nid: 3\ntitle: No status\n---\n"),
+            Some(TaskStatus::Pending)
+        );
+        assert_eq!(
+            status("---\nid: 4\nstatus: review\n---\n"),
+            Some(TaskStatus::Custom("review"))
+        );
+        assert_eq!(status("---\nid: 5\nstatus: not sure\n---\n"), None);
+        assert_eq!(status("no frontmatter"), None);
+    }
+
+    #[test]
+    fn test_error_and_key_lines() {
+        let content = "---\nid: 7\ntitle: Broken\nblocked_by: [1]\npriority: [oops\n---\n\nbody\n";
+        assert_eq!(key_line(content, "blocked_by"), Some(4));
+        assert_eq!(key_line(content, "due"), None);
+        let err = parse_task(content).unwrap_err();
+        assert!(err.line(content).is_some_and(|line| line >= 5));
+        assert_eq!(
+            parse_task("no frontmatter")
+                .unwrap_err()
+                .line("no frontmatter"),
+            Some(1)
+        );
+    }
+
     #[test]
     fn test_split_frontmatter() {
         let content = r#"---
@@ -117,6 +683,30 @@ title: Test task"#;
         assert!(split_frontmatter(content).is_err());
     }
 
+    #[test]
+    fn test_split_frontmatter_windows_file() {
+        let content =
+            "\u{feff}---  \r\nid: 1\r\ntitle: Test task\r\n--- \t\r\n\r\nLine one\r\nLine two\r\n";
+        let (frontmatter, body) = split_frontmatter(content).unwrap();
+        assert_eq!(frontmatter, "id: 1\ntitle: Test task");
+        assert_eq!(body, "\nLine one\nLine two");
+    }
+
+    #[test]
+    fn test_windows_file_roundtrips_to_lf() {
+        let content = "\u{feff}---\r\nid: 1\r\ntitle: Test task\r\ncreated: 2026-02-13T10:30:00Z\r\nupdated: 2026-02-13T10:30:00Z\r\n---\r\n\r\nLine one\r\nLine two\r\n";
+        let task = parse_task(content).unwrap();
+        assert_eq!(task.description, "Line one\nLine two");
+
+        let written = serialize_task(&task).unwrap();
+        assert!(!written.contains('\r'));
+        assert!(!written.starts_with('\u{feff}'));
+        assert_eq!(
+            serialize_task(&parse_task(&written).unwrap()).unwrap(),
+            written
+        );
+    }
+
     #[test]
     fn test_parse_task() {
         let content = r#"---
@@ -145,10 +735,15 @@ It can have multiple lines.
         assert_eq!(task.tags, vec!["auth", "security"]);
         assert_eq!(
             task.due,
-            Some(NaiveDate::from_ymd_opt(2026, 2, 20).unwrap())
+            NaiveDate::from_ymd_opt(2026, 2, 20).map(Due::Date)
         );
         assert!(task.description.contains("This is the task description."));
         assert!(task.description.contains("multiple lines"));
+
+        let with_time = content.replace("due: 2026-02-20", "due: 2026-02-20T17:00");
+        let due = parse_task(&with_time).unwrap().due.unwrap();
+        assert_eq!(due.date(), NaiveDate::from_ymd_opt(2026, 2, 20).unwrap());
+        assert_eq!(due.time(), chrono::NaiveTime::from_hms_opt(17, 0, 0));
     }
 
     #[test]
@@ -186,6 +781,105 @@ updated: 2026-02-13T10:30:00Z
         assert!(content.contains("Task description here."));
     }
 
+    #[test]
+    fn test_update_preserves_comments_and_order() {
+        let original = r#"---
+# Tracked for the Q3 release
+title: Fix authentication bug
+id: 7
+status: pending  # blocked on review
+tags:
+- auth
+owner: alice
+created: 2026-02-13T10:30:00Z
+updated: 2026-02-13T10:30:00Z
+---
+
+Body stays as written.
+"#;
+        let mut task = parse_task(original).unwrap();
+        task.priority = Priority::High;
+        task.tags.push("security".to_string());
+        task.updated = "2026-02-14T09:00:00Z".parse().unwrap();
+
+        let updated = update_task_content(original, &task).unwrap();
+        assert_eq!(
+            updated,
+            r#"---
+# Tracked for the Q3 release
+title: Fix authentication bug
+id: 7
+status: pending  # blocked on review
+priority: high
+tags:
+- auth
+- security
+owner: alice
+created: 2026-02-13T10:30:00Z
+updated: 2026-02-14T09:00:00Z
+---
+
+Body stays as written.
+"#
+        );
+    }
+
+    #[test]
+    fn test_format_task_content() {
+        // The due time has no offset, so the result must not depend on the
+        // timezone the tests run in
+        let original = "---\nupdated: 2026-02-13T10:30:00+00:00   \nid: 7\n# Owned by the API team\nowner: alice\ntags: [API, auth, api]\ntitle: Fix login\ndue: 2026-03-01T17:00\ncreated: 2026-02-13T11:30:00+01:00\n---\n\n  \nLine break  \nhere\n\n```\ncode  \n```\n\n";
+        let formatted = format_task_content(original, &FormatRules::default()).unwrap();
+        assert_eq!(
+            formatted,
+            "---\nid: 7\ntitle: Fix login\ntags:\n- api\n- auth\ndue: 2026-03-01T17:00\ncreated: 2026-02-13T10:30:00Z\nupdated: 2026-02-13T10:30:00Z\n# Owned by the API team\nowner: alice\n---\n\nLine break  \nhere\n\n```\ncode  \n```\n"
+        );
+        assert_eq!(
+            format_task_content(&formatted, &FormatRules::default()).unwrap(),
+            formatted
+        );
+
+        let rules = FormatRules {
+            key_order: false,
+            lowercase_tags: false,
+            ..Default::default()
+        };
+        let formatted = format_task_content(original, &rules).unwrap();
+        assert!(formatted.starts_with("---\nupdated: 2026-02-13T10:30:00Z\nid: 7\n"));
+        assert!(formatted.contains("tags: [API, auth, api]\n"));
+    }
+
+    #[test]
+    fn test_update_removes_cleared_fields_and_rewrites_body() {
+        let original = r#"---
+id: 1
+title: Test task
+due: 2026-02-20
+created: 2026-02-13T10:30:00Z
+updated: 2026-02-13T10:30:00Z
+---
+
+Old body.
+"#;
+        let mut task = parse_task(original).unwrap();
+        task.due = None;
+        task.description = "New body.".to_string();
+
+        let updated = update_task_content(original, &task).unwrap();
+        assert!(!updated.contains("due:"));
+        assert!(updated.ends_with("---\n\nNew body.\n"));
+        assert_eq!(parse_task(&updated).unwrap(), task);
+    }
+
+    #[test]
+    fn test_update_falls_back_for_unparseable_original() {
+        let task = Task::new(1, TaskKind::Task, "Test task");
+        assert_eq!(
+            update_task_content("not a task", &task).unwrap(),
+            serialize_task(&task).unwrap()
+        );
+    }
+
     #[test]
     fn test_roundtrip() {
         let mut task = Task::new(42, TaskKind::Idea, "New feature idea");
@@ -203,4 +897,37 @@ updated: 2026-02-13T10:30:00Z
         assert_eq!(task.tags, parsed.tags);
         assert_eq!(task.description, parsed.description);
     }
+
+    #[test]
+    fn test_comments_section() {
+        let mut task = Task::new(7, TaskKind::Task, "Discuss");
+        task.description = "Body\n\n## Comments\n\nNot really comments".to_string();
+        task.add_comment(
+            Some("ana@example.com".to_string()),
+            "First\n\n### Details\nhere",
+        );
+        task.add_comment(None, "  Second  ");
+
+        let content = serialize_task(&task).unwrap();
+        assert!(content.contains("\n## Comments\n\n### "));
+        let parsed = parse_task(&content).unwrap();
+        assert_eq!(parsed.description, task.description);
+        assert_eq!(parsed.comments, task.comments);
+        assert_eq!(
+            parsed.comments[0].author.as_deref(),
+            Some("ana@example.com")
+        );
+        assert_eq!(parsed.comments[0].text, "First\n\n### Details\nhere");
+        assert_eq!(parsed.comments[1].text, "Second");
+
+        // Adding a comment keeps the rest of the file as written
+        let original = "---\nid: 7\ntitle: Discuss  # note\nkind: task\n\
+                        created: 2026-03-01T10:00:00Z\nupdated: 2026-03-01T10:00:00Z\n---\n\nBody\n";
+        let mut task = parse_task(original).unwrap();
+        task.add_comment(None, "Looks good");
+        let updated = update_task_content(original, &task).unwrap();
+        assert!(updated.starts_with("---\nid: 7\ntitle: Discuss  # note\n"));
+        assert!(updated.contains("---\n\nBody\n\n## Comments\n\n### "));
+        assert_eq!(parse_task(&updated).unwrap().comments, task.comments);
+    }
 }
@@ -1,7 +1,16 @@
 //! Data models for gittask
 
+pub mod checklist;
+pub mod due;
 pub mod frontmatter;
 pub mod task;
 
-pub use frontmatter::{FrontmatterError, parse_task, serialize_task};
-pub use task::{Priority, Task, TaskKind, TaskStatus};
+pub use checklist::{Checklist, ChecklistItem};
+pub use due::{
+    Due, ReminderOffset, local_date, parse_due, parse_due_date, parse_due_now, set_timezone, today,
+};
+pub use frontmatter::{
+    FormatRules, FrontmatterError, format_task_content, key_line, parse_task, peek_status,
+    serialize_task, update_task_content,
+};
+pub use task::{Comment, Priority, Reopening, Resolution, Task, TaskKind, TaskStatus, TimeEntry};
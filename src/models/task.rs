@@ -1,25 +1,104 @@
 //! Task model and related types
 
-use chrono::{DateTime, NaiveDate, Utc};
+use crate::models::checklist::{self, Checklist};
+use crate::models::due::{Due, ReminderOffset};
+use chrono::{DateTime, NaiveDate, SubsecRound, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
 use std::fmt;
+use std::sync::Mutex;
 
 /// Task status
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
-#[serde(rename_all = "kebab-case")]
+///
+/// Besides the built-in four, a project can define its own workflow statuses
+/// (e.g. `review`) in `[workflow]` of its config. Those are open, like
+/// pending and in-progress tasks, and sort between in-progress and completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
 pub enum TaskStatus {
     #[default]
     Pending,
     InProgress,
+    /// A project-defined status, by its (lowercase) name
+    Custom(&'static str),
     Completed,
     Archived,
 }
 
+/// Names of the custom statuses defined by the configs loaded so far, each
+/// allocated once so `TaskStatus` can stay `Copy`
+static CUSTOM_NAMES: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+
+impl TaskStatus {
+    /// Define a custom status called `name`, as loading a config listing it
+    /// under `[workflow] statuses` does, if that is a valid name for one
+    ///
+    /// Names are lowercase letters, digits and `-`, starting with a letter,
+    /// and must not be one of the built-in statuses or their aliases.
+    pub fn define(name: &str) -> Option<TaskStatus> {
+        let name = name.to_lowercase();
+        let valid = name.starts_with(|c: char| c.is_ascii_lowercase())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+        if !valid || Self::builtin(&name).is_some() {
+            return None;
+        }
+
+        let mut names = CUSTOM_NAMES.lock().unwrap_or_else(|e| e.into_inner());
+        let name = match names.get(name.as_str()) {
+            Some(existing) => *existing,
+            None => {
+                let leaked: &'static str = Box::leak(name.into_boxed_str());
+                names.insert(leaked);
+                leaked
+            }
+        };
+        Some(TaskStatus::Custom(name))
+    }
+
+    /// Parse a status as stored in a task file
+    ///
+    /// Any well-formed custom name is accepted, whether or not a config
+    /// defines it, so files stay readable after a status is removed from
+    /// `[workflow]`. Setting a status is checked against the config instead.
+    pub fn parse_stored(name: &str) -> Result<TaskStatus, String> {
+        let lower = name.to_lowercase();
+        Self::builtin(&lower)
+            .or_else(|| Self::define(&lower))
+            .ok_or_else(|| format!("Unknown status: {}", name))
+    }
+
+    /// The custom status called `name`, if a loaded config defines it
+    pub fn custom(name: &str) -> Option<TaskStatus> {
+        let names = CUSTOM_NAMES.lock().unwrap_or_else(|e| e.into_inner());
+        names
+            .get(name.to_lowercase().as_str())
+            .map(|name| TaskStatus::Custom(name))
+    }
+
+    fn builtin(name: &str) -> Option<TaskStatus> {
+        match name {
+            "pending" => Some(TaskStatus::Pending),
+            "in-progress" | "inprogress" | "in_progress" => Some(TaskStatus::InProgress),
+            "completed" | "done" => Some(TaskStatus::Completed),
+            "archived" => Some(TaskStatus::Archived),
+            _ => None,
+        }
+    }
+
+    /// Whether tasks with this status still need work (not completed or archived)
+    pub fn is_open(self) -> bool {
+        !matches!(self, TaskStatus::Completed | TaskStatus::Archived)
+    }
+}
+
 impl fmt::Display for TaskStatus {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             TaskStatus::Pending => write!(f, "pending"),
             TaskStatus::InProgress => write!(f, "in-progress"),
+            TaskStatus::Custom(name) => write!(f, "{}", name),
             TaskStatus::Completed => write!(f, "completed"),
             TaskStatus::Archived => write!(f, "archived"),
         }
@@ -29,19 +108,36 @@ impl fmt::Display for TaskStatus {
 impl std::str::FromStr for TaskStatus {
     type Err = String;
 
+    /// Parse a built-in status or the name of a custom one
+    ///
+    /// Custom statuses must be defined by a loaded config (see
+    /// [`TaskStatus::define`]); whether the project at hand defines it is
+    /// up to the caller to check.
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "pending" => Ok(TaskStatus::Pending),
-            "in-progress" | "inprogress" | "in_progress" => Ok(TaskStatus::InProgress),
-            "completed" | "done" => Ok(TaskStatus::Completed),
-            "archived" => Ok(TaskStatus::Archived),
-            _ => Err(format!("Unknown status: {}", s)),
-        }
+        let lower = s.to_lowercase();
+        Self::builtin(&lower)
+            .or_else(|| Self::custom(&lower))
+            .ok_or_else(|| format!("Unknown status: {}", s))
     }
 }
 
-/// Task priority
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+impl Serialize for TaskStatus {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for TaskStatus {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        TaskStatus::parse_stored(&name).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Task priority, ordered from low to critical
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Default,
+)]
 #[serde(rename_all = "lowercase")]
 pub enum Priority {
     Low,
@@ -67,17 +163,19 @@ impl std::str::FromStr for Priority {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
-            "low" => Ok(Priority::Low),
-            "medium" | "med" => Ok(Priority::Medium),
-            "high" => Ok(Priority::High),
-            "critical" | "crit" => Ok(Priority::Critical),
+            "low" | "p3" | "3" => Ok(Priority::Low),
+            "medium" | "med" | "p2" | "2" => Ok(Priority::Medium),
+            "high" | "p1" | "1" => Ok(Priority::High),
+            "critical" | "crit" | "p0" | "0" => Ok(Priority::Critical),
             _ => Err(format!("Unknown priority: {}", s)),
         }
     }
 }
 
 /// Task kind/type
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Default,
+)]
 #[serde(rename_all = "lowercase")]
 pub enum TaskKind {
     #[default]
@@ -109,6 +207,98 @@ impl std::str::FromStr for TaskKind {
     }
 }
 
+/// How a closed task was resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Resolution {
+    #[default]
+    Done,
+    Wontfix,
+    Duplicate,
+    Invalid,
+}
+
+impl fmt::Display for Resolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Resolution::Done => write!(f, "done"),
+            Resolution::Wontfix => write!(f, "wontfix"),
+            Resolution::Duplicate => write!(f, "duplicate"),
+            Resolution::Invalid => write!(f, "invalid"),
+        }
+    }
+}
+
+impl std::str::FromStr for Resolution {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "done" | "fixed" => Ok(Resolution::Done),
+            "wontfix" | "wont-fix" | "won't-fix" => Ok(Resolution::Wontfix),
+            "duplicate" | "dup" => Ok(Resolution::Duplicate),
+            "invalid" => Ok(Resolution::Invalid),
+            _ => Err(format!("Unknown resolution: {}", s)),
+        }
+    }
+}
+
+/// How a task was closed before `reopen` undid it, kept as an audit trail
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reopening {
+    /// When the task was reopened
+    pub at: DateTime<Utc>,
+    /// Status it was reopened from (completed or archived)
+    pub status: TaskStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closed_commit: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<Resolution>,
+}
+
+/// A span of time spent working on a task
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimeEntry {
+    pub start: DateTime<Utc>,
+    /// When the session ended (absent while it is still running)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl TimeEntry {
+    /// Length of the session, counting a running session up to `now`
+    pub fn duration(&self, now: DateTime<Utc>) -> chrono::Duration {
+        self.end.unwrap_or(now) - self.start
+    }
+}
+
+/// A comment on a task
+///
+/// Comments live in a `## Comments` section at the end of the task's body,
+/// one `### <timestamp> <author>` heading each, and are only ever appended.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Comment {
+    pub at: DateTime<Utc>,
+    /// Who wrote it, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    pub text: String,
+}
+
+impl Comment {
+    /// A comment written now
+    pub fn new(author: Option<String>, text: &str) -> Self {
+        Comment {
+            // Stored to the second
+            at: Utc::now().trunc_subsecs(0),
+            author,
+            text: text.trim().to_string(),
+        }
+    }
+}
+
 /// A task with all its metadata
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Task {
@@ -120,17 +310,80 @@ pub struct Task {
     pub priority: Priority,
     #[serde(default)]
     pub kind: TaskKind,
+    /// Kept at the top of task lists whatever else they are sorted by
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    /// Hand-curated rank among tasks of the same status, set by `move-up`/`move-down`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<u64>,
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub tags: Vec<String>,
+    /// GTD-style context (e.g. `@computer`, `@errands`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<Due>,
+    /// Date work can start; default lists hide the task until then
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub due: Option<NaiveDate>,
+    pub scheduled: Option<NaiveDate>,
+    /// Reminder offsets relative to `due` (e.g. `-1d`, `-2h`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remind: Vec<ReminderOffset>,
+    /// Estimated effort in hours
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<f64>,
+    /// Identifier in an external tracker (e.g. `GH#123`, `PROJ-42`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_id: Option<String>,
+    /// Qualified ID (`project:id`) of the task this one was delegated from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delegated_from: Option<String>,
+    /// Who is responsible for the task (e.g. an email address)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assignee: Option<String>,
+    /// Who has claimed the task to signal they are working on it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claimed_by: Option<String>,
+    /// When the claim was made
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claimed_at: Option<DateTime<Utc>>,
+    /// ID of the task this one is a subtask of
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<u64>,
+    /// IDs of tasks that must be finished before this one can start
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocked_by: Vec<u64>,
+    /// Git branch the work happens on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    /// Code the task is about, as `path` or `path:line` from the project root
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub refs: Vec<String>,
     pub created: DateTime<Utc>,
     pub updated: DateTime<Utc>,
+    /// When the task was last looked at in `gittask review`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_reviewed: Option<DateTime<Utc>>,
+    /// When the task was completed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed: Option<DateTime<Utc>>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub closed_commit: Option<String>,
-    /// The markdown body (not part of frontmatter)
+    /// How the task was closed (absent means done)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resolution: Option<Resolution>,
+    /// Earlier closures undone by `reopen`, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reopened: Vec<Reopening>,
+    /// Work sessions recorded with `start` / `stop`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub time_entries: Vec<TimeEntry>,
+    /// The markdown body (not part of frontmatter), without the comments
     #[serde(skip)]
     pub description: String,
+    /// Discussion from the end of the body, oldest first
+    #[serde(skip)]
+    pub comments: Vec<Comment>,
 }
 
 impl Task {
@@ -143,12 +396,33 @@ impl Task {
             status: TaskStatus::default(),
             priority: Priority::default(),
             kind,
+            pinned: false,
+            order: None,
             tags: Vec::new(),
+            context: None,
             due: None,
+            scheduled: None,
+            remind: Vec::new(),
+            estimate: None,
+            external_id: None,
+            delegated_from: None,
+            assignee: None,
+            claimed_by: None,
+            claimed_at: None,
+            parent: None,
+            blocked_by: Vec::new(),
+            branch: None,
+            refs: Vec::new(),
             created: now,
             updated: now,
+            last_reviewed: None,
+            completed: None,
             closed_commit: None,
+            resolution: None,
+            reopened: Vec::new(),
+            time_entries: Vec::new(),
             description: String::new(),
+            comments: Vec::new(),
         }
     }
 
@@ -164,20 +438,252 @@ impl Task {
 
     /// Check if the task is open (not completed or archived)
     pub fn is_open(&self) -> bool {
-        matches!(self.status, TaskStatus::Pending | TaskStatus::InProgress)
+        self.status.is_open()
+    }
+
+    /// When each of the task's reminders fires, earliest first
+    ///
+    /// Empty without a due date, since offsets count from it. Reminders
+    /// that would fire beyond the representable dates are left out.
+    pub fn reminder_times(&self) -> Vec<(DateTime<Utc>, ReminderOffset)> {
+        let Some(due) = self.due else {
+            return Vec::new();
+        };
+        let mut times: Vec<_> = self
+            .remind
+            .iter()
+            .filter_map(|offset| Some((offset.fire_time(&due)?, *offset)))
+            .collect();
+        times.sort_by_key(|(at, _)| *at);
+        times
+    }
+
+    /// Whether the task is still open past its due date
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        self.is_open() && self.due.is_some_and(|due| due.is_past(now))
+    }
+
+    /// Check if the task is open and due within the next `days` days, but not yet overdue
+    pub fn is_due_soon(&self, now: DateTime<Utc>, days: u32) -> bool {
+        self.is_open() && self.due.is_some_and(|due| due.is_within(now, days))
+    }
+
+    /// Check if the task is scheduled to start after `date`
+    pub fn is_scheduled_after(&self, date: NaiveDate) -> bool {
+        self.scheduled.is_some_and(|scheduled| scheduled > date)
+    }
+
+    /// Record that `who` has claimed the task
+    pub fn claim(&mut self, who: impl Into<String>, now: DateTime<Utc>) {
+        self.claimed_by = Some(who.into());
+        self.claimed_at = Some(now);
+    }
+
+    /// Drop any claim, returning false if the task was not claimed
+    pub fn release_claim(&mut self) -> bool {
+        self.claimed_at = None;
+        self.claimed_by.take().is_some()
+    }
+
+    /// The claimant, if the task is claimed by someone other than `me`
+    ///
+    /// Any claim counts as someone else's when `me` is unknown.
+    pub fn claimed_by_other(&self, me: Option<&str>) -> Option<&str> {
+        self.claimed_by
+            .as_deref()
+            .filter(|claimant| me.is_none_or(|me| !claimant.eq_ignore_ascii_case(me)))
     }
 
     /// Mark the task as completed with the given commit hash
     pub fn complete(&mut self, commit: Option<String>) {
+        let now = Utc::now();
         self.status = TaskStatus::Completed;
         self.closed_commit = commit;
-        self.updated = Utc::now();
+        self.resolution = None;
+        self.completed = Some(now);
+        self.updated = now;
+    }
+
+    /// Close the task with a resolution, capturing the given commit hash
+    pub fn close(&mut self, commit: Option<String>, resolution: Resolution) {
+        self.complete(commit);
+        self.resolution = Some(resolution);
+    }
+
+    /// Reopen a completed or archived task with the given open status
+    ///
+    /// The completion time, commit and resolution move into `reopened`
+    /// rather than being discarded. Returns false if the task was already open.
+    pub fn reopen(&mut self, status: TaskStatus, now: DateTime<Utc>) -> bool {
+        if self.is_open() {
+            return false;
+        }
+        self.reopened.push(Reopening {
+            at: now,
+            status: self.status,
+            completed: self.completed.take(),
+            closed_commit: self.closed_commit.take(),
+            resolution: self.resolution.take(),
+        });
+        self.status = status;
+        self.updated = now;
+        true
+    }
+
+    /// Check if the task was completed with a `done` resolution
+    pub fn is_done(&self) -> bool {
+        self.status == TaskStatus::Completed
+            && self.resolution.is_none_or(|r| r == Resolution::Done)
+    }
+
+    /// Time from creation to completion, if the task has been completed
+    pub fn cycle_time(&self) -> Option<chrono::Duration> {
+        self.completed.map(|c| c - self.created)
+    }
+
+    /// Short hash of the kind and title, ignoring case and spacing
+    ///
+    /// Lets a sync recognise a task it created earlier that has no external ID.
+    pub fn fingerprint(&self) -> String {
+        let title = self.title.split_whitespace().collect::<Vec<_>>().join(" ");
+        let digest = Sha256::digest(format!("{}\n{}", self.kind, title.to_lowercase()));
+        format!("{:x}", digest)[..16].to_string()
     }
 
     /// Update the task's updated timestamp
     pub fn touch(&mut self) {
         self.updated = Utc::now();
     }
+
+    /// Add a tag if it is not already present
+    pub fn add_tag(&mut self, tag: &str) {
+        if !self.tags.iter().any(|t| t == tag) {
+            self.tags.push(tag.to_string());
+        }
+    }
+
+    /// Remove a tag if present
+    pub fn remove_tag(&mut self, tag: &str) {
+        self.tags.retain(|t| t != tag);
+    }
+
+    /// Record that this task is blocked by another task
+    ///
+    /// Returns false if the dependency was already present.
+    pub fn add_blocker(&mut self, id: u64) -> bool {
+        if self.blocked_by.contains(&id) {
+            return false;
+        }
+        self.blocked_by.push(id);
+        self.blocked_by.sort_unstable();
+        true
+    }
+
+    /// Remove a dependency, returning false if it wasn't present
+    pub fn remove_blocker(&mut self, id: u64) -> bool {
+        let before = self.blocked_by.len();
+        self.blocked_by.retain(|&b| b != id);
+        self.blocked_by.len() != before
+    }
+
+    /// Add a code reference, returning false if it was already present
+    pub fn add_ref(&mut self, reference: &str) -> bool {
+        if self.refs.iter().any(|r| r == reference) {
+            return false;
+        }
+        self.refs.push(reference.to_string());
+        true
+    }
+
+    /// Remove a code reference, returning false if it wasn't present
+    pub fn remove_ref(&mut self, reference: &str) -> bool {
+        let before = self.refs.len();
+        self.refs.retain(|r| r != reference);
+        self.refs.len() != before
+    }
+
+    /// Name of the branch used when working on this task
+    pub fn branch_name(&self) -> String {
+        let slug = self.slug();
+        let slug: String = slug.chars().take(40).collect();
+        format!("task/{}-{}", self.id, slug.trim_end_matches('-'))
+    }
+
+    /// The running work session, if any
+    pub fn active_session(&self) -> Option<&TimeEntry> {
+        self.time_entries.iter().find(|e| e.end.is_none())
+    }
+
+    /// Start working on the task: mark it in progress and open a session
+    ///
+    /// Returns false if a session was already running.
+    pub fn start(&mut self, now: DateTime<Utc>) -> bool {
+        self.status = TaskStatus::InProgress;
+        self.completed = None;
+        self.resolution = None;
+        if self.active_session().is_some() {
+            return false;
+        }
+        self.time_entries.push(TimeEntry {
+            start: now,
+            end: None,
+        });
+        true
+    }
+
+    /// End the running work session, returning its length
+    pub fn stop(&mut self, now: DateTime<Utc>) -> Option<chrono::Duration> {
+        let entry = self.time_entries.iter_mut().find(|e| e.end.is_none())?;
+        entry.end = Some(now);
+        Some(entry.duration(now))
+    }
+
+    /// Total time recorded against the task, including a running session
+    pub fn time_spent(&self, now: DateTime<Utc>) -> chrono::Duration {
+        self.time_entries
+            .iter()
+            .map(|e| e.duration(now))
+            .fold(chrono::Duration::zero(), |acc, d| acc + d)
+    }
+
+    /// Add a comment written now
+    pub fn add_comment(&mut self, author: Option<String>, text: &str) {
+        self.comments.push(Comment::new(author, text));
+    }
+
+    /// Append text to the description, separated by a blank line
+    pub fn append_description(&mut self, text: &str) {
+        if self.description.is_empty() {
+            self.description = text.to_string();
+        } else {
+            self.description = format!("{}\n\n{}", self.description, text);
+        }
+    }
+
+    /// Prepend text to the description, separated by a blank line
+    pub fn prepend_description(&mut self, text: &str) {
+        if self.description.is_empty() {
+            self.description = text.to_string();
+        } else {
+            self.description = format!("{}\n\n{}", text, self.description);
+        }
+    }
+
+    /// The `- [ ]` items of the description
+    pub fn checklist(&self) -> Checklist {
+        Checklist::parse(&self.description)
+    }
+
+    /// Check or uncheck checklist item `index`; false if there is no such item
+    pub fn toggle_check(&mut self, index: usize) -> bool {
+        match checklist::toggle_item(&self.description, index) {
+            Some(description) => {
+                self.description = description;
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -211,7 +717,23 @@ mod tests {
             TaskStatus::Completed
         );
         assert_eq!("done".parse::<TaskStatus>().unwrap(), TaskStatus::Completed);
-        assert!("invalid".parse::<TaskStatus>().is_err());
+        assert_eq!(
+            "pendng".parse::<TaskStatus>().unwrap_err(),
+            "Unknown status: pendng"
+        );
+        assert_eq!(TaskStatus::custom("pendng"), None);
+
+        assert_eq!(
+            TaskStatus::define("review"),
+            Some(TaskStatus::Custom("review"))
+        );
+        assert_eq!(
+            "Review".parse::<TaskStatus>().unwrap(),
+            TaskStatus::Custom("review")
+        );
+        assert!(TaskStatus::define("in review").is_none());
+        assert!(TaskStatus::define("2nd-pass").is_none());
+        assert!(TaskStatus::define("done").is_none());
     }
 
     #[test]
@@ -229,7 +751,12 @@ mod tests {
         assert_eq!("med".parse::<Priority>().unwrap(), Priority::Medium);
         assert_eq!("high".parse::<Priority>().unwrap(), Priority::High);
         assert_eq!("critical".parse::<Priority>().unwrap(), Priority::Critical);
+        assert_eq!("P0".parse::<Priority>().unwrap(), Priority::Critical);
+        assert_eq!("p1".parse::<Priority>().unwrap(), Priority::High);
+        assert_eq!("2".parse::<Priority>().unwrap(), Priority::Medium);
+        assert_eq!("p3".parse::<Priority>().unwrap(), Priority::Low);
         assert!("invalid".parse::<Priority>().is_err());
+        assert!("p4".parse::<Priority>().is_err());
     }
 
     #[test]
@@ -257,6 +784,7 @@ mod tests {
         assert_eq!(task.kind, TaskKind::Task);
         assert!(task.tags.is_empty());
         assert!(task.due.is_none());
+        assert!(task.completed.is_none());
         assert!(task.closed_commit.is_none());
     }
 
@@ -297,5 +825,153 @@ mod tests {
 
         assert_eq!(task.status, TaskStatus::Completed);
         assert_eq!(task.closed_commit, Some("abc123".to_string()));
+        assert!(task.completed.is_some());
+        assert!(task.cycle_time().is_some());
+    }
+
+    #[test]
+    fn test_resolution_parse() {
+        assert_eq!("done".parse::<Resolution>().unwrap(), Resolution::Done);
+        assert_eq!(
+            "wontfix".parse::<Resolution>().unwrap(),
+            Resolution::Wontfix
+        );
+        assert_eq!(
+            "wont-fix".parse::<Resolution>().unwrap(),
+            Resolution::Wontfix
+        );
+        assert_eq!(
+            "duplicate".parse::<Resolution>().unwrap(),
+            Resolution::Duplicate
+        );
+        assert_eq!(
+            "invalid".parse::<Resolution>().unwrap(),
+            Resolution::Invalid
+        );
+        assert!("maybe".parse::<Resolution>().is_err());
+    }
+
+    #[test]
+    fn test_task_close() {
+        let mut task = Task::new(1, TaskKind::Task, "Test");
+        task.close(None, Resolution::Wontfix);
+        assert_eq!(task.status, TaskStatus::Completed);
+        assert_eq!(task.resolution, Some(Resolution::Wontfix));
+        assert!(!task.is_done());
+
+        let mut task = Task::new(2, TaskKind::Task, "Test");
+        task.complete(None);
+        assert!(task.is_done());
+    }
+
+    #[test]
+    fn test_add_remove_tag() {
+        let mut task = Task::new(1, TaskKind::Task, "Test");
+        task.add_tag("bug");
+        task.add_tag("urgent");
+        task.add_tag("bug");
+        assert_eq!(task.tags, vec!["bug", "urgent"]);
+
+        task.remove_tag("bug");
+        assert_eq!(task.tags, vec!["urgent"]);
+
+        task.remove_tag("missing");
+        assert_eq!(task.tags, vec!["urgent"]);
+    }
+
+    #[test]
+    fn test_append_prepend_description() {
+        let mut task = Task::new(1, TaskKind::Task, "Test");
+        task.append_description("First note");
+        assert_eq!(task.description, "First note");
+
+        task.append_description("Second note");
+        assert_eq!(task.description, "First note\n\nSecond note");
+
+        task.prepend_description("Summary");
+        assert_eq!(task.description, "Summary\n\nFirst note\n\nSecond note");
+    }
+
+    #[test]
+    fn test_start_stop_sessions() {
+        let mut task = Task::new(1, TaskKind::Task, "Test");
+        let t0 = Utc::now();
+        assert!(task.start(t0));
+        assert_eq!(task.status, TaskStatus::InProgress);
+        assert!(task.active_session().is_some());
+        assert!(!task.start(t0 + chrono::Duration::minutes(5)));
+        assert_eq!(task.time_entries.len(), 1);
+
+        let spent = task.stop(t0 + chrono::Duration::minutes(30));
+        assert_eq!(spent, Some(chrono::Duration::minutes(30)));
+        assert!(task.active_session().is_none());
+        assert_eq!(task.stop(t0 + chrono::Duration::hours(1)), None);
+
+        let t1 = t0 + chrono::Duration::hours(2);
+        task.start(t1);
+        assert_eq!(
+            task.time_spent(t1 + chrono::Duration::minutes(15)),
+            chrono::Duration::minutes(45)
+        );
+    }
+
+    #[test]
+    fn test_reopen_keeps_closure() {
+        let mut task = Task::new(1, TaskKind::Task, "Flaky test");
+        assert!(!task.reopen(TaskStatus::Pending, Utc::now()));
+
+        task.close(Some("abc123".to_string()), Resolution::Wontfix);
+        let completed = task.completed;
+        assert!(task.reopen(TaskStatus::InProgress, Utc::now()));
+
+        assert_eq!(task.status, TaskStatus::InProgress);
+        assert_eq!(
+            (task.completed, task.closed_commit.as_ref(), task.resolution),
+            (None, None, None)
+        );
+        let entry = &task.reopened[0];
+        assert_eq!(entry.status, TaskStatus::Completed);
+        assert_eq!(entry.completed, completed);
+        assert_eq!(entry.closed_commit.as_deref(), Some("abc123"));
+        assert_eq!(entry.resolution, Some(Resolution::Wontfix));
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let task = Task::new(1, TaskKind::Task, "Fix  the Login");
+        assert_eq!(task.fingerprint().len(), 16);
+        assert_eq!(
+            task.fingerprint(),
+            Task::new(2, TaskKind::Task, " fix the login").fingerprint()
+        );
+        assert_ne!(
+            task.fingerprint(),
+            Task::new(1, TaskKind::Todo, "Fix the login").fingerprint()
+        );
+    }
+
+    #[test]
+    fn test_branch_name() {
+        let task = Task::new(7, TaskKind::Task, "Fix the login bug!");
+        assert_eq!(task.branch_name(), "task/7-fix-the-login-bug");
+    }
+
+    #[test]
+    fn test_claim_release() {
+        let mut task = Task::new(1, TaskKind::Task, "Test");
+        assert_eq!(task.claimed_by_other(Some("bob@example.com")), None);
+
+        task.claim("alice@example.com", Utc::now());
+        assert!(task.claimed_at.is_some());
+        assert_eq!(task.claimed_by_other(Some("Alice@example.com")), None);
+        assert_eq!(
+            task.claimed_by_other(Some("bob@example.com")),
+            Some("alice@example.com")
+        );
+        assert_eq!(task.claimed_by_other(None), Some("alice@example.com"));
+
+        assert!(task.release_claim());
+        assert_eq!(task.claimed_at, None);
+        assert!(!task.release_claim());
     }
 }
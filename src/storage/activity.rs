@@ -0,0 +1,424 @@
+//! Recent task activity derived from git history
+
+use crate::git::GitOperations;
+use crate::git::history::{file_authors, file_deletions, file_history};
+use crate::models::{Task, parse_task};
+use crate::storage::file_store::{FileStore, FileStoreError, TaskFilter};
+use crate::storage::id_generator::IdGenerator;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// A task touched within the activity window, with a summary of what changed
+#[derive(Debug, Clone)]
+pub struct ActivityEntry {
+    /// The task as it is now (or as it was, if deleted)
+    pub task: Task,
+    /// When the change happened, `None` for a deletion that hasn't been
+    /// committed yet
+    pub when: Option<DateTime<Utc>>,
+    /// Human-readable changes (e.g. "status: pending -> completed")
+    pub changes: Vec<String>,
+}
+
+/// Collect tasks updated since `since`, newest first
+///
+/// Changes are computed against the task files in the newest commit made
+/// before `since`. Outside a git repository every entry is reported as
+/// created or updated without field detail.
+pub fn activity_log(
+    store: &FileStore,
+    since: DateTime<Utc>,
+) -> Result<Vec<ActivityEntry>, FileStoreError> {
+    let tasks_dir = &store.location().tasks_dir;
+    let mut baseline: HashMap<u64, Task> = HashMap::new();
+
+    if store.location().exists() {
+        match GitOperations::dir_snapshot_before(tasks_dir, since) {
+            Ok(files) => {
+                for (name, content) in files {
+                    if let Some(id) = IdGenerator::extract_id_from_filename(Path::new(&name))
+                        && let Ok(task) = parse_task(&content)
+                    {
+                        baseline.insert(id, task);
+                    }
+                }
+            }
+            Err(e) => log::debug!("No git history for {:?}: {}", tasks_dir, e),
+        }
+    }
+
+    let current = store.list(&TaskFilter {
+        include_archived: true,
+        ..Default::default()
+    })?;
+
+    let mut entries = Vec::new();
+
+    for task in current {
+        let old = baseline.remove(&task.id);
+        if task.updated < since {
+            continue;
+        }
+
+        let changes = match old {
+            Some(ref old) => describe_changes(old, &task),
+            None if task.created >= since => vec!["created".to_string()],
+            None => Vec::new(),
+        };
+
+        entries.push(ActivityEntry {
+            when: Some(task.updated),
+            task,
+            // Edits to fields without a description still count
+            changes: if changes.is_empty() {
+                vec!["updated".to_string()]
+            } else {
+                changes
+            },
+        });
+    }
+
+    // Whatever remains in the baseline has been deleted since
+    if !baseline.is_empty() {
+        let deleted_at = deletion_times(tasks_dir, since);
+        for (id, task) in baseline {
+            entries.push(ActivityEntry {
+                when: deleted_at.get(&id).copied(),
+                task,
+                changes: vec!["deleted".to_string()],
+            });
+        }
+    }
+
+    // Uncommitted deletions are the most recent changes
+    entries.sort_by(|a, b| match (a.when, b.when) {
+        (Some(a_when), Some(b_when)) if a_when != b_when => b_when.cmp(&a_when),
+        (Some(_), None) => std::cmp::Ordering::Greater,
+        (None, Some(_)) => std::cmp::Ordering::Less,
+        _ => a.task.id.cmp(&b.task.id),
+    });
+
+    Ok(entries)
+}
+
+/// When each task deleted since `since` was deleted, from the deleting
+/// commit
+///
+/// A task whose file was renamed (e.g. by a title edit) before being
+/// deleted shows up more than once; the newest deletion is the real one.
+fn deletion_times(tasks_dir: &Path, since: DateTime<Utc>) -> HashMap<u64, DateTime<Utc>> {
+    let mut times = HashMap::new();
+    match file_deletions(tasks_dir, since) {
+        Ok(deleted) => {
+            for (path, when) in deleted {
+                if let Some(id) = IdGenerator::extract_id_from_filename(&path) {
+                    times.entry(id).or_insert(when);
+                }
+            }
+        }
+        Err(e) => log::debug!("No git history for {:?}: {}", tasks_dir, e),
+    }
+    times
+}
+
+/// One commit in a task's history
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    /// Short commit hash
+    pub commit: String,
+    pub when: DateTime<Utc>,
+    pub author: String,
+    /// Human-readable changes made by the commit
+    pub changes: Vec<String>,
+}
+
+/// The commits that changed a task, oldest first
+///
+/// Title edits rename the task file, so renames are followed and the
+/// timeline starts at the commit that created the task.
+pub fn task_history(store: &FileStore, id: u64) -> Result<Vec<HistoryEntry>, FileStoreError> {
+    let path = store.find_task_file(id)?;
+    let versions = file_history(&store.location().tasks_dir, &path)
+        .map_err(|e| FileStoreError::Io(std::io::Error::other(e.to_string())))?;
+
+    let mut entries = Vec::new();
+    let mut previous: Option<Task> = None;
+    for version in versions.into_iter().rev() {
+        let Ok(task) = parse_task(&version.content) else {
+            continue;
+        };
+        let changes = match previous {
+            Some(ref old) => describe_changes(old, &task),
+            None => vec!["created".to_string()],
+        };
+        previous = Some(task);
+        entries.push(HistoryEntry {
+            commit: version.commit,
+            when: version.when,
+            author: version.author,
+            changes: if changes.is_empty() {
+                vec!["updated".to_string()]
+            } else {
+                changes
+            },
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Email of the author who first committed each task, from git history
+///
+/// Tasks that have never been committed are absent. Outside a git
+/// repository the map is empty.
+pub fn task_creators(store: &FileStore) -> HashMap<u64, String> {
+    let mut creators = HashMap::new();
+    if !store.location().exists() {
+        return creators;
+    }
+
+    match file_authors(&store.location().tasks_dir) {
+        Ok(added) => {
+            for (path, email) in added {
+                if let Some(id) = IdGenerator::extract_id_from_filename(&path) {
+                    creators.entry(id).or_insert(email);
+                }
+            }
+        }
+        Err(e) => log::debug!("No git history for {:?}: {}", store.location().tasks_dir, e),
+    }
+    creators
+}
+
+/// Whether the user with `my_email` is assigned a task, has claimed it, or
+/// created it according to `creators`
+///
+/// A task without a commit has no known creator, so only its assignee and
+/// claim decide; otherwise every such task would count as everyone's.
+pub fn is_mine(creators: &HashMap<u64, String>, task: &Task, my_email: &str) -> bool {
+    [
+        task.assignee.as_ref(),
+        task.claimed_by.as_ref(),
+        creators.get(&task.id),
+    ]
+    .into_iter()
+    .flatten()
+    .any(|who| who.eq_ignore_ascii_case(my_email))
+}
+
+/// Describe the field-level differences between two versions of a task
+pub fn describe_changes(old: &Task, new: &Task) -> Vec<String> {
+    let mut changes = Vec::new();
+
+    if old.title != new.title {
+        changes.push(format!("title: {} -> {}", old.title, new.title));
+    }
+    if old.status != new.status {
+        changes.push(format!("status: {} -> {}", old.status, new.status));
+    }
+    if old.priority != new.priority {
+        changes.push(format!("priority: {} -> {}", old.priority, new.priority));
+    }
+    if old.kind != new.kind {
+        changes.push(format!("kind: {} -> {}", old.kind, new.kind));
+    }
+    if old.tags != new.tags {
+        changes.push(format!(
+            "tags: [{}] -> [{}]",
+            old.tags.join(", "),
+            new.tags.join(", ")
+        ));
+    }
+    if old.context != new.context {
+        changes.push(format!(
+            "context: {} -> {}",
+            display_opt(&old.context),
+            display_opt(&new.context)
+        ));
+    }
+    if old.due != new.due {
+        changes.push(format!(
+            "due: {} -> {}",
+            display_opt(&old.due),
+            display_opt(&new.due)
+        ));
+    }
+    if old.external_id != new.external_id {
+        changes.push(format!(
+            "external_id: {} -> {}",
+            display_opt(&old.external_id),
+            display_opt(&new.external_id)
+        ));
+    }
+    if old.assignee != new.assignee {
+        changes.push(format!(
+            "assignee: {} -> {}",
+            display_opt(&old.assignee),
+            display_opt(&new.assignee)
+        ));
+    }
+    if old.claimed_by != new.claimed_by {
+        changes.push(format!(
+            "claimed by: {} -> {}",
+            display_opt(&old.claimed_by),
+            display_opt(&new.claimed_by)
+        ));
+    }
+    if old.resolution != new.resolution {
+        changes.push(format!(
+            "resolution: {} -> {}",
+            display_opt(&old.resolution),
+            display_opt(&new.resolution)
+        ));
+    }
+    if old.description != new.description {
+        changes.push("description edited".to_string());
+    }
+
+    changes
+}
+
+fn display_opt<T: std::fmt::Display>(value: &Option<T>) -> String {
+    value
+        .as_ref()
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "none".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Priority, TaskKind, TaskStatus};
+    use crate::storage::TaskLocation;
+    use crate::storage::test_support::setup_test_store;
+    use std::process::Command;
+    use tempfile::TempDir;
+
+    fn git_at(dir: &Path, when: DateTime<Utc>, args: &[&str]) {
+        let date = when.to_rfc3339();
+        Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .env("GIT_AUTHOR_DATE", &date)
+            .env("GIT_COMMITTER_DATE", &date)
+            .output()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_describe_changes() {
+        let old = Task::new(1, TaskKind::Task, "Old title");
+        let mut new = old.clone();
+        assert!(describe_changes(&old, &new).is_empty());
+
+        new.title = "New title".to_string();
+        new.status = TaskStatus::Completed;
+        new.priority = Priority::High;
+        new.description = "Notes".to_string();
+
+        let changes = describe_changes(&old, &new);
+        assert_eq!(
+            changes,
+            vec![
+                "title: Old title -> New title",
+                "status: pending -> completed",
+                "priority: medium -> high",
+                "description edited",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_mine() {
+        let mut task = Task::new(1, TaskKind::Task, "Review PR");
+        let creators = HashMap::from([(1, "bob@example.com".to_string())]);
+        assert!(!is_mine(&creators, &task, "alice@example.com"));
+        assert!(is_mine(&creators, &task, "Bob@example.com"));
+        // Uncommitted tasks are nobody's by default
+        assert!(!is_mine(&HashMap::new(), &task, "alice@example.com"));
+
+        task.claimed_by = Some("alice@example.com".to_string());
+        assert!(is_mine(&HashMap::new(), &task, "alice@example.com"));
+        task.claimed_by = None;
+        task.assignee = Some("Alice@example.com".to_string());
+        assert!(is_mine(&creators, &task, "alice@example.com"));
+    }
+
+    #[test]
+    fn test_activity_log_without_history() {
+        let (_temp, store) = setup_test_store();
+
+        let since = Utc::now() - chrono::Duration::days(7);
+        store
+            .create(Task::new(0, TaskKind::Task, "New task"))
+            .unwrap();
+
+        let mut old = Task::new(0, TaskKind::Task, "Stale task");
+        old.created = since - chrono::Duration::days(30);
+        old.updated = old.created;
+        store.create(old).unwrap();
+
+        let entries = activity_log(&store, since).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].task.title, "New task");
+        assert_eq!(entries[0].changes, vec!["created"]);
+    }
+
+    #[test]
+    fn test_activity_log_with_history() {
+        let temp = TempDir::new().unwrap();
+        let now = Utc::now();
+        git_at(temp.path(), now, &["init"]);
+        git_at(temp.path(), now, &["config", "user.email", "test@test.com"]);
+        git_at(temp.path(), now, &["config", "user.name", "Test User"]);
+        let location = TaskLocation::find_project_from(temp.path()).unwrap();
+        location.ensure_exists().unwrap();
+        let store = FileStore::new(location);
+
+        let long_ago = now - chrono::Duration::days(30);
+        for title in ["Estimated", "Committed delete", "Local delete"] {
+            let mut task = Task::new(0, TaskKind::Task, title);
+            task.created = long_ago;
+            task.updated = long_ago;
+            store.create(task).unwrap();
+        }
+        git_at(temp.path(), long_ago, &["add", "-A"]);
+        git_at(temp.path(), long_ago, &["commit", "-m", "create"]);
+
+        let deleted_at = now - chrono::Duration::days(2);
+        store.delete(2).unwrap();
+        git_at(temp.path(), deleted_at, &["add", "-A"]);
+        git_at(temp.path(), deleted_at, &["commit", "-m", "delete"]);
+
+        store.delete(3).unwrap();
+        let mut task = store.read(1).unwrap();
+        task.estimate = Some(2.0);
+        task.updated = now;
+        store.update(&task).unwrap();
+
+        let entries = activity_log(&store, now - chrono::Duration::days(7)).unwrap();
+        let summary: Vec<(u64, Option<i64>, &[String])> = entries
+            .iter()
+            .map(|e| {
+                (
+                    e.task.id,
+                    e.when.map(|w| w.timestamp()),
+                    e.changes.as_slice(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (3, None, &["deleted".to_string()][..]),
+                (1, Some(now.timestamp()), &["updated".to_string()][..]),
+                (
+                    2,
+                    Some(deleted_at.timestamp()),
+                    &["deleted".to_string()][..]
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,70 @@
+//! Kanban board: tasks in one column per status
+//!
+//! Column order comes from `[workflow] columns` (see
+//! [`WorkflowConfig::columns`](crate::storage::config::WorkflowConfig::columns)),
+//! so a board imported from another tool keeps its layout.
+
+use crate::models::{Task, TaskStatus};
+
+/// Group tasks into `columns`, in that order
+///
+/// Every column is kept even when empty. Tasks whose status has no column
+/// get extra columns after the configured ones, in status order.
+pub fn board_columns(tasks: Vec<Task>, columns: &[TaskStatus]) -> Vec<(TaskStatus, Vec<Task>)> {
+    let mut board: Vec<(TaskStatus, Vec<Task>)> =
+        columns.iter().map(|&status| (status, Vec::new())).collect();
+    let mut extra: Vec<(TaskStatus, Vec<Task>)> = Vec::new();
+
+    for task in tasks {
+        match board.iter_mut().find(|(status, _)| *status == task.status) {
+            Some((_, column)) => column.push(task),
+            None => match extra.iter_mut().find(|(status, _)| *status == task.status) {
+                Some((_, column)) => column.push(task),
+                None => extra.push((task.status, vec![task])),
+            },
+        }
+    }
+
+    extra.sort_by_key(|(status, _)| *status);
+    board.extend(extra);
+    board
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TaskKind;
+
+    fn task(id: u64, status: TaskStatus) -> Task {
+        let mut task = Task::new(id, TaskKind::Task, "Task");
+        task.status = status;
+        task
+    }
+
+    #[test]
+    fn test_board_columns_keep_order() {
+        let review = TaskStatus::define("review").unwrap();
+        let columns = [review, TaskStatus::Pending, TaskStatus::Completed];
+        let tasks = vec![
+            task(1, TaskStatus::Pending),
+            task(2, review),
+            task(3, TaskStatus::InProgress),
+            task(4, TaskStatus::Pending),
+        ];
+
+        let board = board_columns(tasks, &columns);
+        let layout: Vec<(String, Vec<u64>)> = board
+            .iter()
+            .map(|(status, tasks)| (status.to_string(), tasks.iter().map(|t| t.id).collect()))
+            .collect();
+        assert_eq!(
+            layout,
+            [
+                ("review".to_string(), vec![2]),
+                ("pending".to_string(), vec![1, 4]),
+                ("completed".to_string(), vec![]),
+                ("in-progress".to_string(), vec![3]),
+            ]
+        );
+    }
+}
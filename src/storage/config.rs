@@ -0,0 +1,626 @@
+//! Per-project configuration stored in `.tasks/config.toml`
+
+use crate::models::{Due, FormatRules, Priority, TaskKind, TaskStatus};
+use crate::storage::layout::TaskLayout;
+use crate::storage::writes;
+use chrono::format::StrftimeItems;
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Config file name within the tasks directory
+const CONFIG_FILE: &str = "config.toml";
+
+/// Errors related to project configuration
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid config.toml: {0}")]
+    Parse(#[from] toml::de::Error),
+    #[error("Failed to write config.toml: {0}")]
+    Serialize(#[from] toml::ser::Error),
+    #[error("Invalid config.toml: {0}")]
+    Invalid(String),
+}
+
+/// Project configuration
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectConfig {
+    pub storage: StorageConfig,
+    pub defaults: DefaultsConfig,
+    pub wip: WipConfig,
+    pub display: DisplayConfig,
+    pub urgency: UrgencyConfig,
+    pub retention: RetentionConfig,
+    pub capacity: CapacityConfig,
+    pub validation: ValidationConfig,
+    pub workflow: WorkflowConfig,
+    /// `[fmt]` section: what `gittask fmt` normalizes
+    pub fmt: FormatRules,
+    /// `[kind.<kind>]` sections: defaults for new tasks of each kind
+    ///
+    /// Keyed by name, so a section for a kind that doesn't exist is kept
+    /// (and reported by [`ProjectConfig::unknown_kinds`]) rather than
+    /// failing the whole config.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub kind: BTreeMap<String, KindDefaults>,
+    /// `[[embed]]` entries refreshed by a bare `gittask embed`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub embed: Vec<EmbedConfig>,
+}
+
+/// `[storage]` section
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StorageConfig {
+    /// Layout to use when the tasks directory has no layout marker
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub layout: Option<TaskLayout>,
+    /// Reject every change to the tasks (e.g. for a project only mounted for reporting)
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub read_only: bool,
+    /// Fail on task files that can't be read or parsed instead of skipping them
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub strict: bool,
+    /// When upserting a task without a known external ID, update an existing
+    /// task with the same kind and title instead of creating another
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub fingerprint: bool,
+}
+
+/// `[defaults]` section: values filled in for new tasks
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DefaultsConfig {
+    /// Assign new tasks without an assignee to the git user creating them
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub assignee_from_git: bool,
+}
+
+/// `[kind.<kind>]` section, e.g. `[kind.idea]`
+///
+/// Applied to new tasks of that kind before any explicit values.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KindDefaults {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    /// Tags added to every new task of the kind
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    /// Template applied when none is given explicitly
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+}
+
+/// `[wip]` section: limits on tasks in progress at once
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WipConfig {
+    /// Most tasks in progress across the project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_in_progress: Option<usize>,
+    /// Most tasks in progress for any one assignee
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_per_assignee: Option<usize>,
+}
+
+/// `[retention]` section: archiving and purging old tasks with `gittask prune`
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionConfig {
+    /// Archive tasks completed more than this many days ago
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive_after_days: Option<u32>,
+    /// Move archived tasks older than this many days into a compressed snapshot
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purge_after_days: Option<u32>,
+    /// Also prune whenever `gittask list` runs
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub auto: bool,
+}
+
+/// `[capacity]` section: hours available per week, for `gittask plan`
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CapacityConfig {
+    /// Hours the whole project can take on per week
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub weekly_hours: Option<f64>,
+    /// Hours per week for each assignee (e.g. `"ana@example.com" = 20`)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub assignees: BTreeMap<String, f64>,
+}
+
+/// `[validation]` section: rules every created or updated task must pass
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ValidationConfig {
+    /// Longest title allowed, in characters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_title_length: Option<usize>,
+    /// Tags that may be used, exactly or as `prefix/*`; empty allows any tag
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allowed_tags: Vec<String>,
+    /// Reject due dates that have already passed when they are set
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub due_in_future: bool,
+    /// `[validation.required]`: fields each kind must fill in (e.g. `task = ["assignee"]`)
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub required: BTreeMap<TaskKind, Vec<String>>,
+}
+
+impl ValidationConfig {
+    /// Fields that `required` can name
+    pub const FIELDS: &[&str] = &[
+        "assignee",
+        "context",
+        "description",
+        "due",
+        "estimate",
+        "refs",
+        "tags",
+    ];
+
+    /// Whether no rule is configured
+    pub fn is_empty(&self) -> bool {
+        self == &ValidationConfig::default()
+    }
+
+    /// Check that every required field is one that can be checked
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        for field in self.required.values().flatten() {
+            if !Self::FIELDS.contains(&field.as_str()) {
+                return Err(ConfigError::Invalid(format!(
+                    "unknown required field '{}' (expected one of: {})",
+                    field,
+                    Self::FIELDS.join(", ")
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `[workflow]` section: statuses beyond the built-in ones
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WorkflowConfig {
+    /// Extra open statuses tasks can be set to, e.g. `["review", "blocked"]`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub statuses: Vec<String>,
+    /// Board columns from left to right, built-in or custom statuses
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub columns: Vec<String>,
+}
+
+impl WorkflowConfig {
+    /// Whether tasks of this project may be set to `status`
+    pub fn allows(&self, status: TaskStatus) -> bool {
+        match status {
+            TaskStatus::Custom(name) => self.statuses.iter().any(|s| s == name),
+            _ => true,
+        }
+    }
+
+    /// Parse a status given for this project, rejecting custom statuses
+    /// it doesn't define
+    pub fn parse_status(&self, name: &str) -> Result<TaskStatus, String> {
+        name.parse()
+            .ok()
+            .filter(|status| self.allows(*status))
+            .ok_or_else(|| format!("Unknown status: {}", name))
+    }
+
+    /// Board columns in order
+    ///
+    /// Without `columns` in the config: pending, in-progress, the custom
+    /// statuses in the order listed, then completed.
+    pub fn columns(&self) -> Vec<TaskStatus> {
+        if !self.columns.is_empty() {
+            return self.columns.iter().filter_map(|c| c.parse().ok()).collect();
+        }
+        let mut columns = vec![TaskStatus::Pending, TaskStatus::InProgress];
+        columns.extend(self.statuses.iter().filter_map(|s| TaskStatus::custom(s)));
+        columns.push(TaskStatus::Completed);
+        columns
+    }
+
+    /// Check that every status is a valid name not taken by a built-in one,
+    /// and that every column is a status tasks can have
+    ///
+    /// Defines the statuses, so they parse from then on.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        for name in &self.statuses {
+            let valid = TaskStatus::define(name).is_some_and(|s| s.to_string() == *name);
+            if !valid {
+                return Err(ConfigError::Invalid(format!(
+                    "bad workflow status '{}' (use lowercase letters, digits and '-', \
+                     and not a built-in status)",
+                    name
+                )));
+            }
+        }
+        for column in &self.columns {
+            if !column.parse().is_ok_and(|status| self.allows(status)) {
+                return Err(ConfigError::Invalid(format!(
+                    "bad workflow column '{}' (expected a built-in status or one of `statuses`)",
+                    column
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An `[[embed]]` entry: a task checklist kept up to date in a Markdown file
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmbedConfig {
+    /// File to update, relative to the project root
+    pub file: PathBuf,
+    /// Heading the checklist goes under, e.g. `## Roadmap`
+    pub section: String,
+    /// Filter query such as `status:pending kind:task`
+    #[serde(default)]
+    pub filter: String,
+}
+
+/// `[urgency]` section: weights of the factors in a task's urgency score
+///
+/// Each factor is scaled to 0..1 before weighting; tag weights are added
+/// as they are for every matching tag.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UrgencyConfig {
+    pub priority: f64,
+    pub due: f64,
+    pub age: f64,
+    /// Extra score per tag (e.g. `quickwin = 3.0`); negative values demote
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, f64>,
+}
+
+impl Default for UrgencyConfig {
+    fn default() -> Self {
+        UrgencyConfig {
+            priority: 6.0,
+            due: 12.0,
+            age: 2.0,
+            tags: BTreeMap::new(),
+        }
+    }
+}
+
+/// `[display]` section: how dates and times are shown, and which due dates
+/// count as soon
+///
+/// Task files always store UTC; this only affects what the CLI prints.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplayConfig {
+    /// strftime format for dates (default `%Y-%m-%d`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date_format: Option<String>,
+    /// strftime format for times of day (default `%H:%M`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_format: Option<String>,
+    /// `local` (default), `utc` or a fixed offset such as `+05:30`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timezone: Option<String>,
+    /// Days ahead in which open tasks count as due soon (default 3)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub due_soon_days: Option<u32>,
+}
+
+impl DisplayConfig {
+    const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+    const DEFAULT_TIME_FORMAT: &str = "%H:%M";
+    const DEFAULT_DUE_SOON_DAYS: u32 = 3;
+
+    /// The "due soon" window in days
+    pub fn due_soon_days(&self) -> u32 {
+        self.due_soon_days.unwrap_or(Self::DEFAULT_DUE_SOON_DAYS)
+    }
+
+    /// Check the formats and timezone, so formatting can't fail later
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        for format in [&self.date_format, &self.time_format].into_iter().flatten() {
+            StrftimeItems::new(format)
+                .parse()
+                .map_err(|_| ConfigError::Invalid(format!("bad date/time format '{}'", format)))?;
+        }
+        self.offset()?;
+        Ok(())
+    }
+
+    /// Fixed offset to show times and count days in, or `None` for the local timezone
+    pub fn offset(&self) -> Result<Option<FixedOffset>, ConfigError> {
+        match self.timezone.as_deref().map(str::trim) {
+            None | Some("local") => Ok(None),
+            Some("utc" | "UTC") => Ok(FixedOffset::east_opt(0)),
+            Some(offset) => offset.parse().map(Some).map_err(|_| {
+                ConfigError::Invalid(format!(
+                    "bad timezone '{}' (expected local, utc or e.g. +02:00)",
+                    offset
+                ))
+            }),
+        }
+    }
+
+    /// Format a calendar date
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        date.format(
+            self.date_format
+                .as_deref()
+                .unwrap_or(Self::DEFAULT_DATE_FORMAT),
+        )
+        .to_string()
+    }
+
+    /// Format a due date, with its time in the configured timezone if it has one
+    pub fn format_due(&self, due: &Due) -> String {
+        match due {
+            Due::Date(date) => self.format_date(*date),
+            Due::At(at) => self.format_timestamp(at.to_utc()),
+            Due::Local(_) => self.format_due(&due.resolve(self.offset().ok().flatten())),
+        }
+    }
+
+    /// Format a timestamp as date and time in the configured timezone
+    pub fn format_timestamp(&self, when: DateTime<Utc>) -> String {
+        let format = format!(
+            "{} {}",
+            self.date_format
+                .as_deref()
+                .unwrap_or(Self::DEFAULT_DATE_FORMAT),
+            self.time_format
+                .as_deref()
+                .unwrap_or(Self::DEFAULT_TIME_FORMAT)
+        );
+        match self.offset().ok().flatten() {
+            Some(offset) => when.with_timezone(&offset).format(&format).to_string(),
+            None => when.with_timezone(&Local).format(&format).to_string(),
+        }
+    }
+}
+
+impl ProjectConfig {
+    /// Path of the config file for a tasks directory
+    pub fn path(tasks_dir: &Path) -> PathBuf {
+        tasks_dir.join(CONFIG_FILE)
+    }
+
+    /// Defaults for new tasks of `kind`, from its `[kind.<kind>]` section
+    pub fn kind_defaults(&self, kind: TaskKind) -> KindDefaults {
+        self.kind
+            .iter()
+            .find(|(name, _)| name.parse() == Ok(kind))
+            .map(|(_, defaults)| defaults.clone())
+            .unwrap_or_default()
+    }
+
+    /// Names of `[kind.<kind>]` sections that don't name a task kind
+    pub fn unknown_kinds(&self) -> Vec<&str> {
+        self.kind
+            .keys()
+            .filter(|name| name.parse::<TaskKind>().is_err())
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Load the config, falling back to defaults if the file doesn't exist
+    pub fn load(tasks_dir: &Path) -> Result<Self, ConfigError> {
+        let path = Self::path(tasks_dir);
+        if !path.exists() {
+            return Ok(ProjectConfig::default());
+        }
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Parse and check the contents of a config file
+    pub fn parse(content: &str) -> Result<Self, ConfigError> {
+        let config: ProjectConfig = toml::from_str(content)?;
+        config.display.validate()?;
+        config.validation.validate()?;
+        config.workflow.validate()?;
+        Ok(config)
+    }
+
+    /// Write the config file
+    pub fn save(&self, tasks_dir: &Path) -> Result<(), ConfigError> {
+        writes::write(&Self::path(tasks_dir), toml::to_string(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_missing_config_is_default() {
+        let temp = TempDir::new().unwrap();
+        assert_eq!(
+            ProjectConfig::load(temp.path()).unwrap(),
+            ProjectConfig::default()
+        );
+    }
+
+    #[test]
+    fn test_config_roundtrip() {
+        let temp = TempDir::new().unwrap();
+        std::fs::write(
+            ProjectConfig::path(temp.path()),
+            "[storage]\nlayout = \"sharded\"\n",
+        )
+        .unwrap();
+
+        let mut config = ProjectConfig::load(temp.path()).unwrap();
+        assert_eq!(config.storage.layout, Some(TaskLayout::Sharded));
+        assert!(!config.defaults.assignee_from_git);
+        assert_eq!(config.urgency, UrgencyConfig::default());
+
+        config.storage.layout = Some(TaskLayout::Flat);
+        config.defaults.assignee_from_git = true;
+        config.urgency.tags.insert("quickwin".to_string(), 3.0);
+        config.kind.insert(
+            "idea".to_string(),
+            KindDefaults {
+                priority: Some(Priority::Low),
+                tags: vec!["someday".to_string()],
+                ..Default::default()
+            },
+        );
+        config.embed.push(EmbedConfig {
+            file: PathBuf::from("README.md"),
+            section: "## Roadmap".to_string(),
+            filter: "status:pending".to_string(),
+        });
+        config.save(temp.path()).unwrap();
+        assert_eq!(ProjectConfig::load(temp.path()).unwrap(), config);
+    }
+
+    #[test]
+    fn test_kind_defaults() {
+        let config: ProjectConfig = toml::from_str(
+            "[kind.idea]\npriority = \"low\"\n\n[kind.task]\ntags = [\"bug\"]\ntemplate = \"bugreport\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config.kind_defaults(TaskKind::Idea).priority,
+            Some(Priority::Low)
+        );
+        assert_eq!(
+            config.kind_defaults(TaskKind::Task).template.as_deref(),
+            Some("bugreport")
+        );
+        assert_eq!(
+            config.kind_defaults(TaskKind::Todo),
+            KindDefaults::default()
+        );
+        assert!(config.unknown_kinds().is_empty());
+
+        // A section for a kind that doesn't exist is reported, not an error
+        let config: ProjectConfig =
+            toml::from_str("[kind.bug]\npriority = \"low\"\n\n[kind.idea]\n").unwrap();
+        assert_eq!(config.unknown_kinds(), ["bug"]);
+        assert_eq!(
+            config.kind_defaults(TaskKind::Idea),
+            KindDefaults::default()
+        );
+    }
+
+    #[test]
+    fn test_validation_section() {
+        let config: ProjectConfig = toml::from_str(
+            "[validation]\nmax_title_length = 60\nallowed_tags = [\"bug\", \"area/*\"]\n\n[validation.required]\ntask = [\"assignee\", \"due\"]\n",
+        )
+        .unwrap();
+        assert_eq!(config.validation.max_title_length, Some(60));
+        assert_eq!(config.validation.required[&TaskKind::Task].len(), 2);
+        config.validation.validate().unwrap();
+        assert!(ProjectConfig::default().validation.is_empty());
+
+        let unknown: ProjectConfig =
+            toml::from_str("[validation.required]\ntask = [\"owner\"]\n").unwrap();
+        assert!(matches!(
+            unknown.validation.validate(),
+            Err(ConfigError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_workflow_section() {
+        let config: ProjectConfig =
+            toml::from_str("[workflow]\nstatuses = [\"review\", \"blocked\"]\n").unwrap();
+        config.workflow.validate().unwrap();
+        assert!(config.workflow.allows(TaskStatus::Custom("review")));
+        assert!(config.workflow.allows(TaskStatus::Completed));
+        assert!(!config.workflow.allows(TaskStatus::Custom("qa")));
+        assert_eq!(
+            config.workflow.parse_status("Review"),
+            Ok(TaskStatus::Custom("review"))
+        );
+        assert_eq!(
+            WorkflowConfig::default().parse_status("review"),
+            Err("Unknown status: review".to_string())
+        );
+        assert!(config.workflow.parse_status("pendng").is_err());
+
+        for bad in ["done", "In Review", "Review"] {
+            let workflow = WorkflowConfig {
+                statuses: vec![bad.to_string()],
+                ..Default::default()
+            };
+            assert!(matches!(workflow.validate(), Err(ConfigError::Invalid(_))));
+        }
+
+        let columns: Vec<String> = config
+            .workflow
+            .columns()
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(
+            columns,
+            ["pending", "in-progress", "review", "blocked", "completed"]
+        );
+        let workflow = WorkflowConfig {
+            statuses: vec!["review".to_string()],
+            columns: vec!["review".to_string(), "done".to_string()],
+        };
+        workflow.validate().unwrap();
+        assert_eq!(
+            workflow.columns(),
+            [TaskStatus::Custom("review"), TaskStatus::Completed]
+        );
+        let workflow = WorkflowConfig {
+            columns: vec!["qa".to_string()],
+            ..Default::default()
+        };
+        assert!(workflow.validate().is_err());
+    }
+
+    #[test]
+    fn test_display_formats() {
+        let when = Utc.with_ymd_and_hms(2026, 3, 4, 23, 30, 0).unwrap();
+        let display = DisplayConfig {
+            date_format: Some("%d/%m/%Y".to_string()),
+            time_format: None,
+            timezone: Some("+02:00".to_string()),
+            due_soon_days: None,
+        };
+        display.validate().unwrap();
+        assert_eq!(display.format_timestamp(when), "05/03/2026 01:30");
+        assert_eq!(display.format_date(when.date_naive()), "04/03/2026");
+        assert_eq!(display.due_soon_days(), 3);
+
+        let utc = DisplayConfig {
+            timezone: Some("utc".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(utc.format_timestamp(when), "2026-03-04 23:30");
+
+        let bad_zone = DisplayConfig {
+            timezone: Some("Mars/Olympus".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(bad_zone.validate(), Err(ConfigError::Invalid(_))));
+        let bad_format = DisplayConfig {
+            date_format: Some("%Q".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            bad_format.validate(),
+            Err(ConfigError::Invalid(_))
+        ));
+    }
+}
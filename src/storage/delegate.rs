@@ -0,0 +1,68 @@
+//! Handing tasks over to another project (`gittask delegate`)
+//!
+//! Delegating creates a mirror of the task in the target project whose
+//! `delegated_from` holds the source's qualified ID. The two stay loosely
+//! linked: completing the mirror offers to complete the source as well.
+
+use crate::models::Task;
+use crate::storage::file_store::{FileStore, FileStoreError};
+use crate::storage::registry::project_name;
+
+/// Create a mirror of task `id` from `source` in `target`
+///
+/// The mirror gets the title, kind, priority, tags, context, due date and
+/// description, plus the target project's defaults for its kind.
+pub fn delegate(source: &FileStore, id: u64, target: &FileStore) -> Result<Task, FileStoreError> {
+    let task = source.read(id)?;
+    if source.location().tasks_dir == target.location().tasks_dir {
+        return Err(FileStoreError::DelegateToSelf(id));
+    }
+
+    let mut mirror = target.new_task(task.kind, &task.title, None)?;
+    mirror.priority = task.priority;
+    for tag in &task.tags {
+        mirror.add_tag(tag);
+    }
+    mirror.context = task.context.clone().or(mirror.context);
+    mirror.due = task.due;
+    mirror.description = task.description.clone();
+    mirror.delegated_from = Some(format!(
+        "{}:{}",
+        project_name(&source.location().root),
+        task.id
+    ));
+    target.create(mirror)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Priority, TaskKind};
+    use crate::storage::test_support::test_store_at;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_delegate() {
+        let temp = TempDir::new().unwrap();
+        let api = test_store_at(&temp.path().join("api"));
+        let web = test_store_at(&temp.path().join("web"));
+        web.create(Task::new(0, TaskKind::Task, "Existing"))
+            .unwrap();
+
+        let mut task = Task::new(0, TaskKind::Task, "Add login endpoint");
+        task.priority = Priority::High;
+        task.tags = vec!["auth".to_string()];
+        task.description = "Needed by the new login page.".to_string();
+        let task = api.create(task).unwrap();
+
+        let mirror = delegate(&api, task.id, &web).unwrap();
+        assert_eq!(mirror.id, 2);
+        assert_eq!(mirror.delegated_from.as_deref(), Some("api:1"));
+        assert_eq!(mirror.priority, Priority::High);
+        assert_eq!(mirror.tags, vec!["auth"]);
+        assert_eq!(web.read(2).unwrap().description, task.description);
+
+        assert!(delegate(&api, task.id, &api).is_err());
+        assert!(delegate(&api, 9, &web).is_err());
+    }
+}
@@ -0,0 +1,248 @@
+//! Dependency graph between tasks in one store
+//!
+//! A task is blocked while any task in its `blocked_by` list is still open.
+//! Dependencies on completed, archived or missing tasks don't block.
+
+use crate::models::Task;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Tasks indexed by ID with helpers for dependency queries
+#[derive(Debug, Default)]
+pub struct DependencyGraph {
+    tasks: BTreeMap<u64, Task>,
+}
+
+impl DependencyGraph {
+    /// Build a graph from every task in a store (archived included)
+    pub fn new(tasks: impl IntoIterator<Item = Task>) -> Self {
+        DependencyGraph {
+            tasks: tasks.into_iter().map(|t| (t.id, t)).collect(),
+        }
+    }
+
+    /// Look up a task by ID
+    pub fn get(&self, id: u64) -> Option<&Task> {
+        self.tasks.get(&id)
+    }
+
+    /// Blockers of a task that are still open
+    pub fn open_blockers(&self, id: u64) -> Vec<u64> {
+        self.tasks
+            .get(&id)
+            .map(|task| {
+                task.blocked_by
+                    .iter()
+                    .copied()
+                    .filter(|b| self.tasks.get(b).is_some_and(|t| t.is_open()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Open tasks that are waiting on other open tasks, with their blockers
+    pub fn blocked(&self) -> Vec<(&Task, Vec<u64>)> {
+        self.tasks
+            .values()
+            .filter(|t| t.is_open())
+            .filter_map(|t| {
+                let blockers = self.open_blockers(t.id);
+                (!blockers.is_empty()).then_some((t, blockers))
+            })
+            .collect()
+    }
+
+    /// Open tasks in an order where every task follows its open blockers
+    ///
+    /// Ties are broken by ID. Tasks on a dependency cycle (and anything
+    /// waiting on them) can never become ready and are left out.
+    pub fn topological_order(&self) -> Vec<&Task> {
+        let open: Vec<&Task> = self.tasks.values().filter(|t| t.is_open()).collect();
+
+        let mut pending: HashMap<u64, usize> = HashMap::new();
+        let mut dependents: HashMap<u64, Vec<u64>> = HashMap::new();
+        for task in &open {
+            let blockers = self.open_blockers(task.id);
+            pending.insert(task.id, blockers.len());
+            for blocker in blockers {
+                dependents.entry(blocker).or_default().push(task.id);
+            }
+        }
+
+        let mut ready: BTreeSet<u64> = pending
+            .iter()
+            .filter(|(_, n)| **n == 0)
+            .map(|(id, _)| *id)
+            .collect();
+        let mut order = Vec::with_capacity(open.len());
+
+        while let Some(id) = ready.pop_first() {
+            order.push(&self.tasks[&id]);
+            for dependent in dependents.get(&id).into_iter().flatten() {
+                if let Some(n) = pending.get_mut(dependent) {
+                    *n -= 1;
+                    if *n == 0 {
+                        ready.insert(*dependent);
+                    }
+                }
+            }
+        }
+
+        order
+    }
+
+    /// The cycle that making `id` wait on `blocker` would close, if any
+    ///
+    /// Follows `blocked_by` links of every task, open or not, and returns
+    /// the path `[id, blocker, ..., id]` read as "waits on".
+    pub fn cycle_with(&self, id: u64, blocker: u64) -> Option<Vec<u64>> {
+        if id == blocker {
+            return Some(vec![id, id]);
+        }
+
+        // Depth-first search from the blocker back to `id`
+        let mut parents: HashMap<u64, u64> = HashMap::new();
+        let mut stack = vec![blocker];
+        let mut seen = BTreeSet::from([blocker]);
+        while let Some(current) = stack.pop() {
+            let Some(task) = self.tasks.get(&current) else {
+                continue;
+            };
+            for &next in &task.blocked_by {
+                if next == id {
+                    let mut path = vec![id];
+                    let mut at = current;
+                    let mut tail = vec![at];
+                    while let Some(&parent) = parents.get(&at) {
+                        tail.push(parent);
+                        at = parent;
+                    }
+                    path.extend(tail.into_iter().rev());
+                    path.push(id);
+                    return Some(path);
+                }
+                if seen.insert(next) {
+                    parents.insert(next, current);
+                    stack.push(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Every dependency as `(blocker, dependent)`, ordered by dependent
+    ///
+    /// Links to tasks that no longer exist are left out.
+    pub fn edges(&self) -> Vec<(&Task, &Task)> {
+        self.tasks
+            .values()
+            .flat_map(|task| {
+                task.blocked_by
+                    .iter()
+                    .filter_map(|blocker| self.tasks.get(blocker))
+                    .map(move |blocker| (blocker, task))
+            })
+            .collect()
+    }
+
+    /// The graph in Graphviz DOT format
+    ///
+    /// Only tasks with dependencies appear. Edges point from a blocker to
+    /// the task waiting on it; closed tasks are drawn dashed and grey.
+    pub fn to_dot(&self) -> String {
+        let edges = self.edges();
+        let involved: BTreeSet<u64> = edges
+            .iter()
+            .flat_map(|(blocker, task)| [blocker.id, task.id])
+            .collect();
+
+        let mut dot = String::from("digraph tasks {\n    rankdir=LR;\n    node [shape=box];\n");
+        for id in involved {
+            let task = &self.tasks[&id];
+            let label = format!("#{} {}", id, task.title)
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"");
+            let style = if task.is_open() {
+                ""
+            } else {
+                ", style=dashed, color=grey, fontcolor=grey"
+            };
+            dot.push_str(&format!("    {} [label=\"{}\"{}];\n", id, label, style));
+        }
+        for (blocker, task) in edges {
+            dot.push_str(&format!("    {} -> {};\n", blocker.id, task.id));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Open tasks with no open blockers, in topological order
+    pub fn ready(&self) -> Vec<&Task> {
+        self.topological_order()
+            .into_iter()
+            .filter(|t| self.open_blockers(t.id).is_empty())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TaskKind, TaskStatus};
+
+    fn task(id: u64, blocked_by: &[u64]) -> Task {
+        let mut task = Task::new(id, TaskKind::Task, format!("Task {}", id));
+        task.blocked_by = blocked_by.to_vec();
+        task
+    }
+
+    #[test]
+    fn test_blocked_and_ready() {
+        let mut done = task(1, &[]);
+        done.status = TaskStatus::Completed;
+        let graph = DependencyGraph::new([done, task(2, &[1]), task(3, &[2]), task(4, &[2, 9])]);
+
+        assert!(graph.open_blockers(2).is_empty());
+        assert_eq!(graph.open_blockers(4), vec![2]);
+
+        let blocked: Vec<u64> = graph.blocked().iter().map(|(t, _)| t.id).collect();
+        assert_eq!(blocked, vec![3, 4]);
+
+        let ready: Vec<u64> = graph.ready().iter().map(|t| t.id).collect();
+        assert_eq!(ready, vec![2]);
+
+        let order: Vec<u64> = graph.topological_order().iter().map(|t| t.id).collect();
+        assert_eq!(order, vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn test_cycle_with() {
+        let graph =
+            DependencyGraph::new([task(1, &[2]), task(2, &[3]), task(3, &[]), task(4, &[])]);
+        assert_eq!(graph.cycle_with(3, 1), Some(vec![3, 1, 2, 3]));
+        assert_eq!(graph.cycle_with(2, 1), Some(vec![2, 1, 2]));
+        assert_eq!(graph.cycle_with(4, 4), Some(vec![4, 4]));
+        assert_eq!(graph.cycle_with(1, 3), None);
+        assert_eq!(graph.cycle_with(4, 1), None);
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let mut done = task(1, &[]);
+        done.status = TaskStatus::Completed;
+        done.title = "Say \"hi\"".to_string();
+        let graph = DependencyGraph::new([done, task(2, &[1]), task(3, &[])]);
+        assert_eq!(
+            graph.to_dot(),
+            "digraph tasks {\n    rankdir=LR;\n    node [shape=box];\n    \
+             1 [label=\"#1 Say \\\"hi\\\"\", style=dashed, color=grey, fontcolor=grey];\n    \
+             2 [label=\"#2 Task 2\"];\n    1 -> 2;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_cycles_are_left_out_of_order() {
+        let graph = DependencyGraph::new([task(1, &[2]), task(2, &[1]), task(3, &[])]);
+        let order: Vec<u64> = graph.topological_order().iter().map(|t| t.id).collect();
+        assert_eq!(order, vec![3]);
+    }
+}
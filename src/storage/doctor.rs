@@ -0,0 +1,592 @@
+//! Consistency checks and repairs for a tasks directory
+
+use crate::models::{FormatRules, Task, format_task_content, key_line, parse_task, serialize_task};
+use crate::storage::config::{ProjectConfig, WorkflowConfig};
+use crate::storage::file_store::{FileStore, FileStoreError, read_task_file};
+use crate::storage::id_generator::IdGenerator;
+use crate::storage::layout;
+use crate::storage::writes;
+use chrono::Utc;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Directory within the tasks directory that unreadable task files are moved to
+pub const QUARANTINE_DIR: &str = ".broken";
+
+/// Report appended to by every quarantine, inside `QUARANTINE_DIR`
+pub const QUARANTINE_REPORT: &str = "REPORT.txt";
+
+/// A problem found in a tasks directory
+#[derive(Debug, Clone, PartialEq)]
+pub enum Issue {
+    /// Several files carry the same task ID
+    DuplicateId { id: u64, paths: Vec<PathBuf> },
+    /// A task file could not be read or parsed
+    Unreadable { path: PathBuf, error: String },
+    /// An open task refers to code that is no longer there
+    BrokenRef {
+        id: u64,
+        reference: String,
+        problem: String,
+    },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::DuplicateId { id, paths } => {
+                let names: Vec<String> = paths.iter().map(|p| file_name(p)).collect();
+                write!(f, "ID {} is used by {}", id, names.join(", "))
+            }
+            Issue::Unreadable { path, error } => write!(f, "{}: {}", file_name(path), error),
+            Issue::BrokenRef {
+                id,
+                reference,
+                problem,
+            } => write!(f, "#{} refers to {}: {}", id, reference, problem),
+        }
+    }
+}
+
+/// A problem `validate` found in one task file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileProblem {
+    /// Task file, relative to the project root
+    pub path: PathBuf,
+    /// 1-based line the problem is on, if it can be pinned down
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for FileProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.display())?;
+        if let Some(line) = self.line {
+            write!(f, ":{}", line)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// A task that was given a new ID by `fix_duplicate_ids`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Renumbered {
+    pub old_id: u64,
+    pub new_id: u64,
+    pub old_path: PathBuf,
+    pub new_path: PathBuf,
+}
+
+/// What `format_files` did, or would do, to the task files
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FormatReport {
+    /// Files that were (or, when checking, would be) rewritten
+    pub changed: Vec<PathBuf>,
+    /// Files left alone because they can't be formatted safely, with why
+    pub skipped: Vec<(PathBuf, String)>,
+}
+
+fn file_name(path: &std::path::Path) -> String {
+    path.file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Check that a `path` or `path:line` code reference points at existing code
+///
+/// Returns what is wrong with it, relative paths being taken from `root`.
+pub fn check_ref(root: &Path, reference: &str) -> Option<String> {
+    let (path, line) = match reference.rsplit_once(':') {
+        Some((path, line)) => match line.parse::<usize>() {
+            Ok(line) if line > 0 => (path, Some(line)),
+            _ => return Some(format!("'{}' is not a line number", line)),
+        },
+        None => (reference, None),
+    };
+
+    let path = root.join(path);
+    if !path.is_file() {
+        return Some("file not found".to_string());
+    }
+    let line = line?;
+    let lines = match std::fs::read_to_string(&path) {
+        Ok(content) => content.lines().count(),
+        Err(e) => return Some(e.to_string()),
+    };
+    (line > lines).then(|| format!("file has only {} lines", lines))
+}
+
+/// A task file moved aside by `quarantine`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quarantined {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    pub error: String,
+}
+
+/// Check a store for duplicate IDs, unreadable task files and code
+/// references of open tasks that no longer resolve
+pub fn diagnose(store: &FileStore) -> Result<Vec<Issue>, FileStoreError> {
+    let mut issues: Vec<Issue> = store
+        .duplicate_ids()?
+        .into_iter()
+        .map(|(id, paths)| Issue::DuplicateId { id, paths })
+        .collect();
+
+    if store.location().exists() {
+        let mut files = layout::task_files(&store.location().tasks_dir)?;
+        files.sort();
+        for path in files {
+            if let Err(FileStoreError::Unreadable { path, error }) = read_task_file(&path) {
+                issues.push(Issue::Unreadable { path, error });
+            }
+        }
+    }
+
+    // Completed tasks keep their refs as history; only open ones must resolve
+    let root = &store.location().root;
+    for task in store.list(&Default::default())? {
+        if !task.is_open() {
+            continue;
+        }
+        for reference in &task.refs {
+            if let Some(problem) = check_ref(root, reference) {
+                issues.push(Issue::BrokenRef {
+                    id: task.id,
+                    reference: reference.clone(),
+                    problem,
+                });
+            }
+        }
+    }
+
+    Ok(issues)
+}
+
+/// Check every task file the way other clones will read it
+///
+/// Reports files that don't parse, IDs used by several files, file names
+/// whose ID differs from the frontmatter, and `blocked_by` or `parent`
+/// entries naming tasks that don't exist. Problems are ordered by file.
+pub fn validate(store: &FileStore) -> Result<Vec<FileProblem>, FileStoreError> {
+    let location = store.location();
+    if !location.exists() {
+        return Ok(Vec::new());
+    }
+    let mut files = Vec::new();
+    for path in layout::task_files(&location.tasks_dir)? {
+        let content = std::fs::read_to_string(&path)?;
+        let relative = path
+            .strip_prefix(&location.root)
+            .unwrap_or(&path)
+            .to_path_buf();
+        files.push((relative, content));
+    }
+    let workflow = ProjectConfig::load(&location.tasks_dir)?.workflow;
+    Ok(validate_files(files, &workflow))
+}
+
+/// Check task files given as (path, content) pairs, like `validate`
+///
+/// Used for the files as of a commit, which aren't on disk.
+pub fn validate_files(
+    mut files: Vec<(PathBuf, String)>,
+    workflow: &WorkflowConfig,
+) -> Vec<FileProblem> {
+    files.sort();
+
+    let mut problems = Vec::new();
+    let mut parsed: Vec<(PathBuf, String, Task)> = Vec::new();
+    for (path, content) in files {
+        match parse_task(&content) {
+            Ok(task) => parsed.push((path, content, task)),
+            Err(e) => problems.push(FileProblem {
+                line: e.line(&content),
+                path,
+                message: e.message(),
+            }),
+        }
+    }
+
+    let mut by_id: BTreeMap<u64, Vec<&PathBuf>> = BTreeMap::new();
+    for (path, _, task) in &parsed {
+        by_id.entry(task.id).or_default().push(path);
+    }
+    let ids: HashSet<u64> = by_id.keys().copied().collect();
+
+    for (path, content, task) in &parsed {
+        let problem = |key: &str, message: String| FileProblem {
+            path: path.clone(),
+            line: key_line(content, key),
+            message,
+        };
+
+        if let Some(file_id) = IdGenerator::extract_id_from_filename(path)
+            && file_id != task.id
+        {
+            problems.push(problem(
+                "id",
+                format!("file name says #{} but the id is {}", file_id, task.id),
+            ));
+        }
+        let others: Vec<String> = by_id[&task.id]
+            .iter()
+            .filter(|p| **p != path)
+            .map(|p| file_name(p))
+            .collect();
+        if !others.is_empty() {
+            problems.push(problem(
+                "id",
+                format!("id {} is also used by {}", task.id, others.join(", ")),
+            ));
+        }
+        for blocker in task.blocked_by.iter().filter(|b| !ids.contains(b)) {
+            problems.push(problem(
+                "blocked_by",
+                format!("blocked_by refers to missing task #{}", blocker),
+            ));
+        }
+        if !workflow.allows(task.status) {
+            problems.push(problem(
+                "status",
+                format!("status '{}' is not in [workflow] statuses", task.status),
+            ));
+        }
+        if let Some(parent) = task.parent.filter(|p| !ids.contains(p)) {
+            problems.push(problem(
+                "parent",
+                format!("parent refers to missing task #{}", parent),
+            ));
+        }
+    }
+
+    problems.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+    problems
+}
+
+/// Move task files that can't be read or parsed into `.tasks/.broken/`
+///
+/// Each move is logged with the parse error in `.broken/REPORT.txt`. A file
+/// whose name is already taken there gets a numeric suffix.
+pub fn quarantine(store: &FileStore) -> Result<Vec<Quarantined>, FileStoreError> {
+    let tasks_dir = &store.location().tasks_dir;
+    if !store.location().exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut broken = Vec::new();
+    let mut files = layout::task_files(tasks_dir)?;
+    files.sort();
+    for path in files {
+        if let Err(FileStoreError::Unreadable { path, error }) = read_task_file(&path) {
+            broken.push((path, error));
+        }
+    }
+    if broken.is_empty() {
+        return Ok(Vec::new());
+    }
+    store.ensure_writable()?;
+
+    let dir = tasks_dir.join(QUARANTINE_DIR);
+    let mut moved: Vec<Quarantined> = Vec::new();
+    for (from, error) in broken {
+        let name = file_name(&from);
+        let mut to = dir.join(&name);
+        let mut n = 1;
+        while to.exists() || moved.iter().any(|q| q.to == to) {
+            to = dir.join(format!("{}.{}", name, n));
+            n += 1;
+        }
+        moved.push(Quarantined { from, to, error });
+    }
+
+    let mut report = String::new();
+    let now = Utc::now().format("%Y-%m-%dT%H:%M:%SZ");
+    for q in &moved {
+        writes::rename(&q.from, &q.to)?;
+        report.push_str(&format!(
+            "{}  {} -> {}: {}\n",
+            now,
+            q.from.strip_prefix(tasks_dir).unwrap_or(&q.from).display(),
+            file_name(&q.to),
+            q.error
+        ));
+    }
+    writes::append(&dir.join(QUARANTINE_REPORT), report)?;
+    Ok(moved)
+}
+
+/// Normalize every task file by `rules`, or with `write` off only report
+/// which files aren't formatted
+///
+/// Files that don't parse are skipped, as are files whose formatted version
+/// wouldn't read back as the same task.
+pub fn format_files(
+    store: &FileStore,
+    rules: &FormatRules,
+    write: bool,
+) -> Result<FormatReport, FileStoreError> {
+    let mut report = FormatReport::default();
+    if !store.location().exists() {
+        return Ok(report);
+    }
+    let mut files = layout::task_files(&store.location().tasks_dir)?;
+    files.sort();
+
+    let mut formatted = Vec::new();
+    for path in files {
+        let content = std::fs::read_to_string(&path)?;
+        match format_task_content(&content, rules) {
+            Ok(new) if new != content => formatted.push((path, new)),
+            Ok(_) => {}
+            Err(e) => report.skipped.push((path, e.to_string())),
+        }
+    }
+    if write && !formatted.is_empty() {
+        store.ensure_writable()?;
+    }
+
+    for (path, content) in formatted {
+        if write {
+            writes::write(&path, content)?;
+        }
+        report.changed.push(path);
+    }
+    Ok(report)
+}
+
+/// Give every duplicated task but the oldest a fresh ID
+///
+/// Within each group the task created first keeps its ID. Files that can't
+/// be parsed are left alone.
+pub fn fix_duplicate_ids(store: &FileStore) -> Result<Vec<Renumbered>, FileStoreError> {
+    let tasks_dir = &store.location().tasks_dir;
+    let mut renumbered = Vec::new();
+    let duplicates = store.duplicate_ids()?;
+    if !duplicates.is_empty() {
+        store.ensure_writable()?;
+    }
+
+    for (id, paths) in duplicates {
+        let mut tasks: Vec<(PathBuf, Task)> = paths
+            .into_iter()
+            .filter_map(|path| {
+                let task = parse_task(&std::fs::read_to_string(&path).ok()?).ok()?;
+                Some((path, task))
+            })
+            .collect();
+        tasks.sort_by(|a, b| a.1.created.cmp(&b.1.created).then(a.0.cmp(&b.0)));
+
+        for (old_path, mut task) in tasks.into_iter().skip(1) {
+            let _lock = IdGenerator::lock(tasks_dir)
+                .map_err(|e| FileStoreError::Io(std::io::Error::other(e.to_string())))?;
+            let new_id = store.next_id()?;
+
+            task.id = new_id;
+            let dir = store.layout().dir_for(tasks_dir, new_id);
+            let new_path = dir.join(task.filename());
+            writes::rewrite(&old_path, &new_path, serialize_task(&task)?)?;
+
+            renumbered.push(Renumbered {
+                old_id: id,
+                new_id,
+                old_path,
+                new_path,
+            });
+        }
+    }
+
+    Ok(renumbered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TaskKind, TaskStatus};
+    use crate::storage::test_support::setup_test_store;
+
+    #[test]
+    fn test_validate_reports_file_and_line() {
+        let (_temp, store) = setup_test_store();
+        let location = store.location().clone();
+        store.create(Task::new(0, TaskKind::Task, "Fine")).unwrap();
+        assert!(validate(&store).unwrap().is_empty());
+
+        let write = |name: &str, content: &str| {
+            std::fs::write(location.tasks_dir.join(name), content).unwrap();
+        };
+        let mut waiting = Task::new(2, TaskKind::Task, "Waiting");
+        waiting.blocked_by = vec![9];
+        write("waiting-002.md", &serialize_task(&waiting).unwrap());
+        let renamed = Task::new(1, TaskKind::Task, "Copied");
+        write("copied-003.md", &serialize_task(&renamed).unwrap());
+        write("broken-004.md", "---\nid: 4\ntitle: [unclosed\n---\n");
+        let mut retired = Task::new(5, TaskKind::Task, "Retired");
+        retired.status = TaskStatus::define("retired").unwrap();
+        write("retired-005.md", &serialize_task(&retired).unwrap());
+
+        let problems: Vec<String> = validate(&store)
+            .unwrap()
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        assert_eq!(problems.len(), 6);
+        assert!(problems[0].starts_with(".tasks/broken-004.md:3: "));
+        assert!(!problems[0].contains(" at line "));
+        assert_eq!(
+            problems[1..],
+            [
+                ".tasks/copied-003.md:2: file name says #3 but the id is 1",
+                ".tasks/copied-003.md:2: id 1 is also used by fine-001.md",
+                ".tasks/fine-001.md:2: id 1 is also used by copied-003.md",
+                ".tasks/retired-005.md:4: status 'retired' is not in [workflow] statuses",
+                ".tasks/waiting-002.md:7: blocked_by refers to missing task #9",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicate_ids_are_detected_and_fixed() {
+        let (_temp, store) = setup_test_store();
+        let location = store.location().clone();
+
+        store.create(Task::new(0, TaskKind::Task, "Ours")).unwrap();
+        store.create(Task::new(0, TaskKind::Task, "Other")).unwrap();
+
+        // Simulate a merge that brought in another task #1
+        let mut theirs = Task::new(1, TaskKind::Task, "Theirs");
+        theirs.created += chrono::Duration::seconds(1);
+        std::fs::write(
+            location.tasks_dir.join(theirs.filename()),
+            serialize_task(&theirs).unwrap(),
+        )
+        .unwrap();
+
+        let store = FileStore::new(location);
+        assert!(matches!(
+            store.read(1),
+            Err(FileStoreError::AmbiguousId(1, ref paths)) if paths.len() == 2
+        ));
+        assert!(matches!(
+            diagnose(&store).unwrap()[..],
+            [Issue::DuplicateId { id: 1, .. }]
+        ));
+
+        let fixed = fix_duplicate_ids(&store).unwrap();
+        assert_eq!(fixed.len(), 1);
+        assert_eq!(fixed[0].new_id, 3);
+        assert_eq!(store.read(1).unwrap().title, "Ours");
+        assert_eq!(store.read(3).unwrap().title, "Theirs");
+        assert!(diagnose(&store).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_format_files() {
+        let (_temp, store) = setup_test_store();
+        let location = store.location().clone();
+        let task = store.create(Task::new(0, TaskKind::Task, "Fine")).unwrap();
+        let messy = location.tasks_dir.join("2-messy.md");
+        std::fs::write(&messy, "---\ntitle: Messy\nid: 2\ntags: [Ops]\ncreated: 2026-02-13T10:30:00Z\nupdated: 2026-02-13T10:30:00Z\n---\n").unwrap();
+        let broken = location.tasks_dir.join("3-broken.md");
+        std::fs::write(&broken, "---\nid: [oops\n---\n").unwrap();
+
+        let rules = FormatRules::default();
+        let report = format_files(&store, &rules, false).unwrap();
+        assert_eq!(report.changed, std::slice::from_ref(&messy));
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].0, broken);
+        assert!(
+            std::fs::read_to_string(&messy)
+                .unwrap()
+                .starts_with("---\ntitle")
+        );
+
+        format_files(&store, &rules, true).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&messy).unwrap(),
+            "---\nid: 2\ntitle: Messy\ntags:\n- ops\ncreated: 2026-02-13T10:30:00Z\nupdated: 2026-02-13T10:30:00Z\n---\n"
+        );
+        assert!(
+            format_files(&store, &rules, false)
+                .unwrap()
+                .changed
+                .is_empty()
+        );
+
+        // Fields gittask adds to a formatted file keep it formatted
+        let patch = crate::storage::TaskPatch {
+            due: Some(Some("2026-05-01".parse().unwrap())),
+            ..Default::default()
+        };
+        store.patch(task.id, &patch, None).unwrap();
+        assert!(
+            format_files(&store, &rules, false)
+                .unwrap()
+                .changed
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_quarantine() {
+        let (_temp, store) = setup_test_store();
+        let location = store.location().clone();
+        store.create(Task::new(0, TaskKind::Task, "Fine")).unwrap();
+        let broken = location.tasks_dir.join("2-broken.md");
+        std::fs::write(&broken, "---\nid: [oops\n---\n").unwrap();
+
+        assert_eq!(store.list(&Default::default()).unwrap().len(), 1);
+        let config = location.tasks_dir.join("config.toml");
+        std::fs::write(&config, "[storage]\nstrict = true\n").unwrap();
+        assert!(matches!(
+            store.list(&Default::default()),
+            Err(FileStoreError::Unreadable { ref path, .. }) if path == &broken
+        ));
+        std::fs::remove_file(&config).unwrap();
+
+        let moved = quarantine(&store).unwrap();
+        assert_eq!(moved.len(), 1);
+        assert!(!broken.exists());
+        assert_eq!(moved[0].to, location.tasks_dir.join(".broken/2-broken.md"));
+        let report =
+            std::fs::read_to_string(location.tasks_dir.join(".broken/REPORT.txt")).unwrap();
+        assert!(report.contains("2-broken.md -> 2-broken.md: "));
+        assert!(diagnose(&store).unwrap().is_empty());
+        assert!(quarantine(&store).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_broken_refs() {
+        let (temp, store) = setup_test_store();
+        std::fs::create_dir(temp.path().join("src")).unwrap();
+        std::fs::write(temp.path().join("src/lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+
+        let mut task = Task::new(0, TaskKind::Task, "Refactor");
+        task.refs = vec![
+            "src/lib.rs:2".to_string(),
+            "src/lib.rs".to_string(),
+            "src/lib.rs:88".to_string(),
+            "src/gone.rs".to_string(),
+        ];
+        let mut done = task.clone();
+        done.status = TaskStatus::Completed;
+        store.create(task).unwrap();
+        store.create(done).unwrap();
+
+        let problems: Vec<String> = diagnose(&store)
+            .unwrap()
+            .iter()
+            .map(|i| i.to_string())
+            .collect();
+        assert_eq!(
+            problems,
+            vec![
+                "#1 refers to src/lib.rs:88: file has only 2 lines",
+                "#1 refers to src/gone.rs: file not found",
+            ]
+        );
+        assert!(check_ref(temp.path(), "src/lib.rs:x").is_some());
+    }
+}
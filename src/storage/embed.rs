@@ -0,0 +1,114 @@
+//! Task checklists embedded in Markdown files such as a README
+//!
+//! The checklist lives between two HTML comment markers under a heading, so
+//! re-running the embed replaces it without touching the rest of the file.
+
+use crate::models::{Task, TaskStatus};
+
+const BEGIN_MARKER: &str = "<!-- gittask:begin -->";
+const END_MARKER: &str = "<!-- gittask:end -->";
+
+/// Render tasks as a GitHub-flavored Markdown checklist
+pub fn render_checklist(tasks: &[Task]) -> String {
+    tasks
+        .iter()
+        .map(|task| {
+            let mark = if task.status == TaskStatus::Completed {
+                'x'
+            } else {
+                ' '
+            };
+            format!("- [{}] {} (#{})\n", mark, task.title, task.id)
+        })
+        .collect()
+}
+
+/// Heading level of a Markdown line (`## Roadmap` is 2), if it is a heading
+fn heading_level(line: &str) -> Option<usize> {
+    let level = line.bytes().take_while(|&b| b == b'#').count();
+    (level > 0 && line[level..].starts_with(' ')).then_some(level)
+}
+
+/// Put `body` between the markers under the `section` heading
+///
+/// Existing markers in the section are reused; otherwise they are added
+/// right below the heading. A missing heading is appended to the end.
+pub fn embed_section(content: &str, section: &str, body: &str) -> String {
+    let section = section.trim();
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let block = format!("{}\n{}{}\n", BEGIN_MARKER, body, END_MARKER);
+
+    let Some(heading) = lines.iter().position(|l| l.trim_end() == section) else {
+        let mut out = content.to_string();
+        if !out.is_empty() && !out.ends_with('\n') {
+            out.push('\n');
+        }
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&format!("{}\n\n{}", section, block));
+        return out;
+    };
+
+    let level = heading_level(section).unwrap_or(usize::MAX);
+    let end = (heading + 1..lines.len())
+        .find(|&i| heading_level(lines[i]).is_some_and(|l| l <= level))
+        .unwrap_or(lines.len());
+    let marker = |m: &str| (heading + 1..end).find(|&i| lines[i].trim() == m);
+
+    let mut out: String = lines[..=heading].concat();
+    match (marker(BEGIN_MARKER), marker(END_MARKER)) {
+        (Some(begin), Some(finish)) if begin < finish => {
+            out.push_str(&lines[heading + 1..begin].concat());
+            out.push_str(&block);
+            out.push_str(&lines[finish + 1..].concat());
+        }
+        _ => {
+            out.push('\n');
+            out.push_str(&block);
+            if lines.get(heading + 1).is_some_and(|l| !l.trim().is_empty()) {
+                out.push('\n');
+            }
+            out.push_str(&lines[heading + 1..].concat());
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TaskKind;
+
+    #[test]
+    fn test_render_checklist() {
+        let mut done = Task::new(2, TaskKind::Task, "Ship");
+        done.status = TaskStatus::Completed;
+        let tasks = vec![Task::new(1, TaskKind::Task, "Plan"), done];
+        assert_eq!(
+            render_checklist(&tasks),
+            "- [ ] Plan (#1)\n- [x] Ship (#2)\n"
+        );
+    }
+
+    #[test]
+    fn test_embed_section() {
+        let readme = "# App\n\n## Roadmap\nComing up:\n\n## License\nMIT\n";
+
+        let once = embed_section(readme, "## Roadmap", "- [ ] A (#1)\n");
+        assert_eq!(
+            once,
+            "# App\n\n## Roadmap\n\n<!-- gittask:begin -->\n- [ ] A (#1)\n<!-- gittask:end -->\n\nComing up:\n\n## License\nMIT\n"
+        );
+
+        // Re-running replaces only the block
+        let twice = embed_section(&once, "## Roadmap", "- [x] A (#1)\n");
+        assert_eq!(twice, once.replace("- [ ] A", "- [x] A"));
+
+        let appended = embed_section("# App", "## Roadmap", "");
+        assert_eq!(
+            appended,
+            "# App\n\n## Roadmap\n\n<!-- gittask:begin -->\n<!-- gittask:end -->\n"
+        );
+    }
+}
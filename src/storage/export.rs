@@ -0,0 +1,280 @@
+//! Exporting tasks to portable formats
+
+use crate::models::Task;
+use crate::storage::file_store::{FileStore, FileStoreError, TaskFilter};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Appended to an export's file name to name its manifest
+pub const MANIFEST_SUFFIX: &str = ".manifest.json";
+
+/// Which timestamp a time window applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeField {
+    Created,
+    Updated,
+    Completed,
+    /// Any of created, updated or completed
+    #[default]
+    Any,
+}
+
+impl fmt::Display for TimeField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeField::Created => write!(f, "created"),
+            TimeField::Updated => write!(f, "updated"),
+            TimeField::Completed => write!(f, "completed"),
+            TimeField::Any => write!(f, "any"),
+        }
+    }
+}
+
+impl std::str::FromStr for TimeField {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "created" => Ok(TimeField::Created),
+            "updated" => Ok(TimeField::Updated),
+            "completed" => Ok(TimeField::Completed),
+            "any" => Ok(TimeField::Any),
+            _ => Err(format!("Unknown time field: {}", s)),
+        }
+    }
+}
+
+/// A time window restricting which tasks are exported
+#[derive(Debug, Clone, Default)]
+pub struct TimeWindow {
+    pub field: TimeField,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl TimeWindow {
+    /// Check whether the task's selected timestamp falls within the window
+    pub fn matches(&self, task: &Task) -> bool {
+        let in_window = |t: DateTime<Utc>| {
+            self.since.is_none_or(|since| t >= since) && self.until.is_none_or(|until| t < until)
+        };
+
+        match self.field {
+            TimeField::Created => in_window(task.created),
+            TimeField::Updated => in_window(task.updated),
+            TimeField::Completed => task.completed.is_some_and(in_window),
+            TimeField::Any => {
+                in_window(task.created)
+                    || in_window(task.updated)
+                    || task.completed.is_some_and(in_window)
+            }
+        }
+    }
+}
+
+/// A task as written to an export file (frontmatter fields plus description)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportedTask {
+    #[serde(flatten)]
+    pub task: Task,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+}
+
+impl From<&Task> for ExportedTask {
+    fn from(task: &Task) -> Self {
+        ExportedTask {
+            task: task.clone(),
+            description: task.description.clone(),
+        }
+    }
+}
+
+impl From<ExportedTask> for Task {
+    fn from(exported: ExportedTask) -> Self {
+        let mut task = exported.task;
+        task.description = exported.description;
+        task
+    }
+}
+
+/// Collect the tasks matching the filter and time window, sorted by ID
+pub fn export_tasks(
+    store: &FileStore,
+    filter: &TaskFilter,
+    window: &TimeWindow,
+) -> Result<Vec<Task>, FileStoreError> {
+    Ok(store
+        .list(filter)?
+        .into_iter()
+        .filter(|t| window.matches(t))
+        .collect())
+}
+
+/// Serialize tasks as a pretty-printed JSON array
+pub fn to_json(tasks: &[Task]) -> Result<String, serde_json::Error> {
+    let exported: Vec<ExportedTask> = tasks.iter().map(ExportedTask::from).collect();
+    serde_json::to_string_pretty(&exported)
+}
+
+/// Why an export failed verification against its manifest
+#[derive(Debug, Error)]
+pub enum VerifyError {
+    #[error("size is {actual} bytes but the manifest says {expected} (truncated?)")]
+    Size { expected: u64, actual: u64 },
+    #[error("SHA-256 is {actual} but the manifest says {expected} (modified?)")]
+    Checksum { expected: String, actual: String },
+    #[error("not a valid export: {0}")]
+    Invalid(#[from] serde_json::Error),
+    #[error("holds {actual} tasks but the manifest says {expected}")]
+    TaskCount { expected: usize, actual: usize },
+}
+
+/// Checksum manifest written next to an export file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportManifest {
+    /// File name of the export the manifest describes
+    pub file: String,
+    pub bytes: u64,
+    /// Hex SHA-256 of the export's bytes
+    pub sha256: String,
+    pub tasks: usize,
+    pub exported: DateTime<Utc>,
+}
+
+impl ExportManifest {
+    /// Describe an export file's content
+    pub fn new(file: impl Into<String>, content: &[u8], tasks: usize, now: DateTime<Utc>) -> Self {
+        ExportManifest {
+            file: file.into(),
+            bytes: content.len() as u64,
+            sha256: sha256_hex(content),
+            tasks,
+            exported: now,
+        }
+    }
+
+    /// Where the manifest of an export file lives
+    pub fn path_for(export: &Path) -> PathBuf {
+        let mut name = export.file_name().unwrap_or_default().to_os_string();
+        name.push(MANIFEST_SUFFIX);
+        export.with_file_name(name)
+    }
+
+    /// Check an export's content, returning its number of tasks
+    pub fn verify(&self, content: &[u8]) -> Result<usize, VerifyError> {
+        if content.len() as u64 != self.bytes {
+            return Err(VerifyError::Size {
+                expected: self.bytes,
+                actual: content.len() as u64,
+            });
+        }
+        let actual = sha256_hex(content);
+        if actual != self.sha256 {
+            return Err(VerifyError::Checksum {
+                expected: self.sha256.clone(),
+                actual,
+            });
+        }
+        let tasks: Vec<ExportedTask> = serde_json::from_slice(content)?;
+        if tasks.len() != self.tasks {
+            return Err(VerifyError::TaskCount {
+                expected: self.tasks,
+                actual: tasks.len(),
+            });
+        }
+        Ok(tasks.len())
+    }
+}
+
+fn sha256_hex(content: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(content))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TaskKind;
+    use chrono::Duration;
+
+    #[test]
+    fn test_time_window_fields() {
+        let now = Utc::now();
+        let mut task = Task::new(1, TaskKind::Task, "Test");
+        task.created = now - Duration::days(30);
+        task.updated = now - Duration::days(2);
+
+        let window = TimeWindow {
+            field: TimeField::Created,
+            since: Some(now - Duration::days(7)),
+            until: None,
+        };
+        assert!(!window.matches(&task));
+
+        let window = TimeWindow {
+            field: TimeField::Updated,
+            ..window
+        };
+        assert!(window.matches(&task));
+
+        let window = TimeWindow {
+            field: TimeField::Completed,
+            ..window
+        };
+        assert!(!window.matches(&task));
+
+        task.completed = Some(now - Duration::days(1));
+        assert!(window.matches(&task));
+
+        let window = TimeWindow {
+            field: TimeField::Any,
+            since: Some(now - Duration::days(7)),
+            until: Some(now - Duration::days(3)),
+        };
+        assert!(!window.matches(&task));
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let mut task = Task::new(7, TaskKind::Idea, "Exported");
+        task.description = "Body text".to_string();
+        task.tags = vec!["a".to_string()];
+
+        let json = to_json(std::slice::from_ref(&task)).unwrap();
+        assert!(json.contains("\"description\": \"Body text\""));
+
+        let parsed: Vec<ExportedTask> = serde_json::from_str(&json).unwrap();
+        let restored: Task = parsed.into_iter().next().unwrap().into();
+        assert_eq!(restored, task);
+    }
+
+    #[test]
+    fn test_manifest_verify() {
+        let tasks = vec![
+            Task::new(1, TaskKind::Task, "One"),
+            Task::new(2, TaskKind::Task, "Two"),
+        ];
+        let json = to_json(&tasks).unwrap() + "\n";
+        let manifest = ExportManifest::new("tasks.json", json.as_bytes(), 2, Utc::now());
+        assert_eq!(manifest.verify(json.as_bytes()).unwrap(), 2);
+        assert_eq!(
+            ExportManifest::path_for(Path::new("out/tasks.json")),
+            Path::new("out/tasks.json.manifest.json")
+        );
+
+        let truncated = &json.as_bytes()[..json.len() / 2];
+        assert!(matches!(
+            manifest.verify(truncated),
+            Err(VerifyError::Size { .. })
+        ));
+        let tampered = json.replace("Two", "Owt");
+        assert!(matches!(
+            manifest.verify(tampered.as_bytes()),
+            Err(VerifyError::Checksum { .. })
+        ));
+    }
+}
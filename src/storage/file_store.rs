@@ -1,12 +1,30 @@
 //! File-based storage for tasks
 
+#[cfg(feature = "git")]
+use crate::git::GitOperations;
 use crate::models::{
-    FrontmatterError, Priority, Task, TaskKind, TaskStatus, parse_task, serialize_task,
+    Comment, Due, FrontmatterError, Priority, Task, TaskKind, TaskStatus, local_date, parse_task,
+    peek_status, serialize_task, today, update_task_content,
 };
+use crate::storage::config::{ConfigError, ProjectConfig};
+use crate::storage::deps::DependencyGraph;
 use crate::storage::id_generator::IdGenerator;
+use crate::storage::index::TaskIndex;
+use crate::storage::layout::{self, TaskLayout};
 use crate::storage::location::TaskLocation;
-use crate::storage::registry::ProjectRegistry;
-use std::path::PathBuf;
+use crate::storage::order::{self, OrderMove};
+use crate::storage::patch::TaskPatch;
+use crate::storage::registry::{ProjectColor, ProjectRegistry};
+use crate::storage::tags::tag_matches;
+use crate::storage::templates::{TaskTemplate, TemplateError};
+use crate::storage::validation::{ValidationError, validate_task};
+use crate::storage::writes::{self, is_dry_run, planned_max_id};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
 use thiserror::Error;
 
 /// Errors related to file storage operations
@@ -20,19 +38,206 @@ pub enum FileStoreError {
     Io(#[from] std::io::Error),
     #[error("Task directory does not exist. Run 'gittask init' first.")]
     DirectoryNotInitialized,
+    #[error(
+        "Task ID {0} is used by several files ({files}). Run 'gittask doctor --fix' to renumber them.",
+        files = display_paths(.1)
+    )]
+    AmbiguousId(u64, Vec<PathBuf>),
+    #[error(
+        "Task {id} was modified at {actual} since it was read (expected {expected}). Re-read it and try again."
+    )]
+    Conflict {
+        id: u64,
+        expected: DateTime<Utc>,
+        actual: DateTime<Utc>,
+    },
+    #[error("{0}")]
+    Config(#[from] ConfigError),
+    #[error("Tasks in {} are read-only", .0.display())]
+    ReadOnly(PathBuf),
+    #[error("{0}")]
+    Template(#[from] TemplateError),
+    #[error(
+        "Cannot read task file {}: {error}. Run 'gittask doctor --quarantine' to move it aside.",
+        .path.display()
+    )]
+    Unreadable { path: PathBuf, error: String },
+    #[error("Task {0} is already in this project; delegate it to another one")]
+    DelegateToSelf(u64),
+    #[error("Dependency would create a cycle: {}", display_cycle(.0))]
+    DependencyCycle(Vec<u64>),
+    #[error("#{parent} can't be the parent of #{id}: it is #{id} itself or one of its subtasks")]
+    ParentCycle { id: u64, parent: u64 },
+    #[error("Task '{title}' breaks the project's validation rules: {}", display_errors(.errors))]
+    Invalid {
+        title: String,
+        errors: Vec<ValidationError>,
+    },
+    #[error("Unknown status '{status}'{}", known_statuses(.known))]
+    UnknownStatus { status: String, known: Vec<String> },
+    #[error("Can't import: {0}")]
+    InvalidImport(String),
+    #[error("Task {id}: {message}")]
+    ChecklistItem { id: u64, message: String },
+}
+
+/// How to fix an `UnknownStatus` error
+fn known_statuses(known: &[String]) -> String {
+    if known.is_empty() {
+        "; add it to `statuses` under [workflow] in config.toml".to_string()
+    } else {
+        format!(" (custom statuses: {})", known.join(", "))
+    }
+}
+
+/// Set by `--read-only`: every store in the process rejects writes
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// Make every `FileStore` in this process reject writes
+pub fn set_read_only(read_only: bool) {
+    READ_ONLY.store(read_only, Ordering::Relaxed);
+}
+
+/// Set by `--strict`: unreadable task files fail listings instead of being skipped
+static STRICT: AtomicBool = AtomicBool::new(false);
+
+/// Make every `FileStore` in this process fail on unreadable task files
+pub fn set_strict(strict: bool) {
+    STRICT.store(strict, Ordering::Relaxed);
+}
+
+/// Join file names for error messages
+fn display_paths(paths: &[PathBuf]) -> String {
+    paths
+        .iter()
+        .map(|p| p.file_name().unwrap_or(p.as_os_str()).to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Render a dependency cycle as `#1 → #2 → #1`
+fn display_cycle(path: &[u64]) -> String {
+    path.iter()
+        .map(|id| format!("#{}", id))
+        .collect::<Vec<_>>()
+        .join(" → ")
+}
+
+/// Render validation errors as `field: message; field: message`
+fn display_errors(errors: &[ValidationError]) -> String {
+    errors
+        .iter()
+        .map(|e| e.to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// What `FileStore::create_or_update_by_external_id` did
+#[derive(Debug, Clone, PartialEq)]
+pub enum Upsert {
+    Created(Task),
+    Updated(Task),
+    /// A matching task already had the same values
+    Unchanged(Task),
+}
+
+impl Upsert {
+    /// The task as stored
+    pub fn task(&self) -> &Task {
+        match self {
+            Upsert::Created(task) | Upsert::Updated(task) | Upsert::Unchanged(task) => task,
+        }
+    }
+
+    /// What happened, as "created", "updated" or "unchanged"
+    pub fn action(&self) -> &'static str {
+        match self {
+            Upsert::Created(_) => "created",
+            Upsert::Updated(_) => "updated",
+            Upsert::Unchanged(_) => "unchanged",
+        }
+    }
 }
 
 /// Filter criteria for listing tasks
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct TaskFilter {
     pub kind: Option<TaskKind>,
     pub status: Option<TaskStatus>,
     pub priority: Option<Priority>,
     pub tags: Vec<String>,
+    pub context: Option<String>,
     pub include_archived: bool,
+    /// Only tasks completed on or after this date
+    pub completed_since: Option<NaiveDate>,
+    /// Only tasks with this external tracker ID
+    pub external_id: Option<String>,
+    /// Only tasks assigned to this person (compared case-insensitively)
+    pub assignee: Option<String>,
+    /// Only tasks with no open blockers (see `DependencyGraph`)
+    ///
+    /// Needs the other tasks of the store, so `matches` ignores it and only
+    /// `FileStore::iter` and `FileStore::list` apply it.
+    pub unblocked: bool,
+    /// Only tasks scheduled on or before this date
+    pub scheduled_before: Option<NaiveDate>,
+    /// Only tasks scheduled on or after this date
+    pub scheduled_after: Option<NaiveDate>,
+    /// Leave out tasks scheduled to start after this date
+    ///
+    /// Lists set it to today so tasks stay hidden until they can be started.
+    pub available_on: Option<NaiveDate>,
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Expected true or false, got '{}'", value))
 }
 
 impl TaskFilter {
+    /// The keys [`TaskFilter::parse`] understands, with what each matches
+    pub const KEYS: &[(&str, &str)] = &[
+        ("status", "status, e.g. pending or a custom one"),
+        ("kind", "task, todo or idea"),
+        ("priority", "low, medium, high or critical (p0-p3 too)"),
+        ("tag", "has this tag; repeat for several"),
+        ("context", "GTD context, e.g. @home"),
+        ("external", "external tracker ID, e.g. GH#123"),
+        ("assignee", "assignee, ignoring case"),
+        ("archived", "true to include archived tasks"),
+        ("unblocked", "true to leave out tasks waiting on open ones"),
+    ];
+
+    /// Parse a filter written as space-separated `key:value` terms
+    ///
+    /// e.g. `status:pending kind:task tag:backend`; see [`TaskFilter::KEYS`].
+    pub fn parse(query: &str) -> Result<Self, String> {
+        let mut filter = TaskFilter::default();
+        for term in query.split_whitespace() {
+            let (key, value) = term
+                .split_once(':')
+                .ok_or_else(|| format!("Expected key:value, got '{}'", term))?;
+            match key {
+                "status" => {
+                    let status = value.parse()?;
+                    filter.include_archived |= status == TaskStatus::Archived;
+                    filter.status = Some(status);
+                }
+                "kind" => filter.kind = Some(value.parse()?),
+                "priority" => filter.priority = Some(value.parse()?),
+                "tag" => filter.tags.push(value.to_string()),
+                "context" => filter.context = Some(value.to_string()),
+                "external" => filter.external_id = Some(value.to_string()),
+                "assignee" => filter.assignee = Some(value.to_string()),
+                "archived" => filter.include_archived = parse_bool(value)?,
+                "unblocked" => filter.unblocked = parse_bool(value)?,
+                _ => return Err(format!("Unknown filter key: {}", key)),
+            }
+        }
+        Ok(filter)
+    }
+
     /// Check if a task matches the filter criteria
     pub fn matches(&self, task: &Task) -> bool {
         // Filter by kind
@@ -56,13 +261,61 @@ impl TaskFilter {
             return false;
         }
 
-        // Filter by tags (all specified tags must be present)
-        if !self.tags.is_empty() {
-            for tag in &self.tags {
-                if !task.tags.contains(tag) {
-                    return false;
-                }
-            }
+        // Filter by tags (every pattern must match one of the task's tags)
+        if !self
+            .tags
+            .iter()
+            .all(|pattern| task.tags.iter().any(|tag| tag_matches(pattern, tag)))
+        {
+            return false;
+        }
+
+        // Filter by context
+        if let Some(ref context) = self.context
+            && task.context.as_ref() != Some(context)
+        {
+            return false;
+        }
+
+        // Filter by external ID
+        if let Some(ref external_id) = self.external_id
+            && task.external_id.as_ref() != Some(external_id)
+        {
+            return false;
+        }
+
+        // Filter by assignee
+        if let Some(ref assignee) = self.assignee
+            && !task
+                .assignee
+                .as_deref()
+                .is_some_and(|a| a.eq_ignore_ascii_case(assignee))
+        {
+            return false;
+        }
+
+        // Filter by completion date (tasks without a completion timestamp never match)
+        if let Some(since) = self.completed_since
+            && task.completed.is_none_or(|c| local_date(c) < since)
+        {
+            return false;
+        }
+
+        // Filter by scheduled date (unscheduled tasks never match a range)
+        if let Some(before) = self.scheduled_before
+            && task.scheduled.is_none_or(|s| s > before)
+        {
+            return false;
+        }
+        if let Some(after) = self.scheduled_after
+            && task.scheduled.is_none_or(|s| s < after)
+        {
+            return false;
+        }
+        if let Some(date) = self.available_on
+            && task.is_scheduled_after(date)
+        {
+            return false;
         }
 
         // Exclude archived unless explicitly included
@@ -77,12 +330,63 @@ impl TaskFilter {
 /// File-based task storage
 pub struct FileStore {
     location: TaskLocation,
+    layout: TaskLayout,
+    /// ID to file path map, rebuilt when the directory's mtime changes
+    id_cache: Mutex<Option<IdCache>>,
+    /// The project config, reloaded when config.toml's mtime changes
+    config_cache: Mutex<Option<(Option<SystemTime>, ProjectConfig)>>,
+}
+
+/// Cached mapping from task ID to task file(s)
+struct IdCache {
+    dir_mtime: SystemTime,
+    paths: HashMap<u64, Vec<PathBuf>>,
+}
+
+impl IdCache {
+    /// Scan the tasks directory once, mapping every task file by ID
+    fn scan(tasks_dir: &Path, dir_mtime: SystemTime) -> Result<Self, FileStoreError> {
+        let mut paths = HashMap::new();
+
+        for path in layout::task_files(tasks_dir)? {
+            if let Some(id) = IdGenerator::extract_id_from_filename(&path) {
+                paths.entry(id).or_insert_with(Vec::new).push(path);
+            }
+        }
+
+        Ok(IdCache { dir_mtime, paths })
+    }
 }
 
 impl FileStore {
     /// Create a new file store for the given location
     pub fn new(location: TaskLocation) -> Self {
-        FileStore { location }
+        let store = FileStore {
+            layout: TaskLayout::detect(&location.tasks_dir),
+            location,
+            id_cache: Mutex::new(None),
+            config_cache: Mutex::new(None),
+        };
+        // Loading the config defines its custom statuses, so task files
+        // using them parse; a broken config is reported by whatever reads it
+        let _ = store.config();
+        store
+    }
+
+    /// The project config, read from disk only when it has changed
+    pub fn config(&self) -> Result<ProjectConfig, FileStoreError> {
+        let mtime = std::fs::metadata(ProjectConfig::path(&self.location.tasks_dir))
+            .and_then(|m| m.modified())
+            .ok();
+        let mut cache = self.config_cache.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((cached_mtime, ref config)) = *cache
+            && cached_mtime == mtime
+        {
+            return Ok(config.clone());
+        }
+        let config = ProjectConfig::load(&self.location.tasks_dir)?;
+        *cache = Some((mtime, config.clone()));
+        Ok(config)
     }
 
     /// Get the task location
@@ -90,25 +394,127 @@ impl FileStore {
         &self.location
     }
 
+    /// Whether writes are disabled, by `set_read_only` or `storage.read_only`
+    pub fn is_read_only(&self) -> Result<bool, FileStoreError> {
+        Ok(READ_ONLY.load(Ordering::Relaxed) || self.config()?.storage.read_only)
+    }
+
+    /// Check if unreadable task files are errors rather than skipped
+    ///
+    /// A config.toml that doesn't parse counts as not strict, so tasks can
+    /// still be read; writes report the broken config.
+    pub fn is_strict(&self) -> Result<bool, FileStoreError> {
+        Ok(STRICT.load(Ordering::Relaxed) || self.config().is_ok_and(|c| c.storage.strict))
+    }
+
+    /// Fail with `ReadOnly` if writes are disabled
+    pub fn ensure_writable(&self) -> Result<(), FileStoreError> {
+        if self.is_read_only()? {
+            return Err(FileStoreError::ReadOnly(self.location.tasks_dir.clone()));
+        }
+        Ok(())
+    }
+
+    /// Fail with `UnknownStatus` if `task` was given a status the project
+    /// doesn't define, or with `Invalid` if it breaks the `[validation]` rules
+    ///
+    /// `previous` is the saved version when updating an existing task.
+    fn ensure_valid(&self, task: &Task, previous: Option<&Task>) -> Result<(), FileStoreError> {
+        let config = self.config()?;
+        if previous.is_none_or(|p| p.status != task.status) && !config.workflow.allows(task.status)
+        {
+            return Err(FileStoreError::UnknownStatus {
+                status: task.status.to_string(),
+                known: config.workflow.statuses,
+            });
+        }
+        let errors = validate_task(&config.validation, task, previous, Utc::now());
+        if !errors.is_empty() {
+            return Err(FileStoreError::Invalid {
+                title: task.title.clone(),
+                errors,
+            });
+        }
+        Ok(())
+    }
+
+    /// A task not yet saved, filled with the project's defaults for its kind
+    ///
+    /// `[kind.<kind>]` in config.toml sets the priority, tags and context,
+    /// then the template is applied: `template` if given, otherwise the
+    /// kind's configured one. Explicit values should be set afterwards.
+    pub fn new_task(
+        &self,
+        kind: TaskKind,
+        title: &str,
+        template: Option<&str>,
+    ) -> Result<Task, FileStoreError> {
+        let mut task = Task::new(0, kind, title);
+        let defaults = self.config()?.kind_defaults(kind);
+
+        if let Some(priority) = defaults.priority {
+            task.priority = priority;
+        }
+        for tag in &defaults.tags {
+            task.add_tag(tag);
+        }
+        task.context = defaults.context;
+
+        if let Some(name) = template.or(defaults.template.as_deref()) {
+            TaskTemplate::load(&self.location, name)?.apply(&mut task, today())?;
+        }
+        Ok(task)
+    }
+
     /// Create a new task and return it with its assigned ID
+    ///
+    /// Without an assignee, the task is assigned to the git user when the
+    /// project config enables `defaults.assignee_from_git`.
     pub fn create(&self, mut task: Task) -> Result<Task, FileStoreError> {
         if !self.location.exists() {
             return Err(FileStoreError::DirectoryNotInitialized);
         }
+        self.ensure_writable()?;
+
+        #[cfg(feature = "git")]
+        if task.assignee.is_none() && self.config()?.defaults.assignee_from_git {
+            task.assignee = GitOperations::current_user(&self.location.root);
+        }
+        task.due = self.pin_due(task.due)?;
+        self.ensure_valid(&task, None)?;
+        if let Some(parent) = task.parent {
+            self.check_parent(0, parent)?;
+        }
+        self.check_blockers(&task, None)?;
 
-        // Generate the next ID
-        let id = IdGenerator::next_id(&self.location.tasks_dir)
+        // Generate the next ID, holding the lock until the file exists
+        let _lock = IdGenerator::lock(&self.location.tasks_dir)
             .map_err(|e| FileStoreError::Io(std::io::Error::other(e.to_string())))?;
-        task.id = id;
+        task.id = self.next_id()?;
 
         // Write the task file
         let path = self.task_path(&task);
-        let content = serialize_task(&task)?;
-        std::fs::write(&path, content)?;
+        writes::write(&path, serialize_task(&task)?)?;
+        self.update_index(|index| index.upsert(&path, &task))?;
 
         Ok(task)
     }
 
+    /// Pin a due time written without an offset to the project's timezone
+    fn pin_due(&self, due: Option<Due>) -> Result<Option<Due>, FileStoreError> {
+        match due {
+            Some(due @ Due::Local(_)) => Ok(Some(due.resolve(self.config()?.display.offset()?))),
+            due => Ok(due),
+        }
+    }
+
+    /// The ID the next new task gets, counting tasks held back by a dry run
+    pub fn next_id(&self) -> Result<u64, FileStoreError> {
+        let next = IdGenerator::next_id(&self.location.tasks_dir)
+            .map_err(|e| FileStoreError::Io(std::io::Error::other(e.to_string())))?;
+        Ok(next.max(planned_max_id(&self.location.tasks_dir) + 1))
+    }
+
     /// Read a task by ID
     pub fn read(&self, id: u64) -> Result<Task, FileStoreError> {
         let path = self.find_task_file(id)?;
@@ -119,141 +525,940 @@ impl FileStore {
 
     /// Update an existing task
     pub fn update(&self, task: &Task) -> Result<(), FileStoreError> {
+        self.ensure_writable()?;
+        let mut task = task.clone();
+        task.due = self.pin_due(task.due)?;
+
         // Find and delete the old file (filename might have changed if title changed)
         let old_path = self.find_task_file(task.id)?;
-        let new_path = self.task_path(task);
+        let new_path = self.task_path(&task);
 
-        if old_path != new_path {
-            std::fs::remove_file(&old_path)?;
-        }
+        // Patch the existing text so comments and key order survive
+        let original = std::fs::read_to_string(&old_path).ok();
+        let previous = original.as_deref().and_then(|text| parse_task(text).ok());
 
-        let content = serialize_task(task)?;
-        std::fs::write(&new_path, content)?;
+        // A position in the old status means nothing in the new one
+        if let Some(previous) = &previous
+            && previous.status != task.status
+            && previous.order == task.order
+        {
+            task.order = None;
+        }
+        let task = &task;
+        self.ensure_valid(task, previous.as_ref())?;
+        if let Some(parent) = task.parent
+            && previous.as_ref().is_none_or(|p| p.parent != task.parent)
+        {
+            self.check_parent(task.id, parent)?;
+        }
+        self.check_blockers(task, previous.as_ref())?;
+        let content = match &original {
+            Some(original) => update_task_content(original, task)?,
+            None => serialize_task(task)?,
+        };
+        writes::rewrite(&old_path, &new_path, content)?;
+        self.update_index(|index| {
+            index.forget(&old_path);
+            index.upsert(&new_path, task)
+        })?;
 
         Ok(())
     }
 
+    /// Apply a partial update to the current on-disk version of a task
+    ///
+    /// The file is re-read under the directory lock so only the fields in
+    /// `patch` change, even if someone else edited the task in the meantime.
+    /// With `expected_updated`, fails with `Conflict` if the task's `updated`
+    /// time no longer matches the one the caller saw.
+    pub fn patch(
+        &self,
+        id: u64,
+        patch: &TaskPatch,
+        expected_updated: Option<DateTime<Utc>>,
+    ) -> Result<Task, FileStoreError> {
+        self.ensure_writable()?;
+        let _lock = IdGenerator::lock(&self.location.tasks_dir)
+            .map_err(|e| FileStoreError::Io(std::io::Error::other(e.to_string())))?;
+
+        let mut task = self.read(id)?;
+        if let Some(expected) = expected_updated
+            && task.updated != expected
+        {
+            return Err(FileStoreError::Conflict {
+                id,
+                expected,
+                actual: task.updated,
+            });
+        }
+
+        patch.apply(&mut task);
+        task.touch();
+        self.update(&task)?;
+
+        Ok(task)
+    }
+
     /// Delete a task by ID
     pub fn delete(&self, id: u64) -> Result<(), FileStoreError> {
+        self.ensure_writable()?;
         let path = self.find_task_file(id)?;
-        std::fs::remove_file(&path)?;
+        writes::remove_file(&path)?;
+        self.update_index(|index| {
+            index.forget(&path);
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    /// Delete a task if `check` accepts it as it is on disk
+    ///
+    /// Like `patch`, this holds the ID lock from reading the task to deleting
+    /// it, so a concurrent write can't change it in between. Returns whether
+    /// the task was deleted.
+    pub fn delete_if(
+        &self,
+        id: u64,
+        check: impl FnOnce(&Task) -> bool,
+    ) -> Result<bool, FileStoreError> {
+        self.ensure_writable()?;
+        let _lock = IdGenerator::lock(&self.location.tasks_dir)
+            .map_err(|e| FileStoreError::Io(std::io::Error::other(e.to_string())))?;
+
+        if !check(&self.read(id)?) {
+            return Ok(false);
+        }
+        self.delete(id)?;
+        Ok(true)
+    }
+
+    /// Apply an in-place change to the on-disk index, if one exists
+    fn update_index(
+        &self,
+        f: impl FnOnce(&mut TaskIndex) -> std::io::Result<()>,
+    ) -> Result<(), FileStoreError> {
+        // The index follows the files on disk, which a dry run leaves alone
+        if is_dry_run() {
+            return Ok(());
+        }
+        if let Some(mut index) = TaskIndex::load(&self.location.tasks_dir) {
+            f(&mut index)?;
+            index.save(&self.location.tasks_dir)?;
+        }
         Ok(())
     }
 
     /// List all tasks, optionally filtered
     pub fn list(&self, filter: &TaskFilter) -> Result<Vec<Task>, FileStoreError> {
+        let mut tasks: Vec<Task> = self.iter(filter)?.collect();
+
+        // Sort by ID
+        tasks.sort_by_key(|t| t.id);
+
+        Ok(tasks)
+    }
+
+    /// Stream tasks matching the filter, parsing files lazily
+    ///
+    /// When an on-disk index exists it is refreshed and served from instead,
+    /// so only changed files are parsed. In strict mode every file is parsed
+    /// up front and the first unreadable one fails with `Unreadable`.
+    pub fn iter(
+        &self,
+        filter: &TaskFilter,
+    ) -> Result<Box<dyn Iterator<Item = Task>>, FileStoreError> {
+        let filter = filter.clone();
+
         if !self.location.exists() {
-            return Ok(Vec::new());
+            return Ok(Box::new(std::iter::empty()));
         }
 
-        let mut tasks = Vec::new();
+        // Checked first so no kind of listing skips an unreadable file
+        if self.is_strict()? {
+            let mut all = Vec::new();
+            for path in layout::task_files(&self.location.tasks_dir)? {
+                all.push(read_task_file(&path)?);
+            }
+            return Ok(Box::new(select(all, filter)));
+        }
 
-        for entry in std::fs::read_dir(&self.location.tasks_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+        if filter.unblocked {
+            let all: Vec<Task> = self
+                .iter(&TaskFilter {
+                    include_archived: true,
+                    ..Default::default()
+                })?
+                .collect();
+            return Ok(Box::new(select(all, filter)));
+        }
 
-            if path.extension().is_some_and(|ext| ext == "md") {
-                match std::fs::read_to_string(&path) {
-                    Ok(content) => match parse_task(&content) {
-                        Ok(task) => {
-                            if filter.matches(&task) {
-                                tasks.push(task);
-                            }
-                        }
-                        Err(e) => {
-                            log::warn!("Failed to parse task file {:?}: {}", path, e);
-                        }
-                    },
-                    Err(e) => {
-                        log::warn!("Failed to read task file {:?}: {}", path, e);
-                    }
-                }
+        if let Some(mut index) = TaskIndex::load(&self.location.tasks_dir) {
+            // A stale index is still refreshed in memory, just not saved
+            if index.refresh(&self.location.tasks_dir)? && !self.is_read_only()? {
+                index.save(&self.location.tasks_dir)?;
             }
+            let tasks: Vec<Task> = index.tasks().filter(|t| filter.matches(t)).collect();
+            return Ok(Box::new(tasks.into_iter()));
         }
 
-        // Sort by ID
-        tasks.sort_by_key(|t| t.id);
+        let files = layout::task_files(&self.location.tasks_dir)?;
+        Ok(Box::new(
+            files
+                .into_iter()
+                .filter_map(read_task_entry)
+                .filter(move |task| filter.matches(task)),
+        ))
+    }
 
-        Ok(tasks)
+    /// Move a task within the hand-curated order of its status
+    ///
+    /// Renumbers the `order` of the tasks sharing its status as needed and
+    /// returns the task's new 0-based position. The whole move happens under
+    /// the directory lock, so concurrent moves don't interleave.
+    pub fn move_task(&self, id: u64, to: OrderMove) -> Result<usize, FileStoreError> {
+        self.ensure_writable()?;
+        let _lock = IdGenerator::lock(&self.location.tasks_dir)
+            .map_err(|e| FileStoreError::Io(std::io::Error::other(e.to_string())))?;
+
+        let task = self.read(id)?;
+        let peers = self.list(&TaskFilter {
+            status: Some(task.status),
+            include_archived: true,
+            ..Default::default()
+        })?;
+        let (position, changed) =
+            order::reorder(peers, id, to).ok_or(FileStoreError::TaskNotFound(id))?;
+        for task in &changed {
+            self.update(task)?;
+        }
+        Ok(position)
     }
 
-    /// Get statistics about tasks
-    pub fn stats(&self) -> Result<TaskStats, FileStoreError> {
-        let all_tasks = self.list(&TaskFilter {
+    /// The stored task an incoming one corresponds to, if any
+    ///
+    /// See `TaskMatcher` for how tasks are matched. To match many tasks,
+    /// build the matcher once with `matcher` instead.
+    pub fn find_match(&self, incoming: &Task) -> Result<Option<Task>, FileStoreError> {
+        Ok(self.matcher()?.find(incoming).cloned())
+    }
+
+    /// Index of the stored tasks for matching incoming ones
+    pub fn matcher(&self) -> Result<TaskMatcher, FileStoreError> {
+        let tasks = self.list(&TaskFilter {
             include_archived: true,
             ..Default::default()
         })?;
+        let fingerprint = self.config()?.storage.fingerprint;
+        let mut matcher = TaskMatcher {
+            by_external_id: HashMap::new(),
+            by_fingerprint: fingerprint.then(HashMap::new),
+        };
+        for task in tasks {
+            matcher.insert(task);
+        }
+        Ok(matcher)
+    }
 
-        let mut stats = TaskStats {
-            total: all_tasks.len(),
+    /// Create a task, or update the one it matches, so syncs can be re-run
+    ///
+    /// Only the `fields` the incoming record has are copied; see `synced`.
+    pub fn create_or_update_by_external_id(
+        &self,
+        incoming: Task,
+        fields: SyncedFields,
+    ) -> Result<Upsert, FileStoreError> {
+        self.upsert(&mut self.matcher()?, incoming, fields)
+    }
+
+    /// `create_or_update_by_external_id` against a prebuilt `matcher`,
+    /// which is kept up to date with the result
+    pub fn upsert(
+        &self,
+        matcher: &mut TaskMatcher,
+        incoming: Task,
+        fields: SyncedFields,
+    ) -> Result<Upsert, FileStoreError> {
+        let Some(existing) = matcher.find(&incoming).cloned() else {
+            let created = self.create(incoming)?;
+            matcher.insert(created.clone());
+            return Ok(Upsert::Created(created));
+        };
+
+        let mut task = synced(&existing, incoming, fields);
+        if task == existing {
+            return Ok(Upsert::Unchanged(existing));
+        }
+        task.touch();
+        self.update(&task)?;
+        matcher.insert(task.clone());
+        Ok(Upsert::Updated(task))
+    }
+
+    /// Make task `id` wait on `blocker`
+    ///
+    /// Fails if the blocker does not exist, or with `DependencyCycle` if the
+    /// blocker already waits on the task, directly or through others (both
+    /// checked by `update`).
+    pub fn add_blocker(&self, id: u64, blocker: u64) -> Result<Task, FileStoreError> {
+        let patch = TaskPatch {
+            add_blockers: vec![blocker],
             ..Default::default()
         };
+        self.patch(id, &patch, None)
+    }
 
-        for task in &all_tasks {
-            match task.status {
-                TaskStatus::Pending => stats.pending += 1,
-                TaskStatus::InProgress => stats.in_progress += 1,
-                TaskStatus::Completed => stats.completed += 1,
-                TaskStatus::Archived => stats.archived += 1,
+    /// Append a comment to task `id`
+    ///
+    /// Without an `author`, the comment is attributed to the git user, if
+    /// one is configured.
+    pub fn add_comment(
+        &self,
+        id: u64,
+        text: &str,
+        author: Option<String>,
+    ) -> Result<Task, FileStoreError> {
+        #[cfg(feature = "git")]
+        let author = author.or_else(|| GitOperations::current_user(&self.location.root));
+        let patch = TaskPatch {
+            add_comment: Some(Comment::new(author, text)),
+            ..Default::default()
+        };
+        self.patch(id, &patch, None)
+    }
+
+    /// Check or uncheck an item of task `id`'s checklist
+    ///
+    /// `item` is the item's 1-based number or a unique piece of its text.
+    /// Returns the updated task and the index of the item toggled.
+    pub fn toggle_check(&self, id: u64, item: &str) -> Result<(Task, usize), FileStoreError> {
+        let task = self.read(id)?;
+        let index = task
+            .checklist()
+            .find(item)
+            .map_err(|message| FileStoreError::ChecklistItem { id, message })?;
+        let patch = TaskPatch {
+            toggle_check: Some(index),
+            ..Default::default()
+        };
+        let task = self.patch(id, &patch, Some(task.updated))?;
+        Ok((task, index))
+    }
+
+    /// Direct subtasks of task `id`, archived ones included, by ID
+    pub fn children(&self, id: u64) -> Result<Vec<Task>, FileStoreError> {
+        let filter = TaskFilter {
+            include_archived: true,
+            ..Default::default()
+        };
+        let mut children: Vec<Task> = self
+            .iter(&filter)?
+            .filter(|t| t.parent == Some(id))
+            .collect();
+        children.sort_by_key(|t| t.id);
+        Ok(children)
+    }
+
+    /// Check the blockers `task` gains over `previous`: each must exist and
+    /// must not already wait on `task`, directly or through others
+    fn check_blockers(&self, task: &Task, previous: Option<&Task>) -> Result<(), FileStoreError> {
+        let added: Vec<u64> = task
+            .blocked_by
+            .iter()
+            .copied()
+            .filter(|b| previous.is_none_or(|p| !p.blocked_by.contains(b)))
+            .collect();
+        if added.is_empty() {
+            return Ok(());
+        }
+
+        let graph = DependencyGraph::new(self.iter(&TaskFilter {
+            include_archived: true,
+            ..Default::default()
+        })?);
+        for blocker in added {
+            if let Some(cycle) = graph.cycle_with(task.id, blocker) {
+                return Err(FileStoreError::DependencyCycle(cycle));
+            }
+            if graph.get(blocker).is_none() {
+                return Err(FileStoreError::TaskNotFound(blocker));
             }
+        }
+        Ok(())
+    }
 
-            match task.kind {
-                TaskKind::Task => stats.tasks += 1,
-                TaskKind::Todo => stats.todos += 1,
-                TaskKind::Idea => stats.ideas += 1,
+    /// Check that task `parent` exists and isn't `id` or below it
+    ///
+    /// `id` is 0 for a task that isn't saved yet.
+    fn check_parent(&self, id: u64, parent: u64) -> Result<(), FileStoreError> {
+        if parent == id {
+            return Err(FileStoreError::ParentCycle { id, parent });
+        }
+        let parents: HashMap<u64, Option<u64>> = self
+            .iter(&TaskFilter {
+                include_archived: true,
+                ..Default::default()
+            })?
+            .map(|t| (t.id, t.parent))
+            .collect();
+        if !parents.contains_key(&parent) {
+            return Err(FileStoreError::TaskNotFound(parent));
+        }
+
+        // Walk up from the new parent; finding `id` on the way means a loop
+        let mut seen = HashSet::new();
+        let mut current = parent;
+        while let Some(&Some(up)) = parents.get(&current) {
+            if up == id {
+                return Err(FileStoreError::ParentCycle { id, parent });
             }
+            if !seen.insert(up) {
+                break;
+            }
+            current = up;
+        }
+        Ok(())
+    }
 
-            // Check for overdue
-            if task.is_open()
-                && let Some(due) = task.due
-                && due < chrono::Utc::now().date_naive()
-            {
-                stats.overdue += 1;
+    /// Get statistics about tasks
+    pub fn stats(&self) -> Result<TaskStats, FileStoreError> {
+        let all_tasks = self.list(&TaskFilter {
+            include_archived: true,
+            ..Default::default()
+        })?;
+
+        let due_soon_days = self
+            .config()
+            .map(|c| c.display)
+            .unwrap_or_default()
+            .due_soon_days();
+        Ok(TaskStats::from_tasks(&all_tasks, Utc::now(), due_soon_days))
+    }
+
+    /// Count open and total tasks without parsing every task file
+    ///
+    /// Served from the index when there is one (refreshed in memory, only
+    /// changed files are parsed); otherwise only each file's `status` line is
+    /// read. Unreadable files are skipped, as `list` does.
+    pub fn count_tasks(&self) -> Result<TaskCounts, FileStoreError> {
+        if !self.location.exists() {
+            return Ok(TaskCounts::default());
+        }
+
+        let statuses: Vec<TaskStatus> =
+            if let Some(mut index) = TaskIndex::load(&self.location.tasks_dir) {
+                index.refresh(&self.location.tasks_dir)?;
+                index.statuses().collect()
+            } else {
+                layout::task_files(&self.location.tasks_dir)?
+                    .iter()
+                    .filter_map(|path| std::fs::read_to_string(path).ok())
+                    .filter_map(|content| peek_status(&content).ok())
+                    .collect()
+            };
+
+        Ok(TaskCounts {
+            open: statuses.iter().filter(|s| s.is_open()).count(),
+            total: statuses.len(),
+        })
+    }
+
+    /// Count open tasks per context, sorted by context name
+    pub fn contexts(&self) -> Result<Vec<(String, usize)>, FileStoreError> {
+        let tasks = self.list(&TaskFilter::default())?;
+
+        let mut counts: std::collections::BTreeMap<String, usize> =
+            std::collections::BTreeMap::new();
+        for task in tasks.iter().filter(|t| t.is_open()) {
+            if let Some(ref context) = task.context {
+                *counts.entry(context.clone()).or_default() += 1;
             }
         }
 
-        Ok(stats)
+        Ok(counts.into_iter().collect())
     }
 
-    /// Get the path for a task file
+    /// Get the path for a task file under the current layout
     fn task_path(&self, task: &Task) -> PathBuf {
-        self.location.tasks_dir.join(task.filename())
+        self.layout
+            .dir_for(&self.location.tasks_dir, task.id)
+            .join(task.filename())
+    }
+
+    /// Layout used for new writes
+    pub fn layout(&self) -> TaskLayout {
+        self.layout
     }
 
     /// Find the file for a task by ID
-    fn find_task_file(&self, id: u64) -> Result<PathBuf, FileStoreError> {
+    ///
+    /// Lookups are served from an in-memory map that is rebuilt only when the
+    /// directory's mtime changes (i.e. files were added, removed or renamed).
+    /// A change within the mtime's granularity goes unnoticed, so an ID that
+    /// is missing, or whose file is gone, triggers one rescan before giving up.
+    /// Fails with `AmbiguousId` if several files carry the ID, which happens
+    /// when branches that both created tasks are merged.
+    pub fn find_task_file(&self, id: u64) -> Result<PathBuf, FileStoreError> {
         if !self.location.exists() {
             return Err(FileStoreError::DirectoryNotInitialized);
         }
 
-        for entry in std::fs::read_dir(&self.location.tasks_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+        let dir_mtime = layout::dir_stamp(&self.location.tasks_dir)?;
+        let mut cache = self.id_cache.lock().unwrap_or_else(|e| e.into_inner());
 
-            if path.extension().is_some_and(|ext| ext == "md")
-                && let Some(file_id) = IdGenerator::extract_id_from_filename(&path)
-                && file_id == id
-            {
-                return Ok(path);
+        let mut fresh = false;
+        if cache.as_ref().is_none_or(|c| c.dir_mtime != dir_mtime) {
+            *cache = Some(IdCache::scan(&self.location.tasks_dir, dir_mtime)?);
+            fresh = true;
+        }
+        let stale = |cache: &Option<IdCache>| {
+            cache
+                .as_ref()
+                .and_then(|c| c.paths.get(&id))
+                .is_none_or(|paths| paths.iter().any(|p| !p.exists()))
+        };
+        if !fresh && stale(&cache) {
+            *cache = Some(IdCache::scan(&self.location.tasks_dir, dir_mtime)?);
+        }
+
+        match cache.as_ref().and_then(|c| c.paths.get(&id)) {
+            Some(paths) if paths.len() == 1 => Ok(paths[0].clone()),
+            Some(paths) => {
+                let mut paths = paths.clone();
+                paths.sort();
+                Err(FileStoreError::AmbiguousId(id, paths))
             }
+            None => Err(FileStoreError::TaskNotFound(id)),
         }
+    }
 
-        Err(FileStoreError::TaskNotFound(id))
+    /// IDs that are used by more than one task file, with their files
+    pub fn duplicate_ids(&self) -> Result<Vec<(u64, Vec<PathBuf>)>, FileStoreError> {
+        if !self.location.exists() {
+            return Ok(Vec::new());
+        }
+
+        let cache = IdCache::scan(
+            &self.location.tasks_dir,
+            layout::dir_stamp(&self.location.tasks_dir)?,
+        )?;
+        let mut duplicates: Vec<(u64, Vec<PathBuf>)> = cache
+            .paths
+            .into_iter()
+            .filter(|(_, paths)| paths.len() > 1)
+            .map(|(id, mut paths)| {
+                paths.sort();
+                (id, paths)
+            })
+            .collect();
+        duplicates.sort_by_key(|(id, _)| *id);
+
+        Ok(duplicates)
     }
 }
 
+/// The stored tasks, indexed for finding the one an incoming task matches
+///
+/// Matches on `external_id`; with `fingerprint` under `[storage]`, a task
+/// without an external ID that has the same fingerprint matches too.
+#[derive(Debug, Clone)]
+pub struct TaskMatcher {
+    by_external_id: HashMap<String, Task>,
+    /// `None` unless fingerprint matching is enabled
+    by_fingerprint: Option<HashMap<String, Task>>,
+}
+
+impl TaskMatcher {
+    /// The stored task `incoming` corresponds to, if any
+    pub fn find(&self, incoming: &Task) -> Option<&Task> {
+        if let Some(ref external_id) = incoming.external_id
+            && let Some(task) = self.by_external_id.get(external_id)
+        {
+            return Some(task);
+        }
+        self.by_fingerprint.as_ref()?.get(&incoming.fingerprint())
+    }
+
+    /// Add a stored task, or replace it after an update
+    ///
+    /// Where several tasks match the same way, the first one added wins.
+    pub fn insert(&mut self, task: Task) {
+        if let Some(by_fingerprint) = self.by_fingerprint.as_mut() {
+            let fingerprint = task.fingerprint();
+            let current = by_fingerprint.get(&fingerprint).map(|t| t.id);
+            if task.external_id.is_none() && current.is_none_or(|id| id == task.id) {
+                by_fingerprint.insert(fingerprint, task.clone());
+            } else if current == Some(task.id) {
+                by_fingerprint.remove(&fingerprint);
+            }
+        }
+        if let Some(ref external_id) = task.external_id
+            && self
+                .by_external_id
+                .get(external_id)
+                .is_none_or(|t| t.id == task.id)
+        {
+            self.by_external_id.insert(external_id.clone(), task);
+        }
+    }
+}
+
+/// The fields `synced` may copy that an incoming record actually has
+///
+/// The title is always present. Records from GitHub, for example, carry no
+/// priority or due date, and syncing them must not reset those.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SyncedFields {
+    pub kind: bool,
+    pub status: bool,
+    pub priority: bool,
+    pub description: bool,
+    pub tags: bool,
+    pub context: bool,
+    pub due: bool,
+}
+
+impl SyncedFields {
+    /// Every field, for an incoming task that is complete
+    pub const ALL: SyncedFields = SyncedFields {
+        kind: true,
+        status: true,
+        priority: true,
+        description: true,
+        tags: true,
+        context: true,
+        due: true,
+    };
+}
+
+/// `existing` with the fields an external tracker owns taken from `incoming`
+///
+/// Those are the title and external ID, plus whichever of the kind, status,
+/// priority, description, tags, context and due date are in `fields`, and
+/// the assignee if `incoming` has one. A status change closes or reopens the
+/// task the usual way. Everything local to gittask, such as dependencies and
+/// time entries, stays.
+pub fn synced(existing: &Task, incoming: Task, fields: SyncedFields) -> Task {
+    let mut task = existing.clone();
+    task.title = incoming.title;
+    task.external_id = incoming.external_id.or(task.external_id);
+    if incoming.assignee.is_some() {
+        task.assignee = incoming.assignee;
+    }
+    if fields.kind {
+        task.kind = incoming.kind;
+    }
+    if fields.priority {
+        task.priority = incoming.priority;
+    }
+    if fields.description {
+        task.description = incoming.description;
+    }
+    if fields.tags {
+        task.tags = incoming.tags;
+    }
+    if fields.context {
+        task.context = incoming.context;
+    }
+    if fields.due {
+        task.due = incoming.due;
+    }
+    if fields.status && task.status != incoming.status {
+        let now = Utc::now();
+        if incoming.status.is_open() {
+            if !task.reopen(incoming.status, now) {
+                task.status = incoming.status;
+            }
+        } else if task.is_open() && incoming.status == TaskStatus::Completed {
+            task.status = TaskStatus::Completed;
+            task.completed = incoming.completed.or(Some(now));
+        } else {
+            task.status = incoming.status;
+        }
+    }
+    task
+}
+
+/// The tasks in `all` that match `filter`
+///
+/// With `unblocked`, a task's blockers are looked up in `all`, so it must
+/// hold every task, archived ones included.
+fn select(all: Vec<Task>, filter: TaskFilter) -> impl Iterator<Item = Task> {
+    let open: HashSet<u64> = if filter.unblocked {
+        all.iter().filter(|t| t.is_open()).map(|t| t.id).collect()
+    } else {
+        HashSet::new()
+    };
+    all.into_iter().filter(move |task| {
+        filter.matches(task) && !task.blocked_by.iter().any(|b| open.contains(b))
+    })
+}
+
+/// Read and parse a task file, failing with `Unreadable`
+pub(crate) fn read_task_file(path: &Path) -> Result<Task, FileStoreError> {
+    std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| parse_task(&content).map_err(|e| e.to_string()))
+        .map_err(|error| FileStoreError::Unreadable {
+            path: path.to_path_buf(),
+            error,
+        })
+}
+
+/// Read and parse a directory entry, logging and skipping unreadable task files
+pub(crate) fn read_task_entry(path: PathBuf) -> Option<Task> {
+    if !layout::is_task_file(&path) {
+        return None;
+    }
+
+    match read_task_file(&path) {
+        Ok(task) => Some(task),
+        Err(e) => {
+            log::warn!("Skipping task file: {}", e);
+            None
+        }
+    }
+}
+
+/// Number of weeks covered by `TaskStats::weekly`, the current one included
+pub const STATS_WEEKS: usize = 8;
+
+/// Labels of the `TaskStats::open_by_age` brackets, youngest first
+pub const AGE_BUCKETS: [&str; 4] = ["<1w", "1-4w", "1-3mo", ">3mo"];
+
+/// Index into `AGE_BUCKETS` for a task of the given age
+fn age_bucket(age: Duration) -> usize {
+    match age.num_days() {
+        ..7 => 0,
+        7..28 => 1,
+        28..91 => 2,
+        _ => 3,
+    }
+}
+
+/// Tasks created and done during one week
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeekActivity {
+    /// Monday the week starts on (UTC)
+    pub start: NaiveDate,
+    pub created: usize,
+    pub completed: usize,
+}
+
+/// The open task that has waited longest
+#[derive(Debug, Clone, PartialEq)]
+pub struct OldestOpen {
+    pub id: u64,
+    pub title: String,
+    pub created: DateTime<Utc>,
+}
+
+/// Open and total task counts, from `FileStore::count_tasks`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TaskCounts {
+    /// Pending and in-progress tasks
+    pub open: usize,
+    /// All tasks, archived ones included
+    pub total: usize,
+}
+
 /// Task statistics
 #[derive(Debug, Default, Clone)]
 pub struct TaskStats {
     pub total: usize,
     pub pending: usize,
     pub in_progress: usize,
+    /// Tasks per custom workflow status; statuses with none are left out
+    pub custom: BTreeMap<String, usize>,
+    /// Completed tasks that were done; the rest count as `closed_not_done`
     pub completed: usize,
+    /// Completed tasks closed with a resolution other than done
+    pub closed_not_done: usize,
     pub archived: usize,
     pub overdue: usize,
+    /// Open tasks due within the "due soon" window
+    pub due_soon: usize,
+    /// Average time from creation to completion
+    pub avg_cycle_time: Option<Duration>,
+    /// Time recorded with `start`/`stop`, running sessions counted up to now
+    pub time_spent: Duration,
+    /// Summed estimates of all tasks, in hours
+    pub estimated_hours: f64,
+    /// Summed estimates of done tasks, in hours
+    pub done_estimated_hours: f64,
+    /// Time recorded on done tasks that have an estimate, to set against
+    /// `done_estimated_hours`
+    pub done_actual: Duration,
     pub tasks: usize,
     pub todos: usize,
     pub ideas: usize,
+    /// Overdue tasks per priority; priorities with none are left out
+    pub overdue_by_priority: BTreeMap<Priority, usize>,
+    /// The last `STATS_WEEKS` weeks, oldest first
+    pub weekly: Vec<WeekActivity>,
+    pub oldest_open: Option<OldestOpen>,
+    /// Open tasks per age bracket (see `AGE_BUCKETS`), by time since creation
+    pub open_by_age: [usize; AGE_BUCKETS.len()],
+}
+
+impl TaskStats {
+    /// Tally a set of tasks, counting overdue ones as of `now` and those due
+    /// within `due_soon_days` days as due soon
+    pub fn from_tasks(tasks: &[Task], now: DateTime<Utc>, due_soon_days: u32) -> Self {
+        let mut stats = TaskStats {
+            total: tasks.len(),
+            ..Default::default()
+        };
+
+        let mut cycle_total = Duration::zero();
+        let mut cycle_count: i32 = 0;
+
+        let today = local_date(now);
+        let this_week = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+        stats.weekly = (0..STATS_WEEKS)
+            .rev()
+            .map(|weeks_ago| WeekActivity {
+                start: this_week - Duration::weeks(weeks_ago as i64),
+                created: 0,
+                completed: 0,
+            })
+            .collect();
+        let week_of = |at: DateTime<Utc>| {
+            let days = (this_week - local_date(at)).num_days();
+            let weeks_ago = if days <= 0 { 0 } else { (days + 6) / 7 } as usize;
+            (weeks_ago < STATS_WEEKS).then(|| STATS_WEEKS - 1 - weeks_ago)
+        };
+
+        for task in tasks {
+            if let Some(week) = week_of(task.created) {
+                stats.weekly[week].created += 1;
+            }
+            if task.is_done()
+                && let Some(week) = task.completed.and_then(week_of)
+            {
+                stats.weekly[week].completed += 1;
+            }
+
+            if task.is_open() {
+                stats.open_by_age[age_bucket(now - task.created)] += 1;
+            }
+
+            if task.is_open()
+                && stats
+                    .oldest_open
+                    .as_ref()
+                    .is_none_or(|oldest| task.created < oldest.created)
+            {
+                stats.oldest_open = Some(OldestOpen {
+                    id: task.id,
+                    title: task.title.clone(),
+                    created: task.created,
+                });
+            }
+
+            // Only tasks that were actually done count towards cycle time
+            if task.is_done()
+                && let Some(cycle) = task.cycle_time()
+            {
+                cycle_total += cycle;
+                cycle_count += 1;
+            }
+
+            stats.time_spent += task.time_spent(now);
+            if let Some(estimate) = task.estimate {
+                stats.estimated_hours += estimate;
+                if task.is_done() {
+                    stats.done_estimated_hours += estimate;
+                    stats.done_actual += task.time_spent(now);
+                }
+            }
+
+            match task.status {
+                TaskStatus::Pending => stats.pending += 1,
+                TaskStatus::InProgress => stats.in_progress += 1,
+                TaskStatus::Custom(name) => *stats.custom.entry(name.to_string()).or_default() += 1,
+                TaskStatus::Completed if task.is_done() => stats.completed += 1,
+                TaskStatus::Completed => stats.closed_not_done += 1,
+                TaskStatus::Archived => stats.archived += 1,
+            }
+
+            match task.kind {
+                TaskKind::Task => stats.tasks += 1,
+                TaskKind::Todo => stats.todos += 1,
+                TaskKind::Idea => stats.ideas += 1,
+            }
+
+            if task.is_overdue(now) {
+                stats.overdue += 1;
+                *stats.overdue_by_priority.entry(task.priority).or_default() += 1;
+            } else if task.is_due_soon(now, due_soon_days) {
+                stats.due_soon += 1;
+            }
+        }
+
+        if cycle_count > 0 {
+            stats.avg_cycle_time = Some(cycle_total / cycle_count);
+        }
+
+        stats
+    }
+
+    /// The statistics as JSON, for `gittask stats --format json` and MCP
+    pub fn to_json(&self) -> serde_json::Value {
+        let overdue_by_priority: serde_json::Map<String, serde_json::Value> = self
+            .overdue_by_priority
+            .iter()
+            .map(|(priority, count)| (priority.to_string(), (*count).into()))
+            .collect();
+        let weekly: Vec<serde_json::Value> = self
+            .weekly
+            .iter()
+            .map(|week| {
+                serde_json::json!({
+                    "week": week.start.to_string(),
+                    "created": week.created,
+                    "completed": week.completed,
+                })
+            })
+            .collect();
+
+        serde_json::json!({
+            "total": self.total,
+            "pending": self.pending,
+            "in_progress": self.in_progress,
+            "custom": self.custom,
+            "completed": self.completed,
+            "closed_not_done": self.closed_not_done,
+            "archived": self.archived,
+            "overdue": self.overdue,
+            "overdue_by_priority": overdue_by_priority,
+            "due_soon": self.due_soon,
+            "avg_cycle_time_hours": self
+                .avg_cycle_time
+                .map(|d| d.num_minutes() as f64 / 60.0),
+            "time_spent_hours": self.time_spent.num_minutes() as f64 / 60.0,
+            "estimate": {
+                "total_hours": self.estimated_hours,
+                "done_hours": self.done_estimated_hours,
+                "done_actual_hours": self.done_actual.num_minutes() as f64 / 60.0,
+            },
+            "by_kind": {
+                "tasks": self.tasks,
+                "todos": self.todos,
+                "ideas": self.ideas
+            },
+            "weekly": weekly,
+            "open_by_age": AGE_BUCKETS
+                .iter()
+                .zip(self.open_by_age)
+                .map(|(age, count)| serde_json::json!({"age": age, "count": count}))
+                .collect::<Vec<_>>(),
+            "oldest_open": self.oldest_open.as_ref().map(|oldest| serde_json::json!({
+                "id": oldest.id,
+                "title": oldest.title,
+                "created": oldest.created.to_rfc3339(),
+            })),
+        })
+    }
 }
 
 /// A task with its project context for aggregated views
@@ -265,6 +1470,8 @@ pub struct AggregatedTask {
     pub project: String,
     /// Project root path
     pub project_path: PathBuf,
+    /// Color the project is shown in, from the registry
+    pub color: Option<ProjectColor>,
 }
 
 impl AggregatedTask {
@@ -304,6 +1511,7 @@ pub fn list_aggregated(
                                 task,
                                 project: project_name.clone(),
                                 project_path: location.root.clone(),
+                                color: registry.color_of(project_path),
                             });
                         }
                     }
@@ -336,12 +1544,55 @@ pub fn list_aggregated(
     Ok(results)
 }
 
+/// List tasks from every task scope inside the repository containing `location`
+///
+/// Each scope is labelled by its path relative to the git root (or the
+/// repository directory name for the root scope).
+pub fn list_scopes(
+    location: &TaskLocation,
+    filter: &TaskFilter,
+) -> Result<Vec<AggregatedTask>, FileStoreError> {
+    let git_root = TaskLocation::find_git_root(&location.root)
+        .map_err(|e| FileStoreError::Io(std::io::Error::other(e.to_string())))?;
+    let scopes = location
+        .find_scopes()
+        .map_err(|e| FileStoreError::Io(std::io::Error::other(e.to_string())))?;
+
+    let mut results = Vec::new();
+
+    for scope in scopes {
+        let scope_name = match scope.root.strip_prefix(&git_root) {
+            Ok(rel) if !rel.as_os_str().is_empty() => rel.to_string_lossy().to_string(),
+            _ => git_root
+                .file_name()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| ".".to_string()),
+        };
+
+        let store = FileStore::new(scope.clone());
+        for task in store.list(filter)? {
+            results.push(AggregatedTask {
+                task,
+                project: scope_name.clone(),
+                project_path: scope.root.clone(),
+                color: None,
+            });
+        }
+    }
+
+    Ok(results)
+}
+
 /// Resolve a qualified ID (e.g., "gittask:1" or just "1")
 /// Returns (project_path, task_id) if found
+///
+/// The project is matched fuzzily only with `fuzzy`, which callers that
+/// write leave off (see `ProjectRegistry::resolve_project_strict`).
 pub fn resolve_qualified_id(
     id_str: &str,
     registry: &ProjectRegistry,
     default_location: Option<&TaskLocation>,
+    fuzzy: bool,
 ) -> Result<(TaskLocation, u64), String> {
     if let Some((project_name, id_part)) = id_str.split_once(':') {
         // Qualified ID: "project:id"
@@ -349,9 +1600,12 @@ pub fn resolve_qualified_id(
             .parse()
             .map_err(|_| format!("Invalid task ID: {}", id_part))?;
 
-        let project_path = registry
-            .find_project(project_name)
-            .ok_or_else(|| format!("Project not found: {}", project_name))?;
+        let project_path = if fuzzy {
+            registry.resolve_project(project_name)
+        } else {
+            registry.resolve_project_strict(project_name)
+        }
+        .map_err(|e| e.to_string())?;
 
         let location = TaskLocation::find_project_from(&project_path)
             .map_err(|e| format!("Failed to find project: {}", e))?;
@@ -374,17 +1628,10 @@ pub fn resolve_qualified_id(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::storage::test_support::setup_test_store;
+    use crate::storage::writes::{PlannedChange, set_dry_run, take_planned_changes};
     use tempfile::TempDir;
 
-    fn setup_test_store() -> (TempDir, FileStore) {
-        let temp = TempDir::new().unwrap();
-        std::fs::create_dir(temp.path().join(".git")).unwrap();
-        let location = TaskLocation::find_project_from(temp.path()).unwrap();
-        location.ensure_exists().unwrap();
-        let store = FileStore::new(location);
-        (temp, store)
-    }
-
     #[test]
     fn test_create_task() {
         let (_temp, store) = setup_test_store();
@@ -396,12 +1643,288 @@ mod tests {
         assert_eq!(created.title, "Test task");
 
         // Verify file exists
-        let files: Vec<_> = std::fs::read_dir(&store.location.tasks_dir)
-            .unwrap()
-            .collect();
+        let files = layout::task_files(&store.location.tasks_dir).unwrap();
         assert_eq!(files.len(), 1);
     }
 
+    #[test]
+    fn test_read_only_config_rejects_writes() {
+        let (_temp, store) = setup_test_store();
+        let mut task = store.create(Task::new(0, TaskKind::Task, "A")).unwrap();
+
+        let mut config = ProjectConfig::default();
+        config.storage.read_only = true;
+        config.save(&store.location.tasks_dir).unwrap();
+
+        let is_read_only = |e: FileStoreError| matches!(e, FileStoreError::ReadOnly(_));
+        let created = store.create(Task::new(0, TaskKind::Task, "B"));
+        assert!(is_read_only(created.unwrap_err()));
+        task.title = "Renamed".to_string();
+        assert!(is_read_only(store.update(&task).unwrap_err()));
+        let patched = store.patch(1, &TaskPatch::default(), None);
+        assert!(is_read_only(patched.unwrap_err()));
+        assert!(is_read_only(store.delete(1).unwrap_err()));
+
+        // Reads still work
+        assert_eq!(store.read(1).unwrap().title, "A");
+        assert_eq!(store.list(&TaskFilter::default()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_local_due_uses_project_timezone() {
+        let (_temp, store) = setup_test_store();
+        let mut config = ProjectConfig::default();
+        config.display.timezone = Some("+05:00".to_string());
+        config.save(&store.location.tasks_dir).unwrap();
+
+        let mut task = Task::new(0, TaskKind::Task, "A");
+        task.due = Some("2026-03-04T17:00".parse().unwrap());
+        store.create(task).unwrap();
+        let due = store.read(1).unwrap().due.unwrap();
+        assert_eq!(due.to_string(), "2026-03-04T17:00:00+05:00");
+
+        config.display.timezone = Some("utc".to_string());
+        config.save(&store.location.tasks_dir).unwrap();
+        let mut task = store.read(1).unwrap();
+        task.due = Some("2026-03-05 09:30".parse().unwrap());
+        store.update(&task).unwrap();
+        let due = store.read(1).unwrap().due.unwrap();
+        assert_eq!(due.to_string(), "2026-03-05T09:30:00+00:00");
+    }
+
+    #[test]
+    fn test_strict_config_fails_every_listing() {
+        let (_temp, store) = setup_test_store();
+        store.create(Task::new(0, TaskKind::Task, "A")).unwrap();
+        std::fs::write(
+            store.location.tasks_dir.join("broken-002.md"),
+            "no frontmatter",
+        )
+        .unwrap();
+        assert_eq!(store.list(&TaskFilter::default()).unwrap().len(), 1);
+
+        let mut config = ProjectConfig::default();
+        config.storage.strict = true;
+        config.save(&store.location.tasks_dir).unwrap();
+        for filter in [
+            TaskFilter::default(),
+            TaskFilter {
+                unblocked: true,
+                ..Default::default()
+            },
+        ] {
+            let listed = store.list(&filter);
+            assert!(matches!(listed, Err(FileStoreError::Unreadable { .. })));
+        }
+    }
+
+    #[test]
+    fn test_subtasks() {
+        let (_temp, store) = setup_test_store();
+        store.create(Task::new(0, TaskKind::Task, "Epic")).unwrap();
+        for title in ["Design", "Build"] {
+            let mut task = Task::new(0, TaskKind::Task, title);
+            task.parent = Some(1);
+            store.create(task).unwrap();
+        }
+        let ids: Vec<u64> = store.children(1).unwrap().iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![2, 3]);
+
+        let mut orphan = Task::new(0, TaskKind::Task, "Orphan");
+        orphan.parent = Some(42);
+        assert!(matches!(
+            store.create(orphan),
+            Err(FileStoreError::TaskNotFound(42))
+        ));
+
+        // A task can't end up under itself
+        let under = |parent| TaskPatch {
+            parent: Some(Some(parent)),
+            ..Default::default()
+        };
+        store.patch(3, &under(2), None).unwrap();
+        for (id, parent) in [(1, 3), (2, 2)] {
+            assert!(matches!(
+                store.patch(id, &under(parent), None),
+                Err(FileStoreError::ParentCycle { .. })
+            ));
+        }
+        assert_eq!(store.read(1).unwrap().parent, None);
+    }
+
+    #[test]
+    fn test_count_tasks() {
+        let (_temp, store) = setup_test_store();
+        for title in ["A", "B", "C"] {
+            store.create(Task::new(0, TaskKind::Task, title)).unwrap();
+        }
+        let mut done = store.read(2).unwrap();
+        done.complete(None);
+        store.update(&done).unwrap();
+        std::fs::write(store.location.tasks_dir.join("broken.md"), "no frontmatter").unwrap();
+
+        let expected = TaskCounts { open: 2, total: 3 };
+        assert_eq!(store.count_tasks().unwrap(), expected);
+
+        TaskIndex::build(&store.location.tasks_dir).unwrap();
+        assert_eq!(store.count_tasks().unwrap(), expected);
+        store.delete(1).unwrap();
+        assert_eq!(
+            store.count_tasks().unwrap(),
+            TaskCounts { open: 1, total: 2 }
+        );
+    }
+
+    #[test]
+    fn test_validation_rules_reject_writes() {
+        let (_temp, store) = setup_test_store();
+        let mut task = store.create(Task::new(0, TaskKind::Task, "Old")).unwrap();
+
+        let mut config = ProjectConfig::default();
+        config.validation.allowed_tags = vec!["bug".to_string()];
+        config
+            .validation
+            .required
+            .insert(TaskKind::Task, vec!["tags".to_string()]);
+        config.save(&store.location.tasks_dir).unwrap();
+
+        let err = store
+            .create(Task::new(0, TaskKind::Task, "New"))
+            .unwrap_err();
+        assert!(
+            matches!(err, FileStoreError::Invalid { ref errors, .. } if errors[0].field == "tags")
+        );
+
+        // The untagged task predates the rule and can still change
+        task.title = "Renamed".to_string();
+        store.update(&task).unwrap();
+        task.add_tag("chore");
+        assert!(matches!(
+            store.update(&task).unwrap_err(),
+            FileStoreError::Invalid { .. }
+        ));
+        assert!(store.read(1).unwrap().tags.is_empty());
+    }
+
+    #[test]
+    fn test_custom_statuses() {
+        let (_temp, store) = setup_test_store();
+        let mut task = store.create(Task::new(0, TaskKind::Task, "Ship")).unwrap();
+
+        task.status = TaskStatus::define("review").unwrap();
+        let err = store.update(&task).unwrap_err();
+        assert!(
+            matches!(err, FileStoreError::UnknownStatus { ref status, .. } if status == "review")
+        );
+
+        let mut config = ProjectConfig::default();
+        config.workflow.statuses = vec!["review".to_string()];
+        config.save(&store.location.tasks_dir).unwrap();
+        store.update(&task).unwrap();
+        let saved = store.read(task.id).unwrap();
+        assert_eq!(saved.status, TaskStatus::Custom("review"));
+        assert!(saved.is_open());
+
+        let filter = TaskFilter::parse("status:review").unwrap();
+        assert_eq!(store.list(&filter).unwrap().len(), 1);
+        assert_eq!(store.stats().unwrap().custom["review"], 1);
+
+        // A status no config defines (say, one removed from the config) still
+        // reads, and the task can be edited, but not moved into it
+        let path = store.find_task_file(task.id).unwrap();
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, content.replace("status: review", "status: qa-hold")).unwrap();
+        let mut saved = store.read(task.id).unwrap();
+        assert_eq!(saved.status.to_string(), "qa-hold");
+        assert_eq!(store.list(&TaskFilter::default()).unwrap().len(), 1);
+        saved.title = "Ship it".to_string();
+        store.update(&saved).unwrap();
+
+        let mut other = store.create(Task::new(0, TaskKind::Task, "Other")).unwrap();
+        other.status = saved.status;
+        assert!(matches!(
+            store.update(&other).unwrap_err(),
+            FileStoreError::UnknownStatus { .. }
+        ));
+    }
+
+    #[test]
+    fn test_dry_run_holds_back_writes() {
+        let (_temp, store) = setup_test_store();
+        let mut task = store.create(Task::new(0, TaskKind::Task, "A")).unwrap();
+        let dir = store.location.tasks_dir.clone();
+        std::fs::remove_file(dir.join(".lock")).unwrap();
+
+        set_dry_run(true);
+        let b = store.create(Task::new(0, TaskKind::Task, "B")).unwrap();
+        let c = store.create(Task::new(0, TaskKind::Task, "C")).unwrap();
+        task.title = "Renamed".to_string();
+        store.update(&task).unwrap();
+        store.delete(1).unwrap();
+        let planned = take_planned_changes();
+        set_dry_run(false);
+
+        assert_eq!((b.id, c.id), (2, 3));
+        assert_eq!(
+            planned,
+            vec![
+                PlannedChange::Create(dir.join("b-002.md")),
+                PlannedChange::Create(dir.join("c-003.md")),
+                PlannedChange::Move {
+                    from: dir.join("a-001.md"),
+                    to: dir.join("renamed-001.md"),
+                },
+                PlannedChange::Delete(dir.join("a-001.md")),
+            ]
+        );
+        assert_eq!(
+            planned[3].to_string(),
+            format!("delete {}", dir.join("a-001.md").display())
+        );
+
+        // Nothing was written, not even the ID lock, and writes go through
+        // again afterwards
+        assert!(!dir.join(".lock").exists());
+        assert_eq!(store.list(&TaskFilter::default()).unwrap().len(), 1);
+        assert_eq!(store.read(1).unwrap().title, "A");
+        assert!(!is_dry_run());
+        assert_eq!(
+            store.create(Task::new(0, TaskKind::Task, "B")).unwrap().id,
+            2
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "git")]
+    fn test_create_defaults_assignee_from_git() {
+        let temp = TempDir::new().unwrap();
+        let repo = git2::Repository::init(temp.path()).unwrap();
+        repo.config()
+            .unwrap()
+            .set_str("user.email", "alice@example.com")
+            .unwrap();
+        let location = TaskLocation::find_project_from(temp.path()).unwrap();
+        location.ensure_exists().unwrap();
+        let store = FileStore::new(location);
+
+        // Off by default
+        let task = store.create(Task::new(0, TaskKind::Task, "A")).unwrap();
+        assert_eq!(task.assignee, None);
+
+        let mut config = ProjectConfig::default();
+        config.defaults.assignee_from_git = true;
+        config.save(&store.location.tasks_dir).unwrap();
+
+        let task = store.create(Task::new(0, TaskKind::Task, "B")).unwrap();
+        assert_eq!(task.assignee.as_deref(), Some("alice@example.com"));
+
+        // An explicit assignee wins
+        let mut task = Task::new(0, TaskKind::Task, "C");
+        task.assignee = Some("bob@example.com".to_string());
+        let task = store.create(task).unwrap();
+        assert_eq!(task.assignee.as_deref(), Some("bob@example.com"));
+    }
+
     #[test]
     fn test_read_task() {
         let (_temp, store) = setup_test_store();
@@ -430,6 +1953,38 @@ mod tests {
         assert_eq!(read.priority, Priority::High);
     }
 
+    #[test]
+    fn test_find_task_file_follows_directory_changes() {
+        let (_temp, store) = setup_test_store();
+
+        let task = store
+            .create(Task::new(0, TaskKind::Task, "Cached task"))
+            .unwrap();
+        let path = store.find_task_file(task.id).unwrap();
+        assert!(path.ends_with("cached-task-001.md"));
+
+        // Rename the file behind the store's back
+        let renamed = store.location.tasks_dir.join("hand-renamed-001.md");
+        std::fs::rename(&path, &renamed).unwrap();
+        // Ensure the directory mtime observably changes on coarse filesystems
+        let dir = std::fs::File::open(&store.location.tasks_dir).unwrap();
+        dir.set_modified(SystemTime::now() + std::time::Duration::from_secs(1))
+            .ok();
+
+        assert_eq!(store.find_task_file(task.id).unwrap(), renamed);
+        assert!(matches!(
+            store.find_task_file(99),
+            Err(FileStoreError::TaskNotFound(99))
+        ));
+
+        // A file added without a visible mtime change is found by rescanning
+        let stamp = layout::dir_stamp(&store.location.tasks_dir).unwrap();
+        let added = store.location.tasks_dir.join("added-002.md");
+        std::fs::copy(&renamed, &added).unwrap();
+        dir.set_modified(stamp).unwrap();
+        assert_eq!(store.find_task_file(2).unwrap(), added);
+    }
+
     #[test]
     fn test_delete_task() {
         let (_temp, store) = setup_test_store();
@@ -442,6 +1997,28 @@ mod tests {
         assert!(store.read(created.id).is_err());
     }
 
+    #[test]
+    fn test_delete_if() {
+        let (_temp, store) = setup_test_store();
+        let created = store
+            .create(Task::new(0, TaskKind::Task, "Test task"))
+            .unwrap();
+
+        assert!(
+            !store
+                .delete_if(created.id, |task| task.title == "Other")
+                .unwrap()
+        );
+        assert!(store.read(created.id).is_ok());
+
+        assert!(
+            store
+                .delete_if(created.id, |task| task.title == "Test task")
+                .unwrap()
+        );
+        assert!(store.read(created.id).is_err());
+    }
+
     #[test]
     fn test_list_tasks() {
         let (_temp, store) = setup_test_store();
@@ -545,6 +2122,192 @@ mod tests {
         assert!(tasks[0].tags.contains(&"bug".to_string()));
     }
 
+    #[test]
+    fn test_filter_keys_match_parse() {
+        // A value for each documented key that narrows the default filter
+        let samples = [
+            ("status", "completed"),
+            ("kind", "idea"),
+            ("priority", "high"),
+            ("tag", "backend"),
+            ("context", "@home"),
+            ("external", "GH#1"),
+            ("assignee", "ana"),
+            ("archived", "true"),
+            ("unblocked", "true"),
+        ];
+        let documented: Vec<&str> = TaskFilter::KEYS.iter().map(|(key, _)| *key).collect();
+        let sampled: Vec<&str> = samples.iter().map(|(key, _)| *key).collect();
+        assert_eq!(documented, sampled);
+        for (key, value) in samples {
+            let filter = TaskFilter::parse(&format!("{}:{}", key, value)).unwrap();
+            assert_ne!(filter, TaskFilter::default(), "{} is ignored", key);
+        }
+
+        // Fields a filter has, or keys people might try, that aren't keys
+        for key in [
+            "due",
+            "project",
+            "title",
+            "include_archived",
+            "external_id",
+            "tags",
+        ] {
+            assert!(!documented.contains(&key));
+            assert!(TaskFilter::parse(&format!("{}:x", key)).is_err(), "{}", key);
+        }
+    }
+
+    #[test]
+    fn test_parse_filter() {
+        let filter = TaskFilter::parse("status:pending kind:task tag:a tag:b").unwrap();
+        assert_eq!(
+            filter,
+            TaskFilter {
+                status: Some(TaskStatus::Pending),
+                kind: Some(TaskKind::Task),
+                tags: vec!["a".to_string(), "b".to_string()],
+                ..Default::default()
+            }
+        );
+        assert!(
+            TaskFilter::parse("status:archived")
+                .unwrap()
+                .include_archived
+        );
+        assert_eq!(TaskFilter::parse("").unwrap(), TaskFilter::default());
+        assert!(TaskFilter::parse("pending").is_err());
+        assert!(TaskFilter::parse("colour:red").is_err());
+        assert!(TaskFilter::parse("status:pendng").is_err());
+
+        let filter = TaskFilter::parse("assignee:Ana@example.com").unwrap();
+        let mut task = Task::new(1, TaskKind::Task, "Review");
+        assert!(!filter.matches(&task));
+        task.assignee = Some("ana@example.com".to_string());
+        assert!(filter.matches(&task));
+    }
+
+    #[test]
+    fn test_contexts() {
+        let (_temp, store) = setup_test_store();
+
+        let mut task1 = Task::new(0, TaskKind::Todo, "Buy milk");
+        task1.context = Some("@errands".to_string());
+        store.create(task1).unwrap();
+
+        let mut task2 = Task::new(0, TaskKind::Todo, "Reply to email");
+        task2.context = Some("@computer".to_string());
+        store.create(task2).unwrap();
+
+        let mut task3 = Task::new(0, TaskKind::Todo, "Post letter");
+        task3.context = Some("@errands".to_string());
+        let mut task3 = store.create(task3).unwrap();
+        task3.complete(None);
+        store.update(&task3).unwrap();
+
+        let contexts = store.contexts().unwrap();
+        assert_eq!(
+            contexts,
+            vec![("@computer".to_string(), 1), ("@errands".to_string(), 1)]
+        );
+
+        let filter = TaskFilter {
+            context: Some("@errands".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(store.list(&filter).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_filter_by_external_id() {
+        let (_temp, store) = setup_test_store();
+
+        let mut task1 = Task::new(0, TaskKind::Task, "Task 1");
+        task1.external_id = Some("GH#123".to_string());
+        store.create(task1).unwrap();
+        store
+            .create(Task::new(0, TaskKind::Task, "Task 2"))
+            .unwrap();
+
+        let filter = TaskFilter {
+            external_id: Some("GH#123".to_string()),
+            ..Default::default()
+        };
+        let tasks = store.list(&filter).unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].title, "Task 1");
+    }
+
+    #[test]
+    fn test_filter_by_scheduled() {
+        let (_temp, store) = setup_test_store();
+        let day = |d| NaiveDate::from_ymd_opt(2026, 3, d).unwrap();
+
+        store
+            .create(Task::new(0, TaskKind::Task, "Unscheduled"))
+            .unwrap();
+        for (title, d) in [("Early", 2), ("Late", 20)] {
+            let mut task = Task::new(0, TaskKind::Task, title);
+            task.scheduled = Some(day(d));
+            store.create(task).unwrap();
+        }
+        let titles = |filter: &TaskFilter| -> Vec<String> {
+            let mut titles: Vec<String> = store
+                .list(filter)
+                .unwrap()
+                .into_iter()
+                .map(|t| t.title)
+                .collect();
+            titles.sort();
+            titles
+        };
+
+        let available = TaskFilter {
+            available_on: Some(day(10)),
+            ..Default::default()
+        };
+        assert_eq!(titles(&available), ["Early", "Unscheduled"]);
+
+        let range = TaskFilter {
+            scheduled_after: Some(day(2)),
+            scheduled_before: Some(day(19)),
+            ..Default::default()
+        };
+        assert_eq!(titles(&range), ["Early"]);
+    }
+
+    #[test]
+    fn test_filter_by_completed_since() {
+        let (_temp, store) = setup_test_store();
+
+        let mut task1 = store
+            .create(Task::new(0, TaskKind::Task, "Task 1"))
+            .unwrap();
+        task1.complete(None);
+        store.update(&task1).unwrap();
+
+        // Completed status without a completion timestamp is not matched
+        let mut task2 = store
+            .create(Task::new(0, TaskKind::Task, "Task 2"))
+            .unwrap();
+        task2.status = TaskStatus::Completed;
+        store.update(&task2).unwrap();
+
+        let filter = TaskFilter {
+            completed_since: Some(today()),
+            ..Default::default()
+        };
+        let tasks = store.list(&filter).unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].id, task1.id);
+
+        let filter = TaskFilter {
+            completed_since: Some(NaiveDate::from_ymd_opt(2999, 1, 1).unwrap()),
+            ..Default::default()
+        };
+        assert!(store.list(&filter).unwrap().is_empty());
+    }
+
     #[test]
     fn test_stats() {
         let (_temp, store) = setup_test_store();
@@ -556,19 +2319,379 @@ mod tests {
             .create(Task::new(0, TaskKind::Todo, "Todo 1"))
             .unwrap();
 
+        let mut task = Task::new(0, TaskKind::Idea, "Idea 1");
+        task.estimate = Some(2.0);
+        task.time_entries = vec![crate::models::TimeEntry {
+            start: Utc::now() - Duration::hours(3),
+            end: Some(Utc::now()),
+        }];
+        let mut task = store.create(task).unwrap();
+        task.complete(None);
+        store.update(&task).unwrap();
+
         let mut task = store
-            .create(Task::new(0, TaskKind::Idea, "Idea 1"))
+            .create(Task::new(0, TaskKind::Idea, "Idea 2"))
             .unwrap();
-        task.status = TaskStatus::Completed;
+        task.close(None, crate::models::Resolution::Wontfix);
+        task.estimate = Some(1.5);
         store.update(&task).unwrap();
 
         let stats = store.stats().unwrap();
-        assert_eq!(stats.total, 3);
+        assert_eq!(stats.total, 4);
         assert_eq!(stats.pending, 2);
         assert_eq!(stats.completed, 1);
+        assert_eq!(stats.closed_not_done, 1);
         assert_eq!(stats.tasks, 1);
         assert_eq!(stats.todos, 1);
-        assert_eq!(stats.ideas, 1);
+        assert_eq!(stats.ideas, 2);
+        assert!(stats.avg_cycle_time.is_some());
+        assert_eq!(stats.estimated_hours, 3.5);
+        assert_eq!(stats.done_estimated_hours, 2.0);
+        assert_eq!(stats.done_actual.num_minutes(), 180);
+        assert_eq!(stats.oldest_open.unwrap().title, "Task 1");
+        let this_week = stats.weekly.last().unwrap();
+        assert_eq!((this_week.created, this_week.completed), (4, 1));
+        assert_eq!(stats.weekly.len(), STATS_WEEKS);
+    }
+
+    #[test]
+    fn test_new_task_kind_defaults() {
+        let (_temp, store) = setup_test_store();
+        let tasks_dir = &store.location().tasks_dir;
+        std::fs::create_dir_all(tasks_dir.join("templates")).unwrap();
+        std::fs::write(
+            tasks_dir.join("templates/bugreport.md"),
+            "---\ntags: [bug]\n---\n## Steps\n",
+        )
+        .unwrap();
+        std::fs::write(
+            tasks_dir.join("config.toml"),
+            "[kind.idea]\npriority = \"low\"\ntags = [\"someday\"]\n\n[kind.task]\ntemplate = \"bugreport\"\n",
+        )
+        .unwrap();
+
+        let idea = store.new_task(TaskKind::Idea, "Dark mode", None).unwrap();
+        assert_eq!(idea.priority, Priority::Low);
+        assert_eq!(idea.tags, vec!["someday"]);
+
+        let task = store.new_task(TaskKind::Task, "Crash", None).unwrap();
+        assert_eq!(task.tags, vec!["bug"]);
+        assert_eq!(task.description, "## Steps");
+
+        let todo = store.new_task(TaskKind::Todo, "Milk", None).unwrap();
+        assert_eq!(todo.priority, Priority::Medium);
+        assert!(
+            store
+                .new_task(TaskKind::Todo, "Milk", Some("nope"))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_stats_weeks_and_overdue() {
+        // Wednesday
+        let now = chrono::TimeZone::with_ymd_and_hms(&Utc, 2026, 3, 11, 12, 0, 0).unwrap();
+        let mut last_week = Task::new(1, TaskKind::Task, "Last week");
+        last_week.created = now - Duration::days(7);
+        last_week.priority = Priority::High;
+        last_week.due = Some(crate::models::Due::Date(
+            now.date_naive() - Duration::days(1),
+        ));
+        let mut monday = Task::new(2, TaskKind::Task, "Monday");
+        monday.created = now - Duration::days(2);
+        monday.due = Some(crate::models::Due::Date(
+            now.date_naive() + Duration::days(2),
+        ));
+        let mut ancient = Task::new(3, TaskKind::Task, "Ancient");
+        ancient.created = now - Duration::weeks(52);
+        ancient.time_entries = vec![
+            crate::models::TimeEntry {
+                start: now - Duration::days(1),
+                end: Some(now - Duration::days(1) + Duration::minutes(90)),
+            },
+            crate::models::TimeEntry {
+                start: now - Duration::minutes(30),
+                end: None,
+            },
+        ];
+
+        let stats = TaskStats::from_tasks(&[last_week, monday, ancient], now, 3);
+        let created: Vec<usize> = stats.weekly.iter().map(|w| w.created).collect();
+        assert_eq!(created, vec![0, 0, 0, 0, 0, 0, 1, 1]);
+        assert_eq!(stats.weekly[7].start.to_string(), "2026-03-09");
+        assert_eq!(stats.open_by_age, [1, 1, 0, 1]);
+        assert_eq!(stats.to_json()["open_by_age"][3]["age"], ">3mo");
+        assert_eq!(stats.to_json()["time_spent_hours"], 2.0);
+        assert_eq!(stats.oldest_open.unwrap().id, 3);
+        assert_eq!(
+            stats.overdue_by_priority,
+            BTreeMap::from([(Priority::High, 1)])
+        );
+        assert_eq!((stats.overdue, stats.due_soon), (1, 1));
+        assert_eq!(stats.time_spent, Duration::hours(2));
+    }
+
+    #[test]
+    fn test_list_scopes() {
+        let (temp, store) = setup_test_store();
+        store
+            .create(Task::new(0, TaskKind::Task, "Root task"))
+            .unwrap();
+
+        let service = temp.path().join("services").join("auth");
+        let scope = TaskLocation::at(&service).unwrap();
+        scope.ensure_exists().unwrap();
+        FileStore::new(scope)
+            .create(Task::new(0, TaskKind::Task, "Auth task"))
+            .unwrap();
+
+        let tasks = list_scopes(store.location(), &TaskFilter::default()).unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[1].project, "services/auth");
+        assert_eq!(tasks[1].task.title, "Auth task");
+    }
+
+    #[test]
+    fn test_sharded_layout() {
+        let (_temp, store) = setup_test_store();
+        let tasks_dir = store.location.tasks_dir.clone();
+        let first = store
+            .create(Task::new(0, TaskKind::Task, "Flat task"))
+            .unwrap();
+
+        layout::migrate(&tasks_dir, TaskLayout::Sharded).unwrap();
+        let store = FileStore::new(store.location.clone());
+        assert_eq!(store.layout(), TaskLayout::Sharded);
+
+        let mut second = store
+            .create(Task::new(0, TaskKind::Task, "Sharded task"))
+            .unwrap();
+        assert_eq!(second.id, 2);
+        assert!(tasks_dir.join("02").join(second.filename()).exists());
+        assert_eq!(store.read(first.id).unwrap().title, "Flat task");
+
+        second.title = "Renamed".to_string();
+        store.update(&second).unwrap();
+        assert!(tasks_dir.join("02").join(second.filename()).exists());
+        assert_eq!(store.list(&TaskFilter::default()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_concurrent_creates_get_distinct_ids() {
+        let (_temp, store) = setup_test_store();
+        let location = store.location.clone();
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let location = location.clone();
+                std::thread::spawn(move || {
+                    FileStore::new(location)
+                        .create(Task::new(0, TaskKind::Task, format!("Task {}", i)))
+                        .unwrap()
+                        .id
+                })
+            })
+            .collect();
+
+        let mut ids: Vec<u64> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        ids.sort();
+        assert_eq!(ids, (1..=8).collect::<Vec<_>>());
+        assert!(
+            std::fs::read_to_string(location.tasks_dir.join(".gitignore"))
+                .unwrap()
+                .contains(".lock")
+        );
+    }
+
+    #[test]
+    fn test_patch_detects_conflicts() {
+        let (_temp, store) = setup_test_store();
+        let task = store
+            .create(Task::new(0, TaskKind::Task, "Original"))
+            .unwrap();
+
+        // Another writer changes the description after we read the task
+        let mut other = store.read(task.id).unwrap();
+        other.description = "Their edit".to_string();
+        other.updated = task.updated + Duration::seconds(5);
+        store.update(&other).unwrap();
+
+        let patch = TaskPatch {
+            priority: Some(Priority::High),
+            ..Default::default()
+        };
+        assert!(matches!(
+            store.patch(task.id, &patch, Some(task.updated)),
+            Err(FileStoreError::Conflict { id: 1, .. })
+        ));
+
+        // Without a precondition only the patched field changes
+        let patched = store.patch(task.id, &patch, None).unwrap();
+        assert_eq!(patched.priority, Priority::High);
+        assert_eq!(patched.description, "Their edit");
+
+        let again = store.patch(task.id, &patch, Some(patched.updated)).unwrap();
+        assert!(again.updated >= patched.updated);
+    }
+
+    #[test]
+    fn test_move_task_within_status() {
+        let (_temp, store) = setup_test_store();
+        for title in ["One", "Two", "Three"] {
+            store.create(Task::new(0, TaskKind::Task, title)).unwrap();
+        }
+        let mut started = store.read(1).unwrap();
+        started.status = TaskStatus::InProgress;
+        store.update(&started).unwrap();
+
+        // Only the pending tasks 2 and 3 take part
+        assert_eq!(store.move_task(3, OrderMove::Up).unwrap(), 0);
+        let orders: Vec<Option<u64>> = (1..=3).map(|id| store.read(id).unwrap().order).collect();
+        assert_eq!(orders, vec![None, Some(2), Some(1)]);
+        assert!(store.move_task(9, OrderMove::Up).is_err());
+
+        // Changing status drops the task's place in the old one
+        let mut moved = store.read(3).unwrap();
+        moved.status = TaskStatus::InProgress;
+        store.update(&moved).unwrap();
+        assert_eq!(store.read(3).unwrap().order, None);
+    }
+
+    #[test]
+    fn test_add_blocker_rejects_cycles() {
+        let (_temp, store) = setup_test_store();
+        for title in ["One", "Two", "Three"] {
+            store.create(Task::new(0, TaskKind::Task, title)).unwrap();
+        }
+        store.add_blocker(1, 2).unwrap();
+        store.add_blocker(2, 3).unwrap();
+
+        let err = store.add_blocker(3, 1).unwrap_err();
+        assert!(matches!(err, FileStoreError::DependencyCycle(ref path) if path == &[3, 1, 2, 3]));
+        assert_eq!(
+            err.to_string(),
+            "Dependency would create a cycle: #3 → #1 → #2 → #3"
+        );
+        assert!(store.read(3).unwrap().blocked_by.is_empty());
+        assert!(store.add_blocker(1, 1).is_err());
+        assert!(store.add_blocker(1, 9).is_err());
+    }
+
+    #[test]
+    fn test_blockers_checked_on_write_and_unblocked_filter() {
+        let (_temp, store) = setup_test_store();
+        for title in ["One", "Two", "Three"] {
+            store.create(Task::new(0, TaskKind::Task, title)).unwrap();
+        }
+        store.add_blocker(2, 1).unwrap();
+
+        let mut waiting = Task::new(0, TaskKind::Task, "Four");
+        waiting.blocked_by = vec![7];
+        assert!(matches!(
+            store.create(waiting),
+            Err(FileStoreError::TaskNotFound(7))
+        ));
+        let mut first = store.read(1).unwrap();
+        first.blocked_by = vec![2];
+        assert!(matches!(
+            store.update(&first),
+            Err(FileStoreError::DependencyCycle(_))
+        ));
+
+        let filter = TaskFilter::parse("unblocked:true").unwrap();
+        let ids: Vec<u64> = store.list(&filter).unwrap().iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![1, 3]);
+
+        let mut first = store.read(1).unwrap();
+        first.complete(None);
+        store.update(&first).unwrap();
+        let ids: Vec<u64> = store.list(&filter).unwrap().iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_create_or_update_by_external_id() {
+        let (_temp, store) = setup_test_store();
+        let incoming = |title: &str, external_id: Option<&str>| {
+            let mut task = Task::new(0, TaskKind::Task, title);
+            task.external_id = external_id.map(str::to_string);
+            task
+        };
+
+        let created = store
+            .create_or_update_by_external_id(incoming("Crash", Some("GH#1")), SyncedFields::ALL)
+            .unwrap();
+        assert!(matches!(created, Upsert::Created(ref t) if t.id == 1));
+        store
+            .add_blocker(1, store.create(incoming("Other", None)).unwrap().id)
+            .unwrap();
+
+        let same = store
+            .create_or_update_by_external_id(incoming("Crash", Some("GH#1")), SyncedFields::ALL)
+            .unwrap();
+        assert!(matches!(same, Upsert::Unchanged(_)));
+
+        let mut closed = incoming("Crash on login", Some("GH#1"));
+        closed.status = TaskStatus::Completed;
+        let updated = store
+            .create_or_update_by_external_id(closed, SyncedFields::ALL)
+            .unwrap();
+        assert!(matches!(updated, Upsert::Updated(_)));
+        let task = store.read(1).unwrap();
+        assert_eq!(task.title, "Crash on login");
+        assert!(task.completed.is_some());
+        assert_eq!(task.blocked_by, vec![2]);
+
+        // Without an external ID, only a fingerprint match avoids a duplicate
+        assert!(matches!(
+            store
+                .create_or_update_by_external_id(incoming("other", None), SyncedFields::ALL)
+                .unwrap(),
+            Upsert::Created(_)
+        ));
+        std::fs::write(
+            store.location().tasks_dir.join("config.toml"),
+            "[storage]\nfingerprint = true\n",
+        )
+        .unwrap();
+        let matched = store
+            .create_or_update_by_external_id(incoming("OTHER", Some("GH#2")), SyncedFields::ALL)
+            .unwrap();
+        assert_eq!(matched.task().id, 2);
+        assert_eq!(store.read(2).unwrap().external_id.as_deref(), Some("GH#2"));
+    }
+
+    #[test]
+    fn test_synced_keeps_missing_fields() {
+        let (_temp, store) = setup_test_store();
+        let mut task = Task::new(0, TaskKind::Task, "Crash");
+        task.external_id = Some("GH#1".to_string());
+        task.priority = Priority::Critical;
+        task.due = Some("2030-01-01".parse().unwrap());
+        let mut task = store.create(task).unwrap();
+        task.complete(Some("abc1234".to_string()));
+        store.update(&task).unwrap();
+
+        // A GitHub record has a title and state, but no priority or due date
+        let mut incoming = Task::new(0, TaskKind::Task, "Crash on login");
+        incoming.external_id = Some("GH#1".to_string());
+        let fields = SyncedFields {
+            status: true,
+            ..Default::default()
+        };
+        let upsert = store
+            .create_or_update_by_external_id(incoming, fields)
+            .unwrap();
+        assert_eq!(upsert.action(), "updated");
+
+        let task = store.read(1).unwrap();
+        assert_eq!(task.title, "Crash on login");
+        assert_eq!(task.priority, Priority::Critical);
+        assert_eq!(task.due, Some("2030-01-01".parse().unwrap()));
+        assert_eq!(task.status, TaskStatus::Pending);
+        assert_eq!(task.closed_commit, None);
+        assert_eq!(task.reopened.len(), 1);
+        assert_eq!(task.reopened[0].closed_commit.as_deref(), Some("abc1234"));
     }
 
     #[test]
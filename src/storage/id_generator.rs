@@ -1,5 +1,9 @@
 //! Sequential ID generation for tasks
 
+use crate::storage::layout;
+use crate::storage::location::ensure_gitignored;
+use crate::storage::writes::is_dry_run;
+use std::fs::{File, OpenOptions};
 use std::path::Path;
 use thiserror::Error;
 
@@ -12,9 +16,17 @@ pub enum IdError {
     InvalidFilename(String),
 }
 
+/// Lock file serializing ID allocation within a tasks directory
+const LOCK_FILE: &str = ".lock";
+
 /// Generates sequential IDs for tasks
 pub struct IdGenerator;
 
+/// Exclusive hold on ID allocation for a tasks directory, released on drop
+pub struct IdLock {
+    _file: Option<File>,
+}
+
 impl IdGenerator {
     /// Scan a directory and return the next available ID
     pub fn next_id(tasks_dir: &Path) -> Result<u64, IdError> {
@@ -22,6 +34,30 @@ impl IdGenerator {
         Ok(max_id + 1)
     }
 
+    /// Block until this process holds the ID allocation lock for `tasks_dir`
+    ///
+    /// Keep the guard until the new task file is written so that a concurrent
+    /// create (e.g. the CLI and the MCP server) sees it when picking its ID.
+    /// A dry run writes nothing, so it takes no lock (and creates no lock file).
+    pub fn lock(tasks_dir: &Path) -> Result<IdLock, IdError> {
+        if is_dry_run() {
+            return Ok(IdLock { _file: None });
+        }
+        let path = tasks_dir.join(LOCK_FILE);
+        if !path.exists() {
+            ensure_gitignored(tasks_dir, LOCK_FILE)?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(path)?;
+        file.lock()?;
+
+        Ok(IdLock { _file: Some(file) })
+    }
+
     /// Find the maximum ID in the tasks directory
     pub fn find_max_id(tasks_dir: &Path) -> Result<u64, IdError> {
         if !tasks_dir.exists() {
@@ -30,13 +66,8 @@ impl IdGenerator {
 
         let mut max_id: u64 = 0;
 
-        for entry in std::fs::read_dir(tasks_dir)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            if path.extension().is_some_and(|ext| ext == "md")
-                && let Some(id) = Self::extract_id_from_filename(&path)
-            {
+        for path in layout::task_files(tasks_dir)? {
+            if let Some(id) = Self::extract_id_from_filename(&path) {
                 max_id = max_id.max(id);
             }
         }
@@ -0,0 +1,613 @@
+//! Importing tasks from other tools
+
+use crate::models::{Task, TaskKind, TaskStatus};
+use crate::storage::config::ProjectConfig;
+use crate::storage::file_store::{
+    FileStore, FileStoreError, SyncedFields, TaskFilter, Upsert, synced,
+};
+use crate::storage::writes;
+use chrono::{DateTime, Utc};
+use serde_json::{Value, json};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Supported import formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImportFormat {
+    #[default]
+    Json,
+    /// `gh project item-list --format json` output from a GitHub project board
+    GithubProject,
+}
+
+impl fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportFormat::Json => write!(f, "json"),
+            ImportFormat::GithubProject => write!(f, "github-project"),
+        }
+    }
+}
+
+impl std::str::FromStr for ImportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(ImportFormat::Json),
+            "github-project" | "gh-project" => Ok(ImportFormat::GithubProject),
+            _ => Err(format!("Unknown import format: {}", s)),
+        }
+    }
+}
+
+/// Result of an import run
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    /// Tasks created (or that would be created in a dry run)
+    pub created: Vec<Task>,
+    /// Existing tasks changed with `update` (or that would be in a dry run)
+    pub updated: Vec<Task>,
+    /// Records skipped, with their 1-based index and reason
+    pub skipped: Vec<(usize, String)>,
+    /// Records that failed, with their 1-based index and error
+    pub errors: Vec<(usize, String)>,
+    /// Custom statuses added to `[workflow]` for board columns
+    pub new_statuses: Vec<String>,
+}
+
+/// Import tasks from JSON text: an array, a single object, or one object per line
+///
+/// Records whose `external_id` already exists in the store are skipped, or
+/// with `update` applied to the matching task (see
+/// `FileStore::create_or_update_by_external_id`). With `dry_run` nothing is
+/// written.
+pub fn import_json(
+    store: &FileStore,
+    input: &str,
+    dry_run: bool,
+    update: bool,
+) -> Result<ImportReport, FileStoreError> {
+    let records: Vec<Result<Value, String>> = match serde_json::from_str::<Value>(input) {
+        Ok(Value::Array(items)) => items.into_iter().map(Ok).collect(),
+        Ok(value) => vec![Ok(value)],
+        Err(_) => input
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(|e| format!("Invalid JSON: {}", e)))
+            .collect(),
+    };
+    import_records(store, records, dry_run, update, ImportReport::default())
+}
+
+/// Import the items of a GitHub project board (Projects v2)
+///
+/// `input` is the output of `gh project item-list <number> --format json`.
+/// Each board column (the item's `status`) becomes a status: To do, In
+/// progress and Done map to the built-in ones and any other column to a
+/// custom status of the same name, which is added to `[workflow] statuses`.
+/// Columns are appended to `[workflow] columns` in board order so `gittask
+/// board` lays them out the same way. The board order is taken from a
+/// `fields` list as printed by `gh project field-list --format json` when the
+/// input has one (e.g. both files merged with `jq -s add`), and from the
+/// order columns first appear in otherwise.
+pub fn import_github_project(
+    store: &FileStore,
+    input: &str,
+    dry_run: bool,
+    update: bool,
+) -> Result<ImportReport, FileStoreError> {
+    let mut report = ImportReport::default();
+    let board: Value = serde_json::from_str(input).map_err(|e| {
+        FileStoreError::InvalidImport(format!("invalid GitHub project JSON: {}", e))
+    })?;
+    let items = board
+        .get("items")
+        .and_then(Value::as_array)
+        .ok_or_else(|| {
+            FileStoreError::InvalidImport("no 'items' array in GitHub project JSON".to_string())
+        })?;
+
+    // Board order: the Status field's options, then any other columns in use
+    let mut columns: Vec<String> = board
+        .get("fields")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|field| field.get("name").and_then(Value::as_str) == Some("Status"))
+        .filter_map(|field| field.get("options").and_then(Value::as_array))
+        .flatten()
+        .filter_map(|option| option.get("name").and_then(Value::as_str))
+        .map(str::to_string)
+        .collect();
+    for item in items {
+        if let Some(column) = item.get("status").and_then(Value::as_str)
+            && !columns.iter().any(|c| c == column)
+        {
+            columns.push(column.to_string());
+        }
+    }
+
+    let statuses = columns
+        .iter()
+        .enumerate()
+        .map(|(position, column)| {
+            let status = column_status(column, position).map_err(FileStoreError::InvalidImport)?;
+            Ok((column.as_str(), status))
+        })
+        .collect::<Result<HashMap<&str, TaskStatus>, FileStoreError>>()?;
+
+    let tasks_dir = &store.location().tasks_dir;
+    let mut config = ProjectConfig::load(tasks_dir)?;
+    let mut changed = false;
+    for column in &columns {
+        let status = statuses[column.as_str()];
+        let name = status.to_string();
+        if matches!(status, TaskStatus::Custom(_)) && !config.workflow.statuses.contains(&name) {
+            config.workflow.statuses.push(name.clone());
+            report.new_statuses.push(name.clone());
+            changed = true;
+        }
+        if !config.workflow.columns.contains(&name) {
+            config.workflow.columns.push(name);
+            changed = true;
+        }
+    }
+
+    let records = items
+        .iter()
+        .map(|item| project_item_record(item, &statuses).map(Value::Object))
+        .collect();
+    if !changed || dry_run {
+        return import_records(store, records, dry_run, update, report);
+    }
+
+    // The tasks can only be saved with their statuses defined, so the new
+    // config goes first and is put back if the import fails
+    let path = ProjectConfig::path(tasks_dir);
+    let original = std::fs::read_to_string(&path).ok();
+    config.save(tasks_dir)?;
+    let result = import_records(store, records, dry_run, update, report);
+    if result.is_err() {
+        match original {
+            Some(original) => writes::write(&path, original)?,
+            None => writes::remove_file(&path)?,
+        }
+    }
+    result
+}
+
+/// The status a board column maps to
+///
+/// Columns are slugified into status names. One whose slug can't name a
+/// status, because it doesn't start with an ASCII letter, becomes
+/// `column-<slug>`, or `column-<n>` for its 1-based `position` on the board
+/// when nothing of it is left.
+fn column_status(column: &str, position: usize) -> Result<TaskStatus, String> {
+    let mut slug = String::new();
+    for c in column.trim().to_lowercase().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    match slug {
+        "todo" | "to-do" | "no-status" => Ok(TaskStatus::Pending),
+        // Custom statuses for new columns are added to the config
+        _ => slug
+            .parse()
+            .ok()
+            .or_else(|| TaskStatus::define(slug))
+            .or_else(|| match slug {
+                "" => TaskStatus::define(&format!("column-{}", position + 1)),
+                _ => TaskStatus::define(&format!("column-{}", slug)),
+            })
+            .ok_or_else(|| format!("Board column '{}' can't be used as a status", column)),
+    }
+}
+
+/// Turn a board item into a record for [`parse_record`]
+///
+/// Issues and pull requests are identified as `GH#<number>` like imported
+/// issues; draft items by their project item ID.
+fn project_item_record(
+    item: &Value,
+    statuses: &HashMap<&str, TaskStatus>,
+) -> Result<serde_json::Map<String, Value>, String> {
+    let obj = item.as_object().ok_or("Item is not a JSON object")?;
+    let content = obj.get("content");
+    let field = |key: &str| {
+        obj.get(key)
+            .or_else(|| content.and_then(|c| c.get(key)))
+            .cloned()
+    };
+
+    let mut record = serde_json::Map::new();
+    for key in ["title", "body", "labels"] {
+        if let Some(value) = field(key) {
+            record.insert(key.to_string(), value);
+        }
+    }
+    let status = obj
+        .get("status")
+        .and_then(Value::as_str)
+        .and_then(|column| statuses.get(column))
+        .copied()
+        .unwrap_or(TaskStatus::Pending);
+    record.insert("status".to_string(), json!(status.to_string()));
+    match content.and_then(|c| c.get("number")) {
+        Some(number) => {
+            record.insert("number".to_string(), number.clone());
+        }
+        None => {
+            if let Some(id) = obj.get("id") {
+                record.insert("external_id".to_string(), id.clone());
+            }
+        }
+    }
+    Ok(record)
+}
+
+/// Create or update tasks from parsed records, adding to `report`
+fn import_records(
+    store: &FileStore,
+    records: Vec<Result<Value, String>>,
+    dry_run: bool,
+    update: bool,
+    mut report: ImportReport,
+) -> Result<ImportReport, FileStoreError> {
+    let mut known_external_ids: HashSet<String> = store
+        .list(&TaskFilter {
+            include_archived: true,
+            ..Default::default()
+        })?
+        .into_iter()
+        .filter_map(|t| t.external_id)
+        .collect();
+
+    let mut matcher = if update { Some(store.matcher()?) } else { None };
+
+    for (i, record) in records.into_iter().enumerate() {
+        let index = i + 1;
+        let (task, fields) = match record.and_then(|value| parse_record(&value)) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                report.errors.push((index, e));
+                continue;
+            }
+        };
+
+        if let Some(ref mut matcher) = matcher {
+            if dry_run {
+                match matcher.find(&task) {
+                    Some(existing) if synced(existing, task.clone(), fields) == *existing => report
+                        .skipped
+                        .push((index, format!("#{} is unchanged", existing.id))),
+                    Some(existing) => report.updated.push(synced(existing, task, fields)),
+                    None => report.created.push(task),
+                }
+                continue;
+            }
+            match store.upsert(matcher, task, fields) {
+                Ok(Upsert::Created(task)) => report.created.push(task),
+                Ok(Upsert::Updated(task)) => report.updated.push(task),
+                Ok(Upsert::Unchanged(task)) => report
+                    .skipped
+                    .push((index, format!("#{} is unchanged", task.id))),
+                Err(e) => report.errors.push((index, e.to_string())),
+            }
+            continue;
+        }
+
+        if let Some(ref external_id) = task.external_id
+            && !known_external_ids.insert(external_id.clone())
+        {
+            report
+                .skipped
+                .push((index, format!("external_id {} already exists", external_id)));
+            continue;
+        }
+
+        if dry_run {
+            report.created.push(task);
+        } else {
+            match store.create(task) {
+                Ok(created) => report.created.push(created),
+                Err(e) => report.errors.push((index, e.to_string())),
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Convert one JSON record into a task, with the fields it has
+///
+/// Accepts gittask's own export format as well as common shapes from other
+/// tools: `body` for the description, `labels` (strings or `{name}` objects)
+/// for tags, `state` for status, and `number` as a `GH#<n>` external ID.
+pub fn parse_record(value: &Value) -> Result<(Task, SyncedFields), String> {
+    let obj = value.as_object().ok_or("Record is not a JSON object")?;
+
+    let title = obj
+        .get("title")
+        .and_then(|v| v.as_str())
+        .filter(|t| !t.trim().is_empty())
+        .ok_or("Missing 'title'")?;
+
+    let mut fields = SyncedFields::default();
+    let kind: TaskKind = match obj.get("kind").and_then(|v| v.as_str()) {
+        Some(k) => {
+            fields.kind = true;
+            k.parse()?
+        }
+        None => TaskKind::default(),
+    };
+
+    let mut task = Task::new(0, kind, title.trim());
+
+    if let Some(status) = obj
+        .get("status")
+        .or_else(|| obj.get("state"))
+        .and_then(|v| v.as_str())
+    {
+        fields.status = true;
+        task.status = match status.to_lowercase().as_str() {
+            "open" => TaskStatus::Pending,
+            "closed" => TaskStatus::Completed,
+            other => other.parse()?,
+        };
+    }
+
+    if let Some(p) = obj.get("priority").and_then(|v| v.as_str()) {
+        task.priority = p.parse()?;
+        fields.priority = true;
+    }
+
+    if let Some(desc) = obj
+        .get("description")
+        .or_else(|| obj.get("body"))
+        .and_then(|v| v.as_str())
+    {
+        task.description = desc.trim().to_string();
+        fields.description = true;
+    }
+
+    if let Some(tags) = obj
+        .get("tags")
+        .or_else(|| obj.get("labels"))
+        .and_then(|v| v.as_array())
+    {
+        task.tags = tags
+            .iter()
+            .filter_map(|t| {
+                t.as_str()
+                    .or_else(|| t.get("name").and_then(|n| n.as_str()))
+                    .map(|s| s.to_string())
+            })
+            .collect();
+        fields.tags = true;
+    }
+
+    if let Some(context) = obj.get("context").and_then(|v| v.as_str()) {
+        task.context = Some(context.to_string());
+        fields.context = true;
+    }
+
+    if let Some(due) = obj.get("due").and_then(|v| v.as_str()) {
+        task.due = Some(due.parse()?);
+        fields.due = true;
+    }
+
+    task.external_id = match obj.get("external_id").and_then(|v| v.as_str()) {
+        Some(id) => Some(id.to_string()),
+        None => obj
+            .get("number")
+            .and_then(|v| v.as_u64())
+            .map(|n| format!("GH#{}", n)),
+    };
+
+    for (key, field) in [
+        ("created", &mut task.created),
+        ("updated", &mut task.updated),
+    ] {
+        if let Some(ts) = obj.get(key).and_then(|v| v.as_str()) {
+            *field = ts
+                .parse::<DateTime<Utc>>()
+                .map_err(|e| format!("Invalid '{}' timestamp: {}", key, e))?;
+        }
+    }
+
+    if task.status == TaskStatus::Completed {
+        task.completed = match obj.get("completed").and_then(|v| v.as_str()) {
+            Some(ts) => Some(
+                ts.parse::<DateTime<Utc>>()
+                    .map_err(|e| format!("Invalid 'completed' timestamp: {}", e))?,
+            ),
+            None => Some(task.updated),
+        };
+    }
+
+    Ok((task, fields))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Priority;
+    use crate::storage::test_support::setup_test_store;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_record_github_issue() {
+        let record = json!({
+            "number": 42,
+            "title": "Crash on login",
+            "body": "Steps to reproduce...",
+            "state": "OPEN",
+            "labels": [{"name": "bug"}, {"name": "auth"}]
+        });
+        let (task, _) = parse_record(&record).unwrap();
+        assert_eq!(task.title, "Crash on login");
+        assert_eq!(task.status, TaskStatus::Pending);
+        assert_eq!(task.tags, vec!["bug", "auth"]);
+        assert_eq!(task.external_id, Some("GH#42".to_string()));
+        assert_eq!(task.description, "Steps to reproduce...");
+    }
+
+    #[test]
+    fn test_parse_record_errors() {
+        assert!(parse_record(&json!("not an object")).is_err());
+        assert!(parse_record(&json!({"kind": "task"})).is_err());
+        assert!(parse_record(&json!({"title": "x", "priority": "urgent"})).is_err());
+    }
+
+    #[test]
+    fn test_import_json_reports_per_record() {
+        let (_temp, store) = setup_test_store();
+
+        let input = r#"[
+            {"title": "First", "priority": "high"},
+            {"priority": "low"},
+            {"title": "Second", "external_id": "PROJ-1"},
+            {"title": "Second again", "external_id": "PROJ-1"}
+        ]"#;
+
+        let report = import_json(&store, input, false, false).unwrap();
+        assert_eq!(report.created.len(), 2);
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].0, 2);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.skipped[0].0, 4);
+
+        let tasks = store.list(&TaskFilter::default()).unwrap();
+        assert_eq!(tasks.len(), 2);
+        assert_eq!(tasks[0].priority, Priority::High);
+    }
+
+    #[test]
+    fn test_import_json_dry_run_and_lines() {
+        let (_temp, store) = setup_test_store();
+
+        let input = "{\"title\": \"One\"}\n{\"title\": \"Two\"}\nnot json\n";
+        let report = import_json(&store, input, true, false).unwrap();
+        assert_eq!(report.created.len(), 2);
+        assert_eq!(report.errors.len(), 1);
+        assert!(store.list(&TaskFilter::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_import_json_update() {
+        let (_temp, store) = setup_test_store();
+        let input = r#"[{"title": "Crash", "number": 1}, {"title": "Typo", "number": 2}]"#;
+        import_json(&store, input, false, true).unwrap();
+
+        // A re-run with one issue closed updates it and leaves the other alone
+        let input = r#"[{"title": "Crash", "number": 1, "state": "closed"}, {"title": "Typo", "number": 2}]"#;
+        let dry = import_json(&store, input, true, true).unwrap();
+        assert_eq!((dry.created.len(), dry.updated.len()), (0, 1));
+        let report = import_json(&store, input, false, true).unwrap();
+        assert!(report.created.is_empty());
+        assert_eq!(report.updated.len(), 1);
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(store.read(1).unwrap().status, TaskStatus::Completed);
+        assert_eq!(store.list(&TaskFilter::default()).unwrap().len(), 2);
+
+        // Fields GitHub issues don't carry survive every later round, as
+        // `sync` re-runs the import
+        let mut typo = store.read(2).unwrap();
+        typo.priority = Priority::High;
+        typo.due = Some("2026-03-04".parse().unwrap());
+        store.update(&typo).unwrap();
+        for _ in 0..2 {
+            let report = import_json(&store, input, false, true).unwrap();
+            assert!(report.updated.is_empty());
+        }
+        let typo = store.read(2).unwrap();
+        assert_eq!(typo.priority, Priority::High);
+        assert_eq!(typo.due, Some("2026-03-04".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_import_github_project() {
+        let (_temp, store) = setup_test_store();
+        let input = r#"{
+            "items": [
+                {"id": "PVTI_1", "status": "In review", "title": "Login",
+                 "content": {"type": "Issue", "number": 7, "title": "Login", "body": "OAuth"}},
+                {"id": "PVTI_2", "status": "Todo", "title": "Draft idea",
+                 "content": {"type": "DraftIssue", "title": "Draft idea"}},
+                {"id": "PVTI_3", "status": "Done", "title": "Setup",
+                 "content": {"type": "Issue", "number": 3, "title": "Setup"}}
+            ],
+            "fields": [
+                {"name": "Status", "options": [
+                    {"name": "Backlog"}, {"name": "Todo"}, {"name": "In Progress"},
+                    {"name": "In review"}, {"name": "Done"}
+                ]}
+            ]
+        }"#;
+
+        let dry = import_github_project(&store, input, true, false).unwrap();
+        assert_eq!(dry.new_statuses, ["backlog", "in-review"]);
+        assert_eq!(dry.created.len(), 3);
+        assert!(store.list(&TaskFilter::default()).unwrap().is_empty());
+
+        let report = import_github_project(&store, input, false, false).unwrap();
+        assert_eq!(report.created.len(), 3);
+        let workflow = ProjectConfig::load(&store.location().tasks_dir)
+            .unwrap()
+            .workflow;
+        assert_eq!(workflow.statuses, ["backlog", "in-review"]);
+        assert_eq!(
+            workflow.columns,
+            [
+                "backlog",
+                "pending",
+                "in-progress",
+                "in-review",
+                "completed"
+            ]
+        );
+
+        let login = store.read(1).unwrap();
+        assert_eq!(login.status, TaskStatus::Custom("in-review"));
+        assert_eq!(login.external_id.as_deref(), Some("GH#7"));
+        assert_eq!(login.description, "OAuth");
+        let draft = store.read(2).unwrap();
+        assert_eq!(draft.status, TaskStatus::Pending);
+        assert_eq!(draft.external_id.as_deref(), Some("PVTI_2"));
+        assert_eq!(store.read(3).unwrap().status, TaskStatus::Completed);
+
+        // Re-importing changes nothing
+        let again = import_github_project(&store, input, false, true).unwrap();
+        assert!(again.new_statuses.is_empty());
+        assert_eq!(again.skipped.len(), 3);
+
+        assert!(import_github_project(&store, "[]", false, false).is_err());
+    }
+
+    #[test]
+    fn test_import_github_project_odd_columns() {
+        let (_temp, store) = setup_test_store();
+        let input = r#"{
+            "items": [
+                {"id": "PVTI_1", "status": "🚀", "title": "Launch"},
+                {"id": "PVTI_2", "status": "2025 Q1", "title": "Plan"},
+                {"id": "PVTI_3", "title": "Loose"}
+            ],
+            "fields": [
+                {"name": "Status", "options": [{"name": "Todo"}, {"name": "🚀"}]}
+            ]
+        }"#;
+
+        let report = import_github_project(&store, input, false, false).unwrap();
+        assert_eq!(report.new_statuses, ["column-2", "column-2025-q1"]);
+        let statuses: Vec<String> = (1..=3)
+            .map(|id| store.read(id).unwrap().status.to_string())
+            .collect();
+        assert_eq!(statuses, ["column-2", "column-2025-q1", "pending"]);
+    }
+}
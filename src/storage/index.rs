@@ -0,0 +1,255 @@
+//! Optional on-disk index of parsed task files
+//!
+//! When `.tasks/.index.json` exists, listing only re-parses task files whose
+//! size or modification time changed since they were indexed, and
+//! `FileStore` writes keep the affected entries up to date.
+
+use crate::models::{Task, TaskStatus};
+use crate::storage::export::ExportedTask;
+use crate::storage::file_store::read_task_entry;
+use crate::storage::layout;
+use crate::storage::writes;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Index file name within the tasks directory
+const INDEX_FILE: &str = ".index.json";
+
+/// Current index format version
+const INDEX_VERSION: u32 = 1;
+
+/// File metadata used to detect changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct FileStamp {
+    size: u64,
+    mtime_secs: u64,
+    mtime_nanos: u32,
+}
+
+impl FileStamp {
+    fn of(path: &Path) -> std::io::Result<Self> {
+        let meta = std::fs::metadata(path)?;
+        let mtime = meta
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        Ok(FileStamp {
+            size: meta.len(),
+            mtime_secs: mtime.as_secs(),
+            mtime_nanos: mtime.subsec_nanos(),
+        })
+    }
+}
+
+/// An indexed task file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexEntry {
+    stamp: FileStamp,
+    task: ExportedTask,
+}
+
+/// Index of parsed task files keyed by file name
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TaskIndex {
+    version: u32,
+    entries: BTreeMap<String, IndexEntry>,
+}
+
+impl TaskIndex {
+    /// Path of the index file for a tasks directory
+    pub fn path(tasks_dir: &Path) -> PathBuf {
+        tasks_dir.join(INDEX_FILE)
+    }
+
+    /// Check whether an index exists for a tasks directory
+    pub fn exists(tasks_dir: &Path) -> bool {
+        Self::path(tasks_dir).exists()
+    }
+
+    /// Load the index, returning None if it is missing, unreadable or outdated
+    pub fn load(tasks_dir: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(Self::path(tasks_dir)).ok()?;
+        match serde_json::from_str::<TaskIndex>(&content) {
+            Ok(index) if index.version == INDEX_VERSION => Some(index),
+            Ok(_) => None,
+            Err(e) => {
+                log::warn!("Ignoring unreadable task index: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Build a fresh index from every task file and write it to disk
+    pub fn build(tasks_dir: &Path) -> std::io::Result<Self> {
+        let mut index = TaskIndex {
+            version: INDEX_VERSION,
+            entries: BTreeMap::new(),
+        };
+        index.refresh(tasks_dir)?;
+        index.save(tasks_dir)?;
+        Ok(index)
+    }
+
+    /// Write the index atomically (temp file + rename)
+    pub fn save(&self, tasks_dir: &Path) -> std::io::Result<()> {
+        let content = serde_json::to_string(self).map_err(std::io::Error::other)?;
+        writes::write_cache(&Self::path(tasks_dir), content)
+    }
+
+    /// Remove the index file
+    pub fn remove(tasks_dir: &Path) -> std::io::Result<()> {
+        let path = Self::path(tasks_dir);
+        if path.exists() {
+            writes::remove_file(&path)?;
+        }
+        Ok(())
+    }
+
+    /// Bring entries in line with the directory, re-parsing only changed files
+    ///
+    /// Returns true if any entry changed.
+    pub fn refresh(&mut self, tasks_dir: &Path) -> std::io::Result<bool> {
+        let mut changed = false;
+        let mut seen = HashSet::new();
+
+        for path in layout::task_files(tasks_dir)? {
+            let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+                continue;
+            };
+
+            let stamp = FileStamp::of(&path)?;
+            seen.insert(name.clone());
+
+            if self.entries.get(&name).is_some_and(|e| e.stamp == stamp) {
+                continue;
+            }
+
+            changed = true;
+            match read_task_entry(path) {
+                Some(task) => {
+                    self.entries.insert(
+                        name,
+                        IndexEntry {
+                            stamp,
+                            task: ExportedTask::from(&task),
+                        },
+                    );
+                }
+                None => {
+                    self.entries.remove(&name);
+                }
+            }
+        }
+
+        let before = self.entries.len();
+        self.entries.retain(|name, _| seen.contains(name));
+        changed |= self.entries.len() != before;
+
+        Ok(changed)
+    }
+
+    /// Record the current state of a single task file
+    pub fn upsert(&mut self, path: &Path, task: &Task) -> std::io::Result<()> {
+        let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            return Ok(());
+        };
+        self.entries.insert(
+            name,
+            IndexEntry {
+                stamp: FileStamp::of(path)?,
+                task: ExportedTask::from(task),
+            },
+        );
+        Ok(())
+    }
+
+    /// Forget a task file
+    pub fn forget(&mut self, path: &Path) {
+        if let Some(name) = path.file_name() {
+            self.entries.remove(name.to_string_lossy().as_ref());
+        }
+    }
+
+    /// All indexed tasks, in file name order
+    pub fn tasks(&self) -> impl Iterator<Item = Task> + '_ {
+        self.entries.values().map(|e| e.task.clone().into())
+    }
+
+    /// Status of every indexed task, without cloning the tasks
+    pub fn statuses(&self) -> impl Iterator<Item = TaskStatus> + '_ {
+        self.entries.values().map(|e| e.task.task.status)
+    }
+
+    /// Number of indexed task files
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if the index has no entries
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TaskKind, serialize_task};
+    use crate::storage::TaskFilter;
+    use crate::storage::test_support::setup_test_store;
+
+    #[test]
+    fn test_build_and_refresh() {
+        let (_temp, store) = setup_test_store();
+        let dir = store.location().tasks_dir.clone();
+        store
+            .create(Task::new(0, TaskKind::Task, "Task 1"))
+            .unwrap();
+
+        let mut index = TaskIndex::build(&dir).unwrap();
+        assert_eq!(index.len(), 1);
+        assert!(TaskIndex::exists(&dir));
+        assert!(!index.refresh(&dir).unwrap());
+
+        // Edit a file behind the index's back
+        let mut task = Task::new(1, TaskKind::Task, "Task 1");
+        task.description = "Edited externally with more text".to_string();
+        std::fs::write(dir.join(task.filename()), serialize_task(&task).unwrap()).unwrap();
+        assert!(index.refresh(&dir).unwrap());
+        let tasks: Vec<Task> = index.tasks().collect();
+        assert_eq!(tasks[0].description, "Edited externally with more text");
+
+        std::fs::remove_file(dir.join(task.filename())).unwrap();
+        assert!(index.refresh(&dir).unwrap());
+        assert!(index.is_empty());
+    }
+
+    #[test]
+    fn test_store_writes_update_index() {
+        let (_temp, store) = setup_test_store();
+        let dir = store.location().tasks_dir.clone();
+        TaskIndex::build(&dir).unwrap();
+
+        let mut task = store
+            .create(Task::new(0, TaskKind::Task, "Original"))
+            .unwrap();
+        store.create(Task::new(0, TaskKind::Task, "Other")).unwrap();
+        assert_eq!(TaskIndex::load(&dir).unwrap().len(), 2);
+
+        task.title = "Renamed".to_string();
+        store.update(&task).unwrap();
+        let mut index = TaskIndex::load(&dir).unwrap();
+        assert_eq!(index.len(), 2);
+        assert!(index.tasks().any(|t| t.title == "Renamed"));
+        assert!(!index.refresh(&dir).unwrap());
+
+        store.delete(task.id).unwrap();
+        assert_eq!(TaskIndex::load(&dir).unwrap().len(), 1);
+
+        let tasks = store.list(&TaskFilter::default()).unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].title, "Other");
+    }
+}
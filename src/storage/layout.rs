@@ -0,0 +1,248 @@
+//! Directory layouts for task files
+//!
+//! The default flat layout keeps every task file directly in `.tasks/`. The
+//! sharded layout spreads files over 256 subdirectories named after the low
+//! byte of the task ID (`.tasks/2a/some-task-042.md`), keeping directories
+//! small for repositories with tens of thousands of tasks.
+//!
+//! Reads always look in both places, so a directory can be migrated (or be
+//! half-migrated after an interrupted run) without losing tasks.
+
+use crate::storage::config::ProjectConfig;
+use crate::storage::id_generator::IdGenerator;
+use crate::storage::writes;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::SystemTime;
+
+/// Marker file recording the layout a tasks directory was migrated to
+const LAYOUT_MARKER: &str = ".layout";
+
+/// How task files are arranged inside the tasks directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskLayout {
+    #[default]
+    Flat,
+    Sharded,
+}
+
+impl fmt::Display for TaskLayout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskLayout::Flat => write!(f, "flat"),
+            TaskLayout::Sharded => write!(f, "sharded"),
+        }
+    }
+}
+
+impl FromStr for TaskLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "flat" => Ok(TaskLayout::Flat),
+            "sharded" => Ok(TaskLayout::Sharded),
+            _ => Err(format!("Unknown layout: {}", s)),
+        }
+    }
+}
+
+impl TaskLayout {
+    /// Determine the layout for new writes
+    ///
+    /// The marker file wins because it describes what is on disk; otherwise
+    /// `storage.layout` from `config.toml` is used, defaulting to flat.
+    pub fn detect(tasks_dir: &Path) -> Self {
+        if let Ok(content) = std::fs::read_to_string(tasks_dir.join(LAYOUT_MARKER)) {
+            match content.parse() {
+                Ok(layout) => return layout,
+                Err(e) => log::warn!("Ignoring layout marker: {}", e),
+            }
+        }
+
+        match ProjectConfig::load(tasks_dir) {
+            Ok(config) => config.storage.layout.unwrap_or_default(),
+            Err(e) => {
+                log::warn!("{}", e);
+                TaskLayout::default()
+            }
+        }
+    }
+
+    /// Directory a task file with the given ID belongs in
+    pub fn dir_for(self, tasks_dir: &Path, id: u64) -> PathBuf {
+        match self {
+            TaskLayout::Flat => tasks_dir.to_path_buf(),
+            TaskLayout::Sharded => tasks_dir.join(shard_name(id)),
+        }
+    }
+}
+
+/// Shard directory name for a task ID
+pub fn shard_name(id: u64) -> String {
+    format!("{:02x}", id % 256)
+}
+
+/// Check whether a path is named like a shard directory
+pub fn is_shard_name(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.len() == 2 && n.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+/// Check whether a path is a shard directory
+fn is_shard_dir(path: &Path) -> bool {
+    is_shard_name(path) && path.is_dir()
+}
+
+/// Whether a path looks like a task file: Markdown, but not the README
+pub fn is_task_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "md")
+        && path.file_name().is_some_and(|name| name != "README.md")
+}
+
+/// Whether a path relative to the tasks directory is one `task_files` lists:
+/// a task file at the top or in a shard directory
+pub fn is_task_path(relative: &Path) -> bool {
+    let parent = relative.parent().unwrap_or(Path::new(""));
+    is_task_file(relative)
+        && (parent.as_os_str().is_empty()
+            || (parent.parent() == Some(Path::new("")) && is_shard_name(parent)))
+}
+
+/// Every task file in a tasks directory, flat or sharded
+pub fn task_files(tasks_dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for entry in std::fs::read_dir(tasks_dir)? {
+        let path = entry?.path();
+        if is_shard_dir(&path) {
+            for entry in std::fs::read_dir(&path)? {
+                let path = entry?.path();
+                if is_task_file(&path) {
+                    files.push(path);
+                }
+            }
+        } else if is_task_file(&path) {
+            files.push(path);
+        }
+    }
+
+    Ok(files)
+}
+
+/// Latest modification time of the tasks directory and its shards
+///
+/// Changes whenever a task file is added, removed or renamed anywhere.
+pub fn dir_stamp(tasks_dir: &Path) -> std::io::Result<SystemTime> {
+    let mut latest = std::fs::metadata(tasks_dir)?.modified()?;
+
+    for entry in std::fs::read_dir(tasks_dir)? {
+        let path = entry?.path();
+        if is_shard_dir(&path) {
+            latest = latest.max(std::fs::metadata(&path)?.modified()?);
+        }
+    }
+
+    Ok(latest)
+}
+
+/// Move every task file into `layout` and record it in the marker file
+///
+/// Returns the number of files moved. Empty shard directories are removed
+/// when flattening.
+pub fn migrate(tasks_dir: &Path, layout: TaskLayout) -> std::io::Result<usize> {
+    let mut moved = 0;
+
+    for path in task_files(tasks_dir)? {
+        let Some(id) = IdGenerator::extract_id_from_filename(&path) else {
+            continue;
+        };
+        let Some(name) = path.file_name() else {
+            continue;
+        };
+
+        let dir = layout.dir_for(tasks_dir, id);
+        let target = dir.join(name);
+        if target != path {
+            writes::rename(&path, &target)?;
+            moved += 1;
+        }
+    }
+
+    if layout == TaskLayout::Flat {
+        for entry in std::fs::read_dir(tasks_dir)? {
+            let path = entry?.path();
+            if is_shard_dir(&path) && std::fs::read_dir(&path)?.next().is_none() {
+                writes::remove_dir(&path)?;
+            }
+        }
+    }
+
+    writes::write(&tasks_dir.join(LAYOUT_MARKER), format!("{}\n", layout))?;
+
+    Ok(moved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_shard_name() {
+        assert_eq!(shard_name(1), "01");
+        assert_eq!(shard_name(42), "2a");
+        assert_eq!(shard_name(256), "00");
+    }
+
+    #[test]
+    fn test_is_task_path() {
+        assert!(is_task_path(Path::new("fix-001.md")));
+        assert!(is_task_path(Path::new("0a/fix-010.md")));
+        assert!(!is_task_path(Path::new("README.md")));
+        assert!(!is_task_path(Path::new("config.toml")));
+        assert!(!is_task_path(Path::new("templates/bug.md")));
+        assert!(!is_task_path(Path::new(".broken/fix-002.md")));
+    }
+
+    #[test]
+    fn test_detect_prefers_marker_over_config() {
+        let temp = TempDir::new().unwrap();
+        assert_eq!(TaskLayout::detect(temp.path()), TaskLayout::Flat);
+
+        std::fs::write(
+            ProjectConfig::path(temp.path()),
+            "[storage]\nlayout = \"sharded\"\n",
+        )
+        .unwrap();
+        assert_eq!(TaskLayout::detect(temp.path()), TaskLayout::Sharded);
+
+        std::fs::write(temp.path().join(LAYOUT_MARKER), "flat\n").unwrap();
+        assert_eq!(TaskLayout::detect(temp.path()), TaskLayout::Flat);
+    }
+
+    #[test]
+    fn test_migrate_roundtrip() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        for name in ["a-001.md", "b-002.md", "c-257.md"] {
+            std::fs::write(dir.join(name), "").unwrap();
+        }
+
+        assert_eq!(migrate(dir, TaskLayout::Sharded).unwrap(), 3);
+        assert!(dir.join("01/a-001.md").exists());
+        assert!(dir.join("01/c-257.md").exists());
+        assert!(dir.join("02/b-002.md").exists());
+        assert_eq!(task_files(dir).unwrap().len(), 3);
+        assert_eq!(TaskLayout::detect(dir), TaskLayout::Sharded);
+
+        assert_eq!(migrate(dir, TaskLayout::Flat).unwrap(), 3);
+        assert!(dir.join("a-001.md").exists());
+        assert!(!dir.join("01").exists());
+        assert_eq!(TaskLayout::detect(dir), TaskLayout::Flat);
+    }
+}
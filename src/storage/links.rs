@@ -0,0 +1,252 @@
+//! `#N` and `project:N` references between tasks
+//!
+//! Descriptions can mention other tasks by ID, either in the same project
+//! (`#12`) or in a registered one (`api:12`). References are only picked up
+//! when the project name is known exactly, so `localhost:8080` in a
+//! description doesn't turn into a link.
+
+use crate::models::Task;
+use crate::storage::file_store::{FileStore, FileStoreError, TaskFilter, list_aggregated};
+use crate::storage::location::TaskLocation;
+use crate::storage::registry::ProjectRegistry;
+use crate::storage::registry::project_name;
+use regex::Regex;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+static LINK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:^|[^\w#:/])(?:#|([A-Za-z][\w.-]*):)(\d+)\b").expect("valid regex")
+});
+
+/// A reference to a task found in some text
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TaskLink {
+    /// Project name as written, `None` for `#N`
+    pub project: Option<String>,
+    pub id: u64,
+}
+
+impl fmt::Display for TaskLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.project {
+            Some(project) => write!(f, "{}:{}", project, self.id),
+            None => write!(f, "#{}", self.id),
+        }
+    }
+}
+
+/// A reference together with the task it points at
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedLink {
+    pub link: TaskLink,
+    /// Root of the project the task lives in
+    pub project_path: PathBuf,
+    /// `None` if there is no such task
+    pub task: Option<Task>,
+}
+
+/// A task whose description refers to another one
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backlink {
+    /// Project name, `None` for the task's own project
+    pub project: Option<String>,
+    pub task: Task,
+}
+
+impl Backlink {
+    /// `#id` within the same project, `project:id` otherwise
+    pub fn display_id(&self) -> String {
+        match &self.project {
+            Some(project) => format!("{}:{}", project, self.task.id),
+            None => format!("#{}", self.task.id),
+        }
+    }
+}
+
+/// Every `#N` and `project:N` reference in `text`, in order, without repeats
+///
+/// Project-qualified candidates are returned whatever the name; use
+/// `resolve_links` to keep only those naming a registered project.
+pub fn find_links(text: &str) -> Vec<TaskLink> {
+    let mut links: Vec<TaskLink> = Vec::new();
+    for caps in LINK_RE.captures_iter(text) {
+        let Ok(id) = caps[2].parse() else {
+            continue;
+        };
+        let link = TaskLink {
+            project: caps.get(1).map(|m| m.as_str().to_string()),
+            id,
+        };
+        if !links.contains(&link) {
+            links.push(link);
+        }
+    }
+    links
+}
+
+/// Whether `name` is exactly one of the registry's project names or aliases
+fn is_known(registry: &ProjectRegistry, name: &str) -> bool {
+    registry
+        .names()
+        .iter()
+        .any(|n| n.eq_ignore_ascii_case(name))
+}
+
+/// Look up the tasks referred to in a description of a task in `store`
+///
+/// `project:N` references to projects that aren't registered are dropped;
+/// references to tasks that don't exist are kept with `task: None`.
+pub fn resolve_links(
+    text: &str,
+    store: &FileStore,
+    registry: &ProjectRegistry,
+) -> Vec<ResolvedLink> {
+    let own_root = &store.location().root;
+    let own_name = project_name(own_root);
+    let mut resolved = Vec::new();
+
+    for link in find_links(text) {
+        let project_path = match &link.project {
+            None => own_root.clone(),
+            Some(name) if name.eq_ignore_ascii_case(&own_name) => own_root.clone(),
+            Some(name) if is_known(registry, name) => match registry.resolve_project(name) {
+                Ok(path) => path,
+                Err(_) => continue,
+            },
+            Some(_) => continue,
+        };
+
+        let task = if &project_path == own_root {
+            store.read(link.id).ok()
+        } else {
+            TaskLocation::find_project_from(&project_path)
+                .ok()
+                .and_then(|location| FileStore::new(location).read(link.id).ok())
+        };
+        resolved.push(ResolvedLink {
+            link,
+            project_path,
+            task,
+        });
+    }
+    resolved
+}
+
+/// Whether `task`'s description refers to task `id` of the project called `names`
+///
+/// `local` says whether plain `#id` references count, i.e. whether `task`
+/// lives in that project itself.
+fn refers_to(task: &Task, id: u64, names: &[String], local: bool) -> bool {
+    find_links(&task.description).iter().any(|link| {
+        link.id == id
+            && match &link.project {
+                None => local,
+                Some(project) => names.iter().any(|n| n.eq_ignore_ascii_case(project)),
+            }
+    })
+}
+
+/// Tasks whose descriptions refer to task `id` of `store`
+///
+/// Searches the store itself for `#id` and `name:id`, and every other
+/// registered project for `name:id`, where `name` is the project's
+/// directory name or one of its aliases. Archived tasks are included.
+pub fn backlinks(
+    store: &FileStore,
+    id: u64,
+    registry: &ProjectRegistry,
+) -> Result<Vec<Backlink>, FileStoreError> {
+    let root = &store.location().root;
+    let mut names = registry.aliases_of(root);
+    names.push(project_name(root));
+    let filter = TaskFilter {
+        include_archived: true,
+        ..Default::default()
+    };
+
+    let mut found: Vec<Backlink> = store
+        .list(&filter)?
+        .into_iter()
+        .filter(|task| task.id != id && refers_to(task, id, &names, true))
+        .map(|task| Backlink {
+            project: None,
+            task,
+        })
+        .collect();
+
+    let mut others: Vec<Backlink> = list_aggregated(registry, &filter)?
+        .into_iter()
+        .filter(|agg| &agg.project_path != root && refers_to(&agg.task, id, &names, false))
+        .map(|agg| Backlink {
+            project: Some(agg.project),
+            task: agg.task,
+        })
+        .collect();
+    others.sort_by(|a, b| a.project.cmp(&b.project).then(a.task.id.cmp(&b.task.id)));
+    found.extend(others);
+    Ok(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TaskKind;
+    use crate::storage::test_support::test_store_at;
+    use tempfile::TempDir;
+
+    fn task_with(title: &str, description: &str) -> Task {
+        let mut task = Task::new(0, TaskKind::Task, title);
+        task.description = description.to_string();
+        task
+    }
+
+    #[test]
+    fn test_find_links() {
+        let links: Vec<String> = find_links(
+            "Follows #2 and api:14 (see #2 again).\n\
+             Not links: GH#7, http://localhost:8080, a#3, 10:30, ##",
+        )
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+        assert_eq!(links, vec!["#2", "api:14"]);
+        assert!(find_links("#12").contains(&TaskLink {
+            project: None,
+            id: 12
+        }));
+    }
+
+    #[test]
+    fn test_resolve_links_and_backlinks() {
+        let temp = TempDir::new().unwrap();
+        let api = test_store_at(&temp.path().join("api"));
+        let web = test_store_at(&temp.path().join("web"));
+        let registry =
+            ProjectRegistry::fixed([api.location().root.clone(), web.location().root.clone()]);
+
+        let endpoint = api.create(task_with("Login endpoint", "")).unwrap();
+        api.create(task_with("Rate limit", "Applies to #1, not #9"))
+            .unwrap();
+        web.create(task_with("Login page", "Waits on api:1 and localhost:3000"))
+            .unwrap();
+        web.create(task_with("Unrelated", "See #1")).unwrap();
+
+        let page = web.read(1).unwrap();
+        let links = resolve_links(&page.description, &web, &registry);
+        assert_eq!(links.len(), 1);
+        assert_eq!(links[0].link.to_string(), "api:1");
+        assert_eq!(links[0].task.as_ref().unwrap().title, endpoint.title);
+
+        let links = resolve_links(&api.read(2).unwrap().description, &api, &registry);
+        assert_eq!(links.len(), 2);
+        assert!(links[1].task.is_none());
+
+        let found: Vec<String> = backlinks(&api, 1, &registry)
+            .unwrap()
+            .iter()
+            .map(|b| b.display_id())
+            .collect();
+        assert_eq!(found, vec!["#2", "web:1"]);
+    }
+}
@@ -1,6 +1,7 @@
 //! Task directory location detection and management
 
-use std::path::PathBuf;
+use crate::storage::writes;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
 /// Task directory name
@@ -38,19 +39,65 @@ impl TaskLocation {
     }
 
     /// Find the project task location starting from a specific directory
-    pub fn find_project_from(start: &std::path::Path) -> Result<Self, TaskLocationError> {
-        // Walk up the directory tree looking for .git
+    ///
+    /// Uses the nearest `.tasks` directory walking up from `start`, bounded by
+    /// the git root. Falls back to the git root when no scope exists yet.
+    pub fn find_project_from(start: &Path) -> Result<Self, TaskLocationError> {
+        Self::find_project_below_home(start, dirs::home_dir().as_deref())
+    }
+
+    /// `find_project_from`, never taking the global store in `home` for a
+    /// nested scope
+    fn find_project_below_home(
+        start: &Path,
+        home: Option<&Path>,
+    ) -> Result<Self, TaskLocationError> {
+        let git_root = Self::find_git_root(start)?;
         let mut current = start.to_path_buf();
-        loop {
-            let git_dir = current.join(".git");
-            if git_dir.exists() {
-                let tasks_dir = current.join(TASKS_DIR);
+        while current != git_root {
+            let tasks_dir = current.join(TASKS_DIR);
+            if tasks_dir.is_dir() && Some(current.as_path()) != home {
                 return Ok(TaskLocation {
                     root: current,
                     tasks_dir,
                     is_global: false,
                 });
             }
+            if !current.pop() {
+                break;
+            }
+        }
+
+        Ok(TaskLocation {
+            tasks_dir: git_root.join(TASKS_DIR),
+            root: git_root,
+            is_global: false,
+        })
+    }
+
+    /// Task location rooted at the current directory (for nested scopes)
+    pub fn here() -> Result<Self, TaskLocationError> {
+        let current = std::env::current_dir()?;
+        Self::at(&current)
+    }
+
+    /// Task location rooted at a specific directory inside a git repository
+    pub fn at(dir: &Path) -> Result<Self, TaskLocationError> {
+        Self::find_git_root(dir)?;
+        Ok(TaskLocation {
+            root: dir.to_path_buf(),
+            tasks_dir: dir.join(TASKS_DIR),
+            is_global: false,
+        })
+    }
+
+    /// Find the git repository root containing `start`
+    pub fn find_git_root(start: &Path) -> Result<PathBuf, TaskLocationError> {
+        let mut current = start.to_path_buf();
+        loop {
+            if current.join(".git").exists() {
+                return Ok(current);
+            }
 
             if !current.pop() {
                 return Err(TaskLocationError::NotInGitRepo);
@@ -58,6 +105,15 @@ impl TaskLocation {
         }
     }
 
+    /// Find every task scope (`.tasks` directory) inside this location's git repository
+    pub fn find_scopes(&self) -> Result<Vec<TaskLocation>, TaskLocationError> {
+        let git_root = Self::find_git_root(&self.root)?;
+        let mut scopes = Vec::new();
+        collect_scopes(&git_root, true, &mut scopes)?;
+        scopes.sort_by(|a, b| a.root.cmp(&b.root));
+        Ok(scopes)
+    }
+
     /// Get the global task location (~/.tasks)
     pub fn global() -> Result<Self, TaskLocationError> {
         let home = dirs::home_dir().ok_or(TaskLocationError::NoHomeDirectory)?;
@@ -76,13 +132,64 @@ impl TaskLocation {
 
     /// Create the tasks directory if it doesn't exist
     pub fn ensure_exists(&self) -> Result<(), TaskLocationError> {
-        if !self.tasks_dir.exists() {
-            std::fs::create_dir_all(&self.tasks_dir)?;
-        }
+        writes::create_dir_all(&self.tasks_dir)?;
         Ok(())
     }
 }
 
+/// Make sure a pattern is listed in the tasks directory's .gitignore
+pub fn ensure_gitignored(tasks_dir: &Path, pattern: &str) -> std::io::Result<()> {
+    let path = tasks_dir.join(".gitignore");
+    let existing = std::fs::read_to_string(&path).unwrap_or_default();
+
+    if !existing.lines().any(|line| line.trim() == pattern) {
+        let mut content = existing;
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(pattern);
+        content.push('\n');
+        writes::write(&path, content)?;
+    }
+
+    Ok(())
+}
+
+/// Recursively collect `.tasks` directories, skipping hidden directories and nested repositories
+fn collect_scopes(
+    dir: &Path,
+    is_repo_root: bool,
+    scopes: &mut Vec<TaskLocation>,
+) -> Result<(), TaskLocationError> {
+    if !is_repo_root && dir.join(".git").exists() {
+        return Ok(());
+    }
+
+    let tasks_dir = dir.join(TASKS_DIR);
+    if tasks_dir.is_dir() {
+        scopes.push(TaskLocation {
+            root: dir.to_path_buf(),
+            tasks_dir,
+            is_global: false,
+        });
+    }
+
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || name == "target" || name == "node_modules" {
+            continue;
+        }
+
+        if entry.file_type()?.is_dir() {
+            collect_scopes(&entry.path(), false, scopes)?;
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -124,6 +231,95 @@ mod tests {
         assert!(TaskLocation::find_project_from(temp.path()).is_err());
     }
 
+    #[test]
+    fn test_find_nearest_scope() {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir(temp.path().join(".git")).unwrap();
+        std::fs::create_dir(temp.path().join(".tasks")).unwrap();
+        let service = temp.path().join("services").join("auth");
+        std::fs::create_dir_all(service.join(".tasks")).unwrap();
+        let nested = service.join("src");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let loc = TaskLocation::find_project_from(&nested).unwrap();
+        assert_eq!(loc.root, service);
+        assert_eq!(loc.tasks_dir, service.join(".tasks"));
+
+        let other = temp.path().join("services").join("billing");
+        std::fs::create_dir_all(&other).unwrap();
+        let loc = TaskLocation::find_project_from(&other).unwrap();
+        assert_eq!(loc.root, temp.path());
+    }
+
+    #[test]
+    fn test_scope_bounded_by_git_root() {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir(temp.path().join(".tasks")).unwrap();
+        let repo = temp.path().join("repo");
+        std::fs::create_dir_all(repo.join(".git")).unwrap();
+
+        let loc = TaskLocation::find_project_from(&repo).unwrap();
+        assert_eq!(loc.root, repo);
+        assert!(!loc.exists());
+    }
+
+    #[test]
+    fn test_global_store_is_not_a_project() {
+        let home = TempDir::new().unwrap();
+        std::fs::create_dir(home.path().join(".tasks")).unwrap();
+        let dir = home.path().join("notes");
+        std::fs::create_dir(&dir).unwrap();
+
+        assert!(matches!(
+            TaskLocation::find_project_below_home(&dir, Some(home.path())),
+            Err(TaskLocationError::NotInGitRepo)
+        ));
+
+        // Nor a scope of a repository the home directory is inside of
+        let repo = TempDir::new().unwrap();
+        std::fs::create_dir(repo.path().join(".git")).unwrap();
+        let home = repo.path().join("home");
+        std::fs::create_dir_all(home.join(".tasks")).unwrap();
+        std::fs::create_dir(home.join("notes")).unwrap();
+        let loc = TaskLocation::find_project_below_home(&home.join("notes"), Some(&home)).unwrap();
+        assert_eq!(loc.root, repo.path());
+    }
+
+    #[test]
+    fn test_find_scopes() {
+        let temp = TempDir::new().unwrap();
+        std::fs::create_dir(temp.path().join(".git")).unwrap();
+        std::fs::create_dir(temp.path().join(".tasks")).unwrap();
+        std::fs::create_dir_all(temp.path().join("services/auth/.tasks")).unwrap();
+        std::fs::create_dir_all(temp.path().join("services/billing/.tasks")).unwrap();
+        std::fs::create_dir_all(temp.path().join("vendor/lib/.git")).unwrap();
+        std::fs::create_dir_all(temp.path().join("vendor/lib/.tasks")).unwrap();
+
+        let loc = TaskLocation::find_project_from(temp.path()).unwrap();
+        let scopes = loc.find_scopes().unwrap();
+        let roots: Vec<_> = scopes.iter().map(|s| s.root.clone()).collect();
+        assert_eq!(
+            roots,
+            vec![
+                temp.path().to_path_buf(),
+                temp.path().join("services/auth"),
+                temp.path().join("services/billing"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_at_requires_git_repo() {
+        let temp = TempDir::new().unwrap();
+        assert!(TaskLocation::at(temp.path()).is_err());
+
+        std::fs::create_dir(temp.path().join(".git")).unwrap();
+        let sub = temp.path().join("sub");
+        std::fs::create_dir(&sub).unwrap();
+        let loc = TaskLocation::at(&sub).unwrap();
+        assert_eq!(loc.tasks_dir, sub.join(".tasks"));
+    }
+
     #[test]
     fn test_ensure_exists() {
         let temp = TempDir::new().unwrap();
@@ -0,0 +1,148 @@
+//! Eisenhower matrix: open tasks sorted into urgent/important quadrants
+//!
+//! Urgency comes from due dates and importance from priority, so the matrix
+//! complements the status-based views rather than replacing them.
+
+use crate::models::{Priority, Task};
+use chrono::{Duration, NaiveDate};
+use std::fmt;
+
+/// Default number of days ahead a due date makes a task urgent
+pub const DEFAULT_URGENT_DAYS: i64 = 3;
+
+/// One quadrant of the matrix, in reading order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Quadrant {
+    /// Urgent and important
+    DoFirst,
+    /// Important, not urgent
+    Schedule,
+    /// Urgent, not important
+    Delegate,
+    /// Neither urgent nor important
+    Eliminate,
+}
+
+impl Quadrant {
+    pub const ALL: [Quadrant; 4] = [
+        Quadrant::DoFirst,
+        Quadrant::Schedule,
+        Quadrant::Delegate,
+        Quadrant::Eliminate,
+    ];
+
+    /// Place a task given whether it is urgent and important
+    pub fn of(urgent: bool, important: bool) -> Self {
+        match (urgent, important) {
+            (true, true) => Quadrant::DoFirst,
+            (false, true) => Quadrant::Schedule,
+            (true, false) => Quadrant::Delegate,
+            (false, false) => Quadrant::Eliminate,
+        }
+    }
+}
+
+impl fmt::Display for Quadrant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Quadrant::DoFirst => write!(f, "Do first (urgent, important)"),
+            Quadrant::Schedule => write!(f, "Schedule (important)"),
+            Quadrant::Delegate => write!(f, "Delegate (urgent)"),
+            Quadrant::Eliminate => write!(f, "Eliminate (neither)"),
+        }
+    }
+}
+
+/// Open tasks grouped by quadrant
+#[derive(Debug, Default)]
+pub struct EisenhowerMatrix {
+    quadrants: [Vec<Task>; 4],
+}
+
+impl EisenhowerMatrix {
+    /// Sort the open tasks among `tasks` into quadrants
+    ///
+    /// A task is urgent when it is due within `urgent_days` of `today`
+    /// (overdue included) and important when its priority is high or
+    /// critical. Each quadrant is ordered by due date, then priority, then ID.
+    pub fn new(tasks: impl IntoIterator<Item = Task>, today: NaiveDate, urgent_days: i64) -> Self {
+        let horizon = today + Duration::days(urgent_days);
+        let mut matrix = EisenhowerMatrix::default();
+        for task in tasks.into_iter().filter(|t| t.is_open()) {
+            let urgent = task.due.is_some_and(|due| due.date() <= horizon);
+            let important = matches!(task.priority, Priority::High | Priority::Critical);
+            matrix.quadrants[Quadrant::of(urgent, important) as usize].push(task);
+        }
+        for tasks in &mut matrix.quadrants {
+            tasks.sort_by(|a, b| {
+                // Tasks without a due date go last
+                (a.due.is_none(), a.due)
+                    .cmp(&(b.due.is_none(), b.due))
+                    .then(rank(b.priority).cmp(&rank(a.priority)))
+                    .then(a.id.cmp(&b.id))
+            });
+        }
+        matrix
+    }
+
+    /// Tasks in one quadrant
+    pub fn get(&self, quadrant: Quadrant) -> &[Task] {
+        &self.quadrants[quadrant as usize]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.quadrants.iter().all(|q| q.is_empty())
+    }
+}
+
+fn rank(priority: Priority) -> u8 {
+    match priority {
+        Priority::Low => 0,
+        Priority::Medium => 1,
+        Priority::High => 2,
+        Priority::Critical => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Due, TaskKind};
+
+    fn task(id: u64, priority: Priority, due: Option<NaiveDate>) -> Task {
+        let mut task = Task::new(id, TaskKind::Task, format!("Task {}", id));
+        task.priority = priority;
+        task.due = due.map(Due::Date);
+        task
+    }
+
+    #[test]
+    fn test_quadrants() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 10).unwrap();
+        let soon = Some(today + Duration::days(2));
+        let later = Some(today + Duration::days(10));
+        let overdue = Some(today - Duration::days(1));
+
+        let mut done = task(6, Priority::Critical, overdue);
+        done.complete(None);
+
+        let matrix = EisenhowerMatrix::new(
+            vec![
+                task(1, Priority::High, soon),
+                task(2, Priority::Critical, later),
+                task(3, Priority::Low, overdue),
+                task(4, Priority::Medium, None),
+                task(5, Priority::Critical, overdue),
+                done,
+            ],
+            today,
+            DEFAULT_URGENT_DAYS,
+        );
+
+        let ids = |q| matrix.get(q).iter().map(|t| t.id).collect::<Vec<_>>();
+        assert_eq!(ids(Quadrant::DoFirst), vec![5, 1]);
+        assert_eq!(ids(Quadrant::Schedule), vec![2]);
+        assert_eq!(ids(Quadrant::Delegate), vec![3]);
+        assert_eq!(ids(Quadrant::Eliminate), vec![4]);
+    }
+}
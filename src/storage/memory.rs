@@ -0,0 +1,113 @@
+//! Read-only task storage over files held in memory
+//!
+//! For callers without a filesystem, such as a browser-based viewer built for
+//! wasm32 that fetches a `.tasks/` directory from a repository host. The
+//! files are handed over as (path, content) pairs and parsed once.
+
+use crate::models::{Task, parse_task};
+use crate::storage::config::DisplayConfig;
+use crate::storage::file_store::{TaskFilter, TaskStats};
+use crate::storage::layout;
+use chrono::{DateTime, Utc};
+use std::path::Path;
+
+/// Tasks parsed from in-memory copies of their files
+#[derive(Debug, Default, Clone)]
+pub struct MemoryStore {
+    /// Sorted by ID
+    tasks: Vec<Task>,
+    /// Paths of task files that failed to parse
+    skipped: Vec<String>,
+}
+
+impl MemoryStore {
+    /// Parse the task files among `files`, given as (path, content) pairs
+    ///
+    /// Paths are relative to the tasks directory, flat or sharded. Files that
+    /// aren't task files (config, README, templates) are ignored.
+    pub fn from_files<I, P, C>(files: I) -> Self
+    where
+        I: IntoIterator<Item = (P, C)>,
+        P: AsRef<str>,
+        C: AsRef<str>,
+    {
+        let mut store = MemoryStore::default();
+        for (path, content) in files {
+            let path = path.as_ref();
+            // Directly in the tasks directory or in one of its shards
+            let placed = Path::new(path).parent().is_none_or(|dir| {
+                dir.as_os_str().is_empty()
+                    || (dir.components().count() == 1 && layout::is_shard_name(dir))
+            });
+            if !placed || !layout::is_task_file(Path::new(path)) {
+                continue;
+            }
+            match parse_task(content.as_ref()) {
+                Ok(task) => store.tasks.push(task),
+                Err(_) => store.skipped.push(path.to_string()),
+            }
+        }
+        store.tasks.sort_by_key(|t| t.id);
+        store
+    }
+
+    /// Look up a task by ID
+    pub fn read(&self, id: u64) -> Option<&Task> {
+        self.tasks.iter().find(|t| t.id == id)
+    }
+
+    /// Tasks matching the filter, sorted by ID
+    pub fn list(&self, filter: &TaskFilter) -> Vec<Task> {
+        self.tasks
+            .iter()
+            .filter(|t| filter.matches(t))
+            .cloned()
+            .collect()
+    }
+
+    /// Statistics over every task, counting overdue ones as of `now`
+    ///
+    /// Uses the default "due soon" window, as config.toml isn't read here.
+    pub fn stats(&self, now: DateTime<Utc>) -> TaskStats {
+        let due_soon_days = DisplayConfig::default().due_soon_days();
+        TaskStats::from_tasks(&self.tasks, now, due_soon_days)
+    }
+
+    /// Paths of task files that could not be parsed
+    pub fn skipped(&self) -> &[String] {
+        &self.skipped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TaskKind, TaskStatus, serialize_task};
+
+    #[test]
+    fn test_from_files() {
+        let mut done = Task::new(2, TaskKind::Task, "Done");
+        done.status = TaskStatus::Completed;
+        let open = Task::new(1, TaskKind::Idea, "Open");
+
+        let store = MemoryStore::from_files([
+            ("2a/done-002.md", serialize_task(&done).unwrap()),
+            ("open-001.md", serialize_task(&open).unwrap()),
+            ("README.md", "# Tasks\n".to_string()),
+            ("templates/bug.md", "---\npriority: high\n---\n".to_string()),
+            ("broken-003.md", "no frontmatter".to_string()),
+        ]);
+
+        let ids: Vec<u64> = store
+            .list(&TaskFilter::default())
+            .iter()
+            .map(|t| t.id)
+            .collect();
+        assert_eq!(ids, vec![1, 2]);
+        assert_eq!(store.read(2).unwrap().title, "Done");
+        assert_eq!(store.skipped(), ["broken-003.md"]);
+
+        let stats = store.stats(Utc::now());
+        assert_eq!((stats.total, stats.completed, stats.ideas), (2, 1, 1));
+    }
+}
@@ -1,14 +1,98 @@
 //! Storage layer for task files
 
+#[cfg(feature = "git")]
+pub mod activity;
+pub mod board;
+pub mod config;
+pub mod delegate;
+pub mod deps;
+pub mod doctor;
+pub mod embed;
+pub mod export;
 pub mod file_store;
 pub mod id_generator;
+#[cfg(feature = "import")]
+pub mod import;
+pub mod index;
+pub mod layout;
+pub mod links;
 pub mod location;
+pub mod matrix;
+pub mod memory;
+pub mod order;
+pub mod patch;
+pub mod plan;
 pub mod registry;
+pub mod reminders;
+pub mod retention;
+pub mod review;
+pub mod scaffold;
+#[cfg(feature = "git")]
+pub mod scan;
+pub mod search;
+pub mod subtasks;
+#[cfg(feature = "import")]
+pub mod sync;
+pub mod tags;
+pub mod templates;
+#[cfg(test)]
+pub(crate) mod test_support;
+pub mod urgency;
+pub mod validation;
+#[cfg(feature = "watch")]
+pub mod watcher;
+pub mod wip;
+pub mod writes;
 
+#[cfg(feature = "git")]
+pub use activity::{
+    ActivityEntry, HistoryEntry, activity_log, is_mine, task_creators, task_history,
+};
+pub use board::board_columns;
+pub use config::{
+    ConfigError, EmbedConfig, KindDefaults, ProjectConfig, RetentionConfig, UrgencyConfig,
+    WipConfig, WorkflowConfig,
+};
+pub use delegate::delegate;
+pub use deps::DependencyGraph;
+pub use doctor::{FileProblem, FormatReport, Issue, Quarantined, Renumbered};
+pub use embed::{embed_section, render_checklist};
+pub use export::{ExportManifest, ExportedTask, TimeField, TimeWindow, VerifyError, export_tasks};
 pub use file_store::{
-    AggregatedTask, FileStore, FileStoreError, TaskFilter, TaskStats, list_aggregated,
-    resolve_qualified_id,
+    AGE_BUCKETS, AggregatedTask, FileStore, FileStoreError, OldestOpen, STATS_WEEKS, SyncedFields,
+    TaskCounts, TaskFilter, TaskMatcher, TaskStats, Upsert, WeekActivity, list_aggregated,
+    list_scopes, resolve_qualified_id, set_read_only, set_strict,
 };
 pub use id_generator::IdGenerator;
+#[cfg(feature = "import")]
+pub use import::{ImportFormat, ImportReport, import_github_project, import_json};
+pub use index::TaskIndex;
+pub use layout::TaskLayout;
+pub use links::{Backlink, ResolvedLink, TaskLink, backlinks, find_links, resolve_links};
 pub use location::{TaskLocation, TaskLocationError};
-pub use registry::{ProjectRegistry, ProjectStatus, RegistryError};
+pub use matrix::{EisenhowerMatrix, Quadrant};
+pub use memory::MemoryStore;
+pub use order::{OrderMove, order_key, reorder, sort_by_order};
+pub use patch::TaskPatch;
+pub use plan::{CapacityPlan, PlanLine, plan_capacity};
+pub use registry::{
+    ProjectColor, ProjectRegistry, ProjectStatus, RegistryError, find_projects, project_name,
+};
+pub use reminders::{UpcomingReminder, upcoming_reminders};
+pub use retention::{PrunePlan, plan_prune, prune};
+pub use review::{ReviewAction, ReviewReason, review_queue};
+pub use scaffold::scaffold;
+#[cfg(feature = "git")]
+pub use scan::{CodeTodo, ScanReport, scan_code};
+pub use search::{AggregatedMatch, GrepMatch, grep, search_aggregated};
+pub use subtasks::task_tree;
+#[cfg(feature = "import")]
+pub use sync::{SyncError, SyncStatus, parse_interval, sync_once, sync_round};
+pub use tags::{TagNode, group_by_namespace, tag_counts, tag_matches, tag_tree};
+pub use templates::{TaskTemplate, TemplateError};
+pub use urgency::{rank_by_urgency, urgency};
+pub use validation::{ValidationError, validate_task};
+#[cfg(feature = "watch")]
+pub use watcher::{TaskEvent, TaskWatcher, WatchError};
+pub use wip::{WipViolation, check_wip, wip_overloads};
+pub use writes::{PlannedChange, is_dry_run, set_dry_run, take_planned_changes};
@@ -0,0 +1,99 @@
+//! Hand-curated order of tasks within a status
+//!
+//! Tasks carry an optional `order` number. Tasks sharing a status are ranked
+//! by it, with unordered tasks after the ordered ones by ID. Moving a task
+//! renumbers its status group from 1 so the numbers stay small and dense.
+//! A task that changes status loses its `order`, joining the new status
+//! after the ordered tasks.
+
+use crate::models::Task;
+
+/// Where to move a task within its status
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderMove {
+    Up,
+    Down,
+    /// To a 0-based position; past the end means last
+    To(usize),
+}
+
+/// Sort key within a status: ordered tasks first, then by ID
+pub fn order_key(task: &Task) -> (u64, u64) {
+    (task.order.unwrap_or(u64::MAX), task.id)
+}
+
+/// Sort tasks by status, then by their order within it
+pub fn sort_by_order(tasks: &mut [Task]) {
+    tasks.sort_by_key(|t| (t.status, order_key(t)));
+}
+
+/// Move the task `id` among `peers` (the tasks sharing its status)
+///
+/// Returns its new 0-based position and the tasks whose `order` changed, or
+/// `None` if `id` is not among the peers.
+pub fn reorder(mut peers: Vec<Task>, id: u64, to: OrderMove) -> Option<(usize, Vec<Task>)> {
+    peers.sort_by_key(order_key);
+    let from = peers.iter().position(|t| t.id == id)?;
+    let to = match to {
+        OrderMove::Up => from.saturating_sub(1),
+        OrderMove::Down => from + 1,
+        OrderMove::To(position) => position,
+    }
+    .min(peers.len() - 1);
+
+    let task = peers.remove(from);
+    peers.insert(to, task);
+
+    let changed = peers
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, mut task)| {
+            let order = Some(i as u64 + 1);
+            (task.order != order).then(|| {
+                task.order = order;
+                task
+            })
+        })
+        .collect();
+    Some((to, changed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TaskKind, TaskStatus};
+
+    fn ids(tasks: &[Task]) -> Vec<u64> {
+        tasks.iter().map(|t| t.id).collect()
+    }
+
+    #[test]
+    fn test_reorder() {
+        let peers: Vec<Task> = (1..=4)
+            .map(|id| Task::new(id, TaskKind::Task, format!("Task {}", id)))
+            .collect();
+
+        // Unordered tasks are numbered by ID on the first move
+        let (position, changed) = reorder(peers.clone(), 3, OrderMove::Up).unwrap();
+        assert_eq!(position, 1);
+        let mut moved = changed.clone();
+        moved.sort_by_key(order_key);
+        assert_eq!(ids(&moved), vec![1, 3, 2, 4]);
+
+        // Moving again only touches the swapped pair
+        let mut peers: Vec<Task> = peers
+            .into_iter()
+            .map(|t| moved.iter().find(|m| m.id == t.id).cloned().unwrap_or(t))
+            .collect();
+        let (_, changed) = reorder(peers.clone(), 3, OrderMove::Up).unwrap();
+        assert_eq!(ids(&changed), vec![3, 1]);
+
+        assert_eq!(reorder(peers.clone(), 4, OrderMove::Down).unwrap().0, 3);
+        assert_eq!(reorder(peers.clone(), 2, OrderMove::To(9)).unwrap().0, 3);
+        assert!(reorder(peers.clone(), 7, OrderMove::Up).is_none());
+
+        peers[0].status = TaskStatus::Completed;
+        sort_by_order(&mut peers);
+        assert_eq!(ids(&peers), vec![3, 2, 4, 1]);
+    }
+}
@@ -0,0 +1,194 @@
+//! Partial task updates
+
+use crate::models::{Comment, Due, Priority, ReminderOffset, Resolution, Task};
+use chrono::{DateTime, NaiveDate, Utc};
+
+/// A set of field changes to apply to a task
+///
+/// `None` leaves a field untouched; for optional fields `Some(None)` clears
+/// it. Tag and description edits are applied in the order listed here.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TaskPatch {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub append_description: Option<String>,
+    pub prepend_description: Option<String>,
+    pub priority: Option<Priority>,
+    pub pinned: Option<bool>,
+    pub due: Option<Option<Due>>,
+    pub scheduled: Option<Option<NaiveDate>>,
+    pub remind: Option<Vec<ReminderOffset>>,
+    pub estimate: Option<Option<f64>>,
+    pub tags: Option<Vec<String>>,
+    pub add_tags: Vec<String>,
+    pub remove_tags: Vec<String>,
+    pub external_id: Option<Option<String>>,
+    pub assignee: Option<Option<String>>,
+    pub context: Option<Option<String>>,
+    pub parent: Option<Option<u64>>,
+    pub add_blockers: Vec<u64>,
+    pub remove_blockers: Vec<u64>,
+    pub add_refs: Vec<String>,
+    pub remove_refs: Vec<String>,
+    pub add_comment: Option<Comment>,
+    /// Checklist item to check or uncheck, after the description edits
+    pub toggle_check: Option<usize>,
+    pub branch: Option<Option<String>>,
+    /// Start working at this time (see [`Task::start`])
+    pub start: Option<DateTime<Utc>>,
+    /// End the running work session at this time
+    pub stop: Option<DateTime<Utc>>,
+    /// Close the task with a resolution, recording this commit (see
+    /// [`Task::close`])
+    pub close: Option<(Option<String>, Resolution)>,
+}
+
+impl TaskPatch {
+    /// Check if the patch changes nothing
+    pub fn is_empty(&self) -> bool {
+        *self == TaskPatch::default()
+    }
+
+    /// Apply the changes to a task, without touching its `updated` time
+    pub fn apply(&self, task: &mut Task) {
+        if let Some(ref title) = self.title {
+            task.title = title.clone();
+        }
+        if let Some(ref description) = self.description {
+            task.description = description.clone();
+        }
+        if let Some(ref text) = self.append_description {
+            task.append_description(text);
+        }
+        if let Some(ref text) = self.prepend_description {
+            task.prepend_description(text);
+        }
+        if let Some(index) = self.toggle_check {
+            task.toggle_check(index);
+        }
+        if let Some(priority) = self.priority {
+            task.priority = priority;
+        }
+        if let Some(pinned) = self.pinned {
+            task.pinned = pinned;
+        }
+        if let Some(due) = self.due {
+            task.due = due;
+        }
+        if let Some(scheduled) = self.scheduled {
+            task.scheduled = scheduled;
+        }
+        if let Some(ref remind) = self.remind {
+            task.remind = remind.clone();
+        }
+        if let Some(estimate) = self.estimate {
+            task.estimate = estimate;
+        }
+        if let Some(ref tags) = self.tags {
+            task.tags = tags.clone();
+        }
+        for tag in &self.add_tags {
+            task.add_tag(tag);
+        }
+        for tag in &self.remove_tags {
+            task.remove_tag(tag);
+        }
+        if let Some(ref external_id) = self.external_id {
+            task.external_id = external_id.clone();
+        }
+        if let Some(ref assignee) = self.assignee {
+            task.assignee = assignee.clone();
+        }
+        if let Some(ref context) = self.context {
+            task.context = context.clone();
+        }
+        if let Some(parent) = self.parent {
+            task.parent = parent;
+        }
+        for &id in &self.add_blockers {
+            task.add_blocker(id);
+        }
+        for &id in &self.remove_blockers {
+            task.remove_blocker(id);
+        }
+        for reference in &self.add_refs {
+            task.add_ref(reference);
+        }
+        for reference in &self.remove_refs {
+            task.remove_ref(reference);
+        }
+        if let Some(ref comment) = self.add_comment {
+            task.comments.push(comment.clone());
+        }
+        if let Some(ref branch) = self.branch {
+            task.branch = branch.clone();
+        }
+        if let Some(at) = self.start {
+            task.start(at);
+        }
+        if let Some(at) = self.stop {
+            task.stop(at);
+        }
+        if let Some((ref commit, resolution)) = self.close {
+            task.close(commit.clone(), resolution);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TaskKind;
+
+    #[test]
+    fn test_apply_only_touches_set_fields() {
+        let mut task = Task::new(1, TaskKind::Task, "Original");
+        task.tags = vec!["a".to_string(), "b".to_string()];
+        task.context = Some("@home".to_string());
+        task.description = "Body".to_string();
+
+        let patch = TaskPatch {
+            priority: Some(Priority::High),
+            add_tags: vec!["c".to_string()],
+            remove_tags: vec!["a".to_string()],
+            context: Some(None),
+            append_description: Some("More".to_string()),
+            pinned: Some(true),
+            estimate: Some(Some(1.5)),
+            ..Default::default()
+        };
+        assert!(!patch.is_empty());
+        patch.apply(&mut task);
+
+        assert_eq!(task.title, "Original");
+        assert_eq!(task.priority, Priority::High);
+        assert!(task.pinned);
+        assert_eq!(task.estimate, Some(1.5));
+        assert_eq!(task.tags, vec!["b", "c"]);
+        assert_eq!(task.context, None);
+        assert_eq!(task.description, "Body\n\nMore");
+    }
+
+    #[test]
+    fn test_apply_start_and_stop() {
+        let mut task = Task::new(1, TaskKind::Task, "Timed");
+        let start = Utc::now();
+        TaskPatch {
+            start: Some(start),
+            branch: Some(Some("task/1-timed".to_string())),
+            ..Default::default()
+        }
+        .apply(&mut task);
+        assert_eq!(task.status, crate::models::TaskStatus::InProgress);
+        assert_eq!(task.branch.as_deref(), Some("task/1-timed"));
+        assert!(task.active_session().is_some());
+
+        TaskPatch {
+            stop: Some(start + chrono::Duration::minutes(5)),
+            ..Default::default()
+        }
+        .apply(&mut task);
+        assert!(task.active_session().is_none());
+        assert_eq!(task.time_spent(start).num_minutes(), 5);
+    }
+}
@@ -0,0 +1,169 @@
+//! Capacity planning: estimated work against the hours available
+//!
+//! Counts open tasks due on or before the end date, plus every task in
+//! progress, and compares their summed estimates with the `[capacity]`
+//! weekly hours over the days until then.
+
+use crate::models::{Task, TaskStatus};
+use crate::storage::config::CapacityConfig;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+
+/// Estimated work and capacity for the project or one assignee
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanLine {
+    /// `None` for the whole project, or for unassigned tasks in `by_assignee`
+    pub assignee: Option<String>,
+    pub tasks: usize,
+    pub estimated_hours: f64,
+    /// Tasks counted that have no estimate
+    pub unestimated: usize,
+    /// Hours available until the end date, when a capacity is configured
+    pub capacity_hours: Option<f64>,
+}
+
+impl PlanLine {
+    fn new(assignee: Option<String>, capacity_hours: Option<f64>) -> Self {
+        PlanLine {
+            assignee,
+            tasks: 0,
+            estimated_hours: 0.0,
+            unestimated: 0,
+            capacity_hours,
+        }
+    }
+
+    fn add(&mut self, task: &Task) {
+        self.tasks += 1;
+        match task.estimate {
+            Some(hours) => self.estimated_hours += hours,
+            None => self.unestimated += 1,
+        }
+    }
+
+    /// Hours of estimated work beyond the capacity, if any
+    pub fn overcommitted_by(&self) -> Option<f64> {
+        self.capacity_hours
+            .map(|capacity| self.estimated_hours - capacity)
+            .filter(|over| *over > 0.0)
+    }
+}
+
+/// Work planned until a date against the available capacity
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapacityPlan {
+    pub until: NaiveDate,
+    /// Weeks from today until the end date (fractional)
+    pub weeks: f64,
+    pub total: PlanLine,
+    /// One line per assignee, by name, then unassigned tasks
+    pub by_assignee: Vec<PlanLine>,
+}
+
+/// Whether a task counts towards the work due by `until`
+///
+/// Tasks in progress always do; other open tasks, including those in a
+/// custom status, if they are due by then.
+fn is_planned(task: &Task, until: NaiveDate) -> bool {
+    task.status == TaskStatus::InProgress
+        || (task.is_open() && task.due.is_some_and(|due| due.date() <= until))
+}
+
+/// Sum up the work due by `until` and the capacity from `today` until then
+pub fn plan_capacity(
+    tasks: &[Task],
+    config: &CapacityConfig,
+    today: NaiveDate,
+    until: NaiveDate,
+) -> CapacityPlan {
+    // The end date is included
+    let weeks = ((until - today).num_days() + 1).max(0) as f64 / 7.0;
+    let hours = |weekly: f64| weekly * weeks;
+
+    let mut total = PlanLine::new(None, config.weekly_hours.map(hours));
+    let mut assigned: BTreeMap<String, PlanLine> = BTreeMap::new();
+    let mut unassigned = PlanLine::new(None, None);
+
+    for task in tasks.iter().filter(|t| is_planned(t, until)) {
+        total.add(task);
+        match &task.assignee {
+            Some(assignee) => assigned
+                .entry(assignee.clone())
+                .or_insert_with(|| {
+                    PlanLine::new(
+                        Some(assignee.clone()),
+                        config.assignees.get(assignee).copied().map(hours),
+                    )
+                })
+                .add(task),
+            None => unassigned.add(task),
+        }
+    }
+
+    let mut by_assignee: Vec<PlanLine> = assigned.into_values().collect();
+    if unassigned.tasks > 0 {
+        by_assignee.push(unassigned);
+    }
+    CapacityPlan {
+        until,
+        weeks,
+        total,
+        by_assignee,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Due, TaskKind};
+
+    #[test]
+    fn test_plan_capacity() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        let until = today + chrono::Days::new(13);
+        let task = |id: u64, estimate: Option<f64>, assignee: Option<&str>, due_in: Option<u64>| {
+            let mut task = Task::new(id, TaskKind::Task, format!("Task {}", id));
+            task.estimate = estimate;
+            task.assignee = assignee.map(str::to_string);
+            task.due = due_in.map(|days| Due::Date(today + chrono::Days::new(days)));
+            task
+        };
+        let mut started = task(4, Some(10.0), Some("bo"), None);
+        started.status = TaskStatus::InProgress;
+        let tasks = vec![
+            task(1, Some(30.0), Some("ana"), Some(3)),
+            task(2, None, None, Some(10)),
+            // Due after the end date, or undated and not started
+            task(3, Some(99.0), Some("ana"), Some(20)),
+            task(5, Some(99.0), None, None),
+            started,
+        ];
+        let config = CapacityConfig {
+            weekly_hours: Some(15.0),
+            assignees: BTreeMap::from([("ana".to_string(), 10.0)]),
+        };
+
+        let plan = plan_capacity(&tasks, &config, today, until);
+        assert_eq!(plan.weeks, 2.0);
+        assert_eq!(plan.total.tasks, 3);
+        assert_eq!(plan.total.estimated_hours, 40.0);
+        assert_eq!(plan.total.unestimated, 1);
+        assert_eq!(plan.total.overcommitted_by(), Some(10.0));
+
+        let names: Vec<Option<&str>> = plan
+            .by_assignee
+            .iter()
+            .map(|l| l.assignee.as_deref())
+            .collect();
+        assert_eq!(names, vec![Some("ana"), Some("bo"), None]);
+        assert_eq!(plan.by_assignee[0].overcommitted_by(), Some(10.0));
+        assert_eq!(plan.by_assignee[1].capacity_hours, None);
+
+        // Open in a custom status counts like pending
+        let mut review = task(6, Some(2.0), None, Some(1));
+        review.status = TaskStatus::define("review").unwrap();
+        assert!(is_planned(&review, until));
+        review.status = TaskStatus::Completed;
+        assert!(!is_planned(&review, until));
+    }
+}
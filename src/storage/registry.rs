@@ -1,15 +1,32 @@
 //! Project registry for aggregating tasks across multiple projects
 
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 use thiserror::Error;
 
+use crate::storage::config::{ProjectConfig, WorkflowConfig};
 use crate::storage::location::TaskLocation;
-use crate::storage::{FileStore, TaskFilter};
+#[cfg(feature = "import")]
+use crate::storage::sync::SyncStatus;
+use crate::storage::writes;
+use crate::storage::{FileStore, TaskCounts};
 
 /// Registry file name within the global tasks directory
-const REGISTRY_FILE: &str = ".projects";
+pub(crate) const REGISTRY_FILE: &str = ".projects";
+
+/// How many directories deep `find_projects` looks below where it starts
+const SCAN_DEPTH: usize = 4;
+
+/// Prefix of settings lines that give a project a color (`color:cyan=path`)
+const COLOR_PREFIX: &str = "color:";
+
+/// Extension of the file next to the registry holding aliases and colors
+///
+/// The registry itself stays one path per line, which is all older
+/// versions understand.
+const SETTINGS_EXTENSION: &str = "meta";
 
 /// Errors related to the project registry
 #[derive(Debug, Error)]
@@ -18,6 +35,115 @@ pub enum RegistryError {
     NoHomeDirectory,
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
+    #[error("The project list is fixed and cannot be changed")]
+    Fixed,
+    #[error("Project not found: {name}{}", known_projects(.known))]
+    ProjectNotFound { name: String, known: Vec<String> },
+    #[error("Project '{name}' is ambiguous; could be {}", .candidates.join(", "))]
+    AmbiguousProject {
+        name: String,
+        candidates: Vec<String>,
+    },
+    #[error("Invalid alias '{0}': use letters, digits, '-', '_' or '.'")]
+    InvalidAlias(String),
+    #[error("Not a registered project: {}", .0.display())]
+    NotRegistered(PathBuf),
+}
+
+/// Color a project is shown in by aggregated views
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectColor {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Gray,
+}
+
+impl ProjectColor {
+    /// Every color, in the order they are listed in help and errors
+    pub const ALL: [ProjectColor; 7] = [
+        ProjectColor::Red,
+        ProjectColor::Green,
+        ProjectColor::Yellow,
+        ProjectColor::Blue,
+        ProjectColor::Magenta,
+        ProjectColor::Cyan,
+        ProjectColor::Gray,
+    ];
+}
+
+impl std::fmt::Display for ProjectColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ProjectColor::Red => "red",
+            ProjectColor::Green => "green",
+            ProjectColor::Yellow => "yellow",
+            ProjectColor::Blue => "blue",
+            ProjectColor::Magenta => "magenta",
+            ProjectColor::Cyan => "cyan",
+            ProjectColor::Gray => "gray",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl std::str::FromStr for ProjectColor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let wanted = match s.to_lowercase().as_str() {
+            "grey" => "gray".to_string(),
+            other => other.to_string(),
+        };
+        Self::ALL
+            .into_iter()
+            .find(|c| c.to_string() == wanted)
+            .ok_or_else(|| {
+                let names: Vec<String> = Self::ALL.iter().map(|c| c.to_string()).collect();
+                format!("Unknown color: {} (expected {})", s, names.join(", "))
+            })
+    }
+}
+
+fn known_projects(known: &[String]) -> String {
+    if known.is_empty() {
+        String::new()
+    } else {
+        format!(" (known: {})", known.join(", "))
+    }
+}
+
+/// Whether a registry line's `name=` prefix is an alias rather than part of a path
+fn is_alias(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+/// Name a project goes by in qualified IDs: its directory name
+pub fn project_name(root: &Path) -> String {
+    root.file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| root.to_string_lossy().to_string())
+}
+
+/// Lines of a registry file, with a trailing newline if there are any
+fn lines(lines: &[String]) -> String {
+    if lines.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n", lines.join("\n"))
+    }
+}
+
+/// Whether the characters of `needle` appear in `haystack` in order
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut chars = haystack.chars();
+    needle.chars().all(|c| chars.any(|h| h == c))
 }
 
 /// Status information for a registered project
@@ -31,61 +157,87 @@ pub struct ProjectStatus {
     pub exists: bool,
     /// Whether the .tasks directory exists
     pub has_tasks_dir: bool,
-    /// Number of open tasks (pending + in-progress)
-    pub open_tasks: usize,
-    /// Total number of tasks
-    pub total_tasks: usize,
+    /// Alternative names for the project
+    pub aliases: Vec<String>,
+    /// Color the project is shown in, if one was set
+    pub color: Option<ProjectColor>,
+    /// Task counts, worked out the first time they are asked for
+    counts: OnceLock<TaskCounts>,
+    /// Last GitHub sync, if the project was ever synced
+    #[cfg(feature = "import")]
+    pub sync: Option<SyncStatus>,
 }
 
 impl ProjectStatus {
     /// Create a new ProjectStatus by inspecting the project path
     pub fn from_path(path: &Path) -> Self {
-        let name = path
-            .file_name()
-            .map(|s| s.to_string_lossy().to_string())
-            .unwrap_or_else(|| path.to_string_lossy().to_string());
+        let name = project_name(path);
 
         let exists = path.exists();
         let tasks_dir = path.join(".tasks");
         let has_tasks_dir = tasks_dir.exists();
 
-        let (open_tasks, total_tasks) = if has_tasks_dir {
-            if let Ok(location) = TaskLocation::find_project_from(path) {
-                let store = FileStore::new(location);
-                if let Ok(tasks) = store.list(&TaskFilter {
-                    include_archived: true,
-                    ..Default::default()
-                }) {
-                    let open = tasks.iter().filter(|t| t.is_open()).count();
-                    (open, tasks.len())
-                } else {
-                    (0, 0)
-                }
-            } else {
-                (0, 0)
-            }
-        } else {
-            (0, 0)
-        };
-
         ProjectStatus {
             path: path.to_path_buf(),
             name,
+            aliases: Vec::new(),
+            color: None,
             exists,
             has_tasks_dir,
-            open_tasks,
-            total_tasks,
+            counts: OnceLock::new(),
+            #[cfg(feature = "import")]
+            sync: SyncStatus::load(&tasks_dir),
         }
     }
+
+    /// Open and total task counts
+    ///
+    /// Listing projects doesn't count anything until a count is needed,
+    /// and then only reads each task's status (see `FileStore::count_tasks`).
+    pub fn counts(&self) -> TaskCounts {
+        *self.counts.get_or_init(|| {
+            if !self.has_tasks_dir {
+                return TaskCounts::default();
+            }
+            TaskLocation::find_project_from(&self.path)
+                .ok()
+                .and_then(|location| FileStore::new(location).count_tasks().ok())
+                .unwrap_or_default()
+        })
+    }
+
+    /// Number of open tasks (pending + in-progress)
+    pub fn open_tasks(&self) -> usize {
+        self.counts().open
+    }
+
+    /// Total number of tasks
+    pub fn total_tasks(&self) -> usize {
+        self.counts().total
+    }
+
+    /// One-line summary of the last GitHub sync, if there was one
+    ///
+    /// Always `None` without the `import` feature.
+    pub fn sync_summary(&self) -> Option<String> {
+        #[cfg(feature = "import")]
+        return self.sync.as_ref().map(SyncStatus::summary);
+        #[cfg(not(feature = "import"))]
+        None
+    }
 }
 
 /// Registry of projects for aggregated task views
 #[derive(Debug)]
 pub struct ProjectRegistry {
-    /// Path to the registry file (~/.tasks/.projects)
-    registry_path: PathBuf,
+    /// Path to the registry file (~/.tasks/.projects); None for a fixed list
+    registry_path: Option<PathBuf>,
     /// Registered project paths
     projects: HashSet<PathBuf>,
+    /// Alternative names, each pointing at a registered project
+    aliases: BTreeMap<String, PathBuf>,
+    /// Colors of registered projects
+    colors: BTreeMap<PathBuf, ProjectColor>,
 }
 
 impl ProjectRegistry {
@@ -97,46 +249,104 @@ impl ProjectRegistry {
     }
 
     /// Load the registry from a specific path
+    ///
+    /// Each line is a project path. Aliases (`alias=path`) and colors
+    /// (`color:<color>=path`) are kept in a `.meta` file next to it, and
+    /// are also accepted in the registry itself, where earlier versions
+    /// wrote them. A setting for a project that isn't registered, or with
+    /// an unknown color, is ignored.
     pub fn load_from(path: &Path) -> Result<Self, RegistryError> {
-        let projects = if path.exists() {
-            let content = fs::read_to_string(path)?;
-            content
-                .lines()
-                .filter(|line| !line.trim().is_empty())
-                .map(|line| PathBuf::from(line.trim()))
-                .collect()
-        } else {
-            HashSet::new()
+        let mut projects = HashSet::new();
+        let mut aliases = BTreeMap::new();
+        let mut colors = BTreeMap::new();
+        let mut read = |file: &Path, settings_only: bool| -> Result<(), RegistryError> {
+            if !file.exists() {
+                return Ok(());
+            }
+            let content = fs::read_to_string(file)?;
+            for line in content.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                if let Some((color, project)) = line
+                    .strip_prefix(COLOR_PREFIX)
+                    .and_then(|rest| rest.split_once('='))
+                {
+                    match color.parse() {
+                        Ok(color) => {
+                            colors.insert(PathBuf::from(project.trim()), color);
+                        }
+                        Err(e) => log::warn!("Ignoring registry line '{}': {}", line, e),
+                    }
+                    continue;
+                }
+                match line.split_once('=') {
+                    Some((alias, project)) if is_alias(alias) => {
+                        aliases.insert(alias.to_string(), PathBuf::from(project.trim()));
+                    }
+                    _ if settings_only => log::warn!("Ignoring registry line '{}'", line),
+                    _ => {
+                        projects.insert(PathBuf::from(line));
+                    }
+                }
+            }
+            Ok(())
         };
+        read(path, false)?;
+        read(&Self::settings_path(path), true)?;
 
+        // Aliases written into the registry by earlier versions also
+        // registered their project
+        projects.extend(aliases.values().cloned());
+        colors.retain(|p, _| projects.contains(p));
         Ok(ProjectRegistry {
-            registry_path: path.to_path_buf(),
+            registry_path: Some(path.to_path_buf()),
             projects,
+            aliases,
+            colors,
         })
     }
 
-    /// Save the registry to disk
-    pub fn save(&self) -> Result<(), RegistryError> {
-        // Ensure parent directory exists
-        if let Some(parent) = self.registry_path.parent() {
-            fs::create_dir_all(parent)?;
+    /// The aliases and colors file that goes with a registry file
+    fn settings_path(registry_path: &Path) -> PathBuf {
+        registry_path.with_extension(SETTINGS_EXTENSION)
+    }
+
+    /// A registry over a fixed set of project paths that is never saved
+    pub fn fixed(paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        ProjectRegistry {
+            registry_path: None,
+            projects: paths.into_iter().collect(),
+            aliases: BTreeMap::new(),
+            colors: BTreeMap::new(),
         }
+    }
 
-        let content: String = self
+    /// Save the registry to disk
+    ///
+    /// Project paths go to the registry file, aliases and colors to the
+    /// settings file next to it.
+    pub fn save(&self) -> Result<(), RegistryError> {
+        let registry_path = self.registry_path.as_ref().ok_or(RegistryError::Fixed)?;
+        let mut projects: Vec<String> = self
             .projects
             .iter()
             .map(|p| p.to_string_lossy().to_string())
-            .collect::<Vec<_>>()
-            .join("\n");
-
-        // Add trailing newline if there's content
-        let content = if content.is_empty() {
-            content
-        } else {
-            format!("{}\n", content)
-        };
+            .collect();
+        projects.sort();
+        let settings: Vec<String> = self
+            .aliases
+            .iter()
+            .map(|(alias, p)| format!("{}={}", alias, p.to_string_lossy()))
+            .chain(
+                self.colors
+                    .iter()
+                    .map(|(p, color)| format!("{}{}={}", COLOR_PREFIX, color, p.to_string_lossy())),
+            )
+            .collect();
 
-        fs::write(&self.registry_path, content)?;
+        writes::write(registry_path, lines(&projects))?;
+        let settings_path = Self::settings_path(registry_path);
+        if !settings.is_empty() || settings_path.exists() {
+            writes::write(&settings_path, lines(&settings))?;
+        }
         Ok(())
     }
 
@@ -165,11 +375,74 @@ impl ProjectRegistry {
                 .unwrap_or(false);
 
         if removed {
+            let projects = &self.projects;
+            self.aliases.retain(|_, p| projects.contains(p));
+            self.colors.retain(|p, _| projects.contains(p));
             self.save()?;
         }
         Ok(removed)
     }
 
+    /// Give a registered project an alternative name, replacing any
+    /// project the alias pointed at before
+    pub fn set_alias(&mut self, alias: &str, path: &Path) -> Result<(), RegistryError> {
+        if !is_alias(alias) {
+            return Err(RegistryError::InvalidAlias(alias.to_string()));
+        }
+        let project = self.registered(path)?;
+
+        self.aliases.insert(alias.to_string(), project);
+        self.save()
+    }
+
+    /// Set or clear the color of a registered project
+    pub fn set_color(
+        &mut self,
+        path: &Path,
+        color: Option<ProjectColor>,
+    ) -> Result<(), RegistryError> {
+        let project = self.registered(path)?;
+        match color {
+            Some(color) => self.colors.insert(project, color),
+            None => self.colors.remove(&project),
+        };
+        self.save()
+    }
+
+    /// Color of a registered project, if one was set
+    pub fn color_of(&self, path: &Path) -> Option<ProjectColor> {
+        self.colors.get(path).copied()
+    }
+
+    /// The registered path of a project, given as is or canonicalized
+    fn registered(&self, path: &Path) -> Result<PathBuf, RegistryError> {
+        [path.to_path_buf()]
+            .into_iter()
+            .chain(path.canonicalize().ok())
+            .find(|p| self.projects.contains(p))
+            .ok_or_else(|| RegistryError::NotRegistered(path.to_path_buf()))
+    }
+
+    /// Aliases of a registered project, sorted
+    pub fn aliases_of(&self, path: &Path) -> Vec<String> {
+        self.aliases
+            .iter()
+            .filter(|(_, p)| p.as_path() == path)
+            .map(|(alias, _)| alias.clone())
+            .collect()
+    }
+
+    /// Every name a project can be referred to by: directory names and aliases
+    pub fn names(&self) -> Vec<String> {
+        let names: BTreeSet<String> = self
+            .projects
+            .iter()
+            .map(|p| project_name(p))
+            .chain(self.aliases.keys().cloned())
+            .collect();
+        names.into_iter().collect()
+    }
+
     /// Get all registered project paths
     pub fn projects(&self) -> &HashSet<PathBuf> {
         &self.projects
@@ -185,12 +458,30 @@ impl ProjectRegistry {
         self.projects.len()
     }
 
+    /// Workflow with every custom status a registered project defines, for
+    /// checking statuses used across projects
+    pub fn workflow(&self) -> WorkflowConfig {
+        let mut workflow = WorkflowConfig::default();
+        for path in &self.projects {
+            if let Ok(location) = TaskLocation::find_project_from(path)
+                && let Ok(config) = ProjectConfig::load(&location.tasks_dir)
+            {
+                workflow.statuses.extend(config.workflow.statuses);
+            }
+        }
+        workflow
+    }
+
     /// Get status information for all registered projects
     pub fn project_statuses(&self) -> Vec<ProjectStatus> {
         let mut statuses: Vec<_> = self
             .projects
             .iter()
-            .map(|p| ProjectStatus::from_path(p))
+            .map(|p| ProjectStatus {
+                aliases: self.aliases_of(p),
+                color: self.color_of(p),
+                ..ProjectStatus::from_path(p)
+            })
             .collect();
 
         // Sort by name
@@ -198,34 +489,104 @@ impl ProjectRegistry {
         statuses
     }
 
-    /// Find a project by name (case-insensitive prefix match)
+    /// Find a project by name, see `resolve_project`
     pub fn find_project(&self, name: &str) -> Option<PathBuf> {
-        let name_lower = name.to_lowercase();
+        self.resolve_project(name).ok()
+    }
 
-        // First try exact match
-        for path in &self.projects {
-            if let Some(dir_name) = path.file_name()
-                && dir_name.to_string_lossy().to_lowercase() == name_lower
-            {
-                return Some(path.clone());
-            }
-        }
+    /// Find a project by directory name or alias, ignoring case
+    ///
+    /// Tries, in order: an exact alias or name, a unique prefix, and a unique
+    /// fuzzy match (the letters of `name` in order, e.g. `gtsk` for
+    /// `gittask`). Several matches at the first step that finds any are
+    /// reported as ambiguous rather than guessed between.
+    pub fn resolve_project(&self, name: &str) -> Result<PathBuf, RegistryError> {
+        self.resolve(name, true)
+    }
 
-        // Then try prefix match
-        let mut matches: Vec<_> = self
+    /// Like `resolve_project`, but without the fuzzy match
+    ///
+    /// For commands that write, where a guess could change the wrong project.
+    pub fn resolve_project_strict(&self, name: &str) -> Result<PathBuf, RegistryError> {
+        self.resolve(name, false)
+    }
+
+    fn resolve(&self, name: &str, fuzzy: bool) -> Result<PathBuf, RegistryError> {
+        let wanted = name.to_lowercase();
+        let candidates: Vec<(String, &PathBuf)> = self
             .projects
             .iter()
-            .filter(|path| {
-                path.file_name()
-                    .map(|n| n.to_string_lossy().to_lowercase().starts_with(&name_lower))
-                    .unwrap_or(false)
-            })
+            .map(|p| (project_name(p), p))
+            .chain(self.aliases.iter().map(|(alias, p)| (alias.clone(), p)))
             .collect();
 
-        if matches.len() == 1 {
-            Some(matches.pop()?.clone())
-        } else {
-            None
+        let steps: [&dyn Fn(&str) -> bool; 3] =
+            [&|n| n == wanted, &|n| n.starts_with(&wanted), &|n| {
+                is_subsequence(&wanted, n)
+            }];
+        for matches in &steps[..if fuzzy { 3 } else { 2 }] {
+            let found: BTreeMap<&PathBuf, &str> = candidates
+                .iter()
+                .filter(|(n, _)| matches(&n.to_lowercase()))
+                .map(|(n, p)| (*p, n.as_str()))
+                .collect();
+            match found.len() {
+                0 => continue,
+                1 => return Ok(found.into_keys().next().cloned().unwrap_or_default()),
+                _ => {
+                    return Err(RegistryError::AmbiguousProject {
+                        name: name.to_string(),
+                        candidates: found
+                            .iter()
+                            .map(|(p, n)| format!("{} ({})", n, p.display()))
+                            .collect(),
+                    });
+                }
+            }
+        }
+
+        Err(RegistryError::ProjectNotFound {
+            name: name.to_string(),
+            known: self.names(),
+        })
+    }
+}
+
+/// Repositories under `dir` that track tasks, for linking in bulk
+///
+/// A repository counts when its root has both `.git` and a `.tasks`
+/// directory. Hidden directories, build output and unreadable directories
+/// are skipped, nothing inside a repository is searched further, and the
+/// search stops a few levels below `dir`. Paths are canonical and sorted.
+pub fn find_projects(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut found = Vec::new();
+    collect_projects(&dir.canonicalize()?, SCAN_DEPTH, &mut found);
+    found.sort();
+    Ok(found)
+}
+
+fn collect_projects(dir: &Path, depth: usize, found: &mut Vec<PathBuf>) {
+    if dir.join(".git").exists() {
+        if dir.join(".tasks").is_dir() {
+            found.push(dir.to_path_buf());
+        }
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    if depth == 0 {
+        return;
+    }
+
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || name == "target" || name == "node_modules" {
+            continue;
+        }
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            collect_projects(&entry.path(), depth - 1, found);
         }
     }
 }
@@ -244,6 +605,44 @@ mod tests {
         assert!(registry.is_empty());
     }
 
+    #[test]
+    fn test_find_projects() {
+        let temp = TempDir::new().unwrap();
+        let root = temp.path().canonicalize().unwrap();
+        for dir in [
+            "code/api/.git",
+            "code/api/.tasks",
+            "code/api/sub/.git",
+            "code/api/sub/.tasks",
+            "code/plain/.git",
+            "notes/.tasks",
+            ".hidden/app/.git",
+            ".hidden/app/.tasks",
+            "a/b/c/d/e/.git",
+            "a/b/c/d/e/.tasks",
+        ] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+
+        let found = find_projects(&root).unwrap();
+        assert_eq!(found, vec![root.join("code/api")]);
+        assert!(find_projects(&root.join("missing")).is_err());
+    }
+
+    #[test]
+    fn test_fixed_registry() {
+        let temp = TempDir::new().unwrap();
+        let a = temp.path().join("alpha");
+        let b = temp.path().join("beta");
+        fs::create_dir(&a).unwrap();
+        fs::create_dir(&b).unwrap();
+
+        let mut registry = ProjectRegistry::fixed([a.clone()]);
+        assert_eq!(registry.find_project("alpha"), Some(a));
+        assert!(registry.find_project("beta").is_none());
+        assert!(matches!(registry.link(&b), Err(RegistryError::Fixed)));
+    }
+
     #[test]
     fn test_link_project() {
         let temp = TempDir::new().unwrap();
@@ -337,6 +736,140 @@ mod tests {
         assert!(registry.find_project("nonexistent").is_none());
     }
 
+    #[test]
+    fn test_resolve_ambiguous_and_fuzzy() {
+        let temp = TempDir::new().unwrap();
+        let mut registry = ProjectRegistry::load_from(&temp.path().join(".projects")).unwrap();
+        for name in ["api-server", "api-client", "webapp"] {
+            let path = temp.path().join(name);
+            fs::create_dir(&path).unwrap();
+            registry.link(&path).unwrap();
+        }
+
+        let err = registry.resolve_project("api").unwrap_err();
+        assert!(matches!(
+            err,
+            RegistryError::AmbiguousProject { ref candidates, .. } if candidates.len() == 2
+        ));
+        assert!(err.to_string().contains("api-client"));
+
+        // Fuzzy: letters in order, but only when nothing is written
+        let webapp = registry.resolve_project("wbp").unwrap();
+        assert!(webapp.ends_with("webapp"));
+        assert!(registry.resolve_project_strict("wbp").is_err());
+        assert_eq!(registry.resolve_project_strict("web").unwrap(), webapp);
+
+        let err = registry.resolve_project("mobile").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Project not found: mobile (known: api-client, api-server, webapp)"
+        );
+    }
+
+    #[test]
+    fn test_aliases() {
+        let temp = TempDir::new().unwrap();
+        let registry_path = temp.path().join(".projects");
+        let mut registry = ProjectRegistry::load_from(&registry_path).unwrap();
+        let server = temp.path().join("api-server");
+        let client = temp.path().join("api-client");
+        fs::create_dir(&server).unwrap();
+        fs::create_dir(&client).unwrap();
+        registry.link(&server).unwrap();
+        registry.link(&client).unwrap();
+
+        registry.set_alias("api", &server).unwrap();
+        assert!(matches!(
+            registry.set_alias("a b", &server),
+            Err(RegistryError::InvalidAlias(_))
+        ));
+        assert!(matches!(
+            registry.set_alias("x", &temp.path().join("other")),
+            Err(RegistryError::NotRegistered(_))
+        ));
+
+        // The registry stays one path per line for older versions, with
+        // the alias next to it
+        let content = fs::read_to_string(&registry_path).unwrap();
+        assert!(content.lines().all(|line| !line.contains('=')));
+        let settings = fs::read_to_string(temp.path().join(".projects.meta")).unwrap();
+        assert!(settings.starts_with("api="));
+
+        // The alias wins over the ambiguous prefix, and survives a reload
+        let registry = ProjectRegistry::load_from(&registry_path).unwrap();
+        assert_eq!(registry.len(), 2);
+        let resolved = registry.resolve_project("API").unwrap();
+        assert!(resolved.ends_with("api-server"));
+        assert_eq!(registry.aliases_of(&resolved), vec!["api"]);
+        assert_eq!(registry.names(), vec!["api", "api-client", "api-server"]);
+
+        let mut registry = registry;
+        registry.unlink(&resolved).unwrap();
+        assert!(registry.aliases_of(&resolved).is_empty());
+    }
+
+    #[test]
+    fn test_settings_in_the_registry_file() {
+        // As written before aliases and colors moved to their own file
+        let temp = TempDir::new().unwrap();
+        let registry_path = temp.path().join(".projects");
+        fs::write(
+            &registry_path,
+            "/code/web\napi=/code/api-server\ncolor:cyan=/code/web\n",
+        )
+        .unwrap();
+
+        let mut registry = ProjectRegistry::load_from(&registry_path).unwrap();
+        assert_eq!(registry.len(), 2);
+        let web = Path::new("/code/web");
+        assert_eq!(registry.color_of(web), Some(ProjectColor::Cyan));
+        assert!(
+            registry
+                .resolve_project("api")
+                .unwrap()
+                .ends_with("api-server")
+        );
+
+        registry.set_color(web, None).unwrap();
+        assert_eq!(
+            fs::read_to_string(&registry_path).unwrap(),
+            "/code/api-server\n/code/web\n"
+        );
+        let registry = ProjectRegistry::load_from(&registry_path).unwrap();
+        assert_eq!(registry.aliases_of(Path::new("/code/api-server")), ["api"]);
+    }
+
+    #[test]
+    fn test_colors() {
+        let temp = TempDir::new().unwrap();
+        let registry_path = temp.path().join(".projects");
+        let mut registry = ProjectRegistry::load_from(&registry_path).unwrap();
+        let api = temp.path().join("api");
+        fs::create_dir(&api).unwrap();
+        registry.link(&api).unwrap();
+        let api = api.canonicalize().unwrap();
+
+        registry.set_color(&api, Some(ProjectColor::Cyan)).unwrap();
+        assert!(matches!(
+            registry.set_color(&temp.path().join("other"), Some(ProjectColor::Red)),
+            Err(RegistryError::NotRegistered(_))
+        ));
+        assert_eq!("Grey".parse(), Ok(ProjectColor::Gray));
+        assert!("teal".parse::<ProjectColor>().is_err());
+
+        let mut registry = ProjectRegistry::load_from(&registry_path).unwrap();
+        assert_eq!(registry.len(), 1);
+        assert_eq!(registry.color_of(&api), Some(ProjectColor::Cyan));
+        assert_eq!(
+            registry.project_statuses()[0].color,
+            Some(ProjectColor::Cyan)
+        );
+
+        registry.set_color(&api, None).unwrap();
+        let registry = ProjectRegistry::load_from(&registry_path).unwrap();
+        assert_eq!(registry.color_of(&api), None);
+    }
+
     #[test]
     fn test_project_status() {
         let temp = TempDir::new().unwrap();
@@ -347,7 +880,7 @@ mod tests {
         assert_eq!(status.name, "myproject");
         assert!(status.exists);
         assert!(!status.has_tasks_dir);
-        assert_eq!(status.open_tasks, 0);
-        assert_eq!(status.total_tasks, 0);
+        assert_eq!(status.open_tasks(), 0);
+        assert_eq!(status.total_tasks(), 0);
     }
 }
@@ -0,0 +1,99 @@
+//! Upcoming reminder firings across tasks and projects
+
+use crate::models::ReminderOffset;
+use crate::storage::file_store::AggregatedTask;
+use chrono::{DateTime, Utc};
+
+/// A reminder that fires at a given time
+#[derive(Debug, Clone)]
+pub struct UpcomingReminder {
+    pub at: DateTime<Utc>,
+    pub offset: ReminderOffset,
+    pub task: AggregatedTask,
+}
+
+/// Reminders of open tasks firing in `from..until`, earliest first
+pub fn upcoming_reminders(
+    tasks: impl IntoIterator<Item = AggregatedTask>,
+    from: DateTime<Utc>,
+    until: DateTime<Utc>,
+) -> Vec<UpcomingReminder> {
+    let mut upcoming: Vec<UpcomingReminder> = tasks
+        .into_iter()
+        .filter(|t| t.task.is_open())
+        .flat_map(|t| {
+            t.task
+                .reminder_times()
+                .into_iter()
+                .filter(|(at, _)| *at >= from && *at < until)
+                .map(move |(at, offset)| UpcomingReminder {
+                    at,
+                    offset,
+                    task: t.clone(),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    upcoming.sort_by(|a, b| {
+        a.at.cmp(&b.at)
+            .then_with(|| a.task.project.cmp(&b.task.project))
+            .then(a.task.task.id.cmp(&b.task.task.id))
+    });
+    upcoming
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Due, Task, TaskKind};
+    use chrono::Duration;
+    use std::path::PathBuf;
+
+    fn aggregated(project: &str, task: Task) -> AggregatedTask {
+        AggregatedTask {
+            task,
+            project: project.to_string(),
+            project_path: PathBuf::from(project),
+            color: None,
+        }
+    }
+
+    #[test]
+    fn test_upcoming_reminders() {
+        let now = Utc::now();
+        let due = Due::At((now + Duration::days(2)).fixed_offset());
+
+        let mut call = Task::new(1, TaskKind::Task, "Call");
+        call.due = Some(due);
+        call.remind = vec!["-1d".parse().unwrap(), "-3d".parse().unwrap()];
+        let mut ship = Task::new(1, TaskKind::Task, "Ship");
+        ship.due = Some(due);
+        ship.remind = vec!["-2h".parse().unwrap()];
+        let mut done = ship.clone();
+        done.id = 2;
+        done.complete(None);
+        let undated = Task::new(3, TaskKind::Task, "Undated");
+
+        let upcoming = upcoming_reminders(
+            vec![
+                aggregated("web", ship),
+                aggregated("api", call),
+                aggregated("web", done),
+                aggregated("web", undated),
+            ],
+            now,
+            now + Duration::days(7),
+        );
+        let fired: Vec<(String, String)> = upcoming
+            .iter()
+            .map(|r| (r.task.qualified_id(), r.offset.to_string()))
+            .collect();
+        assert_eq!(
+            fired,
+            vec![
+                ("api:1".to_string(), "-1d".to_string()),
+                ("web:1".to_string(), "-2h".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,175 @@
+//! Archiving and purging old tasks (`gittask prune`)
+//!
+//! Completed tasks past `[retention] archive_after_days` are archived.
+//! Archived tasks past `purge_after_days` are written to a gzip-compressed
+//! JSON snapshot in `.tasks/snapshots/` and removed from the tasks directory;
+//! `gittask import` reads a snapshot back.
+
+use crate::models::{Task, TaskStatus};
+use crate::storage::config::RetentionConfig;
+use crate::storage::export;
+use crate::storage::file_store::{FileStore, FileStoreError};
+use crate::storage::writes;
+use chrono::{DateTime, Duration, Utc};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Directory within the tasks directory that holds purge snapshots
+pub const SNAPSHOT_DIR: &str = "snapshots";
+
+/// Tasks a prune would change
+#[derive(Debug, Default, Clone)]
+pub struct PrunePlan {
+    /// Completed tasks to archive
+    pub archive: Vec<Task>,
+    /// Archived tasks to move into a snapshot
+    pub purge: Vec<Task>,
+}
+
+impl PrunePlan {
+    /// Check if the prune changes nothing
+    pub fn is_empty(&self) -> bool {
+        self.archive.is_empty() && self.purge.is_empty()
+    }
+}
+
+/// When a closed task was closed: its completion time, or its last update
+/// if it was archived without being completed
+fn closed_at(task: &Task) -> DateTime<Utc> {
+    task.completed.unwrap_or(task.updated)
+}
+
+/// Pick the tasks to archive and purge as of `now`
+pub fn plan_prune(tasks: &[Task], config: &RetentionConfig, now: DateTime<Utc>) -> PrunePlan {
+    let older_than = |days: Option<u32>, task: &Task| {
+        days.is_some_and(|d| closed_at(task) < now - Duration::days(d as i64))
+    };
+
+    let mut plan = PrunePlan::default();
+    for task in tasks {
+        match task.status {
+            TaskStatus::Completed if older_than(config.archive_after_days, task) => {
+                plan.archive.push(task.clone());
+            }
+            TaskStatus::Archived if older_than(config.purge_after_days, task) => {
+                plan.purge.push(task.clone());
+            }
+            _ => {}
+        }
+    }
+    plan
+}
+
+/// Carry out a prune, returning the snapshot written for purged tasks
+pub fn prune(
+    store: &FileStore,
+    plan: &PrunePlan,
+    now: DateTime<Utc>,
+) -> Result<Option<PathBuf>, FileStoreError> {
+    store.ensure_writable()?;
+
+    for task in &plan.archive {
+        let mut task = task.clone();
+        task.status = TaskStatus::Archived;
+        task.updated = now;
+        store.update(&task)?;
+    }
+
+    if plan.purge.is_empty() {
+        return Ok(None);
+    }
+
+    // Write the snapshot before deleting anything, so a failure loses nothing.
+    // Two prunes within a second get numbered snapshots rather than one
+    // overwriting the other.
+    let dir = store.location().tasks_dir.join(SNAPSHOT_DIR);
+    let stamp = now.format("%Y%m%dT%H%M%SZ");
+    let json = export::to_json(&plan.purge).map_err(std::io::Error::from)?;
+    let mut attempt = 1;
+    let path = loop {
+        let path = dir.join(if attempt == 1 {
+            format!("archive-{}.json.gz", stamp)
+        } else {
+            format!("archive-{}-{}.json.gz", stamp, attempt)
+        });
+        match writes::write_new_with(&path, |file| {
+            let mut encoder = GzEncoder::new(file, Compression::default());
+            encoder.write_all(json.as_bytes())?;
+            encoder.finish()?;
+            Ok(())
+        }) {
+            Ok(()) => break path,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => attempt += 1,
+            Err(e) => return Err(e.into()),
+        }
+    };
+
+    for task in &plan.purge {
+        store.delete(task.id)?;
+    }
+    Ok(Some(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TaskKind;
+    use crate::storage::test_support::setup_test_store;
+    use flate2::read::GzDecoder;
+
+    #[test]
+    fn test_prune() {
+        let (_temp, store) = setup_test_store();
+        let now = Utc::now();
+
+        let mut old = Task::new(0, TaskKind::Task, "Done long ago");
+        old.status = TaskStatus::Completed;
+        old.completed = Some(now - Duration::days(40));
+        let mut recent = Task::new(0, TaskKind::Task, "Done yesterday");
+        recent.status = TaskStatus::Completed;
+        recent.completed = Some(now - Duration::days(1));
+        let mut ancient = Task::new(0, TaskKind::Task, "Archived ages ago");
+        ancient.status = TaskStatus::Archived;
+        ancient.completed = Some(now - Duration::days(400));
+        for task in [old, recent, ancient, Task::new(0, TaskKind::Task, "Open")] {
+            store.create(task).unwrap();
+        }
+
+        let config = RetentionConfig {
+            archive_after_days: Some(30),
+            purge_after_days: Some(365),
+            auto: false,
+        };
+        let all = store
+            .list(&crate::storage::TaskFilter {
+                include_archived: true,
+                ..Default::default()
+            })
+            .unwrap();
+        let plan = plan_prune(&all, &config, now);
+        let ids = |tasks: &[Task]| tasks.iter().map(|t| t.id).collect::<Vec<_>>();
+        assert_eq!((ids(&plan.archive), ids(&plan.purge)), (vec![1], vec![3]));
+
+        let snapshot = prune(&store, &plan, now).unwrap().unwrap();
+        assert_eq!(store.read(1).unwrap().status, TaskStatus::Archived);
+        assert!(store.read(3).is_err());
+
+        let json = std::io::read_to_string(GzDecoder::new(std::fs::File::open(&snapshot).unwrap()))
+            .unwrap();
+        assert!(json.contains("Archived ages ago"));
+
+        // A second prune in the same second keeps the first snapshot
+        let plan = PrunePlan {
+            archive: Vec::new(),
+            purge: vec![store.read(1).unwrap()],
+        };
+        let second = prune(&store, &plan, now).unwrap().unwrap();
+        assert_ne!(second, snapshot);
+        assert!(second.to_string_lossy().ends_with("-2.json.gz"));
+        let json = std::io::read_to_string(GzDecoder::new(std::fs::File::open(snapshot).unwrap()))
+            .unwrap();
+        assert!(json.contains("Archived ages ago"));
+    }
+}
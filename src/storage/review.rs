@@ -0,0 +1,167 @@
+//! Picking tasks for a periodic review and applying review decisions
+
+use crate::models::{Due, Priority, Task, TaskStatus, local_date};
+use chrono::{DateTime, Duration, Utc};
+use std::fmt;
+
+/// Default number of days without changes or review before a task is stale
+pub const DEFAULT_STALE_DAYS: i64 = 30;
+
+/// Days a snoozed task's due date moves out
+pub const SNOOZE_DAYS: i64 = 7;
+
+/// Why a task needs reviewing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewReason {
+    Overdue,
+    Stale,
+}
+
+impl fmt::Display for ReviewReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReviewReason::Overdue => write!(f, "overdue"),
+            ReviewReason::Stale => write!(f, "stale"),
+        }
+    }
+}
+
+/// A decision taken on a task during review
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewAction {
+    /// Leave the task as it is
+    Keep,
+    /// Push the due date out by [`SNOOZE_DAYS`] from today
+    Snooze,
+    /// Archive the task
+    Archive,
+    /// Raise the priority one level
+    Bump,
+}
+
+impl ReviewAction {
+    /// Map a single-key answer (`k`, `s`, `a`, `b`) to an action
+    pub fn from_key(key: char) -> Option<Self> {
+        match key.to_ascii_lowercase() {
+            'k' => Some(ReviewAction::Keep),
+            's' => Some(ReviewAction::Snooze),
+            'a' => Some(ReviewAction::Archive),
+            'b' => Some(ReviewAction::Bump),
+            _ => None,
+        }
+    }
+
+    /// Apply the action, recording `now` as the review time
+    pub fn apply(self, task: &mut Task, now: DateTime<Utc>) {
+        match self {
+            ReviewAction::Keep => {}
+            ReviewAction::Snooze => {
+                task.due = Some(Due::Date(local_date(now) + Duration::days(SNOOZE_DAYS)));
+            }
+            ReviewAction::Archive => task.status = TaskStatus::Archived,
+            ReviewAction::Bump => {
+                task.priority = match task.priority {
+                    Priority::Low => Priority::Medium,
+                    Priority::Medium => Priority::High,
+                    Priority::High | Priority::Critical => Priority::Critical,
+                };
+            }
+        }
+        task.last_reviewed = Some(now);
+        task.updated = now;
+    }
+}
+
+/// Open tasks that need reviewing, overdue ones first (by due date), then
+/// stale ones (least recently touched first)
+///
+/// A task is stale when neither an edit nor a review happened in the last
+/// `stale_days`. Tasks reviewed within that window are left out, even when
+/// overdue, so a review session can be resumed where it stopped.
+pub fn review_queue(
+    tasks: impl IntoIterator<Item = Task>,
+    now: DateTime<Utc>,
+    stale_days: i64,
+) -> Vec<(Task, ReviewReason)> {
+    let cutoff = now - Duration::days(stale_days);
+
+    let mut queue: Vec<(Task, ReviewReason)> = tasks
+        .into_iter()
+        .filter(|t| t.is_open())
+        .filter(|t| t.last_reviewed.is_none_or(|r| r < cutoff))
+        .filter_map(|t| {
+            if t.is_overdue(now) {
+                Some((t, ReviewReason::Overdue))
+            } else if t.updated < cutoff {
+                Some((t, ReviewReason::Stale))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    queue.sort_by(|(a, ra), (b, rb)| match (ra, rb) {
+        (ReviewReason::Overdue, ReviewReason::Stale) => std::cmp::Ordering::Less,
+        (ReviewReason::Stale, ReviewReason::Overdue) => std::cmp::Ordering::Greater,
+        (ReviewReason::Overdue, ReviewReason::Overdue) => a.due.cmp(&b.due).then(a.id.cmp(&b.id)),
+        (ReviewReason::Stale, ReviewReason::Stale) => {
+            a.updated.cmp(&b.updated).then(a.id.cmp(&b.id))
+        }
+    });
+    queue
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TaskKind;
+
+    #[test]
+    fn test_review_queue() {
+        let now = Utc::now();
+        let old = now - Duration::days(60);
+
+        let mut stale = Task::new(1, TaskKind::Task, "Stale");
+        stale.updated = old;
+        let mut overdue = Task::new(2, TaskKind::Task, "Overdue");
+        overdue.due = Some(Due::Date(local_date(now) - Duration::days(1)));
+        let fresh = Task::new(3, TaskKind::Task, "Fresh");
+        let mut reviewed = Task::new(4, TaskKind::Task, "Reviewed");
+        reviewed.updated = old;
+        reviewed.last_reviewed = Some(now - Duration::days(2));
+        let mut done = Task::new(5, TaskKind::Task, "Done");
+        done.updated = old;
+        done.complete(None);
+
+        let queue = review_queue(
+            vec![stale, overdue, fresh, reviewed, done],
+            now,
+            DEFAULT_STALE_DAYS,
+        );
+        let ids: Vec<(u64, ReviewReason)> = queue.iter().map(|(t, r)| (t.id, *r)).collect();
+        assert_eq!(
+            ids,
+            vec![(2, ReviewReason::Overdue), (1, ReviewReason::Stale)]
+        );
+    }
+
+    #[test]
+    fn test_review_actions() {
+        let now = Utc::now();
+        let mut task = Task::new(1, TaskKind::Task, "Task");
+
+        ReviewAction::from_key('B').unwrap().apply(&mut task, now);
+        assert_eq!(task.priority, Priority::High);
+        assert_eq!(task.last_reviewed, Some(now));
+
+        ReviewAction::Snooze.apply(&mut task, now);
+        assert_eq!(
+            task.due,
+            Some(Due::Date(local_date(now) + Duration::days(SNOOZE_DAYS)))
+        );
+
+        ReviewAction::Archive.apply(&mut task, now);
+        assert_eq!(task.status, TaskStatus::Archived);
+        assert_eq!(ReviewAction::from_key('x'), None);
+    }
+}
@@ -0,0 +1,151 @@
+//! Files written by `gittask init` alongside the tasks directory
+
+use crate::storage::config::ProjectConfig;
+use crate::storage::location::TaskLocation;
+use crate::storage::registry::REGISTRY_FILE;
+use crate::storage::templates::TaskTemplate;
+use crate::storage::writes;
+use std::path::{Path, PathBuf};
+
+/// Name of the merge driver task files are routed through
+pub const MERGE_DRIVER: &str = "gittask";
+
+const README: &str = "\
+# Tasks
+
+This directory is managed by gittask.
+Each task is a Markdown file with YAML frontmatter, versioned with the code.
+
+```bash
+gittask list            # open tasks
+gittask add task \"...\"  # add a task
+gittask show <id>       # task details
+```
+
+Settings live in `config.toml` and task templates in `templates/`.
+";
+
+const CONFIG: &str = "\
+# gittask project settings; every key is optional.
+
+# [defaults]
+# assignee_from_git = true
+
+# [kind.idea]
+# priority = \"low\"
+# tags = [\"someday\"]
+# template = \"idea\"
+
+# [wip]
+# max_in_progress = 5
+# max_per_assignee = 2
+
+# [display]
+# date_format = \"%Y-%m-%d\"
+# timezone = \"local\"
+
+# [retention]
+# archive_after_days = 30
+# purge_after_days = 365
+";
+
+const BUG_TEMPLATE: &str = "\
+---
+priority: high
+tags: [bug]
+---
+## Steps to reproduce
+
+## Expected behaviour
+";
+
+/// Write the scaffold files that don't exist yet, returning the ones created
+///
+/// The `.gitattributes` routing task files through the merge driver is
+/// skipped for the global location, which isn't a repository; it gets an
+/// empty project registry instead. With `templates`, a `templates/`
+/// directory with an example is added too.
+pub fn scaffold(location: &TaskLocation, templates: bool) -> std::io::Result<Vec<PathBuf>> {
+    let dir = &location.tasks_dir;
+    let mut created = Vec::new();
+
+    write_new(&dir.join("README.md"), README, &mut created)?;
+    write_new(&ProjectConfig::path(dir), CONFIG, &mut created)?;
+    if location.is_global {
+        write_new(&dir.join(REGISTRY_FILE), "", &mut created)?;
+    } else {
+        let attributes = format!("*.md merge={}\nREADME.md !merge\n", MERGE_DRIVER);
+        write_new(&dir.join(".gitattributes"), &attributes, &mut created)?;
+    }
+    if templates {
+        let templates_dir = TaskTemplate::dir(location);
+        write_new(&templates_dir.join("bug.md"), BUG_TEMPLATE, &mut created)?;
+    }
+
+    Ok(created)
+}
+
+/// Write `content` to `path` unless the file already exists
+fn write_new(path: &Path, content: &str, created: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if !path.exists() {
+        writes::write(path, content)?;
+        created.push(path.to_path_buf());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::test_support::setup_test_store;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_scaffold_keeps_existing_files() {
+        let (_temp, store) = setup_test_store();
+        let location = store.location().clone();
+        std::fs::write(location.tasks_dir.join("README.md"), "Ours\n").unwrap();
+
+        let created = scaffold(&location, true).unwrap();
+        let names: Vec<_> = created
+            .iter()
+            .map(|p| p.strip_prefix(&location.tasks_dir).unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                Path::new("config.toml"),
+                Path::new(".gitattributes"),
+                Path::new("templates/bug.md"),
+            ]
+        );
+        assert_eq!(
+            std::fs::read_to_string(location.tasks_dir.join("README.md")).unwrap(),
+            "Ours\n"
+        );
+
+        // The commented-out config is valid and changes nothing
+        assert_eq!(
+            ProjectConfig::load(&location.tasks_dir).unwrap(),
+            ProjectConfig::default()
+        );
+        assert!(TaskTemplate::load(&location, "bug").is_ok());
+        assert!(scaffold(&location, true).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_scaffold_global() {
+        let temp = TempDir::new().unwrap();
+        let location = TaskLocation {
+            root: temp.path().to_path_buf(),
+            tasks_dir: temp.path().join(".tasks"),
+            is_global: true,
+        };
+        location.ensure_exists().unwrap();
+
+        let created = scaffold(&location, false).unwrap();
+        assert_eq!(created.len(), 3);
+        assert!(location.tasks_dir.join(REGISTRY_FILE).exists());
+        assert!(!location.tasks_dir.join(".gitattributes").exists());
+    }
+}
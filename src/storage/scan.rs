@@ -0,0 +1,243 @@
+//! Turning TODO-style code comments into tasks
+
+use crate::git::GitOperations;
+use crate::models::{Task, TaskKind};
+use crate::storage::file_store::{FileStore, FileStoreError, TaskFilter};
+use regex::Regex;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+/// Files larger than this are assumed not to be hand-written source
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// Directories never worth scanning, besides hidden ones
+const SKIP_DIRS: &[&str] = &["target", "node_modules", "vendor"];
+
+static COMMENT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?:^|\s)(?://+|#+|/\*+|\*|--|<!--|;+)\s*(TODO|FIXME|XXX|HACK)\b(?:\([^)]*\))?:?\s*(.*)$",
+    )
+    .unwrap()
+});
+
+/// A marker comment found in a source file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeTodo {
+    /// Path relative to the project root
+    pub path: PathBuf,
+    /// 1-based line number
+    pub line: usize,
+    pub marker: String,
+    pub text: String,
+}
+
+impl CodeTodo {
+    /// `path:line` reference to the comment
+    pub fn source_ref(&self) -> String {
+        format!("{}:{}", self.path.display(), self.line)
+    }
+
+    /// The task tracking this comment, not yet assigned an ID
+    pub fn to_task(&self) -> Task {
+        let mut task = Task::new(0, TaskKind::Todo, &self.text);
+        task.description = format!("From `{}` ({})", self.source_ref(), self.marker);
+        task
+    }
+
+    /// Whether an existing task already tracks this comment
+    fn is_tracked_by(&self, task: &Task) -> bool {
+        task.title == self.text
+            && task
+                .description
+                .contains(&format!("`{}:", self.path.display()))
+    }
+}
+
+/// Result of a scan run
+#[derive(Debug, Default)]
+pub struct ScanReport {
+    /// Tasks created (or that would be created in a dry run), with their comment
+    pub created: Vec<(CodeTodo, Task)>,
+    /// Comments already tracked by a task
+    pub skipped: Vec<CodeTodo>,
+}
+
+/// Find marker comments in the files under `dir`
+///
+/// Hidden directories, build output and git-ignored paths are skipped, as are
+/// binary and very large files. Results are ordered by path and line.
+pub fn find_todos(root: &Path, dir: &Path) -> io::Result<Vec<CodeTodo>> {
+    let repo = GitOperations::repo(root).ok();
+    let mut todos = Vec::new();
+    walk(root, dir, repo.as_ref(), &mut todos)?;
+    todos.sort_by(|a, b| a.path.cmp(&b.path).then(a.line.cmp(&b.line)));
+    Ok(todos)
+}
+
+fn walk(
+    root: &Path,
+    dir: &Path,
+    repo: Option<&git2::Repository>,
+    todos: &mut Vec<CodeTodo>,
+) -> io::Result<()> {
+    let metadata = fs::metadata(dir)?;
+    if metadata.is_file() {
+        scan_file(root, dir, todos);
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        let rel = path.strip_prefix(root).unwrap_or(&path);
+        if repo.is_some_and(|r| r.is_path_ignored(rel).unwrap_or(false)) {
+            continue;
+        }
+
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if !name.starts_with('.') && !SKIP_DIRS.contains(&name.as_str()) {
+                walk(root, &path, repo, todos)?;
+            }
+        } else if file_type.is_file() && entry.metadata()?.len() <= MAX_FILE_SIZE {
+            scan_file(root, &path, todos);
+        }
+    }
+    Ok(())
+}
+
+/// Collect marker comments from one file, ignoring files that aren't text
+fn scan_file(root: &Path, path: &Path, todos: &mut Vec<CodeTodo>) {
+    let Ok(bytes) = fs::read(path) else {
+        return;
+    };
+    if bytes.iter().take(8000).any(|&b| b == 0) {
+        return;
+    }
+    let Ok(content) = String::from_utf8(bytes) else {
+        return;
+    };
+
+    let rel = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+    for (i, line) in content.lines().enumerate() {
+        if let Some((marker, text)) = parse_line(line) {
+            todos.push(CodeTodo {
+                path: rel.clone(),
+                line: i + 1,
+                marker,
+                text,
+            });
+        }
+    }
+}
+
+/// Extract the marker and text from a comment line
+fn parse_line(line: &str) -> Option<(String, String)> {
+    let caps = COMMENT.captures(line)?;
+    let text = caps[2]
+        .trim()
+        .trim_end_matches("*/")
+        .trim_end_matches("-->")
+        .trim();
+    if text.is_empty() {
+        return None;
+    }
+    Some((caps[1].to_string(), text.to_string()))
+}
+
+/// Create a todo for every marker comment under `dir` not already tracked
+///
+/// `dir` must lie inside the project. With `dry_run` nothing is written.
+pub fn scan_code(
+    store: &FileStore,
+    dir: &Path,
+    dry_run: bool,
+) -> Result<ScanReport, FileStoreError> {
+    let root = store.location().root.canonicalize()?;
+    let dir = dir.canonicalize()?;
+    if !dir.starts_with(&root) {
+        return Err(FileStoreError::Io(io::Error::other(format!(
+            "{} is outside the project",
+            dir.display()
+        ))));
+    }
+    let todos = find_todos(&root, &dir)?;
+
+    let existing = store.list(&TaskFilter {
+        include_archived: true,
+        ..Default::default()
+    })?;
+
+    let mut report = ScanReport::default();
+    for todo in todos {
+        let tracked = existing.iter().any(|t| todo.is_tracked_by(t))
+            || report.created.iter().any(|(_, t)| todo.is_tracked_by(t));
+        if tracked {
+            report.skipped.push(todo);
+            continue;
+        }
+
+        let task = todo.to_task();
+        let task = if dry_run { task } else { store.create(task)? };
+        report.created.push((todo, task));
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::test_support::setup_test_store;
+
+    #[test]
+    fn test_parse_line() {
+        assert_eq!(
+            parse_line("    // TODO: handle expired tokens"),
+            Some(("TODO".to_string(), "handle expired tokens".to_string()))
+        );
+        assert_eq!(
+            parse_line("x = 1  # FIXME(alice) off by one"),
+            Some(("FIXME".to_string(), "off by one".to_string()))
+        );
+        assert_eq!(
+            parse_line("/* HACK: skip cache */"),
+            Some(("HACK".to_string(), "skip cache".to_string()))
+        );
+        assert_eq!(parse_line("// TODO"), None);
+        assert_eq!(parse_line("let todo = \"TODO: not a comment\";"), None);
+        assert_eq!(parse_line("// TODOS are fine"), None);
+    }
+
+    #[test]
+    fn test_scan_code_creates_and_skips() {
+        let (temp, store) = setup_test_store();
+        let src = temp.path().join("src");
+        std::fs::create_dir_all(src.join("auth")).unwrap();
+        std::fs::write(
+            src.join("auth/login.rs"),
+            "fn login() {\n    // TODO: rate limit attempts\n}\n",
+        )
+        .unwrap();
+        std::fs::write(src.join("main.rs"), "// FIXME: parse args\n").unwrap();
+        std::fs::create_dir(temp.path().join("target")).unwrap();
+        std::fs::write(temp.path().join("target/gen.rs"), "// TODO: generated\n").unwrap();
+
+        let report = scan_code(&store, &src.join("auth"), false).unwrap();
+        assert_eq!(report.created.len(), 1);
+        let (todo, task) = &report.created[0];
+        assert_eq!(todo.source_ref(), "src/auth/login.rs:2");
+        assert_eq!(task.title, "rate limit attempts");
+        assert_eq!(task.kind, TaskKind::Todo);
+        assert!(task.id > 0);
+
+        // Rescanning the whole tree only picks up the new comment
+        let report = scan_code(&store, temp.path(), false).unwrap();
+        assert_eq!(report.skipped.len(), 1);
+        assert_eq!(report.created.len(), 1);
+        assert_eq!(report.created[0].1.title, "parse args");
+    }
+}
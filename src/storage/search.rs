@@ -0,0 +1,182 @@
+//! Text search over task titles and bodies
+
+use crate::models::Task;
+use crate::storage::file_store::{FileStore, FileStoreError, TaskFilter, list_aggregated};
+use crate::storage::registry::ProjectRegistry;
+use regex::Regex;
+use std::path::PathBuf;
+
+/// A single matching line from a task
+#[derive(Debug, Clone, PartialEq)]
+pub struct GrepMatch {
+    /// Task ID
+    pub task_id: u64,
+    /// Task title
+    pub title: String,
+    /// Line number within the description (None for a title match)
+    pub line_number: Option<usize>,
+    /// The matching line
+    pub line: String,
+}
+
+/// A matching line from a task in one of the registered projects
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregatedMatch {
+    /// Project name (directory name)
+    pub project: String,
+    /// Project root path
+    pub project_path: PathBuf,
+    pub found: GrepMatch,
+}
+
+impl AggregatedMatch {
+    /// Get the qualified ID (project:id format)
+    pub fn qualified_id(&self) -> String {
+        format!("{}:{}", self.project, self.found.task_id)
+    }
+}
+
+/// Lines of a task's title and description matching a regex
+fn grep_task(task: &Task, pattern: &Regex) -> Vec<GrepMatch> {
+    let mut matches = Vec::new();
+    if pattern.is_match(&task.title) {
+        matches.push(GrepMatch {
+            task_id: task.id,
+            title: task.title.clone(),
+            line_number: None,
+            line: task.title.clone(),
+        });
+    }
+
+    for (i, line) in task.description.lines().enumerate() {
+        if pattern.is_match(line) {
+            matches.push(GrepMatch {
+                task_id: task.id,
+                title: task.title.clone(),
+                line_number: Some(i + 1),
+                line: line.to_string(),
+            });
+        }
+    }
+    matches
+}
+
+/// Search task titles and descriptions for lines matching a regex
+pub fn grep(
+    store: &FileStore,
+    pattern: &Regex,
+    filter: &TaskFilter,
+) -> Result<Vec<GrepMatch>, FileStoreError> {
+    let mut matches = Vec::new();
+
+    for task in store.iter(filter)? {
+        matches.extend(grep_task(&task, pattern));
+    }
+
+    // Keep output stable regardless of directory order
+    matches.sort_by_key(|m| (m.task_id, m.line_number));
+
+    Ok(matches)
+}
+
+/// Search the tasks of every registered project, like `grep`
+///
+/// Projects that can't be read are skipped with a warning. Matches are
+/// ordered by project name, then task ID.
+pub fn search_aggregated(
+    registry: &ProjectRegistry,
+    pattern: &Regex,
+    filter: &TaskFilter,
+) -> Result<Vec<AggregatedMatch>, FileStoreError> {
+    let mut matches = Vec::new();
+    for aggregated in list_aggregated(registry, filter)? {
+        matches.extend(
+            grep_task(&aggregated.task, pattern)
+                .into_iter()
+                .map(|found| AggregatedMatch {
+                    project: aggregated.project.clone(),
+                    project_path: aggregated.project_path.clone(),
+                    found,
+                }),
+        );
+    }
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Task, TaskKind};
+    use crate::storage::test_support::{setup_test_store, test_store_at};
+    use regex::RegexBuilder;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_grep_title_and_body() {
+        let (_temp, store) = setup_test_store();
+
+        let mut task = Task::new(0, TaskKind::Task, "Fix token refresh");
+        task.description = "Tokens expire early.\nCheck the refresh TOKEN path.".to_string();
+        store.create(task).unwrap();
+        store
+            .create(Task::new(0, TaskKind::Task, "Unrelated"))
+            .unwrap();
+
+        let pattern = Regex::new("token").unwrap();
+        let matches = grep(&store, &pattern, &TaskFilter::default()).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].line_number, None);
+
+        let pattern = RegexBuilder::new("token")
+            .case_insensitive(true)
+            .build()
+            .unwrap();
+        let matches = grep(&store, &pattern, &TaskFilter::default()).unwrap();
+        assert_eq!(matches.len(), 3);
+        assert_eq!(matches[1].line_number, Some(1));
+        assert_eq!(matches[2].line, "Check the refresh TOKEN path.");
+    }
+
+    #[test]
+    fn test_grep_respects_filter() {
+        let (_temp, store) = setup_test_store();
+
+        let mut task = Task::new(0, TaskKind::Task, "Tagged match");
+        task.tags = vec!["auth".to_string()];
+        store.create(task).unwrap();
+        store
+            .create(Task::new(0, TaskKind::Task, "Untagged match"))
+            .unwrap();
+
+        let pattern = Regex::new("match").unwrap();
+        let filter = TaskFilter {
+            tags: vec!["auth".to_string()],
+            ..Default::default()
+        };
+        let matches = grep(&store, &pattern, &filter).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].title, "Tagged match");
+    }
+
+    #[test]
+    fn test_search_aggregated() {
+        let temp = TempDir::new().unwrap();
+        let mut projects = Vec::new();
+        for (name, title) in [("beta", "Rotate keys"), ("alpha", "Rotate logs")] {
+            let root = temp.path().join(name);
+            let store = test_store_at(&root);
+            store
+                .create(Task::new(0, TaskKind::Task, "Unrelated"))
+                .unwrap();
+            store.create(Task::new(0, TaskKind::Task, title)).unwrap();
+            projects.push(root);
+        }
+
+        let registry = ProjectRegistry::fixed(projects);
+        let pattern = Regex::new("Rotate").unwrap();
+        let matches = search_aggregated(&registry, &pattern, &TaskFilter::default()).unwrap();
+        let ids: Vec<String> = matches.iter().map(|m| m.qualified_id()).collect();
+        assert_eq!(ids, vec!["alpha:2", "beta:2"]);
+        assert_eq!(matches[0].found.title, "Rotate logs");
+    }
+}
@@ -0,0 +1,95 @@
+//! Parent/child hierarchy between tasks in one store
+//!
+//! A task whose `parent` is set is a subtask of that task. `FileStore`
+//! rejects parents that don't exist or would make a loop; hand-edited files
+//! can still contain one, so nothing here assumes the hierarchy is a tree.
+
+use crate::models::Task;
+use std::collections::{HashMap, HashSet};
+
+/// Order tasks depth-first under their parents, with each task's depth
+///
+/// Siblings keep their order in `tasks`. A task whose parent isn't among
+/// `tasks` (filtered out, or missing) is shown at the top level.
+pub fn task_tree(tasks: Vec<Task>) -> Vec<(usize, Task)> {
+    let ids: HashSet<u64> = tasks.iter().map(|t| t.id).collect();
+    let mut children: HashMap<u64, Vec<usize>> = HashMap::new();
+    let mut roots = Vec::new();
+    for (i, task) in tasks.iter().enumerate() {
+        match task.parent {
+            Some(parent) if parent != task.id && ids.contains(&parent) => {
+                children.entry(parent).or_default().push(i)
+            }
+            _ => roots.push(i),
+        }
+    }
+
+    let mut order: Vec<(usize, usize)> = Vec::with_capacity(tasks.len());
+    let mut placed = vec![false; tasks.len()];
+    let mut stack: Vec<(usize, usize)> = roots.iter().rev().map(|&i| (i, 0)).collect();
+    loop {
+        while let Some((i, depth)) = stack.pop() {
+            if std::mem::replace(&mut placed[i], true) {
+                continue;
+            }
+            order.push((i, depth));
+            if let Some(below) = children.get(&tasks[i].id) {
+                stack.extend(below.iter().rev().map(|&c| (c, depth + 1)));
+            }
+        }
+        // Tasks caught in a parent loop are never reached from a root
+        match placed.iter().position(|p| !p) {
+            Some(i) => stack.push((i, 0)),
+            None => break,
+        }
+    }
+
+    let mut slots: Vec<Option<Task>> = tasks.into_iter().map(Some).collect();
+    order
+        .into_iter()
+        .filter_map(|(i, depth)| slots[i].take().map(|task| (depth, task)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TaskKind;
+
+    fn task(id: u64, parent: Option<u64>) -> Task {
+        let mut task = Task::new(id, TaskKind::Task, format!("Task {}", id));
+        task.parent = parent;
+        task
+    }
+
+    #[test]
+    fn test_task_tree() {
+        let tasks = vec![
+            task(1, None),
+            task(2, Some(1)),
+            task(3, None),
+            task(4, Some(2)),
+            task(5, Some(1)),
+            task(6, Some(99)),
+            task(7, Some(8)),
+            task(8, Some(7)),
+        ];
+        let tree: Vec<(usize, u64)> = task_tree(tasks)
+            .iter()
+            .map(|(depth, t)| (*depth, t.id))
+            .collect();
+        assert_eq!(
+            tree,
+            vec![
+                (0, 1),
+                (1, 2),
+                (2, 4),
+                (1, 5),
+                (0, 3),
+                (0, 6),
+                (0, 7),
+                (1, 8)
+            ]
+        );
+    }
+}
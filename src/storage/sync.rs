@@ -0,0 +1,354 @@
+//! Syncing tasks with GitHub issues (`gittask sync`)
+//!
+//! Issues are fetched with the `gh` CLI, which brings its own authentication,
+//! and imported like `gittask import --update`: new issues become tasks and
+//! known ones are updated in place. Running with an interval repeats the sync,
+//! backing off after failures and waiting for the API quota to reset when it
+//! runs low. Each round is recorded in `.tasks/.sync-status.json`.
+
+use crate::storage::file_store::{FileStore, FileStoreError};
+use crate::storage::import::{ImportReport, import_json};
+use crate::storage::location::ensure_gitignored;
+use crate::storage::writes;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use std::process::Command;
+use thiserror::Error;
+
+/// Status of the last sync rounds, inside the tasks directory
+pub const SYNC_STATUS_FILE: &str = ".sync-status.json";
+
+/// Output of a sync running in the background, inside the tasks directory
+pub const SYNC_LOG_FILE: &str = ".sync.log";
+
+/// Longest wait between rounds after repeated failures
+const MAX_BACKOFF_HOURS: i64 = 6;
+
+/// API requests left for other tools; below this a round waits for the reset
+const RATE_LIMIT_RESERVE: u64 = 100;
+
+/// Issues fetched per sync
+const ISSUE_LIMIT: u32 = 1000;
+
+#[derive(Debug, Error)]
+pub enum SyncError {
+    #[error("Could not run gh (is the GitHub CLI installed?): {0}")]
+    GhMissing(std::io::Error),
+    #[error("gh failed: {0}")]
+    Gh(String),
+    #[error("Unexpected output from gh: {0}")]
+    Output(String),
+    #[error("{0}")]
+    Store(#[from] FileStoreError),
+}
+
+/// Remaining GitHub API quota
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimit {
+    pub remaining: u64,
+    pub reset: DateTime<Utc>,
+}
+
+impl RateLimit {
+    /// Check if a sync should wait for the quota to reset
+    pub fn is_low(&self) -> bool {
+        self.remaining < RATE_LIMIT_RESERVE
+    }
+}
+
+/// What the sync loop last did, for `gittask projects`
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SyncStatus {
+    /// Process running the loop, if it runs with an interval
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_attempt: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_success: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// Failed rounds in a row
+    pub failures: u32,
+    /// Tasks created and updated by the last successful round
+    pub created: usize,
+    pub updated: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit_remaining: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_run: Option<DateTime<Utc>>,
+}
+
+impl SyncStatus {
+    /// Load the status of a tasks directory, if it was ever synced
+    pub fn load(tasks_dir: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(tasks_dir.join(SYNC_STATUS_FILE)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Write the status atomically (temp file + rename)
+    pub fn save(&self, tasks_dir: &Path) -> std::io::Result<()> {
+        ensure_gitignored(tasks_dir, SYNC_STATUS_FILE)?;
+        let content = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        writes::write_atomic(&tasks_dir.join(SYNC_STATUS_FILE), content)
+    }
+
+    /// Record the outcome of a sync attempted at `now`
+    pub fn record(&mut self, now: DateTime<Utc>, result: &Result<ImportReport, SyncError>) {
+        self.last_attempt = Some(now);
+        match result {
+            Ok(report) => {
+                self.last_success = Some(now);
+                self.last_error = None;
+                self.failures = 0;
+                self.created = report.created.len();
+                self.updated = report.updated.len();
+            }
+            Err(e) => {
+                self.last_error = Some(e.to_string());
+                self.failures += 1;
+            }
+        }
+    }
+
+    /// One-line summary, e.g. `ok 2026-03-04 10:15 (+2 ~1)` or `failing (3x): ...`
+    pub fn summary(&self) -> String {
+        match (&self.last_error, self.last_success) {
+            (Some(error), _) if self.failures > 0 => {
+                format!("failing ({}x): {}", self.failures, error)
+            }
+            (_, Some(at)) => format!(
+                "ok {} (+{} ~{})",
+                at.format("%Y-%m-%d %H:%M"),
+                self.created,
+                self.updated
+            ),
+            _ => "never".to_string(),
+        }
+    }
+}
+
+/// Parse an interval like `90s`, `15m`, `2h` or `1d`
+pub fn parse_interval(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let invalid = || format!("Invalid interval: {} (expected e.g. 30s, 15m, 2h)", s);
+    let split = s
+        .char_indices()
+        .last()
+        .map(|(i, _)| i)
+        .ok_or_else(invalid)?;
+    let (amount, unit) = s.split_at(split);
+    let amount: i64 = amount.parse().map_err(|_| invalid())?;
+    let interval = match unit {
+        "s" => Duration::seconds(amount),
+        "m" => Duration::minutes(amount),
+        "h" => Duration::hours(amount),
+        "d" => Duration::days(amount),
+        _ => return Err(invalid()),
+    };
+    if interval <= Duration::zero() {
+        return Err(invalid());
+    }
+    Ok(interval)
+}
+
+/// How long to wait before the next round
+///
+/// After `failures` failed rounds in a row the interval doubles each time,
+/// up to six hours. A low quota pushes the next round past its reset.
+pub fn next_delay(
+    interval: Duration,
+    failures: u32,
+    rate_limit: Option<&RateLimit>,
+    now: DateTime<Utc>,
+) -> Duration {
+    let max = Duration::hours(MAX_BACKOFF_HOURS).max(interval);
+    let mut delay = interval;
+    for _ in 0..failures {
+        delay = (delay * 2).min(max);
+    }
+    if let Some(limit) = rate_limit.filter(|l| l.is_low()) {
+        delay = delay.max(limit.reset - now + Duration::seconds(1));
+    }
+    delay
+}
+
+/// Run `gh` in the project root, returning its standard output
+fn gh(root: &Path, args: &[&str]) -> Result<String, SyncError> {
+    let output = Command::new("gh")
+        .args(args)
+        .current_dir(root)
+        .output()
+        .map_err(SyncError::GhMissing)?;
+    if !output.status.success() {
+        return Err(SyncError::Gh(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    String::from_utf8(output.stdout).map_err(|e| SyncError::Output(e.to_string()))
+}
+
+/// Read the GraphQL quota (used by `gh issue list`) from `gh api rate_limit`
+pub fn parse_rate_limit(json: &str) -> Result<RateLimit, SyncError> {
+    let value: Value = serde_json::from_str(json).map_err(|e| SyncError::Output(e.to_string()))?;
+    let quota = &value["resources"]["graphql"];
+    let remaining = quota["remaining"].as_u64();
+    let reset = quota["reset"]
+        .as_i64()
+        .and_then(|secs| DateTime::from_timestamp(secs, 0));
+    match (remaining, reset) {
+        (Some(remaining), Some(reset)) => Ok(RateLimit { remaining, reset }),
+        _ => Err(SyncError::Output("no GraphQL rate limit".to_string())),
+    }
+}
+
+/// Ask GitHub for the remaining API quota
+pub fn rate_limit(root: &Path) -> Result<RateLimit, SyncError> {
+    parse_rate_limit(&gh(root, &["api", "rate_limit"])?)
+}
+
+/// Fetch every issue, open or closed, as JSON `gittask import` understands
+///
+/// `repo` (`owner/name`) defaults to the repository of the project's remote.
+pub fn fetch_issues(root: &Path, repo: Option<&str>) -> Result<String, SyncError> {
+    let limit = ISSUE_LIMIT.to_string();
+    let mut args = vec![
+        "issue",
+        "list",
+        "--state",
+        "all",
+        "--limit",
+        &limit,
+        "--json",
+        "number,title,body,labels,state",
+    ];
+    if let Some(repo) = repo {
+        args.extend(["--repo", repo]);
+    }
+    gh(root, &args)
+}
+
+/// Fetch the issues and create or update their tasks
+pub fn sync_once(store: &FileStore, repo: Option<&str>) -> Result<ImportReport, SyncError> {
+    store.ensure_writable()?;
+    let issues = fetch_issues(&store.location().root, repo)?;
+    Ok(import_json(store, &issues, false, true)?)
+}
+
+/// Run one round of the sync loop and record it in the status file
+///
+/// Returns how long to wait before the next round.
+pub fn sync_round(
+    store: &FileStore,
+    repo: Option<&str>,
+    interval: Duration,
+    status: &mut SyncStatus,
+) -> Result<Duration, FileStoreError> {
+    store.ensure_writable()?;
+    let root = &store.location().root;
+    let now = Utc::now();
+    status.last_attempt = Some(now);
+
+    // Without quota information the sync is simply attempted
+    let limit = rate_limit(root).ok();
+    status.rate_limit_remaining = limit.as_ref().map(|l| l.remaining);
+    if let Some(limit) = limit.as_ref().filter(|l| l.is_low()) {
+        log::warn!(
+            "GitHub API quota low ({} left), waiting until {}",
+            limit.remaining,
+            limit.reset
+        );
+    } else {
+        let result = sync_once(store, repo);
+        match &result {
+            Ok(report) => log::info!(
+                "Synced: {} created, {} updated, {} failed",
+                report.created.len(),
+                report.updated.len(),
+                report.errors.len()
+            ),
+            Err(e) => log::warn!("Sync failed: {}", e),
+        }
+        status.record(now, &result);
+    }
+
+    let delay = next_delay(interval, status.failures, limit.as_ref(), now);
+    status.next_run = Some(now + delay);
+    status.save(&store.location().tasks_dir)?;
+    Ok(delay)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("15m").unwrap(), Duration::minutes(15));
+        assert_eq!(parse_interval("90s").unwrap(), Duration::seconds(90));
+        assert_eq!(parse_interval("1d").unwrap(), Duration::days(1));
+        assert!(parse_interval("0m").is_err());
+        assert!(parse_interval("15").is_err());
+        assert!(parse_interval("m").is_err());
+    }
+
+    #[test]
+    fn test_next_delay() {
+        let now = Utc::now();
+        let interval = Duration::minutes(15);
+        assert_eq!(next_delay(interval, 0, None, now), interval);
+        assert_eq!(next_delay(interval, 2, None, now), Duration::minutes(60));
+        assert_eq!(next_delay(interval, 20, None, now), Duration::hours(6));
+
+        let plenty = RateLimit {
+            remaining: 4000,
+            reset: now + Duration::hours(1),
+        };
+        assert_eq!(next_delay(interval, 0, Some(&plenty), now), interval);
+        let low = RateLimit {
+            remaining: 10,
+            ..plenty
+        };
+        assert_eq!(
+            next_delay(interval, 0, Some(&low), now),
+            Duration::hours(1) + Duration::seconds(1)
+        );
+    }
+
+    #[test]
+    fn test_parse_rate_limit() {
+        let json = r#"{"resources": {"core": {"remaining": 1, "reset": 0},
+            "graphql": {"limit": 5000, "remaining": 4321, "reset": 1767225600}}}"#;
+        let limit = parse_rate_limit(json).unwrap();
+        assert_eq!(limit.remaining, 4321);
+        assert_eq!(limit.reset.timestamp(), 1767225600);
+        assert!(parse_rate_limit("{}").is_err());
+    }
+
+    #[test]
+    fn test_status_summary_and_round_trip() {
+        let temp = tempfile::TempDir::new().unwrap();
+        assert_eq!(SyncStatus::default().summary(), "never");
+        assert!(SyncStatus::load(temp.path()).is_none());
+
+        let status = SyncStatus {
+            last_success: Some("2026-03-04T10:15:00Z".parse().unwrap()),
+            created: 2,
+            updated: 1,
+            ..Default::default()
+        };
+        assert_eq!(status.summary(), "ok 2026-03-04 10:15 (+2 ~1)");
+        status.save(temp.path()).unwrap();
+        assert_eq!(SyncStatus::load(temp.path()), Some(status.clone()));
+
+        let failing = SyncStatus {
+            last_error: Some("gh failed: HTTP 502".to_string()),
+            failures: 3,
+            ..status
+        };
+        assert_eq!(failing.summary(), "failing (3x): gh failed: HTTP 502");
+    }
+}
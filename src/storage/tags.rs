@@ -0,0 +1,169 @@
+//! Hierarchical tags such as `area/auth` or `team/platform`
+//!
+//! A `/` in a tag separates namespaces. Filters ending in `/*` match every
+//! tag below that prefix, so `area/*` finds `area/auth` and `area/auth/oidc`.
+
+use crate::models::Task;
+use std::collections::BTreeMap;
+
+/// Separator between the levels of a tag
+pub const TAG_SEPARATOR: char = '/';
+
+/// Check a tag against a filter: an exact tag, or a `prefix/*` wildcard
+pub fn tag_matches(pattern: &str, tag: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) if prefix.ends_with(TAG_SEPARATOR) => {
+            tag.len() > prefix.len() && tag.starts_with(prefix)
+        }
+        _ => pattern == tag,
+    }
+}
+
+/// Group tasks by their tags under `namespace`
+///
+/// `area` groups a task tagged `area/auth` under `auth`. A task with several
+/// such tags appears in each group; tasks with none come last under `None`.
+pub fn group_by_namespace(tasks: Vec<Task>, namespace: &str) -> Vec<(Option<String>, Vec<Task>)> {
+    let prefix = format!(
+        "{}{}",
+        namespace.trim_end_matches(TAG_SEPARATOR),
+        TAG_SEPARATOR
+    );
+    let mut groups: BTreeMap<String, Vec<Task>> = BTreeMap::new();
+    let mut ungrouped = Vec::new();
+
+    for task in tasks {
+        let names: Vec<String> = task
+            .tags
+            .iter()
+            .filter_map(|tag| tag.strip_prefix(&prefix))
+            .filter(|name| !name.is_empty())
+            .map(str::to_string)
+            .collect();
+        if names.is_empty() {
+            ungrouped.push(task);
+            continue;
+        }
+        for name in names {
+            groups.entry(name).or_default().push(task.clone());
+        }
+    }
+
+    let mut grouped: Vec<(Option<String>, Vec<Task>)> = groups
+        .into_iter()
+        .map(|(name, tasks)| (Some(name), tasks))
+        .collect();
+    if !ungrouped.is_empty() {
+        grouped.push((None, ungrouped));
+    }
+    grouped
+}
+
+/// Number of open tasks carrying each tag, sorted by tag
+pub fn tag_counts(tasks: &[Task]) -> Vec<(String, usize)> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for task in tasks.iter().filter(|t| t.is_open()) {
+        for tag in &task.tags {
+            *counts.entry(tag.clone()).or_default() += 1;
+        }
+    }
+    counts.into_iter().collect()
+}
+
+/// One level of the tag hierarchy
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagNode {
+    /// The last segment of the tag (`auth` for `area/auth`)
+    pub name: String,
+    /// Open tasks tagged with this tag or any tag below it
+    pub count: usize,
+    pub children: Vec<TagNode>,
+}
+
+/// Arrange the tags of open tasks into a tree by their `/` segments
+pub fn tag_tree(tasks: &[Task]) -> Vec<TagNode> {
+    #[derive(Default)]
+    struct Builder {
+        tasks: std::collections::BTreeSet<u64>,
+        children: BTreeMap<String, Builder>,
+    }
+
+    fn finish(children: BTreeMap<String, Builder>) -> Vec<TagNode> {
+        children
+            .into_iter()
+            .map(|(name, node)| TagNode {
+                name,
+                count: node.tasks.len(),
+                children: finish(node.children),
+            })
+            .collect()
+    }
+
+    let mut root = Builder::default();
+    for task in tasks.iter().filter(|t| t.is_open()) {
+        for tag in &task.tags {
+            let mut node = &mut root;
+            for segment in tag.split(TAG_SEPARATOR).filter(|s| !s.is_empty()) {
+                node = node.children.entry(segment.to_string()).or_default();
+                node.tasks.insert(task.id);
+            }
+        }
+    }
+    finish(root.children)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TaskKind, TaskStatus};
+
+    fn tagged(id: u64, tags: &[&str]) -> Task {
+        let mut task = Task::new(id, TaskKind::Task, format!("Task {}", id));
+        task.tags = tags.iter().map(|t| t.to_string()).collect();
+        task
+    }
+
+    #[test]
+    fn test_tag_matches() {
+        assert!(tag_matches("area/*", "area/auth"));
+        assert!(tag_matches("area/*", "area/auth/oidc"));
+        assert!(!tag_matches("area/*", "area"));
+        assert!(!tag_matches("area/*", "areas/auth"));
+        assert!(tag_matches("area/auth", "area/auth"));
+        assert!(!tag_matches("area", "area/auth"));
+    }
+
+    #[test]
+    fn test_group_and_tree() {
+        let mut done = tagged(4, &["area/auth"]);
+        done.status = TaskStatus::Completed;
+        let tasks = vec![
+            tagged(1, &["area/auth", "team/platform"]),
+            tagged(2, &["area/auth/oidc", "area/billing"]),
+            tagged(3, &["bug"]),
+            done,
+        ];
+
+        let groups: Vec<(Option<String>, Vec<u64>)> = group_by_namespace(tasks.clone(), "area")
+            .into_iter()
+            .map(|(name, tasks)| (name, tasks.iter().map(|t| t.id).collect()))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                (Some("auth".to_string()), vec![1, 4]),
+                (Some("auth/oidc".to_string()), vec![2]),
+                (Some("billing".to_string()), vec![2]),
+                (None, vec![3]),
+            ]
+        );
+
+        let tree = tag_tree(&tasks);
+        let names: Vec<(&str, usize)> = tree.iter().map(|n| (n.name.as_str(), n.count)).collect();
+        assert_eq!(names, vec![("area", 2), ("bug", 1), ("team", 1)]);
+        let auth = &tree[0].children[0];
+        assert_eq!((auth.name.as_str(), auth.count), ("auth", 2));
+        assert_eq!(auth.children[0].name, "oidc");
+        assert_eq!(tag_counts(&tasks)[0], ("area/auth".to_string(), 1));
+    }
+}
@@ -0,0 +1,208 @@
+//! Task templates stored in `.tasks/templates/`
+//!
+//! A template is a Markdown file whose optional frontmatter sets defaults
+//! for new tasks and whose body becomes the task description:
+//!
+//! ```markdown
+//! ---
+//! priority: high
+//! tags: [bug]
+//! due: +1w
+//! ---
+//! ## Steps to reproduce
+//! ```
+
+use crate::models::frontmatter::split_frontmatter;
+use crate::models::{Due, Priority, Task, parse_due_date};
+use crate::storage::location::TaskLocation;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Templates directory within the tasks directory
+const TEMPLATES_DIR: &str = "templates";
+
+/// Errors related to task templates
+#[derive(Debug, Error)]
+pub enum TemplateError {
+    #[error("Template not found: {0}")]
+    NotFound(String),
+    #[error("Invalid template {0}: {1}")]
+    Invalid(String, String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Defaults and body for new tasks
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskTemplate {
+    /// Template name (file stem)
+    #[serde(skip_deserializing)]
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<Priority>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    /// Due date, absolute or relative to the day the task is created
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<String>,
+    /// Description for new tasks
+    #[serde(skip_deserializing)]
+    pub body: String,
+}
+
+impl TaskTemplate {
+    /// Directory holding the templates of a location
+    pub fn dir(location: &TaskLocation) -> PathBuf {
+        location.tasks_dir.join(TEMPLATES_DIR)
+    }
+
+    /// Load a template by name
+    ///
+    /// Names are file stems in the templates directory, so anything that
+    /// could reach outside it is rejected.
+    pub fn load(location: &TaskLocation, name: &str) -> Result<Self, TemplateError> {
+        if name.is_empty() || name.contains(['/', '\\']) || name.contains("..") {
+            return Err(TemplateError::Invalid(
+                name.to_string(),
+                "names can't contain path separators or '..'".to_string(),
+            ));
+        }
+        let path = Self::dir(location).join(format!("{}.md", name));
+        if !path.is_file() {
+            return Err(TemplateError::NotFound(name.to_string()));
+        }
+        Self::from_file(&path)
+    }
+
+    /// All templates of a location, sorted by name
+    pub fn list(location: &TaskLocation) -> Result<Vec<Self>, TemplateError> {
+        let dir = Self::dir(location);
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut templates = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "md") {
+                templates.push(Self::from_file(&path)?);
+            }
+        }
+        templates.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(templates)
+    }
+
+    fn from_file(path: &Path) -> Result<Self, TemplateError> {
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let content = std::fs::read_to_string(path)?;
+
+        let mut template = match split_frontmatter(&content) {
+            Ok((frontmatter, body)) => {
+                let mut template: TaskTemplate = if frontmatter.trim().is_empty() {
+                    TaskTemplate::default()
+                } else {
+                    serde_yaml::from_str(&frontmatter)
+                        .map_err(|e| TemplateError::Invalid(name.clone(), e.to_string()))?
+                };
+                template.body = body.trim().to_string();
+                template
+            }
+            // No frontmatter: the whole file is the body
+            Err(_) => TaskTemplate {
+                body: content.trim().to_string(),
+                ..Default::default()
+            },
+        };
+        template.name = name;
+
+        Ok(template)
+    }
+
+    /// Fill a new task with the template's defaults
+    ///
+    /// Tags are added to any already present; other fields are overwritten,
+    /// so apply the template before explicit values.
+    pub fn apply(&self, task: &mut Task, today: NaiveDate) -> Result<(), TemplateError> {
+        if let Some(priority) = self.priority {
+            task.priority = priority;
+        }
+        for tag in &self.tags {
+            task.add_tag(tag);
+        }
+        if self.context.is_some() {
+            task.context = self.context.clone();
+        }
+        if let Some(ref due) = self.due {
+            task.due = Some(Due::Date(
+                parse_due_date(due, today)
+                    .map_err(|e| TemplateError::Invalid(self.name.clone(), e))?,
+            ));
+        }
+        if !self.body.is_empty() {
+            task.description = self.body.clone();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TaskKind;
+    use crate::storage::test_support::setup_test_store;
+
+    #[test]
+    fn test_load_and_apply_template() {
+        let (temp, store) = setup_test_store();
+        let location = store.location().clone();
+        let dir = TaskTemplate::dir(&location);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("bug-report.md"),
+            "---\npriority: high\ntags: [bug]\ndue: +1w\n---\n\n## Steps to reproduce\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("plain.md"), "Just a checklist\n").unwrap();
+
+        let names: Vec<String> = TaskTemplate::list(&location)
+            .unwrap()
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(names, vec!["bug-report", "plain"]);
+
+        let template = TaskTemplate::load(&location, "bug-report").unwrap();
+        let mut task = Task::new(0, TaskKind::Task, "Crash on save");
+        task.tags = vec!["ui".to_string()];
+        let today = NaiveDate::from_ymd_opt(2026, 2, 18).unwrap();
+        template.apply(&mut task, today).unwrap();
+
+        assert_eq!(task.priority, Priority::High);
+        assert_eq!(task.tags, vec!["ui", "bug"]);
+        assert_eq!(
+            task.due,
+            NaiveDate::from_ymd_opt(2026, 2, 25).map(Due::Date)
+        );
+        assert_eq!(task.description, "## Steps to reproduce");
+
+        assert!(matches!(
+            TaskTemplate::load(&location, "missing"),
+            Err(TemplateError::NotFound(_))
+        ));
+        std::fs::write(temp.path().join("secret.md"), "Outside\n").unwrap();
+        for name in ["../../secret", "sub/bug-report", "..\\secret", ""] {
+            assert!(matches!(
+                TaskTemplate::load(&location, name),
+                Err(TemplateError::Invalid(..))
+            ));
+        }
+    }
+}
@@ -0,0 +1,24 @@
+//! Fixtures shared by the storage tests
+
+use crate::storage::{FileStore, TaskLocation};
+use std::path::Path;
+use tempfile::TempDir;
+
+/// A store for a new project in a temporary directory with a fake `.git`
+///
+/// The store's files live as long as the returned `TempDir`.
+pub(crate) fn setup_test_store() -> (TempDir, FileStore) {
+    let temp = TempDir::new().unwrap();
+    let store = test_store_at(temp.path());
+    (temp, store)
+}
+
+/// A store for a new project at `root`, created with a fake `.git`
+///
+/// For tests that need several projects, or care what they're called.
+pub(crate) fn test_store_at(root: &Path) -> FileStore {
+    std::fs::create_dir_all(root.join(".git")).unwrap();
+    let location = TaskLocation::find_project_from(root).unwrap();
+    location.ensure_exists().unwrap();
+    FileStore::new(location)
+}
@@ -0,0 +1,129 @@
+//! Urgency scores for ranking open tasks
+//!
+//! The score is a weighted sum of priority, due date proximity and age, plus
+//! per-tag bonuses, with weights from the `[urgency]` config section.
+
+use crate::models::{Priority, Task};
+use crate::storage::config::UrgencyConfig;
+use chrono::{DateTime, Utc};
+
+/// Days overdue at which the due factor maxes out
+const OVERDUE_MAX_DAYS: f64 = 7.0;
+/// Days ahead beyond which the due factor stays at its floor
+const DUE_HORIZON_DAYS: f64 = 14.0;
+/// Due factor for a due date beyond the horizon
+const DUE_FLOOR: f64 = 0.2;
+/// Age at which the age factor maxes out
+const AGE_MAX_DAYS: f64 = 365.0;
+
+fn priority_factor(priority: Priority) -> f64 {
+    match priority {
+        Priority::Low => 0.0,
+        Priority::Medium => 1.0 / 3.0,
+        Priority::High => 2.0 / 3.0,
+        Priority::Critical => 1.0,
+    }
+}
+
+/// 0 without a due date; otherwise rising linearly from 0.2 two weeks out
+/// to 1.0 a week overdue
+fn due_factor(task: &Task, now: DateTime<Utc>) -> f64 {
+    let Some(due) = task.due else {
+        return 0.0;
+    };
+    let days_left = (due.anchor() - now).num_minutes() as f64 / (24.0 * 60.0);
+    if days_left <= -OVERDUE_MAX_DAYS {
+        1.0
+    } else if days_left >= DUE_HORIZON_DAYS {
+        DUE_FLOOR
+    } else {
+        DUE_FLOOR
+            + (1.0 - DUE_FLOOR) * (DUE_HORIZON_DAYS - days_left)
+                / (DUE_HORIZON_DAYS + OVERDUE_MAX_DAYS)
+    }
+}
+
+fn age_factor(task: &Task, now: DateTime<Utc>) -> f64 {
+    let days = (now - task.created).num_minutes() as f64 / (24.0 * 60.0);
+    (days / AGE_MAX_DAYS).clamp(0.0, 1.0)
+}
+
+/// Urgency score of a task at `now`; higher is more urgent
+pub fn urgency(task: &Task, config: &UrgencyConfig, now: DateTime<Utc>) -> f64 {
+    let tags: f64 = task
+        .tags
+        .iter()
+        .filter_map(|tag| config.tags.get(tag))
+        .sum();
+    config.priority * priority_factor(task.priority)
+        + config.due * due_factor(task, now)
+        + config.age * age_factor(task, now)
+        + tags
+}
+
+/// Pair tasks with their urgency, most urgent first (ties by ID)
+pub fn rank_by_urgency(
+    tasks: impl IntoIterator<Item = Task>,
+    config: &UrgencyConfig,
+    now: DateTime<Utc>,
+) -> Vec<(Task, f64)> {
+    let mut ranked: Vec<(Task, f64)> = tasks
+        .into_iter()
+        .map(|t| {
+            let score = urgency(&t, config, now);
+            (t, score)
+        })
+        .collect();
+    ranked.sort_by(|(a, sa), (b, sb)| sb.total_cmp(sa).then(a.id.cmp(&b.id)));
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Due, TaskKind};
+    use chrono::Duration;
+
+    #[test]
+    fn test_urgency_factors() {
+        let now = Utc::now();
+        let config = UrgencyConfig::default();
+
+        let mut task = Task::new(1, TaskKind::Task, "Task");
+        task.created = now;
+        task.priority = Priority::Critical;
+        assert!((urgency(&task, &config, now) - 6.0).abs() < 1e-6);
+
+        task.due = Some(Due::At((now - Duration::days(30)).fixed_offset()));
+        assert!((urgency(&task, &config, now) - 18.0).abs() < 1e-6);
+
+        task.due = Some(Due::At((now + Duration::days(60)).fixed_offset()));
+        assert!((due_factor(&task, now) - DUE_FLOOR).abs() < 1e-6);
+        task.due = Some(Due::At((now + Duration::days(3)).fixed_offset()));
+        let soon = due_factor(&task, now);
+        assert!(soon > DUE_FLOOR && soon < 1.0);
+
+        task.created = now - Duration::days(730);
+        assert!((age_factor(&task, now) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_rank_by_urgency_with_tag_weights() {
+        let now = Utc::now();
+        let mut config = UrgencyConfig::default();
+        config.tags.insert("quickwin".to_string(), 5.0);
+
+        let mut high = Task::new(1, TaskKind::Task, "High");
+        high.priority = Priority::High;
+        let mut quick = Task::new(2, TaskKind::Task, "Quick");
+        quick.priority = Priority::Low;
+        quick.add_tag("quickwin");
+        let medium = Task::new(3, TaskKind::Task, "Medium");
+
+        let ids: Vec<u64> = rank_by_urgency(vec![medium, high, quick], &config, now)
+            .into_iter()
+            .map(|(t, _)| t.id)
+            .collect();
+        assert_eq!(ids, vec![2, 1, 3]);
+    }
+}
@@ -0,0 +1,168 @@
+//! Frontmatter validation rules
+//!
+//! Rules come from the `[validation]` section of `.tasks/config.toml` and
+//! are checked by `FileStore::create` and `FileStore::update`, so tasks in
+//! a shared repository keep consistent metadata whichever tool writes them.
+
+use crate::models::Task;
+use crate::storage::config::ValidationConfig;
+use crate::storage::tags::tag_matches;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fmt;
+
+/// A rule a task breaks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValidationError {
+    /// Frontmatter field the rule is about
+    pub field: String,
+    pub message: String,
+}
+
+impl ValidationError {
+    fn new(field: &str, message: impl Into<String>) -> Self {
+        ValidationError {
+            field: field.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// Whether `field` (one of `ValidationConfig::FIELDS`) has a value
+fn is_filled(task: &Task, field: &str) -> bool {
+    match field {
+        "assignee" => task.assignee.is_some(),
+        "context" => task.context.is_some(),
+        "description" => !task.description.trim().is_empty(),
+        "due" => task.due.is_some(),
+        "estimate" => task.estimate.is_some(),
+        "refs" => !task.refs.is_empty(),
+        "tags" => !task.tags.is_empty(),
+        _ => true,
+    }
+}
+
+/// Rules `task` breaks, in field order
+///
+/// `previous` is the saved version of the task when updating it. Only
+/// what the update changes is checked: a task that became overdue, or that
+/// predates a rule, can still be edited, but not made any worse.
+pub fn validate_task(
+    config: &ValidationConfig,
+    task: &Task,
+    previous: Option<&Task>,
+    now: DateTime<Utc>,
+) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+
+    if let Some(max) = config.max_title_length
+        && previous.is_none_or(|p| p.title != task.title)
+    {
+        let length = task.title.chars().count();
+        if length > max {
+            errors.push(ValidationError::new(
+                "title",
+                format!("{} characters long (at most {})", length, max),
+            ));
+        }
+    }
+
+    if !config.allowed_tags.is_empty() {
+        let added = task
+            .tags
+            .iter()
+            .filter(|tag| previous.is_none_or(|p| !p.tags.contains(tag)));
+        for tag in added {
+            if !config.allowed_tags.iter().any(|p| tag_matches(p, tag)) {
+                errors.push(ValidationError::new(
+                    "tags",
+                    format!("'{}' is not an allowed tag", tag),
+                ));
+            }
+        }
+    }
+
+    if config.due_in_future
+        && let Some(due) = &task.due
+        && previous.is_none_or(|p| p.due.as_ref() != Some(due))
+        && due.is_past(now)
+    {
+        errors.push(ValidationError::new("due", "must not be in the past"));
+    }
+
+    if let Some(fields) = config.required.get(&task.kind) {
+        for field in fields {
+            if !is_filled(task, field) && previous.is_none_or(|p| is_filled(p, field)) {
+                errors.push(ValidationError::new(
+                    field,
+                    format!("required for kind {}", task.kind),
+                ));
+            }
+        }
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Due, TaskKind, TaskStatus};
+    use chrono::{Duration, TimeZone};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_validate_task() {
+        let now = Utc.with_ymd_and_hms(2026, 5, 1, 12, 0, 0).unwrap();
+        let config = ValidationConfig {
+            max_title_length: Some(10),
+            allowed_tags: vec!["bug".to_string(), "area/*".to_string()],
+            due_in_future: true,
+            required: BTreeMap::from([(TaskKind::Task, vec!["assignee".to_string()])]),
+        };
+
+        let mut task = Task::new(1, TaskKind::Task, "A rather long title");
+        task.tags = vec!["bug".to_string(), "area/ui".to_string(), "misc".to_string()];
+        task.due = Some(Due::At((now - Duration::hours(1)).fixed_offset()));
+        let fields: Vec<String> = validate_task(&config, &task, None, now)
+            .iter()
+            .map(|e| e.field.clone())
+            .collect();
+        assert_eq!(fields, vec!["title", "tags", "due", "assignee"]);
+
+        // Updates are only checked for what they change
+        let previous = task.clone();
+        task.status = TaskStatus::Completed;
+        assert!(validate_task(&config, &task, Some(&previous), now).is_empty());
+        task.add_tag("wontfix");
+        task.title = "Still far too long".to_string();
+        let fields: Vec<String> = validate_task(&config, &task, Some(&previous), now)
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                "title: 18 characters long (at most 10)",
+                "tags: 'wontfix' is not an allowed tag"
+            ]
+        );
+
+        // Clearing a required field is rejected; ideas have none
+        let mut assigned = previous.clone();
+        assigned.assignee = Some("ana".to_string());
+        assert_eq!(
+            validate_task(&config, &previous, Some(&assigned), now)[0].field,
+            "assignee"
+        );
+        let idea = Task::new(2, TaskKind::Idea, "Someday");
+        assert!(validate_task(&config, &idea, None, now).is_empty());
+        assert!(validate_task(&ValidationConfig::default(), &previous, None, now).is_empty());
+    }
+}
@@ -0,0 +1,165 @@
+//! Filesystem watching for task directories
+
+use crate::models::Task;
+use crate::storage::file_store::read_task_entry;
+use crate::storage::layout;
+use crate::storage::location::TaskLocation;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+use thiserror::Error;
+
+/// Errors related to watching a task directory
+#[derive(Debug, Error)]
+pub enum WatchError {
+    #[error("Watch error: {0}")]
+    Notify(#[from] notify::Error),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Task directory does not exist. Run 'gittask init' first.")]
+    DirectoryNotInitialized,
+    #[error("Watcher disconnected")]
+    Disconnected,
+}
+
+/// A change to a task file
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskEvent {
+    /// A new task file appeared
+    Created(Task),
+    /// An existing task's content changed
+    Modified(Task),
+    /// A task file was removed
+    Deleted { id: u64, path: PathBuf },
+}
+
+/// Watches a task directory and reports typed task events
+///
+/// Raw filesystem notifications are reconciled against the last known state
+/// of each file, so editors that write in several steps produce a single
+/// event and no-op writes produce none.
+pub struct TaskWatcher {
+    _watcher: RecommendedWatcher,
+    rx: Receiver<notify::Result<notify::Event>>,
+    known: HashMap<PathBuf, Task>,
+}
+
+impl TaskWatcher {
+    /// Start watching the tasks directory of a location
+    pub fn new(location: &TaskLocation) -> Result<Self, WatchError> {
+        if !location.exists() {
+            return Err(WatchError::DirectoryNotInitialized);
+        }
+
+        let mut known = HashMap::new();
+        for path in layout::task_files(&location.tasks_dir)? {
+            if let Some(task) = read_task_entry(path.clone()) {
+                known.insert(path, task);
+            }
+        }
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx)?;
+        watcher.watch(&location.tasks_dir, RecursiveMode::Recursive)?;
+
+        Ok(TaskWatcher {
+            _watcher: watcher,
+            rx,
+            known,
+        })
+    }
+
+    /// Block until at least one task event is available
+    pub fn recv(&mut self) -> Result<Vec<TaskEvent>, WatchError> {
+        loop {
+            let event = self.rx.recv().map_err(|_| WatchError::Disconnected)?;
+            let events = self.process(event?)?;
+            if !events.is_empty() {
+                return Ok(events);
+            }
+        }
+    }
+
+    /// Wait up to `timeout` for task events, returning an empty list if none arrived
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Result<Vec<TaskEvent>, WatchError> {
+        let deadline = std::time::Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            match self.rx.recv_timeout(remaining) {
+                Ok(event) => {
+                    let events = self.process(event?)?;
+                    if !events.is_empty() {
+                        return Ok(events);
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => return Ok(Vec::new()),
+                Err(RecvTimeoutError::Disconnected) => return Err(WatchError::Disconnected),
+            }
+        }
+    }
+
+    /// Reconcile the paths touched by a raw event against known state
+    fn process(&mut self, event: notify::Event) -> Result<Vec<TaskEvent>, WatchError> {
+        let mut events = Vec::new();
+
+        for path in event.paths {
+            if !layout::is_task_file(&path) {
+                continue;
+            }
+            if let Some(event) = self.reconcile(&path) {
+                events.push(event);
+            }
+        }
+
+        Ok(events)
+    }
+
+    fn reconcile(&mut self, path: &Path) -> Option<TaskEvent> {
+        match read_task_entry(path.to_path_buf()) {
+            Some(task) => match self.known.insert(path.to_path_buf(), task.clone()) {
+                None => Some(TaskEvent::Created(task)),
+                Some(old) if old != task => Some(TaskEvent::Modified(task)),
+                Some(_) => None,
+            },
+            None if !path.exists() => self.known.remove(path).map(|old| TaskEvent::Deleted {
+                id: old.id,
+                path: path.to_path_buf(),
+            }),
+            // Exists but unparseable (e.g. mid-write); wait for the next event
+            None => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TaskKind;
+    use crate::storage::test_support::setup_test_store;
+
+    #[test]
+    fn test_watcher_reports_typed_events() {
+        let (_temp, store) = setup_test_store();
+        let location = store.location().clone();
+
+        let mut watcher = TaskWatcher::new(&location).unwrap();
+        let timeout = Duration::from_secs(5);
+
+        let mut task = store
+            .create(Task::new(0, TaskKind::Task, "Watched"))
+            .unwrap();
+        let events = watcher.recv_timeout(timeout).unwrap();
+        assert!(matches!(&events[0], TaskEvent::Created(t) if t.title == "Watched"));
+
+        task.description = "Changed".to_string();
+        store.update(&task).unwrap();
+        let events = watcher.recv_timeout(timeout).unwrap();
+        assert!(matches!(&events[0], TaskEvent::Modified(t) if t.description == "Changed"));
+
+        store.delete(task.id).unwrap();
+        let events = watcher.recv_timeout(timeout).unwrap();
+        assert!(matches!(events[0], TaskEvent::Deleted { id: 1, .. }));
+    }
+}
@@ -0,0 +1,167 @@
+//! Work-in-progress limits
+//!
+//! Limits come from the `[wip]` section of `.tasks/config.toml` and cap how
+//! many tasks can be in progress at once, across the project and per assignee.
+
+use crate::models::{Task, TaskStatus};
+use crate::storage::config::{ProjectConfig, WipConfig};
+use crate::storage::file_store::{FileStore, FileStoreError, TaskFilter};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// A WIP limit that is (or would be) exceeded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WipViolation {
+    /// The assignee over their limit, or `None` for the project-wide limit
+    pub assignee: Option<String>,
+    pub limit: usize,
+    pub count: usize,
+}
+
+impl fmt::Display for WipViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let who = self.assignee.as_deref().unwrap_or("project");
+        write!(
+            f,
+            "{} has {} tasks in progress (WIP limit {})",
+            who, self.count, self.limit
+        )
+    }
+}
+
+/// Limits exceeded by the in-progress tasks among `tasks`
+///
+/// The project-wide violation comes first, then assignees by name.
+pub fn wip_overloads(config: &WipConfig, tasks: &[Task]) -> Vec<WipViolation> {
+    let in_progress: Vec<&Task> = tasks
+        .iter()
+        .filter(|t| t.status == TaskStatus::InProgress)
+        .collect();
+
+    let mut violations = Vec::new();
+    if let Some(limit) = config.max_in_progress
+        && in_progress.len() > limit
+    {
+        violations.push(WipViolation {
+            assignee: None,
+            limit,
+            count: in_progress.len(),
+        });
+    }
+
+    if let Some(limit) = config.max_per_assignee {
+        let mut per_assignee: BTreeMap<&str, usize> = BTreeMap::new();
+        for assignee in in_progress.iter().filter_map(|t| t.assignee.as_deref()) {
+            *per_assignee.entry(assignee).or_default() += 1;
+        }
+        violations.extend(
+            per_assignee
+                .into_iter()
+                .filter(|&(_, count)| count > limit)
+                .map(|(assignee, count)| WipViolation {
+                    assignee: Some(assignee.to_string()),
+                    limit,
+                    count,
+                }),
+        );
+    }
+
+    violations
+}
+
+/// Limits that moving `task` into progress would exceed
+///
+/// Nothing is reported for a task that is already in progress, so re-running
+/// `start` on it never trips a limit.
+pub fn check_wip(store: &FileStore, task: &Task) -> Result<Vec<WipViolation>, FileStoreError> {
+    if task.status == TaskStatus::InProgress {
+        return Ok(Vec::new());
+    }
+    let config = ProjectConfig::load(&store.location().tasks_dir)?.wip;
+    if config == WipConfig::default() {
+        return Ok(Vec::new());
+    }
+
+    let mut tasks = store.list(&TaskFilter {
+        status: Some(TaskStatus::InProgress),
+        ..Default::default()
+    })?;
+    let mut started = task.clone();
+    started.status = TaskStatus::InProgress;
+    tasks.retain(|t| t.id != task.id);
+    tasks.push(started);
+
+    Ok(wip_overloads(&config, &tasks)
+        .into_iter()
+        .filter(|v| v.assignee.is_none() || v.assignee == task.assignee)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TaskKind;
+    use crate::storage::test_support::setup_test_store;
+
+    fn in_progress(id: u64, assignee: Option<&str>) -> Task {
+        let mut task = Task::new(id, TaskKind::Task, format!("Task {}", id));
+        task.status = TaskStatus::InProgress;
+        task.assignee = assignee.map(|a| a.to_string());
+        task
+    }
+
+    #[test]
+    fn test_wip_overloads() {
+        let config = WipConfig {
+            max_in_progress: Some(2),
+            max_per_assignee: Some(1),
+        };
+        let tasks = vec![
+            in_progress(1, Some("alice")),
+            in_progress(2, Some("alice")),
+            in_progress(3, Some("bob")),
+            Task::new(4, TaskKind::Task, "Pending"),
+        ];
+
+        let violations = wip_overloads(&config, &tasks);
+        assert_eq!(
+            violations,
+            vec![
+                WipViolation {
+                    assignee: None,
+                    limit: 2,
+                    count: 3
+                },
+                WipViolation {
+                    assignee: Some("alice".to_string()),
+                    limit: 1,
+                    count: 2
+                },
+            ]
+        );
+        assert_eq!(
+            violations[1].to_string(),
+            "alice has 2 tasks in progress (WIP limit 1)"
+        );
+        assert!(wip_overloads(&WipConfig::default(), &tasks).is_empty());
+    }
+
+    #[test]
+    fn test_check_wip() {
+        let (_temp, store) = setup_test_store();
+
+        let mut config = ProjectConfig::default();
+        config.wip.max_per_assignee = Some(1);
+        config.save(&store.location().tasks_dir).unwrap();
+
+        let running = store.create(in_progress(0, Some("alice"))).unwrap();
+        let mut next = Task::new(0, TaskKind::Task, "Next");
+        next.assignee = Some("alice".to_string());
+        let next = store.create(next).unwrap();
+        let other = store.create(Task::new(0, TaskKind::Task, "Other")).unwrap();
+
+        assert_eq!(check_wip(&store, &next).unwrap().len(), 1);
+        assert!(check_wip(&store, &other).unwrap().is_empty());
+        assert!(check_wip(&store, &running).unwrap().is_empty());
+    }
+}
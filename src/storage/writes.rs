@@ -0,0 +1,272 @@
+//! File writes of the storage layer, and `--dry-run`
+//!
+//! Everything the storage layer writes goes through the functions here.
+//! Under [`set_dry_run`] they record a [`PlannedChange`] instead of
+//! touching the disk, so a write path can't forget to check for a dry run.
+
+use crate::storage::id_generator::IdGenerator;
+use std::cell::RefCell;
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// A file change held back by `--dry-run`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlannedChange {
+    Create(PathBuf),
+    Modify(PathBuf),
+    Delete(PathBuf),
+    Move { from: PathBuf, to: PathBuf },
+}
+
+impl PlannedChange {
+    /// Writing `path`: a create if it doesn't exist yet, a modify otherwise
+    pub fn write(path: PathBuf) -> Self {
+        if path.exists() {
+            PlannedChange::Modify(path)
+        } else {
+            PlannedChange::Create(path)
+        }
+    }
+}
+
+impl std::fmt::Display for PlannedChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlannedChange::Create(path) => write!(f, "create {}", path.display()),
+            PlannedChange::Modify(path) => write!(f, "modify {}", path.display()),
+            PlannedChange::Delete(path) => write!(f, "delete {}", path.display()),
+            PlannedChange::Move { from, to } => {
+                write!(f, "move   {} -> {}", from.display(), to.display())
+            }
+        }
+    }
+}
+
+thread_local! {
+    /// Set by `--dry-run`: changes collected instead of written, `None` otherwise
+    ///
+    /// Unlike `--read-only` this is per thread, so a dry run never turns
+    /// writes made by other threads (tests, servers) into no-ops.
+    static DRY_RUN: RefCell<Option<Vec<PlannedChange>>> = const { RefCell::new(None) };
+}
+
+/// Make every write on this thread a no-op that is recorded instead
+///
+/// The recorded changes are returned by `take_planned_changes`.
+pub fn set_dry_run(dry_run: bool) {
+    DRY_RUN.with_borrow_mut(|planned| *planned = dry_run.then(Vec::new));
+}
+
+/// Whether writes are being held back by `set_dry_run`
+pub fn is_dry_run() -> bool {
+    DRY_RUN.with_borrow(|planned| planned.is_some())
+}
+
+/// Record `change` instead of making it, if this is a dry run
+///
+/// Returns whether the change was held back; callers write only if not.
+fn hold_back(change: PlannedChange) -> bool {
+    DRY_RUN.with_borrow_mut(|planned| match planned.as_mut() {
+        Some(planned) => {
+            if !planned.contains(&change) {
+                planned.push(change);
+            }
+            true
+        }
+        None => false,
+    })
+}
+
+/// The changes held back so far, in order, leaving the list empty
+pub fn take_planned_changes() -> Vec<PlannedChange> {
+    DRY_RUN.with_borrow_mut(|planned| planned.as_mut().map(std::mem::take).unwrap_or_default())
+}
+
+/// Highest task ID a held-back change would write into `tasks_dir`
+pub(crate) fn planned_max_id(tasks_dir: &Path) -> u64 {
+    DRY_RUN.with_borrow(|planned| {
+        planned
+            .iter()
+            .flatten()
+            .filter_map(|change| match change {
+                PlannedChange::Create(path) | PlannedChange::Move { to: path, .. } => Some(path),
+                _ => None,
+            })
+            .filter(|path| path.starts_with(tasks_dir))
+            .filter_map(|path| IdGenerator::extract_id_from_filename(path))
+            .max()
+            .unwrap_or(0)
+    })
+}
+
+fn create_parent(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => std::fs::create_dir_all(dir),
+        _ => Ok(()),
+    }
+}
+
+/// Write `contents` to `path`, creating its directory if needed
+pub fn write(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    if hold_back(PlannedChange::write(path.to_path_buf())) {
+        return Ok(());
+    }
+    create_parent(path)?;
+    std::fs::write(path, contents)
+}
+
+/// Write `path` through `f`, creating its directory if needed
+///
+/// For output produced by a writer, such as an archive.
+pub fn write_with(path: &Path, f: impl FnOnce(File) -> io::Result<()>) -> io::Result<()> {
+    if hold_back(PlannedChange::write(path.to_path_buf())) {
+        return Ok(());
+    }
+    create_parent(path)?;
+    f(File::create(path)?)
+}
+
+/// Like [`write_with`], but fails with `AlreadyExists` instead of
+/// truncating a file that is already at `path`
+pub fn write_new_with(path: &Path, f: impl FnOnce(File) -> io::Result<()>) -> io::Result<()> {
+    if hold_back(PlannedChange::write(path.to_path_buf())) {
+        return Ok(());
+    }
+    create_parent(path)?;
+    f(std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)?)
+}
+
+/// Append `contents` to `path`, creating it if needed
+pub fn append(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    use std::io::Write;
+
+    if hold_back(PlannedChange::write(path.to_path_buf())) {
+        return Ok(());
+    }
+    create_parent(path)?;
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(contents.as_ref())
+}
+
+/// Write `contents` to `path` through a temporary file, so readers never
+/// see it half written
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    if hold_back(PlannedChange::write(path.to_path_buf())) {
+        return Ok(());
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    std::fs::write(&tmp, contents)?;
+    std::fs::rename(&tmp, path)
+}
+
+/// Write a cache file, such as the task index, atomically
+///
+/// Caches are rebuilt from the task files whenever needed, so a dry run
+/// skips them without listing them as a change.
+pub fn write_cache(path: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    if is_dry_run() {
+        return Ok(());
+    }
+    write_atomic(path, contents)
+}
+
+/// Move `from` to `to`, creating the target directory if needed
+pub fn rename(from: &Path, to: &Path) -> io::Result<()> {
+    if hold_back(PlannedChange::Move {
+        from: from.to_path_buf(),
+        to: to.to_path_buf(),
+    }) {
+        return Ok(());
+    }
+    create_parent(to)?;
+    std::fs::rename(from, to)
+}
+
+/// Replace `from` with `contents` written to `to` (the same file, renamed)
+pub fn rewrite(from: &Path, to: &Path, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    if from == to {
+        return write(to, contents);
+    }
+    if hold_back(PlannedChange::Move {
+        from: from.to_path_buf(),
+        to: to.to_path_buf(),
+    }) {
+        return Ok(());
+    }
+    create_parent(to)?;
+    std::fs::write(to, contents)?;
+    std::fs::remove_file(from)
+}
+
+/// Delete the file at `path`
+pub fn remove_file(path: &Path) -> io::Result<()> {
+    if hold_back(PlannedChange::Delete(path.to_path_buf())) {
+        return Ok(());
+    }
+    std::fs::remove_file(path)
+}
+
+/// Delete the empty directory `path`
+pub fn remove_dir(path: &Path) -> io::Result<()> {
+    if hold_back(PlannedChange::Delete(path.to_path_buf())) {
+        return Ok(());
+    }
+    std::fs::remove_dir(path)
+}
+
+/// Create the directory `path` and its parents
+pub fn create_dir_all(path: &Path) -> io::Result<()> {
+    if path.is_dir() || hold_back(PlannedChange::Create(path.to_path_buf())) {
+        return Ok(());
+    }
+    std::fs::create_dir_all(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_dry_run_holds_back_writes() {
+        let temp = TempDir::new().unwrap();
+        let file = temp.path().join("dir/a.md");
+        let moved = temp.path().join("b.md");
+
+        set_dry_run(true);
+        write(&file, "a").unwrap();
+        append(&file, "b").unwrap();
+        rename(&file, &moved).unwrap();
+        create_dir_all(&temp.path().join("new")).unwrap();
+        write_cache(&temp.path().join(".index"), "{}").unwrap();
+        let planned = take_planned_changes();
+        set_dry_run(false);
+
+        assert_eq!(
+            planned,
+            [
+                PlannedChange::Create(file.clone()),
+                PlannedChange::Move {
+                    from: file.clone(),
+                    to: moved.clone()
+                },
+                PlannedChange::Create(temp.path().join("new")),
+            ]
+        );
+        assert_eq!(std::fs::read_dir(temp.path()).unwrap().count(), 0);
+
+        write(&file, "a").unwrap();
+        rewrite(&file, &moved, "b").unwrap();
+        assert!(!file.exists());
+        assert_eq!(std::fs::read_to_string(&moved).unwrap(), "b");
+        assert!(!is_dry_run());
+    }
+}
@@ -0,0 +1,5 @@
+//! Local web server: a read-only JSON API and an optional dashboard
+
+pub mod server;
+
+pub use server::{WebOptions, run_web_server};
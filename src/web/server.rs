@@ -0,0 +1,303 @@
+//! Read-only HTTP API and dashboard for `gittask serve`
+//!
+//! A deliberately small HTTP/1.1 server bound to localhost: GET requests
+//! only, one request per connection. `/api/*` answers with JSON; with the
+//! dashboard enabled, `/` serves a single bundled page built on the same API.
+
+use crate::storage::export::ExportedTask;
+use crate::storage::{FileStore, ProjectConfig, TaskFilter, TaskLocation, sort_by_order};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+/// The single-page dashboard served at `/`
+const DASHBOARD: &str = include_str!("dashboard.html");
+
+/// How the web server was started
+#[derive(Debug, Clone)]
+pub struct WebOptions {
+    pub port: u16,
+    /// Serve the dashboard at `/` as well as the API
+    pub ui: bool,
+}
+
+/// An HTTP response ready to be written out
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    fn json(value: &Value) -> Self {
+        Response {
+            status: 200,
+            content_type: "application/json",
+            body: serde_json::to_string_pretty(value).unwrap_or_default(),
+        }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        Response {
+            status,
+            ..Response::json(&json!({"error": message.into()}))
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let reason = match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            _ => "Internal Server Error",
+        };
+        let mut bytes = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.status,
+            reason,
+            self.content_type,
+            self.body.len()
+        )
+        .into_bytes();
+        bytes.extend_from_slice(self.body.as_bytes());
+        bytes
+    }
+}
+
+/// Answer one request against the store
+pub fn handle(store: &FileStore, ui: bool, method: &str, target: &str) -> Response {
+    if method != "GET" {
+        return Response::error(405, "Only GET is supported");
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let params = parse_query(query);
+    match path {
+        "/" | "/index.html" if ui => Response {
+            status: 200,
+            content_type: "text/html; charset=utf-8",
+            body: DASHBOARD.to_string(),
+        },
+        "/api/tasks" => list_tasks(store, &params),
+        "/api/stats" => stats(store),
+        "/api/columns" => columns(store),
+        _ => match path.strip_prefix("/api/tasks/") {
+            Some(id) => get_task(store, id),
+            None => Response::error(404, format!("No such path: {}", path)),
+        },
+    }
+}
+
+/// `GET /api/tasks?status=&kind=&tag=&q=&all`, by status and hand-curated
+/// order, pinned tasks first
+fn list_tasks(store: &FileStore, params: &HashMap<String, String>) -> Response {
+    let mut filter = TaskFilter {
+        include_archived: params.contains_key("all"),
+        tags: params.get("tag").cloned().into_iter().collect(),
+        ..Default::default()
+    };
+    if let Some(status) = params.get("status") {
+        let workflow = match ProjectConfig::load(&store.location().tasks_dir) {
+            Ok(config) => config.workflow,
+            Err(e) => return Response::error(500, e.to_string()),
+        };
+        match workflow.parse_status(status) {
+            Ok(status) => filter.status = Some(status),
+            Err(e) => return Response::error(400, e),
+        }
+    }
+    if let Some(kind) = params.get("kind") {
+        match kind.parse() {
+            Ok(kind) => filter.kind = Some(kind),
+            Err(e) => return Response::error(400, e),
+        }
+    }
+
+    let mut tasks = match store.list(&filter) {
+        Ok(tasks) => tasks,
+        Err(e) => return Response::error(500, e.to_string()),
+    };
+    if let Some(q) = params.get("q").map(|q| q.to_lowercase()) {
+        tasks.retain(|t| {
+            t.title.to_lowercase().contains(&q) || t.description.to_lowercase().contains(&q)
+        });
+    }
+    sort_by_order(&mut tasks);
+    tasks.sort_by_key(|t| !t.pinned);
+
+    let exported: Vec<ExportedTask> = tasks.iter().map(ExportedTask::from).collect();
+    Response::json(&json!(exported))
+}
+
+/// `GET /api/tasks/<id>`
+fn get_task(store: &FileStore, id: &str) -> Response {
+    let Ok(id) = id.parse::<u64>() else {
+        return Response::error(400, format!("Invalid task ID: {}", id));
+    };
+    match store.read(id) {
+        Ok(task) => Response::json(&json!(ExportedTask::from(&task))),
+        Err(e) => Response::error(404, e.to_string()),
+    }
+}
+
+/// `GET /api/stats`
+fn stats(store: &FileStore) -> Response {
+    match store.stats() {
+        Ok(stats) => Response::json(&json!({
+            "total": stats.total,
+            "pending": stats.pending,
+            "in_progress": stats.in_progress,
+            "custom": stats.custom,
+            "completed": stats.completed,
+            "closed_not_done": stats.closed_not_done,
+            "archived": stats.archived,
+            "overdue": stats.overdue,
+            "due_soon": stats.due_soon,
+        })),
+        Err(e) => Response::error(500, e.to_string()),
+    }
+}
+
+/// `GET /api/columns`: board columns in order
+fn columns(store: &FileStore) -> Response {
+    match ProjectConfig::load(&store.location().tasks_dir) {
+        Ok(config) => {
+            let columns: Vec<String> = config
+                .workflow
+                .columns()
+                .iter()
+                .map(|c| c.to_string())
+                .collect();
+            Response::json(&json!(columns))
+        }
+        Err(e) => Response::error(500, e.to_string()),
+    }
+}
+
+/// Split a query string into decoded key/value pairs
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(key), percent_decode(value))
+        })
+        .collect()
+}
+
+/// Decode `%XX` escapes and `+` as space
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+                match u8::from_str_radix(hex, 16) {
+                    Ok(byte) => {
+                        out.push(byte);
+                        i += 2;
+                    }
+                    Err(_) => out.push(b'%'),
+                }
+            }
+            byte => out.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Read one request from the connection and write the response
+async fn serve_connection(
+    mut stream: TcpStream,
+    location: TaskLocation,
+    ui: bool,
+) -> std::io::Result<()> {
+    let (reader, mut writer) = stream.split();
+    let mut reader = BufReader::new(reader);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    // Headers are not used; read past them so the client sees a clean close
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 || line.trim().is_empty() {
+            break;
+        }
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let response = match (parts.next(), parts.next()) {
+        (Some(method), Some(target)) => handle(&FileStore::new(location), ui, method, target),
+        _ => Response::error(400, "Malformed request"),
+    };
+    writer.write_all(&response.to_bytes()).await?;
+    writer.shutdown().await
+}
+
+/// Serve the API (and optionally the dashboard) on `127.0.0.1:<port>`
+pub async fn run_web_server(location: TaskLocation, options: WebOptions) -> anyhow::Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", options.port)).await?;
+    log::info!("Serving tasks on http://{}", listener.local_addr()?);
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let location = location.clone();
+        tokio::spawn(async move {
+            if let Err(e) = serve_connection(stream, location, options.ui).await {
+                log::warn!("Request failed: {}", e);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Task, TaskKind};
+    use crate::storage::test_support::setup_test_store;
+
+    #[test]
+    fn test_handle_routes() {
+        let (_temp, store) = setup_test_store();
+        store
+            .create(Task::new(0, TaskKind::Task, "Fix login"))
+            .unwrap();
+        store
+            .create(Task::new(0, TaskKind::Idea, "Dark mode"))
+            .unwrap();
+
+        let response = handle(&store, false, "GET", "/api/tasks?q=dark+MODE");
+        let tasks: Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(tasks.as_array().unwrap().len(), 1);
+        assert_eq!(tasks[0]["title"], "Dark mode");
+
+        let response = handle(&store, false, "GET", "/api/tasks/1");
+        assert_eq!(response.status, 200);
+        assert!(response.body.contains("Fix login"));
+
+        assert_eq!(
+            handle(&store, false, "GET", "/api/tasks?kind=nope").status,
+            400
+        );
+        let response = handle(&store, false, "GET", "/api/columns");
+        let columns: Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(columns, json!(["pending", "in-progress", "completed"]));
+        assert_eq!(handle(&store, false, "GET", "/").status, 404);
+        assert_eq!(handle(&store, true, "GET", "/").status, 200);
+        assert_eq!(handle(&store, true, "POST", "/api/tasks").status, 405);
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("a%20b+c%2"), "a b c%2");
+        assert_eq!(percent_decode("%E2%9C%93"), "\u{2713}");
+    }
+}
@@ -0,0 +1,56 @@
+//! `--dry-run` from the command line, against a real git repository
+
+use std::path::Path;
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+fn run(dir: &Path, program: &str, args: &[&str]) -> Output {
+    Command::new(program)
+        .args(args)
+        .current_dir(dir)
+        .env("HOME", dir)
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .env("GIT_AUTHOR_NAME", "Test User")
+        .env("GIT_AUTHOR_EMAIL", "test@test.com")
+        .env("GIT_COMMITTER_NAME", "Test User")
+        .env("GIT_COMMITTER_EMAIL", "test@test.com")
+        .output()
+        .unwrap()
+}
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let output = run(dir, "git", args);
+    assert!(output.status.success(), "git {:?} failed", args);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn gittask(dir: &Path, args: &[&str]) -> Output {
+    run(dir, env!("CARGO_BIN_EXE_gittask"), args)
+}
+
+#[test]
+fn test_dry_run_leaves_git_alone() {
+    let temp = TempDir::new().unwrap();
+    let dir = temp.path();
+    git(dir, &["init", "-q"]);
+    git(dir, &["commit", "-q", "--allow-empty", "-m", "initial"]);
+    assert!(gittask(dir, &["init"]).status.success());
+    assert!(gittask(dir, &["add", "task", "Fix thing"]).status.success());
+
+    let branches = git(dir, &["branch", "--list"]);
+    let head = git(dir, &["rev-parse", "HEAD"]);
+    let status = git(dir, &["status", "--porcelain"]);
+
+    for args in [
+        ["--dry-run", "start", "1", "--branch"].as_slice(),
+        ["--dry-run", "close", "1", "--commit"].as_slice(),
+    ] {
+        let output = gittask(dir, args);
+        assert!(!output.status.success(), "{:?} should be refused", args);
+        assert!(String::from_utf8_lossy(&output.stderr).contains("--dry-run"));
+    }
+
+    assert_eq!(git(dir, &["branch", "--list"]), branches);
+    assert_eq!(git(dir, &["rev-parse", "HEAD"]), head);
+    assert_eq!(git(dir, &["status", "--porcelain"]), status);
+}